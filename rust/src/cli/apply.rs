@@ -3,12 +3,15 @@
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{stdin, stdout, Read, Write};
+use std::os::unix::process::CommandExt;
 use std::process::{Command, Stdio};
 use std::str::FromStr;
 
-use nmstate::{NetworkPolicy, NetworkState};
+use nmstate::{
+    NetworkPolicy, NetworkState, NmstateError, VerificationMismatch,
+};
 
-use crate::error::CliError;
+use crate::error::{CliError, DEFAULT_ERROR_CODE};
 
 const DEFAULT_TIMEOUT: u32 = 60;
 
@@ -102,7 +105,19 @@ where
         matches.try_contains_id("MEMORY_ONLY").unwrap_or_default(),
     );
 
-    net_state.apply()?;
+    if matches.try_contains_id("DRY_RUN").unwrap_or_default() {
+        net_state.set_include_secrets(
+            matches.try_contains_id("SHOW_SECRETS").unwrap_or_default(),
+        );
+        return Ok(serde_yaml::to_string(&net_state.apply_plan()?)?);
+    }
+
+    net_state.apply().map_err(apply_error_to_cli_error)?;
+    if kernel_only && no_commit {
+        if let Some(checkpoint) = NetworkState::kernel_checkpoint()? {
+            spawn_kernel_checkpoint_waiter(&checkpoint, timeout)?;
+        }
+    }
     if !matches.try_contains_id("SHOW_SECRETS").unwrap_or_default() {
         net_state.hide_secrets();
     }
@@ -110,6 +125,97 @@ where
     Ok(serde_yaml::to_string(&sorted_net_state)?)
 }
 
+// Show all the verification mismatches as table, so user does not need to
+// dig into the debug logs.
+fn apply_error_to_cli_error(e: NmstateError) -> CliError {
+    let mismatches = e.verification_mismatches();
+    if mismatches.is_empty() {
+        return e.into();
+    }
+    CliError {
+        code: DEFAULT_ERROR_CODE,
+        error_msg: format!(
+            "NmstateError: {}: {} mismatch(es) found after apply\n{}",
+            e.kind(),
+            mismatches.len(),
+            gen_mismatch_table(mismatches)
+        ),
+    }
+}
+
+fn gen_mismatch_table(mismatches: &[VerificationMismatch]) -> String {
+    const HEADERS: [&str; 3] = ["PATH", "DESIRED", "CURRENT"];
+    let mut widths = HEADERS.map(|h| h.len());
+    for mismatch in mismatches {
+        widths[0] = widths[0].max(mismatch.path.len());
+        widths[1] = widths[1].max(mismatch.desired.len());
+    }
+    let mut lines = vec![format!(
+        "{:<w0$}  {:<w1$}  {}",
+        HEADERS[0],
+        HEADERS[1],
+        HEADERS[2],
+        w0 = widths[0],
+        w1 = widths[1],
+    )];
+    for mismatch in mismatches {
+        lines.push(format!(
+            "{:<w0$}  {:<w1$}  {}",
+            mismatch.path,
+            mismatch.desired,
+            mismatch.current,
+            w0 = widths[0],
+            w1 = widths[1],
+        ));
+    }
+    lines.join("\n")
+}
+
+// There is no daemon to rollback uncommitted checkpoint in kernel only mode,
+// hence we spawn a detached `rollback --wait` process which invokes
+// `NetworkState::kernel_checkpoint_wait()` to rollback on timeout. It quits
+// without action once the checkpoint been committed.
+fn spawn_kernel_checkpoint_waiter(
+    checkpoint: &str,
+    timeout: u32,
+) -> Result<(), CliError> {
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.arg("rollback")
+        .arg("--wait")
+        .arg(checkpoint)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Detach from the controlling terminal so that closing the terminal or
+    // logging out does not kill the waiter before the timeout.
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            libc::signal(libc::SIGHUP, libc::SIG_IGN);
+            Ok(())
+        });
+    }
+    cmd.spawn()?;
+    eprintln!(
+        "Checkpoint {checkpoint} will be rolled back in {timeout} seconds \
+        unless committed"
+    );
+    Ok(())
+}
+
+pub(crate) fn rollback_on_timeout(
+    checkpoint: &str,
+) -> Result<String, CliError> {
+    // Survive the Ctrl-C on terminal which spawned us
+    ctrlc::set_handler(|| {}).expect("Error setting Ctrl-C handler");
+    match NetworkState::kernel_checkpoint_wait(checkpoint) {
+        Ok(()) => Ok(checkpoint.to_string()),
+        Err(e) => Err(CliError::from(e)),
+    }
+}
+
 pub(crate) fn commit(checkpoint: &str) -> Result<String, CliError> {
     match NetworkState::checkpoint_commit(checkpoint) {
         Ok(()) => Ok(checkpoint.to_string()),
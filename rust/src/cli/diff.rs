@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use nmstate::NetworkState;
+use std::io::Read;
+
+use crate::error::CliError;
+
+pub(crate) fn diff(matches: &clap::ArgMatches) -> Result<String, CliError> {
+    // The required() has ensured the unwrap() will never fail
+    let desired = read_state(matches.value_of("DESIRED_STATE").unwrap())?;
+    let current = if let Some(file_path) = matches.value_of("CURRENT_STATE") {
+        read_state(file_path)?
+    } else {
+        retrieve_current()?
+    };
+
+    let diff_state = desired.gen_diff(&current)?;
+    Ok(if matches.is_present("JSON") {
+        serde_json::to_string_pretty(&diff_state)?
+    } else {
+        serde_yaml::to_string(&diff_state)?
+    })
+}
+
+fn read_state(state_file: &str) -> Result<NetworkState, CliError> {
+    let mut content = String::new();
+    if state_file == "-" {
+        std::io::stdin().read_to_string(&mut content)?;
+    } else {
+        std::fs::File::open(state_file)?.read_to_string(&mut content)?;
+    };
+    // Replace non-breaking space '\u{A0}'  to normal space
+    let content = content.replace('\u{A0}', " ");
+
+    Ok(NetworkState::new_from_yaml(&content)?)
+}
+
+#[cfg(feature = "query_apply")]
+fn retrieve_current() -> Result<NetworkState, CliError> {
+    let mut net_state = NetworkState::new();
+    // Include secrets, otherwise every password will be shown as changed
+    net_state.set_include_secrets(true);
+    net_state.retrieve()?;
+    Ok(net_state)
+}
+
+#[cfg(not(feature = "query_apply"))]
+fn retrieve_current() -> Result<NetworkState, CliError> {
+    Err(
+        "Retrieving current network state require `query_apply` feature \
+        been enabled during compiling, please use --current instead"
+            .into(),
+    )
+}
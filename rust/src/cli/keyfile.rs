@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use nmstate::NetworkState;
+
+use crate::error::CliError;
+use crate::query::sort_netstate;
+
+const NM_KEYFILE_EXTENSION: &str = "nmconnection";
+
+// Convert all the `*.nmconnection` files in specified folder
+pub(crate) fn import_keyfile(
+    matches: &clap::ArgMatches,
+) -> Result<String, CliError> {
+    // The required() has ensured the unwrap() will never fail
+    let dir = matches.value_of("DIR").unwrap();
+    let mut file_paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let file_path = entry?.path();
+        if file_path.is_file()
+            && file_path.extension().and_then(|e| e.to_str())
+                == Some(NM_KEYFILE_EXTENSION)
+        {
+            file_paths.push(file_path);
+        }
+    }
+    if file_paths.is_empty() {
+        return Err(format!(
+            "No NetworkManager keyfile(*.{NM_KEYFILE_EXTENSION}) found in \
+            folder {dir}"
+        )
+        .into());
+    }
+    // Sort to make the output consistent
+    file_paths.sort_unstable();
+
+    let mut keyfiles = Vec::new();
+    for file_path in file_paths {
+        log::debug!("Reading NetworkManager keyfile {}", file_path.display());
+        keyfiles.push(std::fs::read_to_string(&file_path)?);
+    }
+    let mut net_state = NetworkState::from_nm_keyfiles(&keyfiles)?;
+    if !matches.is_present("SHOW_SECRETS") {
+        net_state.hide_secrets();
+    }
+    Ok(if matches.is_present("JSON") {
+        serde_json::to_string_pretty(&sort_netstate(net_state)?)?
+    } else {
+        serde_yaml::to_string(&sort_netstate(net_state)?)?
+    })
+}
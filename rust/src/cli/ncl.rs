@@ -4,11 +4,14 @@
 mod apply;
 #[cfg(feature = "query_apply")]
 mod autoconf;
+mod diff;
 mod error;
 mod format;
 #[cfg(feature = "gen_conf")]
 mod gen_conf;
 #[cfg(feature = "query_apply")]
+mod keyfile;
+#[cfg(feature = "query_apply")]
 mod policy;
 #[cfg(feature = "query_apply")]
 mod query;
@@ -21,13 +24,16 @@ use log::LevelFilter;
 
 #[cfg(feature = "query_apply")]
 use crate::apply::{
-    apply_from_files, apply_from_stdin, commit, rollback, state_edit,
+    apply_from_files, apply_from_stdin, commit, rollback, rollback_on_timeout,
+    state_edit,
 };
 #[cfg(feature = "query_apply")]
 use crate::autoconf::autoconf;
 #[cfg(feature = "gen_conf")]
 use crate::gen_conf::gen_conf;
 #[cfg(feature = "query_apply")]
+use crate::keyfile::import_keyfile;
+#[cfg(feature = "query_apply")]
 use crate::policy::policy;
 #[cfg(feature = "query_apply")]
 use crate::query::show;
@@ -51,6 +57,8 @@ const SUB_CMD_AUTOCONF: &str = "autoconf";
 const SUB_CMD_SERVICE: &str = "service";
 const SUB_CMD_POLICY: &str = "policy";
 const SUB_CMD_FORMAT: &str = "format";
+const SUB_CMD_IMPORT_KEYFILE: &str = "import-keyfile";
+const SUB_CMD_DIFF: &str = "diff";
 
 fn main() {
     let argv: Vec<String> = std::env::args().collect();
@@ -177,6 +185,15 @@ fn main() {
                         .takes_value(false)
                         .help("Do not make the state persistent"),
                 )
+                .arg(
+                    clap::Arg::new("DRY_RUN")
+                        .long("dry-run")
+                        .takes_value(false)
+                        .help(
+                            "Show the NetworkManager actions apply would take \
+                            without changing anything",
+                        ),
+                )
         )
         .subcommand(
             clap::Command::new(SUB_CMD_GEN_CONF)
@@ -186,6 +203,14 @@ fn main() {
                         .required(true)
                         .index(1)
                         .help("Network state file"),
+                )
+                .arg(
+                    clap::Arg::new("BACKEND")
+                        .long("backend")
+                        .takes_value(true)
+                        .possible_values(["NetworkManager", "systemd-networkd"])
+                        .default_value("NetworkManager")
+                        .help("Network backend to generate configuration for"),
                 ),
         )
         .subcommand(
@@ -206,6 +231,17 @@ fn main() {
                         .required(false)
                         .index(1)
                         .help("checkpoint to rollback"),
+                )
+                .arg(
+                    clap::Arg::new("WAIT")
+                        .long("wait")
+                        .takes_value(false)
+                        .hide(true)
+                        .requires("CHECKPOINT")
+                        .help(
+                            "Wait till kernel only mode checkpoint expired \
+                            and then rollback",
+                        ),
                 ),
         )
         .subcommand(
@@ -314,6 +350,59 @@ fn main() {
                         .help("Network state file"),
                 ),
         )
+        .subcommand(
+            clap::Command::new(SUB_CMD_IMPORT_KEYFILE)
+                .about(
+                    "Generate network state from NetworkManager keyfiles \
+                    in specified folder")
+                .arg(
+                    clap::Arg::new("DIR")
+                        .required(true)
+                        .index(1)
+                        .help(
+                            "Folder holding NetworkManager keyfiles \
+                            (*.nmconnection)"),
+                )
+                .arg(
+                    clap::Arg::new("JSON")
+                        .long("json")
+                        .takes_value(false)
+                        .help("Show state in json format"),
+                )
+                .arg(
+                    clap::Arg::new("SHOW_SECRETS")
+                        .short('s')
+                        .long("show-secrets")
+                        .takes_value(false)
+                        .help("Show secrets(hide by default)"),
+                )
+        )
+        .subcommand(
+            clap::Command::new(SUB_CMD_DIFF)
+                .about(
+                    "Show the properties of desired state which are \
+                    different from current state")
+                .arg(
+                    clap::Arg::new("DESIRED_STATE")
+                        .required(true)
+                        .index(1)
+                        .help("Desired network state file, `-` for stdin"),
+                )
+                .arg(
+                    clap::Arg::new("CURRENT_STATE")
+                        .long("current")
+                        .takes_value(true)
+                        .help(
+                            "Current network state file. \
+                            Retrieve from running system if not defined"),
+                )
+                .arg(
+                    clap::Arg::new("JSON")
+                        .long("json")
+                        .takes_value(false)
+                        .help("Show state in json format"),
+                )
+        )
         .subcommand(
             clap::Command::new(SUB_CMD_VERSION)
             .about("Show version")
@@ -339,7 +428,9 @@ fn main() {
 
     if let Some(matches) = matches.subcommand_matches(SUB_CMD_GEN_CONF) {
         if let Some(file_path) = matches.value_of("STATE_FILE") {
-            print_result_and_exit(gen_conf(file_path));
+            // The default_value() has ensured the unwrap() will never fail
+            let backend = matches.value_of("BACKEND").unwrap();
+            print_result_and_exit(gen_conf(file_path, backend));
         }
     } else if let Some(matches) = matches.subcommand_matches(SUB_CMD_SHOW) {
         print_result_and_exit(show(matches));
@@ -366,7 +457,11 @@ fn main() {
         }
     } else if let Some(matches) = matches.subcommand_matches(SUB_CMD_ROLLBACK) {
         if let Some(checkpoint) = matches.value_of("CHECKPOINT") {
-            print_result_and_exit(rollback(checkpoint));
+            if matches.is_present("WAIT") {
+                print_result_and_exit(rollback_on_timeout(checkpoint));
+            } else {
+                print_result_and_exit(rollback(checkpoint));
+            }
         } else {
             print_result_and_exit(rollback(""))
         }
@@ -381,6 +476,12 @@ fn main() {
         print_result_and_exit(format::format(
             matches.value_of("STATE_FILE").unwrap(),
         ));
+    } else if let Some(matches) =
+        matches.subcommand_matches(SUB_CMD_IMPORT_KEYFILE)
+    {
+        print_result_and_exit(import_keyfile(matches));
+    } else if let Some(matches) = matches.subcommand_matches(SUB_CMD_DIFF) {
+        print_result_and_exit(diff::diff(matches));
     } else if matches.subcommand_matches(SUB_CMD_VERSION).is_some() {
         print_result_and_exit(Ok(format!(
             "{} {}",
@@ -391,7 +492,10 @@ fn main() {
 }
 
 #[cfg(not(feature = "gen_conf"))]
-fn gen_conf(_file_path: &str) -> Result<String, crate::error::CliError> {
+fn gen_conf(
+    _file_path: &str,
+    _backend: &str,
+) -> Result<String, crate::error::CliError> {
     Err("The gc sub-command require `gen_conf` feature been \
         enabled during compiling"
         .into())
@@ -439,6 +543,13 @@ fn rollback(_checkpoint: &str) -> Result<String, crate::error::CliError> {
     )
 }
 
+#[cfg(not(feature = "query_apply"))]
+fn rollback_on_timeout(
+    checkpoint: &str,
+) -> Result<String, crate::error::CliError> {
+    rollback(checkpoint)
+}
+
 #[cfg(not(feature = "query_apply"))]
 fn state_edit(
     _matches: &clap::ArgMatches,
@@ -476,3 +587,14 @@ fn policy(
         enabled during compiling"
         .into())
 }
+
+#[cfg(not(feature = "query_apply"))]
+fn import_keyfile(
+    _matches: &clap::ArgMatches,
+) -> Result<String, crate::error::CliError> {
+    Err(
+        "The import-keyfile sub-command require `query_apply` feature been \
+        enabled during compiling"
+            .into(),
+    )
+}
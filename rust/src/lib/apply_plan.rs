@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+#[serde(rename_all = "kebab-case")]
+/// Action [NetworkState::apply()] will take against the network backend.
+///
+/// [NetworkState::apply()]: crate::NetworkState::apply
+pub enum ApplyPlanActionKind {
+    /// Delete the NetworkManager connection profile
+    DeleteProfile,
+    /// Delete the kernel interface not managed by NetworkManager profile
+    DeleteInterface,
+    /// Change the static hostname via NetworkManager
+    SetHostname,
+    /// Remove the NetworkManager global DNS configuration
+    PurgeGlobalDns,
+    /// Store DNS configuration via NetworkManager global DNS API
+    SetGlobalDns,
+    /// Deactivate the NetworkManager connection profile. Would bounce the
+    /// interface if showing up before activation.
+    Deactivate,
+    /// Create new NetworkManager connection profile
+    AddProfile,
+    /// Update existing NetworkManager connection profile
+    ModifyProfile,
+    /// Activate the NetworkManager connection profile which bounces the
+    /// interface
+    Activate,
+    /// Reapply the NetworkManager connection profile to the activated
+    /// interface without bouncing it
+    Reapply,
+    /// Apply the global OVS database configuration
+    UpdateOvsDb,
+    /// Change the running(transient) hostname
+    SetRunningHostname,
+}
+
+impl std::fmt::Display for ApplyPlanActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::DeleteProfile => "delete-profile",
+                Self::DeleteInterface => "delete-interface",
+                Self::SetHostname => "set-hostname",
+                Self::PurgeGlobalDns => "purge-global-dns",
+                Self::SetGlobalDns => "set-global-dns",
+                Self::Deactivate => "deactivate",
+                Self::AddProfile => "add-profile",
+                Self::ModifyProfile => "modify-profile",
+                Self::Activate => "activate",
+                Self::Reapply => "reapply",
+                Self::UpdateOvsDb => "update-ovs-db",
+                Self::SetRunningHostname => "set-running-hostname",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+#[serde(rename_all = "kebab-case")]
+/// Single step of the plan generated by [NetworkState::apply_plan()].
+///
+/// [NetworkState::apply_plan()]: crate::NetworkState::apply_plan
+pub struct ApplyPlanAction {
+    pub action: ApplyPlanActionKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Interface name
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// NetworkManager setting type of the profile or interface
+    pub iface_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// UUID of NetworkManager connection profile
+    pub uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// ID of NetworkManager connection profile
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Extra information of this action, e.g. the hostname to set.
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// NetworkManager keyfile content of the profile to store.
+    /// Only available for feature `gen_conf`.
+    pub keyfile: Option<String>,
+}
+
+#[cfg(feature = "query_apply")]
+impl ApplyPlanAction {
+    pub(crate) fn new(action: ApplyPlanActionKind) -> Self {
+        Self {
+            action,
+            name: None,
+            iface_type: None,
+            uuid: None,
+            id: None,
+            detail: None,
+            keyfile: None,
+        }
+    }
+}
@@ -207,6 +207,14 @@ impl MergedDnsState {
 
         self.servers != cur_servers || self.searches != cur_searches
     }
+
+    pub(crate) fn gen_diff(&self) -> DnsState {
+        let mut ret = DnsState::new();
+        if self.is_changed() {
+            ret.config = self.desired.config.clone();
+        }
+        ret
+    }
 }
 
 impl MergedNetworkState {
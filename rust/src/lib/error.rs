@@ -63,6 +63,39 @@ pub struct NmstateError {
     msg: String,
     line: String,
     position: usize,
+    mismatches: Vec<VerificationMismatch>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+/// Single property mismatch found when verifying the applied state.
+pub struct VerificationMismatch {
+    /// The JSON path of the property, e.g. `eth1.interface.mtu`.
+    pub path: String,
+    /// The desired value in JSON format.
+    pub desired: String,
+    /// The current value in JSON format, `null` means not found.
+    pub current: String,
+}
+
+impl VerificationMismatch {
+    pub fn new(path: String, desired: String, current: String) -> Self {
+        Self {
+            path,
+            desired,
+            current,
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} desire '{}', current '{}'",
+            self.path, self.desired, self.current
+        )
+    }
 }
 
 impl NmstateError {
@@ -80,6 +113,26 @@ impl NmstateError {
             line: line.to_string(),
             msg,
             position,
+            ..Default::default()
+        }
+    }
+
+    /// Create [ErrorKind::VerificationError] holding all the mismatches.
+    pub fn new_verification_error(
+        mismatches: Vec<VerificationMismatch>,
+    ) -> Self {
+        Self {
+            kind: ErrorKind::VerificationError,
+            msg: format!(
+                "Verification failure: {}",
+                mismatches
+                    .iter()
+                    .map(|m| m.to_string())
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            ),
+            mismatches,
+            ..Default::default()
         }
     }
 
@@ -100,6 +153,26 @@ impl NmstateError {
     pub fn position(&self) -> usize {
         self.position
     }
+
+    /// All the mismatches found by verification for
+    /// [ErrorKind::VerificationError]. Empty for other errors.
+    pub fn verification_mismatches(&self) -> &[VerificationMismatch] {
+        self.mismatches.as_slice()
+    }
+}
+
+// Convert collected verification mismatches into single VerificationError
+#[cfg(feature = "query_apply")]
+pub(crate) fn mismatches_to_result(
+    mismatches: Vec<VerificationMismatch>,
+) -> Result<(), NmstateError> {
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        let e = NmstateError::new_verification_error(mismatches);
+        log::error!("{}", e);
+        Err(e)
+    }
 }
 
 impl From<serde_json::Error> for NmstateError {
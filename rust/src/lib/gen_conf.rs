@@ -2,7 +2,13 @@
 
 use std::collections::HashMap;
 
-use crate::{nm::nm_gen_conf, MergedNetworkState, NetworkState, NmstateError};
+use crate::{
+    networkd::networkd_gen_conf, nm::nm_gen_conf, ErrorKind,
+    MergedNetworkState, NetworkState, NmstateError,
+};
+
+const BACKEND_NM: &str = "NetworkManager";
+const BACKEND_NETWORKD: &str = "systemd-networkd";
 
 impl NetworkState {
     /// Generate offline network configurations.
@@ -15,6 +21,20 @@ impl NetworkState {
     /// The backend name for NetworkManager is `NetworkManager`.
     pub fn gen_conf(
         &self,
+    ) -> Result<HashMap<String, Vec<(String, String)>>, NmstateError> {
+        self.gen_conf_for_backend(BACKEND_NM)
+    }
+
+    /// Generate offline network configurations for specified backend.
+    /// Supported backends are:
+    ///  * `NetworkManager` -- NetworkManager key files.
+    ///  * `systemd-networkd` -- systemd-networkd `.netdev`, `.network` and
+    ///    `.link` files.
+    ///
+    /// The output is the same as [NetworkState::gen_conf()].
+    pub fn gen_conf_for_backend(
+        &self,
+        backend: &str,
     ) -> Result<HashMap<String, Vec<(String, String)>>, NmstateError> {
         let mut ret = HashMap::new();
         let merged_state = MergedNetworkState::new(
@@ -23,7 +43,20 @@ impl NetworkState {
             true,  // gen_conf mode
             false, // memory only
         )?;
-        ret.insert("NetworkManager".to_string(), nm_gen_conf(&merged_state)?);
+        let confs = match backend {
+            BACKEND_NM => nm_gen_conf(&merged_state)?,
+            BACKEND_NETWORKD => networkd_gen_conf(&merged_state)?,
+            _ => {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Unsupported backend {backend}, only support \
+                        {BACKEND_NM} and {BACKEND_NETWORKD}"
+                    ),
+                ));
+            }
+        };
+        ret.insert(backend.to_string(), confs);
         Ok(ret)
     }
 }
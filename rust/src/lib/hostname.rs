@@ -24,4 +24,21 @@ impl MergedHostNameState {
     ) -> Self {
         Self { desired, current }
     }
+
+    pub(crate) fn gen_diff(&self) -> Option<HostNameState> {
+        let desired = self.desired.as_ref()?;
+        let current = self.current.clone().unwrap_or_default();
+        let mut ret = HostNameState::default();
+        if desired.running.is_some() && desired.running != current.running {
+            ret.running = desired.running.clone();
+        }
+        if desired.config.is_some() && desired.config != current.config {
+            ret.config = desired.config.clone();
+        }
+        if ret == HostNameState::default() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
 }
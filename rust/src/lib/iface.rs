@@ -7,12 +7,14 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
     BaseInterface, BondInterface, DummyInterface, ErrorKind, EthernetInterface,
-    InfiniBandInterface, LinuxBridgeInterface, LoopbackInterface,
-    MacVlanInterface, MacVtapInterface, NmstateError, OvsBridgeInterface,
-    OvsInterface, VlanInterface, VrfInterface, VxlanInterface,
+    GeneveInterface, HsrInterface, InfiniBandInterface, IpTunnelInterface,
+    IpVlanInterface, LinuxBridgeInterface, LoopbackInterface, MacVlanInterface,
+    MacVtapInterface, MacsecInterface, NmstateError, OvsBridgeInterface,
+    OvsInterface, TunInterface, VlanInterface, VrfInterface, VxlanInterface,
+    WireGuardInterface, XfrmInterface,
 };
 
-use crate::state::merge_json_value;
+use crate::state::{gen_diff_json_value, merge_json_value};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[non_exhaustive]
@@ -60,6 +62,43 @@ pub enum InterfaceType {
     /// [IP over InfiniBand interface](https://docs.kernel.org/infiniband/ipoib.html)
     /// Deserialize and serialize from/to 'infiniband'.
     InfiniBand,
+    /// [WireGuard VPN interface](https://www.wireguard.com/)
+    /// Deserialize and serialize from/to 'wireguard'.
+    WireGuard,
+    /// GRE tunnel over IPv4 or IPv6.
+    /// Deserialize and serialize from/to 'gre'.
+    Gre,
+    /// Ethernet over GRE tunnel over IPv4 or IPv6.
+    /// Deserialize and serialize from/to 'gretap'.
+    GreTap,
+    /// IPv4 over IPv4 tunnel.
+    /// Deserialize and serialize from/to 'ipip'.
+    IpIp,
+    /// IPv6 over IPv4 tunnel.
+    /// Deserialize and serialize from/to 'sit'.
+    Sit,
+    /// IPv4 or IPv6 over IPv6 tunnel.
+    /// Deserialize and serialize from/to 'ip6tnl'.
+    Ip6Tnl,
+    /// [Geneve interface](https://datatracker.ietf.org/doc/html/rfc8926)
+    /// Deserialize and serialize from/to 'geneve'.
+    Geneve,
+    /// [MACsec interface](https://en.wikipedia.org/wiki/IEEE_802.1AE)
+    /// Deserialize and serialize from/to 'macsec'.
+    Macsec,
+    /// [IP VLAN interface](https://docs.kernel.org/networking/ipvlan.html)
+    /// Deserialize and serialize from/to 'ipvlan'.
+    IpVlan,
+    /// TUN/TAP interface
+    /// Deserialize and serialize from/to 'tun'.
+    Tun,
+    /// High-availability Seamless Redundancy(HSR) or Parallel Redundancy
+    /// Protocol(PRP) interface
+    /// Deserialize and serialize from/to 'hsr'.
+    Hsr,
+    /// [XFRM interface](https://docs.kernel.org/networking/xfrm_device.html)
+    /// Deserialize and serialize from/to 'xfrm'.
+    Xfrm,
     /// Unknown interface.
     Unknown,
     /// Reserved for future use.
@@ -89,6 +128,18 @@ impl From<&str> for InterfaceType {
             "vrf" => InterfaceType::Vrf,
             "vxlan" => InterfaceType::Vxlan,
             "infiniband" => InterfaceType::InfiniBand,
+            "wireguard" => InterfaceType::WireGuard,
+            "gre" => InterfaceType::Gre,
+            "gretap" => InterfaceType::GreTap,
+            "ipip" => InterfaceType::IpIp,
+            "sit" => InterfaceType::Sit,
+            "ip6tnl" => InterfaceType::Ip6Tnl,
+            "geneve" => InterfaceType::Geneve,
+            "macsec" => InterfaceType::Macsec,
+            "ipvlan" => InterfaceType::IpVlan,
+            "tun" => InterfaceType::Tun,
+            "hsr" => InterfaceType::Hsr,
+            "xfrm" => InterfaceType::Xfrm,
             "unknown" => InterfaceType::Unknown,
             _ => InterfaceType::Other(s.to_string()),
         }
@@ -115,6 +166,18 @@ impl std::fmt::Display for InterfaceType {
                 InterfaceType::Vrf => "vrf",
                 InterfaceType::Vxlan => "vxlan",
                 InterfaceType::InfiniBand => "infiniband",
+                InterfaceType::WireGuard => "wireguard",
+                InterfaceType::Gre => "gre",
+                InterfaceType::GreTap => "gretap",
+                InterfaceType::IpIp => "ipip",
+                InterfaceType::Sit => "sit",
+                InterfaceType::Ip6Tnl => "ip6tnl",
+                InterfaceType::Geneve => "geneve",
+                InterfaceType::Macsec => "macsec",
+                InterfaceType::IpVlan => "ipvlan",
+                InterfaceType::Tun => "tun",
+                InterfaceType::Hsr => "hsr",
+                InterfaceType::Xfrm => "xfrm",
                 InterfaceType::Unknown => "unknown",
                 InterfaceType::Other(ref s) => s,
             }
@@ -146,8 +209,13 @@ impl<'de> Deserialize<'de> for InterfaceType {
 
 impl InterfaceType {
     const USERSPACE_IFACE_TYPES: [Self; 1] = [Self::OvsBridge];
-    const CONTROLLER_IFACES_TYPES: [Self; 4] =
-        [Self::Bond, Self::LinuxBridge, Self::OvsBridge, Self::Vrf];
+    const CONTROLLER_IFACES_TYPES: [Self; 5] = [
+        Self::Bond,
+        Self::LinuxBridge,
+        Self::OvsBridge,
+        Self::Vrf,
+        Self::Hsr,
+    ];
 
     // other interfaces are also considered as userspace
     pub(crate) fn is_userspace(&self) -> bool {
@@ -290,6 +358,22 @@ pub enum Interface {
     InfiniBand(InfiniBandInterface),
     /// Linux loopback interface
     Loopback(LoopbackInterface),
+    /// [WireGuard VPN interface](https://www.wireguard.com/)
+    WireGuard(WireGuardInterface),
+    /// IP tunnel interface, including GRE, GRETAP, IPIP, SIT and ip6tnl.
+    IpTunnel(IpTunnelInterface),
+    /// [Geneve interface](https://datatracker.ietf.org/doc/html/rfc8926)
+    Geneve(GeneveInterface),
+    /// [MACsec interface](https://en.wikipedia.org/wiki/IEEE_802.1AE)
+    Macsec(MacsecInterface),
+    /// [IP VLAN interface](https://docs.kernel.org/networking/ipvlan.html)
+    IpVlan(IpVlanInterface),
+    /// [TUN/TAP interface](https://docs.kernel.org/networking/tuntap.html)
+    Tun(TunInterface),
+    /// HSR/PRP interface
+    Hsr(HsrInterface),
+    /// XFRM interface for route-based IPsec
+    Xfrm(XfrmInterface),
 }
 
 impl<'de> Deserialize<'de> for Interface {
@@ -391,6 +475,52 @@ impl<'de> Deserialize<'de> for Interface {
                     .map_err(serde::de::Error::custom)?;
                 Ok(Interface::Loopback(inner))
             }
+            Some(InterfaceType::WireGuard) => {
+                let inner = WireGuardInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::WireGuard(inner))
+            }
+            Some(
+                InterfaceType::Gre
+                | InterfaceType::GreTap
+                | InterfaceType::IpIp
+                | InterfaceType::Sit
+                | InterfaceType::Ip6Tnl,
+            ) => {
+                let inner = IpTunnelInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::IpTunnel(inner))
+            }
+            Some(InterfaceType::Geneve) => {
+                let inner = GeneveInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Geneve(inner))
+            }
+            Some(InterfaceType::Macsec) => {
+                let inner = MacsecInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Macsec(inner))
+            }
+            Some(InterfaceType::IpVlan) => {
+                let inner = IpVlanInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::IpVlan(inner))
+            }
+            Some(InterfaceType::Tun) => {
+                let inner = TunInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Tun(inner))
+            }
+            Some(InterfaceType::Hsr) => {
+                let inner = HsrInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Hsr(inner))
+            }
+            Some(InterfaceType::Xfrm) => {
+                let inner = XfrmInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Xfrm(inner))
+            }
             Some(iface_type) => {
                 log::warn!("Unsupported interface type {}", iface_type);
                 let inner = UnknownInterface::deserialize(v)
@@ -496,6 +626,46 @@ impl Interface {
                 new_iface.base = iface.base.clone_name_type_only();
                 Self::Loopback(new_iface)
             }
+            Self::WireGuard(iface) => {
+                let mut new_iface = WireGuardInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::WireGuard(new_iface)
+            }
+            Self::IpTunnel(iface) => {
+                let mut new_iface = IpTunnelInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::IpTunnel(new_iface)
+            }
+            Self::Geneve(iface) => {
+                let mut new_iface = GeneveInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Geneve(new_iface)
+            }
+            Self::Macsec(iface) => {
+                let mut new_iface = MacsecInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Macsec(new_iface)
+            }
+            Self::IpVlan(iface) => {
+                let mut new_iface = IpVlanInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::IpVlan(new_iface)
+            }
+            Self::Tun(iface) => {
+                let mut new_iface = TunInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Tun(new_iface)
+            }
+            Self::Hsr(iface) => {
+                let mut new_iface = HsrInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Hsr(new_iface)
+            }
+            Self::Xfrm(iface) => {
+                let mut new_iface = XfrmInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Xfrm(new_iface)
+            }
             Self::Unknown(iface) => {
                 let mut new_iface = UnknownInterface::new();
                 new_iface.base = iface.base.clone_name_type_only();
@@ -590,6 +760,14 @@ impl Interface {
             Self::Vrf(iface) => &iface.base,
             Self::InfiniBand(iface) => &iface.base,
             Self::Loopback(iface) => &iface.base,
+            Self::WireGuard(iface) => &iface.base,
+            Self::IpTunnel(iface) => &iface.base,
+            Self::Geneve(iface) => &iface.base,
+            Self::Macsec(iface) => &iface.base,
+            Self::IpVlan(iface) => &iface.base,
+            Self::Tun(iface) => &iface.base,
+            Self::Hsr(iface) => &iface.base,
+            Self::Xfrm(iface) => &iface.base,
             Self::Unknown(iface) => &iface.base,
         }
     }
@@ -609,6 +787,14 @@ impl Interface {
             Self::Vrf(iface) => &mut iface.base,
             Self::InfiniBand(iface) => &mut iface.base,
             Self::Loopback(iface) => &mut iface.base,
+            Self::WireGuard(iface) => &mut iface.base,
+            Self::IpTunnel(iface) => &mut iface.base,
+            Self::Geneve(iface) => &mut iface.base,
+            Self::Macsec(iface) => &mut iface.base,
+            Self::IpVlan(iface) => &mut iface.base,
+            Self::Tun(iface) => &mut iface.base,
+            Self::Hsr(iface) => &mut iface.base,
+            Self::Xfrm(iface) => &mut iface.base,
             Self::Unknown(iface) => &mut iface.base,
         }
     }
@@ -622,6 +808,7 @@ impl Interface {
                 Self::OvsBridge(_) => Some(Vec::new()),
                 Self::Bond(_) => Some(Vec::new()),
                 Self::Vrf(_) => Some(Vec::new()),
+                Self::Hsr(_) => Some(Vec::new()),
                 _ => None,
             }
         } else {
@@ -630,6 +817,7 @@ impl Interface {
                 Self::OvsBridge(iface) => iface.ports(),
                 Self::Bond(iface) => iface.ports(),
                 Self::Vrf(iface) => iface.ports(),
+                Self::Hsr(iface) => iface.ports(),
                 _ => None,
             }
         }
@@ -657,11 +845,29 @@ impl Interface {
             Interface::MacVlan(iface) => iface.sanitize(is_desired)?,
             Interface::MacVtap(iface) => iface.sanitize(is_desired)?,
             Interface::Loopback(iface) => iface.sanitize(is_desired)?,
+            Interface::WireGuard(iface) => iface.sanitize(is_desired)?,
+            Interface::IpTunnel(iface) => iface.sanitize(is_desired)?,
+            Interface::Geneve(iface) => iface.sanitize(is_desired)?,
+            Interface::Macsec(iface) => iface.sanitize(is_desired)?,
+            Interface::IpVlan(iface) => iface.sanitize(is_desired)?,
+            Interface::Hsr(iface) => iface.sanitize(is_desired)?,
+            Interface::Xfrm(iface) => iface.sanitize(is_desired)?,
+            Interface::Vxlan(iface) => iface.sanitize(is_desired)?,
+            Interface::Vlan(iface) => iface.sanitize(is_desired)?,
             _ => (),
         }
         Ok(())
     }
 
+    pub(crate) fn hide_secrets(&mut self) {
+        self.base_iface_mut().hide_secrets();
+        match self {
+            Interface::WireGuard(iface) => iface.hide_secrets(),
+            Interface::Macsec(iface) => iface.hide_secrets(),
+            _ => (),
+        }
+    }
+
     pub(crate) fn parent(&self) -> Option<&str> {
         match self {
             Interface::Vlan(vlan) => vlan.parent(),
@@ -670,6 +876,10 @@ impl Interface {
             Interface::MacVlan(vlan) => vlan.parent(),
             Interface::MacVtap(vtap) => vtap.parent(),
             Interface::InfiniBand(ib) => ib.parent(),
+            Interface::IpTunnel(tun) => tun.parent(),
+            Interface::Macsec(macsec) => macsec.parent(),
+            Interface::IpVlan(vlan) => vlan.parent(),
+            Interface::Xfrm(xfrm) => xfrm.parent(),
             _ => None,
         }
     }
@@ -756,6 +966,51 @@ impl MergedInterface {
         self.for_apply.is_some()
     }
 
+    // Generate `Interface` holding only the properties of `for_apply` which
+    // are different from current. The interface name, type and state are
+    // always included. Return None if nothing changed.
+    pub(crate) fn gen_diff(&self) -> Result<Option<Interface>, NmstateError> {
+        let apply_iface = match self.for_apply.as_ref() {
+            Some(i) => i,
+            None => return Ok(None),
+        };
+        let cur_iface = match self.current.as_ref() {
+            Some(i) => i,
+            None => {
+                return Ok(if apply_iface.is_absent() {
+                    None
+                } else {
+                    Some(apply_iface.clone())
+                });
+            }
+        };
+        if apply_iface.is_absent() {
+            let mut iface = apply_iface.clone_name_type_only();
+            iface.base_iface_mut().state = InterfaceState::Absent;
+            return Ok(Some(iface));
+        }
+
+        let mut cur_iface = cur_iface.clone();
+        cur_iface.sanitize(false).ok();
+        let apply_value = serde_json::to_value(apply_iface)?;
+        let cur_value = serde_json::to_value(&cur_iface)?;
+
+        if let Some(serde_json::Value::Object(mut diff_value)) =
+            gen_diff_json_value(&apply_value, &cur_value)
+        {
+            for key in ["name", "type", "state"] {
+                if let Some(v) = apply_value.get(key) {
+                    diff_value.insert(key.to_string(), v.clone());
+                }
+            }
+            Ok(Some(serde_json::from_value(serde_json::Value::Object(
+                diff_value,
+            ))?))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn pre_inter_ifaces_process(&mut self) {
         if self.merged.is_up() && self.is_desired() {
             self.special_merge();
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+// The Geneve VNI is 24 bits.
+const GENEVE_VNI_MAX: u32 = 0xFFFFFF;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel Geneve interface. The example yaml output of
+/// [crate::NetworkState] with a Geneve interface would be:
+/// ```yml
+/// interfaces:
+/// - name: geneve1
+///   type: geneve
+///   state: up
+///   mtu: 1450
+///   geneve:
+///     id: 102
+///     remote: 192.0.2.2
+///     ttl: 64
+///     tos: 0
+///     destination-port: 6081
+///     external: false
+/// ```
+///
+/// NetworkManager has no Geneve support, hence Geneve interfaces are marked
+/// as `state: ignore` when querying with NetworkManager backend and can
+/// only be changed in kernel mode.
+pub struct GeneveInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geneve: Option<GeneveConfig>,
+}
+
+impl Default for GeneveInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Geneve,
+                ..Default::default()
+            },
+            geneve: None,
+        }
+    }
+}
+
+impl GeneveInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn sanitize(
+        &self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let (true, Some(conf)) = (is_desired, self.geneve.as_ref()) {
+            conf.validate(self.base.name.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct GeneveConfig {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Geneve Network Identifier(VNI), 24 bits.
+    /// Required unless `external` mode is enabled.
+    pub id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Unicast IPv4 or IPv6 address of the remote tunnel endpoint.
+    pub remote: Option<IpAddr>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// TTL of outgoing packets, 0 means using the kernel default.
+    pub ttl: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// Type of Service of outgoing packets.
+    pub tos: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        rename = "destination-port",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// UDP destination port, kernel default is 6081.
+    /// Deserialize and serialize from/to `destination-port`.
+    pub dst_port: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Collect metadata mode where the tunnel parameters are provided by
+    /// the routing or OVS/TC flows instead of this interface. The `id`,
+    /// `remote`, `ttl` and `tos` cannot be set in this mode.
+    pub external: Option<bool>,
+}
+
+impl GeneveConfig {
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        if let Some(id) = self.id {
+            if id > GENEVE_VNI_MAX {
+                return Err(invalid_arg(format!(
+                    "Geneve interface {iface_name} has invalid id {id}, \
+                    should be in the range of 0 to {GENEVE_VNI_MAX}"
+                )));
+            }
+        }
+        if self.external == Some(true) {
+            for (prop, is_set) in [
+                ("id", self.id.unwrap_or_default() != 0),
+                ("remote", self.remote.is_some()),
+                ("ttl", self.ttl.unwrap_or_default() != 0),
+                ("tos", self.tos.unwrap_or_default() != 0),
+            ] {
+                if is_set {
+                    return Err(invalid_arg(format!(
+                        "Geneve interface {iface_name} is in external mode, \
+                        the {prop} property should not be defined"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid_arg(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
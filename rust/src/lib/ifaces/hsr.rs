@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+const HSR_SUPERVISION_ADDRESS_PREFIX: &str = "01:15:4E:00:01:";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel High-availability Seamless Redundancy(HSR) or Parallel
+/// Redundancy Protocol(PRP) interface. The example yaml output of
+/// [crate::NetworkState] with a HSR interface would be:
+/// ```yaml
+/// ---
+/// interfaces:
+///   - name: hsr0
+///     type: hsr
+///     state: up
+///     hsr:
+///       port1: eth1
+///       port2: eth2
+///       supervision-address: 01:15:4E:00:01:00
+///       protocol: hsr
+///       version: 0
+/// ```
+/// The `port1` and `port2` are treated as ports of this interface, hence
+/// their `controller` property will be set to the HSR interface.
+pub struct HsrInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hsr: Option<HsrConfig>,
+}
+
+impl Default for HsrInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Hsr,
+                ..Default::default()
+            },
+            hsr: None,
+        }
+    }
+}
+
+impl HsrInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ports(&self) -> Option<Vec<&str>> {
+        self.hsr
+            .as_ref()
+            .map(|conf| vec![conf.port1.as_str(), conf.port2.as_str()])
+    }
+
+    pub(crate) fn sanitize(
+        &mut self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let Some(conf) = self.hsr.as_mut() {
+            if let Some(addr) = conf.supervision_address.as_mut() {
+                addr.make_ascii_uppercase();
+            }
+            if is_desired {
+                conf.validate(self.base.name.as_str())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct HsrConfig {
+    /// The first port of the HSR ring or the PRP LAN A.
+    pub port1: String,
+    /// The second port of the HSR ring or the PRP LAN B.
+    pub port2: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The multicast MAC address used for supervision frames, only the last
+    /// byte is configurable and it should be in the format of
+    /// `01:15:4E:00:01:XX`.
+    /// Deserialize and serialize from/to `supervision-address`.
+    pub supervision_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<HsrProtocol>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// The HSR protocol version, 0 for HSRv0(IEC 62439-3:2010) and 1 for
+    /// HSRv1(IEC 62439-3:2012). Not allowed for PRP.
+    pub version: Option<u8>,
+}
+
+impl HsrConfig {
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        if self.port1 == self.port2 {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "The port1 and port2 of HSR interface {iface_name} \
+                    cannot be the same interface {}",
+                    self.port1
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if let Some(addr) = self.supervision_address.as_deref() {
+            if self.multicast_spec().is_none() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Invalid supervision address {addr} of HSR \
+                        interface {iface_name}, only the last byte of \
+                        {HSR_SUPERVISION_ADDRESS_PREFIX}00 is configurable"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        if let Some(version) = self.version {
+            if self.protocol == Some(HsrProtocol::Prp) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The version is only valid for HSR protocol, \
+                        but HSR interface {iface_name} is using PRP"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if version > 1 {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Invalid HSR version {version} of interface \
+                        {iface_name}, only 0 and 1 are supported"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    // The last byte of supervision address
+    pub(crate) fn multicast_spec(&self) -> Option<u8> {
+        let addr = self.supervision_address.as_deref()?;
+        if !addr.is_ascii()
+            || addr.len() != HSR_SUPERVISION_ADDRESS_PREFIX.len() + 2
+            || !addr
+                .to_ascii_uppercase()
+                .starts_with(HSR_SUPERVISION_ADDRESS_PREFIX)
+        {
+            return None;
+        }
+        u8::from_str_radix(&addr[HSR_SUPERVISION_ADDRESS_PREFIX.len()..], 16)
+            .ok()
+    }
+
+    #[cfg(feature = "query_apply")]
+    pub(crate) fn set_multicast_spec(&mut self, multicast_spec: u8) {
+        self.supervision_address = Some(format!(
+            "{HSR_SUPERVISION_ADDRESS_PREFIX}{multicast_spec:02X}"
+        ));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum HsrProtocol {
+    /// High-availability Seamless Redundancy.
+    /// Deserialize and serialize from/to `hsr`.
+    Hsr,
+    /// Parallel Redundancy Protocol.
+    /// Deserialize and serialize from/to `prp`.
+    Prp,
+}
+
+impl Default for HsrProtocol {
+    fn default() -> Self {
+        Self::Hsr
+    }
+}
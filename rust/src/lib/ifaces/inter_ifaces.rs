@@ -159,7 +159,7 @@ impl Interfaces {
 
     pub(crate) fn hide_secrets(&mut self) {
         for iface in self.kernel_ifaces.values_mut() {
-            iface.base_iface_mut().hide_secrets();
+            iface.hide_secrets();
         }
     }
 
@@ -595,6 +595,34 @@ impl MergedInterfaces {
         self.user_ifaces.values().chain(self.kernel_ifaces.values())
     }
 
+    pub(crate) fn gen_diff(&self) -> Result<Interfaces, NmstateError> {
+        let mut ret = Interfaces::new();
+        // Follow the order of desired state, then other changed interfaces
+        // sorted by name
+        let mut merged_ifaces: Vec<&MergedInterface> = self
+            .insert_order
+            .iter()
+            .filter_map(|(n, t)| self.get_iface(n, t.clone()))
+            .filter(|i| i.is_changed())
+            .collect();
+        let mut other_ifaces: Vec<&MergedInterface> = self
+            .iter()
+            .filter(|i| {
+                i.is_changed()
+                    && !merged_ifaces.iter().any(|m| std::ptr::eq(*m, *i))
+            })
+            .collect();
+        other_ifaces
+            .sort_unstable_by_key(|i| (i.merged.name(), i.merged.iface_type()));
+        merged_ifaces.extend(other_ifaces);
+        for merged_iface in merged_ifaces {
+            if let Some(iface) = merged_iface.gen_diff()? {
+                ret.push(iface);
+            }
+        }
+        Ok(ret)
+    }
+
     // Contains all the smart modifications, validations among interfaces
     fn process(&mut self) -> Result<(), NmstateError> {
         self.apply_copy_mac_from()?;
@@ -603,6 +631,7 @@ impl MergedInterfaces {
         self.resolve_port_iface_controller_type()?;
         self._set_up_priority()?;
         self.check_overbook_ports()?;
+        self.check_hsr_ports()?;
         self.check_infiniband_as_ports()?;
         self.mark_orphan_interface_as_absent()?;
         self.process_veth_peer_changes()?;
@@ -462,6 +462,36 @@ impl MergedInterfaces {
         Ok(())
     }
 
+    // HSR interface cannot be created without both ports defined.
+    pub(crate) fn check_hsr_ports(&self) -> Result<(), NmstateError> {
+        for iface in self.kernel_ifaces.values().filter(|i| {
+            i.is_desired()
+                && i.merged.is_up()
+                && i.merged.iface_type() == InterfaceType::Hsr
+        }) {
+            if let Interface::Hsr(hsr_iface) = &iface.merged {
+                if hsr_iface
+                    .hsr
+                    .as_ref()
+                    .map(|c| c.port1.is_empty() || c.port2.is_empty())
+                    != Some(false)
+                {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "HSR interface {} requires both port1 and \
+                            port2 defined",
+                            hsr_iface.base.name
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
     // Infiniband over IP can only be port of active_backup bond as it is a
     // layer 3 interface like tun.
     pub(crate) fn check_infiniband_as_ports(&self) -> Result<(), NmstateError> {
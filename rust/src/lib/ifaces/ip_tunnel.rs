@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel IP tunnel interface. This struct holds these interface types:
+///  * [InterfaceType::Gre] -- GRE over IPv4 or IPv6(`ip6gre`).
+///  * [InterfaceType::GreTap] -- Ethernet over GRE over IPv4 or
+///    IPv6(`ip6gretap`).
+///  * [InterfaceType::IpIp] -- IPv4 over IPv4.
+///  * [InterfaceType::Sit] -- IPv6 over IPv4.
+///  * [InterfaceType::Ip6Tnl] -- IPv4 or IPv6 over IPv6.
+///
+/// The IP family of GRE and GRETAP tunnel is determined by the `local` and
+/// `remote` address.
+///
+/// The example yaml output of [crate::NetworkState] with a GRE interface
+/// would be:
+/// ```yml
+/// interfaces:
+/// - name: gre1
+///   type: gre
+///   state: up
+///   mtu: 1476
+///   ip-tunnel:
+///     base-iface: eth1
+///     local: 192.0.2.1
+///     remote: 198.51.100.1
+///     key: 100
+///     ttl: 64
+///     tos: 0
+///     pmtudisc: true
+/// ```
+pub struct IpTunnelInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "ip-tunnel")]
+    pub ip_tunnel: Option<IpTunnelConfig>,
+}
+
+impl Default for IpTunnelInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Gre,
+                ..Default::default()
+            },
+            ip_tunnel: None,
+        }
+    }
+}
+
+impl IpTunnelInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.ip_tunnel
+            .as_ref()
+            .and_then(|cfg| cfg.base_iface.as_deref())
+            .filter(|p| !p.is_empty())
+    }
+
+    // Only GRETAP tunnel is layer 2 interface.
+    pub(crate) fn is_layer2(&self) -> bool {
+        self.base.iface_type == InterfaceType::GreTap
+    }
+
+    // The IPv6 tunnels use different kernel interface kinds.
+    pub(crate) fn is_ipv6(&self) -> bool {
+        self.base.iface_type == InterfaceType::Ip6Tnl
+            || self
+                .ip_tunnel
+                .as_ref()
+                .and_then(|c| c.local.or(c.remote))
+                .map(|i| i.is_ipv6())
+                == Some(true)
+    }
+
+    pub(crate) fn sanitize(
+        &mut self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if !self.is_layer2() {
+            if is_desired {
+                if let Some(mac) = self.base.mac_address.as_ref() {
+                    log::warn!(
+                        "Ignoring MAC address {mac} of {} interface {} \
+                        as it is a layer 3(IP) interface",
+                        self.base.iface_type,
+                        self.base.name.as_str()
+                    );
+                }
+            }
+            self.base.mac_address = None;
+            if self.base.accept_all_mac_addresses == Some(false) {
+                self.base.accept_all_mac_addresses = None;
+            }
+        }
+        if is_desired {
+            if let Some(conf) = self.ip_tunnel.as_ref() {
+                self.validate(conf)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self, conf: &IpTunnelConfig) -> Result<(), NmstateError> {
+        let iface_name = self.base.name.as_str();
+        let iface_type = &self.base.iface_type;
+        if let (Some(local), Some(remote)) = (conf.local, conf.remote) {
+            if local.is_ipv6() != remote.is_ipv6() {
+                return Err(invalid_arg(format!(
+                    "The local address {local} and remote address {remote} \
+                    of {iface_type} interface {iface_name} are not in the \
+                    same IP family"
+                )));
+            }
+        }
+        let is_ipv6 = self.is_ipv6();
+        let expect_ipv6 = match iface_type {
+            InterfaceType::IpIp | InterfaceType::Sit => Some(false),
+            InterfaceType::Ip6Tnl => Some(true),
+            _ => None,
+        };
+        if let Some(expect_ipv6) = expect_ipv6 {
+            for addr in [conf.local, conf.remote].iter().flatten() {
+                if addr.is_ipv6() != expect_ipv6 {
+                    return Err(invalid_arg(format!(
+                        "{iface_type} interface {iface_name} only support \
+                        IPv{} local and remote address, but got {addr}",
+                        if expect_ipv6 { 6 } else { 4 }
+                    )));
+                }
+            }
+        }
+        if conf.key.is_some()
+            && !matches!(iface_type, InterfaceType::Gre | InterfaceType::GreTap)
+        {
+            return Err(invalid_arg(format!(
+                "The key is only supported by GRE and GRETAP tunnel, \
+                but {iface_type} interface {iface_name} has key defined"
+            )));
+        }
+        if is_ipv6 {
+            if conf.tos.is_some() {
+                return Err(invalid_arg(format!(
+                    "The tos is not supported by IPv6 tunnel \
+                    {iface_type} interface {iface_name}"
+                )));
+            }
+            if conf.pmtudisc.is_some() {
+                return Err(invalid_arg(format!(
+                    "The pmtudisc is not supported by IPv6 tunnel \
+                    {iface_type} interface {iface_name}"
+                )));
+            }
+        } else if conf.pmtudisc == Some(false)
+            && conf.ttl.unwrap_or_default() != 0
+        {
+            return Err(invalid_arg(format!(
+                "The pmtudisc: false requires ttl to be 0(inherit), but \
+                {iface_type} interface {iface_name} has ttl {} defined",
+                conf.ttl.unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn invalid_arg(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct IpTunnelConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The interface to bind the tunnel to.
+    /// Deserialize and serialize from/to `base-iface`.
+    pub base_iface: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Local endpoint address of the tunnel.
+    pub local: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Remote endpoint address of the tunnel.
+    pub remote: Option<IpAddr>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// GRE key used for both incoming and outgoing packets.
+    /// Only supported by GRE and GRETAP tunnel.
+    pub key: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// TTL(hop limit for IPv6 tunnel) of tunneled packets, 0 means
+    /// inheriting from the inner packet.
+    pub ttl: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// Type of Service of tunneled packets, 0 means inheriting from the
+    /// inner packet. Not supported by IPv6 tunnel.
+    pub tos: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Whether to enable Path MTU Discovery on this tunnel.
+    /// Disabling it requires `ttl` to be 0. Not supported by IPv6 tunnel.
+    pub pmtudisc: Option<bool>,
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel IP VLAN interface. The example yaml output of
+/// [crate::NetworkState] with a ip vlan interface would be:
+/// ```yaml
+/// ---
+/// interfaces:
+///   - name: ipvlan0
+///     type: ipvlan
+///     state: up
+///     ipvlan:
+///       base-iface: eth1
+///       mode: l3
+///       private: true
+/// ```
+/// When neither `private` nor `vepa` is enabled, the IP VLAN interface is
+/// working in bridge mode.
+pub struct IpVlanInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipvlan: Option<IpVlanConfig>,
+}
+
+impl Default for IpVlanInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::IpVlan,
+                ..Default::default()
+            },
+            ipvlan: None,
+        }
+    }
+}
+
+impl IpVlanInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn sanitize(
+        &self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let (true, Some(conf)) = (is_desired, self.ipvlan.as_ref()) {
+            if conf.private == Some(true) && conf.vepa == Some(true) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The private and vepa flags of IP VLAN interface {} \
+                        cannot be enabled at the same time",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.ipvlan.as_ref().map(|cfg| cfg.base_iface.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct IpVlanConfig {
+    pub base_iface: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<IpVlanMode>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Do not allow communication between IP VLAN interfaces sharing the
+    /// same base interface.
+    pub private: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Send all traffic to external switch even it is targeting other IP
+    /// VLAN interface sharing the same base interface.
+    pub vepa: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum IpVlanMode {
+    /// Deserialize and serialize from/to `l2`.
+    L2,
+    /// Deserialize and serialize from/to `l3`.
+    L3,
+    /// Deserialize and serialize from/to `l3s`.
+    L3s,
+}
+
+impl Default for IpVlanMode {
+    fn default() -> Self {
+        Self::L3
+    }
+}
@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+// NetworkManager requires 128 bits CAK and up to 256 bits CKN in hex string.
+const MKA_CAK_LEN: usize = 32;
+const MKA_CKN_MAX_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel MACsec(IEEE 802.1AE) interface. The example yaml output of
+/// [crate::NetworkState] with a MACsec interface would be:
+/// ```yml
+/// ---
+/// interfaces:
+///   - name: macsec0
+///     type: macsec
+///     state: up
+///     macsec:
+///       base-iface: eth1
+///       port: 1
+///       encrypt: true
+///       validation: strict
+///       send-sci: true
+///       mka-cak: <_password_hid_by_nmstate>
+///       mka-ckn: <_password_hid_by_nmstate>
+/// ```
+///
+/// The MKA(MACsec Key Agreement) is done by wpa_supplicant, hence the
+/// `mka-cak` and `mka-ckn` are only supported by NetworkManager backend.
+pub struct MacsecInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macsec: Option<MacsecConfig>,
+}
+
+impl Default for MacsecInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Macsec,
+                ..Default::default()
+            },
+            macsec: None,
+        }
+    }
+}
+
+impl MacsecInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.macsec
+            .as_ref()
+            .map(|cfg| cfg.base_iface.as_str())
+            .filter(|p| !p.is_empty())
+    }
+
+    pub(crate) fn sanitize(
+        &self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let (true, Some(conf)) = (is_desired, self.macsec.as_ref()) {
+            conf.validate(self.base.name.as_str())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if let Some(conf) = self.macsec.as_mut() {
+            conf.hide_secrets();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct MacsecConfig {
+    /// The interface MACsec is running on.
+    /// Deserialize and serialize from/to `base-iface`.
+    pub base_iface: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// Port number used in Secure Channel Identifier(SCI), range 1 to 65534.
+    pub port: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Whether to encrypt the traffic. When disabled, the traffic is only
+    /// authenticated.
+    pub encrypt: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Validation mode of incoming frames.
+    pub validation: Option<MacsecValidation>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Whether to include the SCI in every packet.
+    /// Deserialize and serialize from/to `send-sci`.
+    pub send_sci: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// MKA pre-shared Connectivity Association Key(CAK) in 32 hex digits.
+    /// Replaced to `<_password_hid_by_nmstate>` when querying.
+    /// Deserialize and serialize from/to `mka-cak`.
+    pub mka_cak: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// MKA pre-shared Connectivity Association Key Name(CKN) in up to 64
+    /// hex digits.
+    /// Replaced to `<_password_hid_by_nmstate>` when querying.
+    /// Deserialize and serialize from/to `mka-ckn`.
+    pub mka_ckn: Option<String>,
+}
+
+impl MacsecConfig {
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        if self.base_iface.is_empty() {
+            return Err(invalid_arg(format!(
+                "MACsec interface {iface_name} has no base-iface defined"
+            )));
+        }
+        if let Some(port) = self.port {
+            if port == 0 || port == u16::MAX {
+                return Err(invalid_arg(format!(
+                    "Invalid port {port} of MACsec interface {iface_name}, \
+                    should be in the range of 1 to 65534"
+                )));
+            }
+        }
+        match (self.mka_cak.as_deref(), self.mka_ckn.as_deref()) {
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(invalid_arg(format!(
+                    "The mka-cak and mka-ckn of MACsec interface \
+                    {iface_name} should be defined together"
+                )));
+            }
+            (Some(cak), Some(ckn)) => {
+                if cak != NetworkState::PASSWORD_HID_BY_NMSTATE
+                    && !(cak.len() == MKA_CAK_LEN && is_hex_str(cak))
+                {
+                    return Err(invalid_arg(format!(
+                        "The mka-cak of MACsec interface {iface_name} \
+                        should be {MKA_CAK_LEN} hex digits"
+                    )));
+                }
+                if ckn != NetworkState::PASSWORD_HID_BY_NMSTATE
+                    && !(ckn.len() <= MKA_CKN_MAX_LEN
+                        && ckn.len() % 2 == 0
+                        && is_hex_str(ckn))
+                {
+                    return Err(invalid_arg(format!(
+                        "The mka-ckn of MACsec interface {iface_name} \
+                        should be even number of hex digits not exceeding \
+                        {MKA_CKN_MAX_LEN}"
+                    )));
+                }
+            }
+            (None, None) => (),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if self.mka_cak.is_some() {
+            self.mka_cak =
+                Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+        }
+        if self.mka_ckn.is_some() {
+            self.mka_ckn =
+                Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+        }
+    }
+}
+
+fn is_hex_str(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn invalid_arg(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum MacsecValidation {
+    /// Accept all incoming frames without validation.
+    /// Deserialize and serialize from/to `disabled`.
+    Disabled,
+    /// Validate incoming frames but accept invalid ones.
+    /// Deserialize and serialize from/to `check`.
+    Check,
+    /// Drop incoming frames failed the validation.
+    /// Deserialize and serialize from/to `strict`.
+    Strict,
+}
+
+impl From<MacsecValidation> for u8 {
+    fn from(v: MacsecValidation) -> u8 {
+        match v {
+            MacsecValidation::Disabled => 0,
+            MacsecValidation::Check => 1,
+            MacsecValidation::Strict => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for MacsecValidation {
+    type Error = ();
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(Self::Disabled),
+            1 => Ok(Self::Check),
+            2 => Ok(Self::Strict),
+            _ => Err(()),
+        }
+    }
+}
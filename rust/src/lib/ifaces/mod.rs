@@ -4,6 +4,8 @@ mod bridge_vlan;
 mod dummy;
 mod ethernet;
 mod ethtool;
+mod geneve;
+mod hsr;
 pub(crate) mod inter_ifaces;
 mod loopback;
 mod vrf;
@@ -11,12 +13,18 @@ mod vxlan;
 // The pub(crate) is only for unit test
 mod infiniband;
 pub(crate) mod inter_ifaces_controller;
+mod ip_tunnel;
+mod ip_vlan;
 mod linux_bridge;
 mod mac_vlan;
 mod mac_vtap;
+mod macsec;
 mod ovs;
 mod sriov;
+mod tun;
 mod vlan;
+mod wireguard;
+mod xfrm;
 
 pub use base::*;
 pub use bond::{
@@ -36,9 +44,13 @@ pub use ethtool::{
     EthtoolCoalesceConfig, EthtoolConfig, EthtoolFeatureConfig,
     EthtoolPauseConfig, EthtoolRingConfig,
 };
+pub use geneve::{GeneveConfig, GeneveInterface};
+pub use hsr::{HsrConfig, HsrInterface, HsrProtocol};
 pub use infiniband::{InfiniBandConfig, InfiniBandInterface, InfiniBandMode};
 pub(crate) use inter_ifaces::MergedInterfaces;
 pub use inter_ifaces::*;
+pub use ip_tunnel::{IpTunnelConfig, IpTunnelInterface};
+pub use ip_vlan::{IpVlanConfig, IpVlanInterface, IpVlanMode};
 pub use linux_bridge::{
     LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeMulticastRouterType,
     LinuxBridgeOptions, LinuxBridgePortConfig, LinuxBridgeStpOptions,
@@ -46,12 +58,16 @@ pub use linux_bridge::{
 pub use loopback::LoopbackInterface;
 pub use mac_vlan::{MacVlanConfig, MacVlanInterface, MacVlanMode};
 pub use mac_vtap::{MacVtapConfig, MacVtapInterface, MacVtapMode};
+pub use macsec::{MacsecConfig, MacsecInterface, MacsecValidation};
 pub use ovs::{
     OvsBridgeBondConfig, OvsBridgeBondMode, OvsBridgeBondPortConfig,
     OvsBridgeConfig, OvsBridgeInterface, OvsBridgeOptions, OvsBridgePortConfig,
     OvsDpdkConfig, OvsInterface, OvsPatchConfig,
 };
 pub use sriov::{SrIovConfig, SrIovVfConfig};
-pub use vlan::{VlanConfig, VlanInterface, VlanProtocol};
+pub use tun::{TunConfig, TunInterface, TunMode};
+pub use vlan::{VlanConfig, VlanInterface, VlanProtocol, VlanQosMapping};
 pub use vrf::{VrfConfig, VrfInterface};
 pub use vxlan::{VxlanConfig, VxlanInterface};
+pub use wireguard::{WireGuardConfig, WireGuardInterface, WireGuardPeerConfig};
+pub use xfrm::{XfrmConfig, XfrmInterface};
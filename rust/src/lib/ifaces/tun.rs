@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, InterfaceType};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel TUN/TAP interface. The example yaml output of
+/// [crate::NetworkState] with a TAP interface would be:
+/// ```yaml
+/// ---
+/// interfaces:
+///   - name: tap0
+///     type: tun
+///     state: up
+///     tun:
+///       mode: tap
+///       owner: 107
+///       group: 107
+///       multi-queue: true
+///       vnet-hdr: true
+///       persist: true
+/// ```
+///
+/// The kernel does not support creating TUN/TAP interface via netlink, hence
+/// creating it is only supported by NetworkManager backend which always
+/// create persistent TUN/TAP interface.
+pub struct TunInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tun: Option<TunConfig>,
+}
+
+impl Default for TunInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Tun,
+                ..Default::default()
+            },
+            tun: None,
+        }
+    }
+}
+
+impl TunInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct TunConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<TunMode>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// The user ID allowed to use this interface.
+    pub owner: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// The group ID allowed to use this interface.
+    pub group: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Whether to allow multiple file descriptors to attach to this
+    /// interface for parallel packet processing.
+    /// Deserialize and serialize from/to `multi-queue`.
+    pub multi_queue: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Whether to prepend virtio-net header to packets.
+    /// Deserialize and serialize from/to `vnet-hdr`.
+    pub vnet_hdr: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Whether the interface persists after its owner process closed it.
+    pub persist: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum TunMode {
+    /// Layer 3 TUN interface.
+    /// Deserialize and serialize from/to `tun`.
+    Tun,
+    /// Layer 2 TAP interface.
+    /// Deserialize and serialize from/to `tap`.
+    Tap,
+}
+
+impl Default for TunMode {
+    fn default() -> Self {
+        Self::Tun
+    }
+}
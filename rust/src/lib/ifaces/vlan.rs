@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, InterfaceType};
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+const VLAN_PRIORITY_MAX: u32 = 7;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -23,6 +25,16 @@ use crate::{BaseInterface, InterfaceType};
 ///   vlan:
 ///     base-iface: eth1
 ///     id: 101
+///     reorder-headers: true
+///     gvrp: false
+///     mvrp: false
+///     loose-binding: false
+///     ingress-qos-map:
+///     - from: 5
+///       to: 5
+///     egress-qos-map:
+///     - from: 5
+///       to: 5
 /// ```
 pub struct VlanInterface {
     #[serde(flatten)]
@@ -51,6 +63,27 @@ impl VlanInterface {
     pub(crate) fn parent(&self) -> Option<&str> {
         self.vlan.as_ref().map(|cfg| cfg.base_iface.as_str())
     }
+
+    pub(crate) fn sanitize(
+        &mut self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let Some(conf) = self.vlan.as_mut() {
+            if is_desired {
+                conf.validate(self.base.name.as_str())?;
+            }
+            // Kernel does not report mapping to 0 as it is the default.
+            for qos_map in
+                [conf.ingress_qos_map.as_mut(), conf.egress_qos_map.as_mut()]
+                    .iter_mut()
+                    .flatten()
+            {
+                qos_map.retain(|m| m.to != 0);
+                qos_map.sort_unstable();
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -63,6 +96,122 @@ pub struct VlanConfig {
     /// Could be `802.1q` or `802.1ad`. Default to `802.1q` if not defined.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub protocol: Option<VlanProtocol>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Reorder the output packet headers to hide the VLAN tag.
+    /// Deserialize and serialize from/to `reorder-headers`.
+    pub reorder_headers: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Register the VLAN using GARP VLAN Registration Protocol.
+    pub gvrp: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Register the VLAN using Multiple VLAN Registration Protocol.
+    pub mvrp: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Do not follow the operational state of base interface.
+    /// Deserialize and serialize from/to `loose-binding`.
+    pub loose_binding: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Mapping from the 802.1p priority(0-7) of incoming VLAN header to the
+    /// Linux packet priority. Mapping to 0 is ignored as it is the default.
+    /// Set to empty list to remove all existing mappings.
+    /// Deserialize and serialize from/to `ingress-qos-map`.
+    pub ingress_qos_map: Option<Vec<VlanQosMapping>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Mapping from the Linux packet priority to the 802.1p priority(0-7) of
+    /// outgoing VLAN header. Mapping to 0 is ignored as it is the default.
+    /// Set to empty list to remove all existing mappings.
+    /// Deserialize and serialize from/to `egress-qos-map`.
+    pub egress_qos_map: Option<Vec<VlanQosMapping>>,
+}
+
+impl VlanConfig {
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        for (map_name, qos_map, is_ingress) in [
+            ("ingress-qos-map", self.ingress_qos_map.as_deref(), true),
+            ("egress-qos-map", self.egress_qos_map.as_deref(), false),
+        ] {
+            let qos_map = qos_map.unwrap_or_default();
+            for (i, mapping) in qos_map.iter().enumerate() {
+                let vlan_prio =
+                    if is_ingress { mapping.from } else { mapping.to };
+                if vlan_prio > VLAN_PRIORITY_MAX {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Invalid {map_name} {mapping} of VLAN interface \
+                            {iface_name}: the 802.1p priority should be in \
+                            the range of 0 to {VLAN_PRIORITY_MAX}"
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+                if qos_map[..i].iter().any(|m| m.from == mapping.from) {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Duplicate priority {} in {map_name} of VLAN \
+                            interface {iface_name}",
+                            mapping.from
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Default,
+)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct VlanQosMapping {
+    #[serde(deserialize_with = "crate::deserializer::u32_or_string")]
+    pub from: u32,
+    #[serde(deserialize_with = "crate::deserializer::u32_or_string")]
+    pub to: u32,
+}
+
+impl VlanQosMapping {
+    pub fn new(from: u32, to: u32) -> Self {
+        Self { from, to }
+    }
+}
+
+impl std::fmt::Display for VlanQosMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.from, self.to)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
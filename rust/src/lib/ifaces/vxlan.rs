@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::net::IpAddr;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, InterfaceType};
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -22,6 +24,12 @@ use crate::{BaseInterface, InterfaceType};
 ///     id: 102
 ///     remote: 239.1.1.1
 ///     destination-port: 1235
+///     ttl: 64
+///     source-port-min: 49152
+///     source-port-max: 65535
+///     fdb-remotes:
+///     - 192.0.2.2
+///     - 192.0.2.3
 /// ```
 pub struct VxlanInterface {
     #[serde(flatten)]
@@ -56,6 +64,22 @@ impl VxlanInterface {
             }
         })
     }
+
+    pub(crate) fn sanitize(
+        &mut self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let Some(conf) = self.vxlan.as_mut() {
+            if let Some(remotes) = conf.fdb_remotes.as_mut() {
+                remotes.sort_unstable();
+                remotes.dedup();
+            }
+            if is_desired {
+                conf.validate(self.base.name.as_str())?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -69,9 +93,9 @@ pub struct VxlanConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub learning: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub local: Option<std::net::IpAddr>,
+    pub local: Option<IpAddr>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub remote: Option<std::net::IpAddr>,
+    pub remote: Option<IpAddr>,
     #[serde(
         rename = "destination-port",
         default,
@@ -79,4 +103,192 @@ pub struct VxlanConfig {
     )]
     /// Deserialize and serialize from/to `destination-port`.
     pub dst_port: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// TTL of outgoing packets, 0 means using the kernel default.
+    pub ttl: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// Type of Service of outgoing packets.
+    pub tos: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// The lower bound of UDP source port range. Should be used along with
+    /// `source_port_max`.
+    /// Deserialize and serialize from/to `source-port-min`.
+    pub source_port_min: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// The upper bound of UDP source port range. Should be used along with
+    /// `source_port_min`.
+    /// Deserialize and serialize from/to `source-port-max`.
+    pub source_port_max: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Lifetime in seconds of FDB entries learnt by the kernel.
+    pub ageing: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Maximum number of FDB entries, 0 means unlimited.
+    pub limit: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Enable ARP and NDP proxy.
+    pub proxy: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Enable route short circuiting.
+    pub rsc: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Generate netlink notifications on link layer address miss.
+    /// Deserialize and serialize from/to `l2-miss`.
+    pub l2_miss: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Generate netlink notifications on IP address miss.
+    /// Deserialize and serialize from/to `l3-miss`.
+    pub l3_miss: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Calculate UDP checksum for IPv4 tunnel.
+    /// Not supported by NetworkManager backend.
+    /// Deserialize and serialize from/to `udp-checksum`.
+    pub udp_checksum: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Skip UDP checksum calculation for transmitted packets over IPv6.
+    /// Not supported by NetworkManager backend.
+    /// Deserialize and serialize from/to `udp6-zero-checksum-tx`.
+    pub udp6_zero_checksum_tx: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Allow incoming UDP packets over IPv6 with zero checksum field.
+    /// Not supported by NetworkManager backend.
+    /// Deserialize and serialize from/to `udp6-zero-checksum-rx`.
+    pub udp6_zero_checksum_rx: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Enable the Group Policy extension(VXLAN-GBP).
+    /// Not supported by NetworkManager backend.
+    pub gbp: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Collect metadata mode where the tunnel parameters are provided by
+    /// the routing or OVS/TC flows instead of this interface.
+    /// Not supported by NetworkManager backend.
+    pub external: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Unicast remote tunnel endpoints for head-end replication of broadcast,
+    /// unknown unicast and multicast traffic. Stored as all-zeros FDB entries
+    /// of this interface.
+    /// Only supported by kernel mode. NetworkManager has no setting for these
+    /// entries, hence changing them with NetworkManager backend or
+    /// `NetworkState::gen_conf()` fails with NotSupportedError. Entries
+    /// applied in kernel mode are not persistent and lost after reboot.
+    /// Set to empty list to remove all existing FDB remotes.
+    /// Deserialize and serialize from/to `fdb-remotes`.
+    pub fdb_remotes: Option<Vec<IpAddr>>,
+}
+
+impl VxlanConfig {
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        match (self.source_port_min, self.source_port_max) {
+            (Some(min), Some(max)) => {
+                if min > max {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "The source-port-min {min} of VXLAN interface \
+                            {iface_name} is bigger than source-port-max {max}"
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+            (None, None) => (),
+            _ => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The source-port-min and source-port-max of VXLAN \
+                        interface {iface_name} should be defined together"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        for remote in self.fdb_remotes.as_deref().unwrap_or_default() {
+            if remote.is_unspecified() || remote.is_multicast() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The FDB remote {remote} of VXLAN interface \
+                        {iface_name} should be unicast address"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if Some(remote) == self.remote.as_ref() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The FDB remote {remote} of VXLAN interface \
+                        {iface_name} is already used as its remote"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
 }
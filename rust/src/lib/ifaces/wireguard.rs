@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// [WireGuard](https://www.wireguard.com/) VPN interface. The example yaml
+/// output of a [crate::NetworkState] with a WireGuard interface would be:
+/// ```yml
+/// ---
+/// interfaces:
+///   - name: wg0
+///     type: wireguard
+///     state: up
+///     ipv4:
+///       enabled: true
+///       address:
+///       - ip: 192.0.2.1
+///         prefix-length: 24
+///     wireguard:
+///       private-key: <_password_hid_by_nmstate>
+///       listen-port: 51820
+///       fwmark: 100
+///       peers:
+///       - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+///         preshared-key: <_password_hid_by_nmstate>
+///         endpoint: 198.51.100.1:51820
+///         allowed-ips:
+///         - 192.0.2.0/24
+///         - 2001:db8::/64
+///         persistent-keepalive: 25
+/// ```
+///
+/// The WireGuard keys and peers are not exposed by the Linux kernel via
+/// rtnetlink, hence they are only shown when interface is managed by
+/// NetworkManager.
+pub struct WireGuardInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wireguard: Option<WireGuardConfig>,
+}
+
+impl Default for WireGuardInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::WireGuard,
+                ..Default::default()
+            },
+            wireguard: None,
+        }
+    }
+}
+
+impl WireGuardInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn sanitize(
+        &mut self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        // WireGuard is a layer 3 interface without MAC address.
+        if is_desired {
+            if let Some(mac) = self.base.mac_address.as_ref() {
+                log::warn!(
+                    "Ignoring MAC address {mac} of WireGuard interface {} \
+                    as it is a layer 3(IP) interface",
+                    self.base.name.as_str()
+                );
+            }
+        }
+        self.base.mac_address = None;
+        if self.base.accept_all_mac_addresses == Some(false) {
+            self.base.accept_all_mac_addresses = None;
+        }
+        if let Some(conf) = self.wireguard.as_mut() {
+            conf.sanitize(self.base.name.as_str(), is_desired)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if let Some(conf) = self.wireguard.as_mut() {
+            conf.hide_secrets();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct WireGuardConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Base64 encoded private key of this interface.
+    /// Replaced to `<_password_hid_by_nmstate>` when querying.
+    /// Deserialize and serialize from/to `private-key`.
+    pub private_key: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// UDP port to listen on, 0 or undefined means random port.
+    /// Deserialize and serialize from/to `listen-port`.
+    pub listen_port: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Firewall mark for outgoing WireGuard packets, 0 means disabled.
+    pub fwmark: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Peer list. When defined, it will override current peer list.
+    pub peers: Option<Vec<WireGuardPeerConfig>>,
+}
+
+impl WireGuardConfig {
+    fn sanitize(
+        &mut self,
+        iface_name: &str,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let Some(peers) = self.peers.as_mut() {
+            if is_desired {
+                let mut pub_keys: HashSet<&str> = HashSet::new();
+                for peer in peers.iter() {
+                    if peer.public_key.is_empty() {
+                        let e = NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "WireGuard interface {iface_name} has peer \
+                                without public-key defined"
+                            ),
+                        );
+                        log::error!("{}", e);
+                        return Err(e);
+                    }
+                    if !pub_keys.insert(peer.public_key.as_str()) {
+                        let e = NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "WireGuard interface {iface_name} has \
+                                duplicate peer with public-key {}",
+                                peer.public_key
+                            ),
+                        );
+                        log::error!("{}", e);
+                        return Err(e);
+                    }
+                }
+            }
+            // NetworkManager index peers by public key, sort them to be
+            // consistent.
+            peers.sort_unstable_by(|a, b| a.public_key.cmp(&b.public_key));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if self.private_key.is_some() {
+            self.private_key =
+                Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+        }
+        for peer in self.peers.as_deref_mut().unwrap_or_default() {
+            if peer.preshared_key.is_some() {
+                peer.preshared_key =
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct WireGuardPeerConfig {
+    /// Base64 encoded public key of the peer.
+    /// Deserialize and serialize from/to `public-key`.
+    pub public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Base64 encoded preshared key for additional symmetric encryption.
+    /// Replaced to `<_password_hid_by_nmstate>` when querying.
+    /// Deserialize and serialize from/to `preshared-key`.
+    pub preshared_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Endpoint of the peer in the format of `host:port` or
+    /// `[ipv6_address]:port`.
+    pub endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// IP networks in the format of `address/prefix` allowed to receive from
+    /// and route to this peer.
+    /// Deserialize and serialize from/to `allowed-ips`.
+    pub allowed_ips: Option<Vec<String>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// Interval in seconds of sending keepalive packet to the peer,
+    /// 0 means disabled.
+    /// Deserialize and serialize from/to `persistent-keepalive`.
+    pub persistent_keepalive: Option<u16>,
+}
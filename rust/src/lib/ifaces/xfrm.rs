@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Linux kernel XFRM interface used by route-based IPsec. The example yaml
+/// output of [crate::NetworkState] with a XFRM interface would be:
+/// ```yaml
+/// ---
+/// interfaces:
+///   - name: ipsec1
+///     type: xfrm
+///     state: up
+///     xfrm:
+///       base-iface: eth1
+///       if-id: 1
+/// ```
+/// The IPsec policies and states with the same `if-id` will be bound to this
+/// interface.
+///
+/// NetworkManager has no support of XFRM interface yet, hence XFRM interface
+/// is only supported by kernel mode.
+pub struct XfrmInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xfrm: Option<XfrmConfig>,
+}
+
+impl Default for XfrmInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Xfrm,
+                ..Default::default()
+            },
+            xfrm: None,
+        }
+    }
+}
+
+impl XfrmInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.xfrm
+            .as_ref()
+            .map(|cfg| cfg.base_iface.as_str())
+            .filter(|p| !p.is_empty())
+    }
+
+    pub(crate) fn sanitize(
+        &self,
+        is_desired: bool,
+    ) -> Result<(), NmstateError> {
+        if let (true, Some(conf)) = (is_desired, self.xfrm.as_ref()) {
+            if conf.if_id == 0 {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The if-id of XFRM interface {} cannot be 0",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct XfrmConfig {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    /// The underlying interface. Optional.
+    pub base_iface: String,
+    #[serde(deserialize_with = "crate::deserializer::u32_or_string")]
+    /// The XFRM interface ID matching the `if_id` of IPsec policies and
+    /// states. Cannot be 0.
+    /// Deserialize and serialize from/to `if-id`.
+    pub if_id: u32,
+}
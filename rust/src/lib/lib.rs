@@ -83,6 +83,7 @@
 //! }
 //! ```
 
+mod apply_plan;
 mod deserializer;
 mod dns;
 mod error;
@@ -96,6 +97,9 @@ mod ip;
 mod lldp;
 mod mptcp;
 mod net_state;
+#[cfg(feature = "gen_conf")]
+mod networkd;
+mod nexthop;
 #[cfg(feature = "query_apply")]
 mod nispor;
 mod nm;
@@ -112,9 +116,10 @@ mod serializer;
 mod state;
 mod unit_tests;
 
+pub use crate::apply_plan::{ApplyPlanAction, ApplyPlanActionKind};
 pub(crate) use crate::dns::MergedDnsState;
 pub use crate::dns::{DnsClientState, DnsState};
-pub use crate::error::{ErrorKind, NmstateError};
+pub use crate::error::{ErrorKind, NmstateError, VerificationMismatch};
 pub use crate::hostname::HostNameState;
 pub(crate) use crate::hostname::MergedHostNameState;
 pub use crate::ieee8021x::Ieee8021XConfig;
@@ -130,17 +135,22 @@ pub use crate::ifaces::{
     BridgePortTunkTag, BridgePortVlanConfig, BridgePortVlanMode,
     BridgePortVlanRange, DummyInterface, EthernetConfig, EthernetDuplex,
     EthernetInterface, EthtoolCoalesceConfig, EthtoolConfig,
-    EthtoolFeatureConfig, EthtoolPauseConfig, EthtoolRingConfig,
-    InfiniBandConfig, InfiniBandInterface, InfiniBandMode, Interfaces,
+    EthtoolFeatureConfig, EthtoolPauseConfig, EthtoolRingConfig, GeneveConfig,
+    GeneveInterface, HsrConfig, HsrInterface, HsrProtocol, InfiniBandConfig,
+    InfiniBandInterface, InfiniBandMode, Interfaces, IpTunnelConfig,
+    IpTunnelInterface, IpVlanConfig, IpVlanInterface, IpVlanMode,
     LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeMulticastRouterType,
     LinuxBridgeOptions, LinuxBridgePortConfig, LinuxBridgeStpOptions,
     LoopbackInterface, MacVlanConfig, MacVlanInterface, MacVlanMode,
-    MacVtapConfig, MacVtapInterface, MacVtapMode, OvsBridgeBondConfig,
-    OvsBridgeBondMode, OvsBridgeBondPortConfig, OvsBridgeConfig,
-    OvsBridgeInterface, OvsBridgeOptions, OvsBridgePortConfig, OvsDpdkConfig,
-    OvsInterface, OvsPatchConfig, SrIovConfig, SrIovVfConfig, VethConfig,
-    VlanConfig, VlanInterface, VlanProtocol, VrfConfig, VrfInterface,
-    VxlanConfig, VxlanInterface,
+    MacVtapConfig, MacVtapInterface, MacVtapMode, MacsecConfig,
+    MacsecInterface, MacsecValidation, OvsBridgeBondConfig, OvsBridgeBondMode,
+    OvsBridgeBondPortConfig, OvsBridgeConfig, OvsBridgeInterface,
+    OvsBridgeOptions, OvsBridgePortConfig, OvsDpdkConfig, OvsInterface,
+    OvsPatchConfig, SrIovConfig, SrIovVfConfig, TunConfig, TunInterface,
+    TunMode, VethConfig, VlanConfig, VlanInterface, VlanProtocol,
+    VlanQosMapping, VrfConfig, VrfInterface, VxlanConfig, VxlanInterface,
+    WireGuardConfig, WireGuardInterface, WireGuardPeerConfig, XfrmConfig,
+    XfrmInterface,
 };
 pub use crate::ip::{
     AddressFamily, Dhcpv4ClientId, Dhcpv6Duid, InterfaceIpAddr, InterfaceIpv4,
@@ -155,7 +165,12 @@ pub use crate::lldp::{
 };
 pub use crate::mptcp::{MptcpAddressFlag, MptcpConfig};
 pub(crate) use crate::net_state::MergedNetworkState;
-pub use crate::net_state::NetworkState;
+pub use crate::net_state::{NetworkState, NetworkStateDiff};
+pub(crate) use crate::nexthop::MergedNexthops;
+pub use crate::nexthop::{
+    NexthopEntry, NexthopGroupMember, NexthopResilientConfig, NexthopState,
+    Nexthops,
+};
 pub(crate) use crate::ovs::MergedOvsDbGlobalConfig;
 pub use crate::ovs::{OvsDbGlobalConfig, OvsDbIfaceConfig};
 #[cfg(feature = "query_apply")]
@@ -163,8 +178,11 @@ pub use crate::policy::{
     NetworkCaptureRules, NetworkPolicy, NetworkStateTemplate,
 };
 pub(crate) use crate::route::MergedRoutes;
-pub use crate::route::{RouteEntry, RouteState, Routes};
+pub use crate::route::{
+    RouteEntry, RouteProtocol, RouteScope, RouteState, RouteType, Routes,
+};
 pub(crate) use crate::route_rule::MergedRouteRules;
 pub use crate::route_rule::{
-    RouteRuleAction, RouteRuleEntry, RouteRuleState, RouteRules,
+    RouteRuleAction, RouteRuleEntry, RouteRulePortRange, RouteRuleState,
+    RouteRuleUidRange, RouteRules,
 };
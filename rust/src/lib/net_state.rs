@@ -2,14 +2,19 @@
 
 #[cfg(not(feature = "gen_conf"))]
 use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use serde::{Deserialize, Deserializer, Serialize};
 
+#[cfg(not(feature = "query_apply"))]
+use crate::ApplyPlanAction;
+
 use crate::{
-    DnsState, ErrorKind, HostNameState, Interface, Interfaces, MergedDnsState,
-    MergedHostNameState, MergedInterfaces, MergedOvsDbGlobalConfig,
-    MergedRouteRules, MergedRoutes, NmstateError, OvsDbGlobalConfig,
-    RouteRules, Routes,
+    DnsState, ErrorKind, HostNameState, Interface, InterfaceIpAddr,
+    InterfaceIpv4, InterfaceIpv6, InterfaceState, InterfaceType, Interfaces,
+    LoopbackInterface, MergedDnsState, MergedHostNameState, MergedInterfaces,
+    MergedNexthops, MergedOvsDbGlobalConfig, MergedRouteRules, MergedRoutes,
+    Nexthops, NmstateError, OvsDbGlobalConfig, RouteEntry, RouteRules, Routes,
 };
 
 #[derive(Clone, Debug, Serialize, Default, PartialEq, Eq)]
@@ -86,6 +91,9 @@ pub struct NetworkState {
     #[serde(rename = "route-rules", default)]
     /// Route rule, deserialize and serialize from/to `route-rules`.
     pub rules: RouteRules,
+    #[serde(default, skip_serializing_if = "Nexthops::is_empty")]
+    /// Nexthop objects and nexthop groups
+    pub nexthops: Nexthops,
     #[serde(default)]
     /// Route
     pub routes: Routes,
@@ -121,6 +129,8 @@ pub struct NetworkState {
     pub(crate) running_config_only: bool,
     #[serde(skip)]
     pub(crate) memory_only: bool,
+    #[serde(skip)]
+    pub(crate) resolv_conf_path: Option<String>,
 }
 
 impl<'de> Deserialize<'de> for NetworkState {
@@ -158,6 +168,11 @@ impl<'de> Deserialize<'de> for NetworkState {
             net_state.rules = RouteRules::deserialize(rule_value)
                 .map_err(serde::de::Error::custom)?;
         }
+        if let Some(nexthop_value) = v.remove("nexthops") {
+            net_state.prop_list.push("nexthops");
+            net_state.nexthops = Nexthops::deserialize(nexthop_value)
+                .map_err(serde::de::Error::custom)?;
+        }
         if let Some(ovsdb_value) = v.remove("ovs-db") {
             net_state.prop_list.push("ovsdb");
             net_state.ovsdb = OvsDbGlobalConfig::deserialize(ovsdb_value)
@@ -186,6 +201,7 @@ impl NetworkState {
         self.hostname.is_none()
             && self.dns.is_empty()
             && self.rules.is_empty()
+            && self.nexthops.is_empty()
             && self.routes.is_empty()
             && self.interfaces.is_empty()
             && self.ovsdb.is_none()
@@ -207,18 +223,20 @@ impl NetworkState {
     /// By default(true), When nmstate applying the network state, after applied
     /// the network state, nmstate will verify whether the outcome network
     /// configuration matches with desired, if not, will rollback to state
-    /// before apply(only when [NetworkState::set_kernel_only()] set to false.
+    /// before apply.
     /// When set to false, no verification will be performed.
     pub fn set_verify_change(&mut self, value: bool) -> &mut Self {
         self.no_verify = !value;
         self
     }
 
-    /// Only available when [NetworkState::set_kernel_only()] set to false.
     /// When set to false, the network configuration will not commit
     /// persistently, and will rollback after timeout defined by
     /// [NetworkState::set_timeout()].  Default to true for making the network
     /// state persistent.
+    /// In `kernel only` mode, there is no daemon to rollback on timeout, the
+    /// caller should invoke [NetworkState::kernel_checkpoint_wait()] with
+    /// checkpoint from [NetworkState::kernel_checkpoint()].
     pub fn set_commit(&mut self, value: bool) -> &mut Self {
         self.no_commit = !value;
         self
@@ -233,6 +251,7 @@ impl NetworkState {
     }
 
     /// Whether to include secrets(like password) in [NetworkState::retrieve()]
+    /// and in the keyfile of [NetworkState::apply_plan()].
     /// Default is false.
     pub fn set_include_secrets(&mut self, value: bool) -> &mut Self {
         self.include_secrets = value;
@@ -262,6 +281,14 @@ impl NetworkState {
         self
     }
 
+    /// The resolv.conf file used for querying and applying DNS resolver
+    /// config in `kernel only` mode.
+    /// Default is `/etc/resolv.conf`.
+    pub fn set_resolv_conf_path(&mut self, value: &str) -> &mut Self {
+        self.resolv_conf_path = Some(value.to_string());
+        self
+    }
+
     /// Create empty [NetworkState]
     pub fn new() -> Self {
         Default::default()
@@ -296,6 +323,36 @@ impl NetworkState {
         self.interfaces.push(iface);
     }
 
+    /// Generate a [NetworkStateDiff] containing only the properties of
+    /// desired state(self) which are different from specified current state.
+    /// The interface name, type and state are always included for changed
+    /// interfaces. Applying the serialized diff on top of current state
+    /// should lead to the same result as applying the desired state.
+    pub fn gen_diff(
+        &self,
+        current: &Self,
+    ) -> Result<NetworkStateDiff, NmstateError> {
+        let merged_state = MergedNetworkState::new(
+            self.clone(),
+            current.clone(),
+            false,
+            false,
+        )?;
+        let mut ret = NetworkStateDiff {
+            interfaces: merged_state.interfaces.gen_diff()?,
+            nexthops: merged_state.nexthops.gen_diff()?,
+            routes: merged_state.routes.gen_diff()?,
+            rules: merged_state.rules.gen_diff()?,
+            dns: merged_state.dns.gen_diff(),
+            hostname: merged_state.hostname.gen_diff(),
+            ..Default::default()
+        };
+        if merged_state.is_global_ovsdb_changed() {
+            ret.ovsdb = merged_state.ovsdb.desired;
+        }
+        Ok(ret)
+    }
+
     #[cfg(not(feature = "query_apply"))]
     pub fn retrieve(&mut self) -> Result<&mut Self, NmstateError> {
         Err(NmstateError::new(
@@ -328,6 +385,19 @@ impl NetworkState {
         ))
     }
 
+    #[cfg(not(feature = "gen_conf"))]
+    pub fn gen_conf_for_backend(
+        &self,
+        _backend: &str,
+    ) -> Result<HashMap<String, Vec<(String, String)>>, NmstateError> {
+        Err(NmstateError::new(
+            ErrorKind::DependencyError,
+            "NetworkState::gen_conf_for_backend() need `genconf` feature \
+            enabled"
+                .into(),
+        ))
+    }
+
     #[cfg(not(feature = "query_apply"))]
     pub fn checkpoint_rollback(_checkpoint: &str) -> Result<(), NmstateError> {
         Err(NmstateError::new(
@@ -347,6 +417,87 @@ impl NetworkState {
                 .into(),
         ))
     }
+
+    #[cfg(not(feature = "query_apply"))]
+    pub fn kernel_checkpoint() -> Result<Option<String>, NmstateError> {
+        Err(NmstateError::new(
+            ErrorKind::DependencyError,
+            "NetworkState::kernel_checkpoint() need `query_apply` \
+            feature enabled"
+                .into(),
+        ))
+    }
+
+    #[cfg(not(feature = "query_apply"))]
+    pub fn kernel_checkpoint_wait(
+        _checkpoint: &str,
+    ) -> Result<(), NmstateError> {
+        Err(NmstateError::new(
+            ErrorKind::DependencyError,
+            "NetworkState::kernel_checkpoint_wait() need `query_apply` \
+            feature enabled"
+                .into(),
+        ))
+    }
+
+    #[cfg(not(feature = "query_apply"))]
+    pub fn from_nm_keyfiles(
+        _keyfiles: &[String],
+    ) -> Result<Self, NmstateError> {
+        Err(NmstateError::new(
+            ErrorKind::DependencyError,
+            "NetworkState::from_nm_keyfiles() need `query_apply` \
+            feature enabled"
+                .into(),
+        ))
+    }
+
+    #[cfg(not(feature = "query_apply"))]
+    pub fn apply_plan(&self) -> Result<Vec<ApplyPlanAction>, NmstateError> {
+        Err(NmstateError::new(
+            ErrorKind::DependencyError,
+            "NetworkState::apply_plan() need `query_apply` feature enabled"
+                .into(),
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Default, PartialEq, Eq)]
+#[non_exhaustive]
+/// The difference between two [NetworkState] generated by
+/// [NetworkState::gen_diff()]. Unlike [NetworkState], sections without any
+/// change are omitted when serializing.
+pub struct NetworkStateDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Changed hostname.
+    pub hostname: Option<HostNameState>,
+    #[serde(
+        rename = "dns-resolver",
+        skip_serializing_if = "DnsState::is_empty"
+    )]
+    /// Changed DNS resolver config, serialize to `dns-resolver`.
+    pub dns: DnsState,
+    #[serde(
+        rename = "route-rules",
+        skip_serializing_if = "RouteRules::is_empty"
+    )]
+    /// Changed route rules, serialize to `route-rules`.
+    pub rules: RouteRules,
+    #[serde(skip_serializing_if = "Nexthops::is_empty")]
+    /// Changed nexthop objects and nexthop groups
+    pub nexthops: Nexthops,
+    #[serde(skip_serializing_if = "Routes::is_empty")]
+    /// Changed routes
+    pub routes: Routes,
+    #[serde(skip_serializing_if = "Interfaces::is_empty")]
+    /// Changed network interfaces
+    pub interfaces: Interfaces,
+    #[serde(
+        rename = "ovs-db",
+        skip_serializing_if = "OvsDbGlobalConfig::is_none"
+    )]
+    /// Changed global configurations of OpenvSwitch daemon
+    pub ovsdb: OvsDbGlobalConfig,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -355,19 +506,89 @@ pub(crate) struct MergedNetworkState {
     pub(crate) dns: MergedDnsState,
     pub(crate) interfaces: MergedInterfaces,
     pub(crate) ovsdb: MergedOvsDbGlobalConfig,
+    pub(crate) nexthops: MergedNexthops,
     pub(crate) routes: MergedRoutes,
     pub(crate) rules: MergedRouteRules,
     pub(crate) memory_only: bool,
     pub(crate) prop_list: Vec<&'static str>,
 }
 
+impl NetworkState {
+    // The routes not bound to any interface are stored to loopback interface
+    // by backends. When loopback interface is not in current state (e.g.
+    // generating config), include it in desired state.
+    fn include_loopback_for_iface_less_routes(&mut self, current: &Self) {
+        let has_iface_less_route = self
+            .routes
+            .config
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .any(|r| {
+                !r.is_absent()
+                    && r.next_hop_iface.is_none()
+                    && r.is_iface_less()
+            });
+        if !has_iface_less_route
+            || self
+                .interfaces
+                .get_iface(
+                    RouteEntry::LOOPBACK_IFACE_NAME,
+                    InterfaceType::Unknown,
+                )
+                .is_some()
+            || current
+                .interfaces
+                .get_iface(
+                    RouteEntry::LOOPBACK_IFACE_NAME,
+                    InterfaceType::Unknown,
+                )
+                .is_some()
+        {
+            return;
+        }
+        log::info!(
+            "Including loopback interface to store routes not bound to any \
+            interface"
+        );
+        let mut ipv4 = InterfaceIpv4::new();
+        ipv4.enabled = true;
+        ipv4.addresses = Some(vec![InterfaceIpAddr {
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            prefix_length: 8,
+            ..Default::default()
+        }]);
+        ipv4.prop_list = vec!["enabled", "addresses"];
+        let mut ipv6 = InterfaceIpv6::new();
+        ipv6.enabled = true;
+        ipv6.addresses = Some(vec![InterfaceIpAddr {
+            ip: IpAddr::V6(Ipv6Addr::LOCALHOST),
+            prefix_length: 128,
+            ..Default::default()
+        }]);
+        ipv6.prop_list = vec!["enabled", "addresses"];
+        let mut iface = LoopbackInterface::new();
+        iface.base.name = RouteEntry::LOOPBACK_IFACE_NAME.to_string();
+        iface.base.prop_list =
+            vec!["name", "iface_type", "state", "ipv4", "ipv6"];
+        iface.base.state = InterfaceState::Up;
+        iface.base.ipv4 = Some(ipv4);
+        iface.base.ipv6 = Some(ipv6);
+        self.interfaces.push(Interface::Loopback(iface));
+        if !self.prop_list.contains(&"interfaces") {
+            self.prop_list.push("interfaces");
+        }
+    }
+}
+
 impl MergedNetworkState {
     pub(crate) fn new(
-        desired: NetworkState,
+        mut desired: NetworkState,
         current: NetworkState,
         gen_conf_mode: bool,
         memory_only: bool,
     ) -> Result<Self, NmstateError> {
+        desired.include_loopback_for_iface_less_routes(&current);
         let interfaces = MergedInterfaces::new(
             desired.interfaces,
             current.interfaces,
@@ -376,6 +597,11 @@ impl MergedNetworkState {
         )?;
         let ignored_ifaces = interfaces.ignored_ifaces.as_slice();
 
+        let mut nexthops =
+            MergedNexthops::new(desired.nexthops, current.nexthops)?;
+        nexthops.validate_routes(&desired.routes)?;
+        nexthops.remove_nexthops_to_ignored_ifaces(ignored_ifaces);
+
         let mut routes =
             MergedRoutes::new(desired.routes, current.routes, &interfaces)?;
         routes.remove_routes_to_ignored_ifaces(ignored_ifaces);
@@ -388,6 +614,7 @@ impl MergedNetworkState {
 
         let ret = Self {
             interfaces,
+            nexthops,
             routes,
             rules,
             dns: MergedDnsState::new(desired.dns, current.dns)?,
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// The systemd-networkd `.netdev`, `.network` and `.link` files share the
+// INI-like format of systemd unit file. Both section and key could be
+// repeated, e.g. multiple `[Route]` sections or multiple `Address=` lines.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NetworkdSection {
+    name: &'static str,
+    props: Vec<(&'static str, String)>,
+}
+
+impl NetworkdSection {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            props: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push<T: std::fmt::Display>(
+        &mut self,
+        key: &'static str,
+        value: T,
+    ) {
+        self.props.push((key, value.to_string()));
+    }
+
+    pub(crate) fn push_opt<T: std::fmt::Display>(
+        &mut self,
+        key: &'static str,
+        value: Option<T>,
+    ) {
+        if let Some(v) = value {
+            self.push(key, v);
+        }
+    }
+
+    pub(crate) fn push_bool(&mut self, key: &'static str, value: bool) {
+        self.push(key, if value { "yes" } else { "no" });
+    }
+
+    pub(crate) fn push_opt_bool(
+        &mut self,
+        key: &'static str,
+        value: Option<bool>,
+    ) {
+        if let Some(v) = value {
+            self.push_bool(key, v);
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.props.is_empty()
+    }
+}
+
+impl std::fmt::Display for NetworkdSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+        for (key, value) in self.props.as_slice() {
+            writeln!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct NetworkdConf {
+    sections: Vec<NetworkdSection>,
+}
+
+impl NetworkdConf {
+    // Empty section is discarded
+    pub(crate) fn push(&mut self, section: NetworkdSection) {
+        if !section.is_empty() {
+            self.sections.push(section);
+        }
+    }
+}
+
+impl std::fmt::Display for NetworkdConf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sections: Vec<String> =
+            self.sections.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", sections.join("\n"))
+    }
+}
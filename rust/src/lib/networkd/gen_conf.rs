@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    nm::{
+        dns::store_dns_config_to_iface, route::store_route_config,
+        route_rule::store_route_rule_config,
+    },
+    ErrorKind, Interface, MergedNetworkState, NmstateError,
+};
+
+use super::{
+    link::gen_link,
+    netdev::gen_netdev,
+    network::{gen_network, gen_stacked_parent_network},
+};
+
+// Make sure our files take precedence over the default ones shipped by
+// systemd, e.g. `99-default.link` and `80-container-host0.network`.
+const FILE_NAME_PREFIX: &str = "10-nmstate-";
+
+pub(crate) fn networkd_gen_conf(
+    merged_state: &MergedNetworkState,
+) -> Result<Vec<(String, String)>, NmstateError> {
+    if merged_state
+        .hostname
+        .desired
+        .as_ref()
+        .and_then(|c| c.config.as_ref())
+        .is_some()
+    {
+        log::warn!(
+            "Cannot store hostname configuration to systemd-networkd \
+            configure, please edit /etc/hostname manually"
+        );
+    }
+
+    if let Some(iface) = merged_state
+        .interfaces
+        .user_ifaces
+        .values()
+        .filter_map(|i| i.for_apply.as_ref())
+        .find(|i| !i.is_absent())
+    {
+        return Err(NmstateError::new(
+            ErrorKind::NotSupportedError,
+            format!(
+                "Interface {} with type {} is not supported by \
+                systemd-networkd",
+                iface.name(),
+                iface.iface_type()
+            ),
+        ));
+    }
+
+    if merged_state.nexthops.is_changed()
+        || merged_state.routes.nexthop_route_changed
+    {
+        return Err(NmstateError::new(
+            ErrorKind::NotSupportedError,
+            "Nexthops and routes using nexthop-id are not supported by \
+            systemd-networkd backend yet"
+                .to_string(),
+        ));
+    }
+
+    // Reuse the logic of NetworkManager plugin to find out which interface
+    // should hold the routes, route rules and DNS config.
+    let mut merged_state = merged_state.clone();
+    store_route_config(&mut merged_state)?;
+    store_route_rule_config(&mut merged_state)?;
+    store_dns_config_to_iface(&mut merged_state, &[], &[])?;
+
+    let mut ifaces: Vec<&Interface> = merged_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .filter_map(|i| i.for_apply.as_ref())
+        .filter(|i| !i.is_absent() && !i.is_ignore())
+        .collect();
+    ifaces.sort_unstable_by_key(|i| i.name());
+
+    let mut ret = Vec::new();
+    for iface in ifaces {
+        let iface_name = iface.name();
+        if let Some(netdev) = gen_netdev(iface, &merged_state)? {
+            ret.push((
+                format!("{FILE_NAME_PREFIX}{iface_name}.netdev"),
+                netdev.to_string(),
+            ));
+        }
+        if let Some(link) = gen_link(iface)? {
+            ret.push((
+                format!("{FILE_NAME_PREFIX}{iface_name}.link"),
+                link.to_string(),
+            ));
+        }
+        ret.push((
+            format!("{FILE_NAME_PREFIX}{iface_name}.network"),
+            gen_network(iface, &merged_state)?.to_string(),
+        ));
+    }
+
+    // systemd-networkd creates VLAN and VXLAN when their parent is
+    // configured, hence parent not mentioned in desired state still need a
+    // `.network` file.
+    let mut parents: Vec<&str> = merged_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .filter_map(|i| i.for_apply.as_ref())
+        .filter(|i| !i.is_absent())
+        .filter_map(|i| i.parent())
+        .filter(|p| {
+            !p.is_empty()
+                && !merged_state.interfaces.kernel_ifaces.contains_key(*p)
+        })
+        .collect();
+    parents.sort_unstable();
+    parents.dedup();
+    for parent in parents {
+        ret.push((
+            format!("{FILE_NAME_PREFIX}{parent}.network"),
+            gen_stacked_parent_network(parent, &merged_state).to_string(),
+        ));
+    }
+
+    Ok(ret)
+}
+
+pub(crate) fn not_supported(iface_name: &str, prop: &str) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::NotSupportedError,
+        format!(
+            "The {prop} property of interface {iface_name} is not \
+            supported by systemd-networkd"
+        ),
+    );
+    log::error!("{}", e);
+    e
+}
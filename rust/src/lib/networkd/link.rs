@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{EthernetDuplex, Interface, NmstateError};
+
+use super::{
+    conf::{NetworkdConf, NetworkdSection},
+    gen_conf::not_supported,
+};
+
+// The `.link` file is applied by udev when device shows up, we only use it
+// for the physical layer settings of ethernet.
+pub(crate) fn gen_link(
+    iface: &Interface,
+) -> Result<Option<NetworkdConf>, NmstateError> {
+    let eth_conf = match iface {
+        Interface::Ethernet(eth_iface) if eth_iface.veth.is_none() => {
+            match eth_iface.ethernet.as_ref() {
+                Some(c) => c,
+                None => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+    if eth_conf.sr_iov.is_some() {
+        return Err(not_supported(iface.name(), "ethernet.sr-iov"));
+    }
+
+    let mut link_section = NetworkdSection::new("Link");
+    link_section.push_opt_bool("AutoNegotiation", eth_conf.auto_neg);
+    link_section
+        .push_opt("BitsPerSecond", eth_conf.speed.map(|s| format!("{s}M")));
+    link_section.push_opt(
+        "Duplex",
+        eth_conf.duplex.as_ref().map(|d| match d {
+            EthernetDuplex::Full => "full",
+            EthernetDuplex::Half => "half",
+        }),
+    );
+    if link_section.is_empty() {
+        return Ok(None);
+    }
+
+    let mut conf = NetworkdConf::default();
+    let mut match_section = NetworkdSection::new("Match");
+    match_section.push("OriginalName", iface.name());
+    conf.push(match_section);
+    conf.push(link_section);
+    Ok(Some(conf))
+}
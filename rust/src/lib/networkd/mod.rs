@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: Apache-2.0
+
+mod conf;
+mod gen_conf;
+mod link;
+mod netdev;
+mod network;
+#[cfg(test)]
+mod unit_tests;
+
+pub(crate) use gen_conf::networkd_gen_conf;
@@ -0,0 +1,494 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    BondAllPortsActive, BondArpValidate, BondInterface, ErrorKind,
+    GeneveInterface, Interface, InterfaceType, IpTunnelInterface,
+    LinuxBridgeInterface, MergedNetworkState, NetworkState, NmstateError,
+    VlanInterface, VlanProtocol, VlanQosMapping, VrfInterface, VxlanInterface,
+    WireGuardInterface,
+};
+
+use super::{
+    conf::{NetworkdConf, NetworkdSection},
+    gen_conf::not_supported,
+};
+
+// Return None for interface not created by systemd-networkd.
+pub(crate) fn gen_netdev(
+    iface: &Interface,
+    merged_state: &MergedNetworkState,
+) -> Result<Option<NetworkdConf>, NmstateError> {
+    let mut netdev_section = NetworkdSection::new("NetDev");
+    netdev_section.push("Name", iface.name());
+    let mut extra_sections: Vec<NetworkdSection> = Vec::new();
+
+    let kind_section = match iface {
+        Interface::Ethernet(eth_iface) => {
+            if let Some(veth_conf) = eth_iface.veth.as_ref() {
+                if is_created_by_veth_peer(
+                    iface.name(),
+                    veth_conf.peer.as_str(),
+                    merged_state,
+                ) {
+                    return Ok(None);
+                }
+                netdev_section.push("Kind", "veth");
+                let mut section = NetworkdSection::new("Peer");
+                section.push("Name", veth_conf.peer.as_str());
+                section
+            } else {
+                return Ok(None);
+            }
+        }
+        // Loopback interface always exists in kernel
+        Interface::Loopback(_) => return Ok(None),
+        Interface::Dummy(_) => {
+            netdev_section.push("Kind", "dummy");
+            NetworkdSection::new("Dummy")
+        }
+        Interface::Bond(bond_iface) => {
+            netdev_section.push("Kind", "bond");
+            gen_bond_section(bond_iface)?
+        }
+        Interface::LinuxBridge(br_iface) => {
+            netdev_section.push("Kind", "bridge");
+            gen_bridge_section(br_iface)?
+        }
+        Interface::Vlan(vlan_iface) => {
+            netdev_section.push("Kind", "vlan");
+            gen_vlan_section(vlan_iface)?
+        }
+        Interface::Vxlan(vxlan_iface) => {
+            netdev_section.push("Kind", "vxlan");
+            gen_vxlan_section(vxlan_iface)?
+        }
+        Interface::IpTunnel(tunnel_iface) => {
+            netdev_section.push("Kind", ip_tunnel_kind(tunnel_iface));
+            gen_ip_tunnel_section(tunnel_iface)?
+        }
+        Interface::Geneve(geneve_iface) => {
+            netdev_section.push("Kind", "geneve");
+            gen_geneve_section(geneve_iface)?
+        }
+        Interface::Vrf(vrf_iface) => {
+            netdev_section.push("Kind", "vrf");
+            gen_vrf_section(vrf_iface)?
+        }
+        Interface::WireGuard(wg_iface) => {
+            netdev_section.push("Kind", "wireguard");
+            extra_sections = gen_wireguard_peer_sections(wg_iface)?;
+            gen_wireguard_section(wg_iface)?
+        }
+        _ => {
+            return Err(NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "Interface {} with type {} is not supported by \
+                    systemd-networkd",
+                    iface.name(),
+                    iface.iface_type()
+                ),
+            ));
+        }
+    };
+
+    let mut conf = NetworkdConf::default();
+    conf.push(netdev_section);
+    conf.push(kind_section);
+    for section in extra_sections {
+        conf.push(section);
+    }
+    Ok(Some(conf))
+}
+
+// When both ends of veth are defined in desired state, only the one with
+// smaller name holds the `.netdev` file.
+fn is_created_by_veth_peer(
+    iface_name: &str,
+    peer: &str,
+    merged_state: &MergedNetworkState,
+) -> bool {
+    if let Some(Interface::Ethernet(peer_iface)) = merged_state
+        .interfaces
+        .kernel_ifaces
+        .get(peer)
+        .and_then(|i| i.for_apply.as_ref())
+    {
+        peer_iface.veth.as_ref().map(|v| v.peer.as_str()) == Some(iface_name)
+            && peer < iface_name
+    } else {
+        false
+    }
+}
+
+fn gen_bond_section(
+    iface: &BondInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let iface_name = iface.base.name.as_str();
+    let mut section = NetworkdSection::new("Bond");
+    let bond_conf = match iface.bond.as_ref() {
+        Some(c) => c,
+        None => return Ok(section),
+    };
+    section.push_opt("Mode", bond_conf.mode.as_ref());
+    let opts = match bond_conf.options.as_ref() {
+        Some(o) => o,
+        None => return Ok(section),
+    };
+
+    if opts.tlb_dynamic_lb.is_some() {
+        return Err(not_supported(iface_name, "tlb_dynamic_lb"));
+    }
+    if opts.use_carrier.is_some() {
+        return Err(not_supported(iface_name, "use_carrier"));
+    }
+    if opts.balance_slb.is_some() {
+        return Err(not_supported(iface_name, "balance-slb"));
+    }
+    if let Some(
+        v @ (BondArpValidate::Filter
+        | BondArpValidate::FilterActive
+        | BondArpValidate::FilterBackup),
+    ) = opts.arp_validate.as_ref()
+    {
+        return Err(not_supported(iface_name, &format!("arp_validate: {v}")));
+    }
+
+    section.push_opt("TransmitHashPolicy", opts.xmit_hash_policy.as_ref());
+    section.push_opt("LACPTransmitRate", opts.lacp_rate.as_ref());
+    section.push_opt("MIIMonitorSec", opts.miimon.map(|v| format!("{v}ms")));
+    section.push_opt("UpDelaySec", opts.updelay.map(|v| format!("{v}ms")));
+    section.push_opt("DownDelaySec", opts.downdelay.map(|v| format!("{v}ms")));
+    section.push_opt(
+        "ARPIntervalSec",
+        opts.arp_interval.map(|v| format!("{v}ms")),
+    );
+    section.push_opt(
+        "ARPIPTargets",
+        opts.arp_ip_target.as_ref().map(|t| t.replace(',', " ")),
+    );
+    section.push_opt("ARPValidate", opts.arp_validate.as_ref());
+    section.push_opt("ARPAllTargets", opts.arp_all_targets.as_ref());
+    section.push_opt("AdSelect", opts.ad_select.as_ref());
+    section.push_opt("AdActorSystemPriority", opts.ad_actor_sys_prio);
+    section.push_opt("AdUserPortKey", opts.ad_user_port_key);
+    section.push_opt("AdActorSystem", opts.ad_actor_system.as_ref());
+    section.push_opt("FailOverMACPolicy", opts.fail_over_mac.as_ref());
+    section.push_opt("PrimaryReselectPolicy", opts.primary_reselect.as_ref());
+    section.push_opt("ResendIGMP", opts.resend_igmp);
+    section.push_opt("PacketsPerSlave", opts.packets_per_slave);
+    // The num_unsol_na is alias of num_grat_arp in kernel
+    section.push_opt("GratuitousARP", opts.num_grat_arp.or(opts.num_unsol_na));
+    section.push_opt("MinLinks", opts.min_links);
+    section.push_opt("LearnPacketIntervalSec", opts.lp_interval);
+    section.push_opt_bool(
+        "AllSlavesActive",
+        opts.all_slaves_active
+            .as_ref()
+            .map(|v| matches!(v, BondAllPortsActive::Delivered)),
+    );
+    Ok(section)
+}
+
+fn gen_bridge_section(
+    iface: &LinuxBridgeInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let iface_name = iface.base.name.as_str();
+    let mut section = NetworkdSection::new("Bridge");
+    let br_conf = match iface.bridge.as_ref() {
+        Some(c) => c,
+        None => return Ok(section),
+    };
+    if let Some(opts) = br_conf.options.as_ref() {
+        for (prop, is_set) in [
+            ("group-addr", opts.group_addr.is_some()),
+            ("hash-max", opts.hash_max.is_some()),
+            (
+                "multicast-last-member-count",
+                opts.multicast_last_member_count.is_some(),
+            ),
+            (
+                "multicast-last-member-interval",
+                opts.multicast_last_member_interval.is_some(),
+            ),
+            (
+                "multicast-membership-interval",
+                opts.multicast_membership_interval.is_some(),
+            ),
+            (
+                "multicast-querier-interval",
+                opts.multicast_querier_interval.is_some(),
+            ),
+            (
+                "multicast-query-interval",
+                opts.multicast_query_interval.is_some(),
+            ),
+            (
+                "multicast-query-response-interval",
+                opts.multicast_query_response_interval.is_some(),
+            ),
+            (
+                "multicast-query-use-ifaddr",
+                opts.multicast_query_use_ifaddr.is_some(),
+            ),
+            ("multicast-router", opts.multicast_router.is_some()),
+            (
+                "multicast-startup-query-count",
+                opts.multicast_startup_query_count.is_some(),
+            ),
+            (
+                "multicast-startup-query-interval",
+                opts.multicast_startup_query_interval.is_some(),
+            ),
+        ] {
+            if is_set {
+                return Err(not_supported(iface_name, prop));
+            }
+        }
+
+        if let Some(stp_opts) = opts.stp.as_ref() {
+            section.push_opt_bool("STP", stp_opts.enabled);
+            section.push_opt("ForwardDelaySec", stp_opts.forward_delay);
+            section.push_opt("HelloTimeSec", stp_opts.hello_time);
+            section.push_opt("MaxAgeSec", stp_opts.max_age);
+            section.push_opt("Priority", stp_opts.priority);
+        }
+        section.push_opt("AgeingTimeSec", opts.mac_ageing_time);
+        section.push_opt(
+            "GroupForwardMask",
+            opts.group_forward_mask.or(opts.group_fwd_mask),
+        );
+        section.push_opt_bool("MulticastQuerier", opts.multicast_querier);
+        section.push_opt_bool("MulticastSnooping", opts.multicast_snooping);
+        section.push_opt(
+            "VLANProtocol",
+            opts.vlan_protocol.as_ref().map(vlan_protocol_to_networkd),
+        );
+    }
+    if br_conf
+        .port
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .any(|p| p.vlan.is_some())
+    {
+        section.push_bool("VLANFiltering", true);
+    }
+    Ok(section)
+}
+
+fn gen_vlan_section(
+    iface: &VlanInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let mut section = NetworkdSection::new("VLAN");
+    if let Some(vlan_conf) = iface.vlan.as_ref() {
+        section.push("Id", vlan_conf.id);
+        section.push_opt(
+            "Protocol",
+            vlan_conf.protocol.as_ref().map(vlan_protocol_to_networkd),
+        );
+        section.push_opt(
+            "EgressQOSMaps",
+            vlan_conf
+                .egress_qos_map
+                .as_deref()
+                .map(vlan_qos_map_to_networkd),
+        );
+        section.push_opt(
+            "IngressQOSMaps",
+            vlan_conf
+                .ingress_qos_map
+                .as_deref()
+                .map(vlan_qos_map_to_networkd),
+        );
+        section.push_opt_bool("ReorderHeader", vlan_conf.reorder_headers);
+        section.push_opt_bool("GVRP", vlan_conf.gvrp);
+        section.push_opt_bool("MVRP", vlan_conf.mvrp);
+        section.push_opt_bool("LooseBinding", vlan_conf.loose_binding);
+    }
+    Ok(section)
+}
+
+fn gen_vxlan_section(
+    iface: &VxlanInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let mut section = NetworkdSection::new("VXLAN");
+    if let Some(vxlan_conf) = iface.vxlan.as_ref() {
+        section.push("VNI", vxlan_conf.id);
+        section.push_opt("Local", vxlan_conf.local.as_ref());
+        match vxlan_conf.remote.as_ref() {
+            Some(remote) if remote.is_multicast() => {
+                section.push("Group", remote)
+            }
+            remote => section.push_opt("Remote", remote),
+        }
+        section.push_opt("DestinationPort", vxlan_conf.dst_port);
+        if let (Some(min), Some(max)) =
+            (vxlan_conf.source_port_min, vxlan_conf.source_port_max)
+        {
+            section.push("PortRange", format!("{min}-{max}"));
+        }
+        section.push_opt("TTL", vxlan_conf.ttl);
+        section.push_opt("TOS", vxlan_conf.tos);
+        section.push_opt_bool("MacLearning", vxlan_conf.learning);
+        section.push_opt("FDBAgeingSec", vxlan_conf.ageing);
+        section.push_opt("MaximumFDBEntries", vxlan_conf.limit);
+        section.push_opt_bool("ReduceARPProxy", vxlan_conf.proxy);
+        section.push_opt_bool("RouteShortCircuit", vxlan_conf.rsc);
+        section.push_opt_bool("L2MissNotification", vxlan_conf.l2_miss);
+        section.push_opt_bool("L3MissNotification", vxlan_conf.l3_miss);
+        section.push_opt_bool("UDPChecksum", vxlan_conf.udp_checksum);
+        section.push_opt_bool(
+            "UDP6ZeroChecksumTx",
+            vxlan_conf.udp6_zero_checksum_tx,
+        );
+        section.push_opt_bool(
+            "UDP6ZeroChecksumRx",
+            vxlan_conf.udp6_zero_checksum_rx,
+        );
+        section.push_opt_bool("GroupPolicyExtension", vxlan_conf.gbp);
+        section.push_opt_bool("External", vxlan_conf.external);
+        if vxlan_conf.base_iface.is_empty() {
+            section.push_bool("Independent", true);
+        }
+    }
+    Ok(section)
+}
+
+fn gen_geneve_section(
+    iface: &GeneveInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let mut section = NetworkdSection::new("GENEVE");
+    if let Some(geneve_conf) = iface.geneve.as_ref() {
+        if geneve_conf.external == Some(true) {
+            return Err(not_supported(iface.base.name.as_str(), "external"));
+        }
+        section.push_opt("Id", geneve_conf.id);
+        section.push_opt("Remote", geneve_conf.remote.as_ref());
+        section.push_opt("TTL", geneve_conf.ttl);
+        section.push_opt("TOS", geneve_conf.tos);
+        section.push_opt("DestinationPort", geneve_conf.dst_port);
+    }
+    Ok(section)
+}
+
+fn ip_tunnel_kind(iface: &IpTunnelInterface) -> &'static str {
+    match (&iface.base.iface_type, iface.is_ipv6()) {
+        (InterfaceType::GreTap, true) => "ip6gretap",
+        (InterfaceType::GreTap, false) => "gretap",
+        (InterfaceType::IpIp, _) => "ipip",
+        (InterfaceType::Sit, _) => "sit",
+        (InterfaceType::Ip6Tnl, _) => "ip6tnl",
+        (_, true) => "ip6gre",
+        (_, false) => "gre",
+    }
+}
+
+fn gen_ip_tunnel_section(
+    iface: &IpTunnelInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let mut section = NetworkdSection::new("Tunnel");
+    if iface.base.iface_type == InterfaceType::Ip6Tnl {
+        section.push("Mode", "ip6ip6");
+    }
+    if let Some(tunnel_conf) = iface.ip_tunnel.as_ref() {
+        section.push_opt("Local", tunnel_conf.local.as_ref());
+        section.push_opt("Remote", tunnel_conf.remote.as_ref());
+        section.push_opt("Key", tunnel_conf.key);
+        section.push_opt("TTL", tunnel_conf.ttl);
+        section.push_opt("TOS", tunnel_conf.tos);
+        section.push_opt_bool("DiscoverPathMTU", tunnel_conf.pmtudisc);
+    }
+    if iface.parent().is_none() {
+        section.push_bool("Independent", true);
+    }
+    Ok(section)
+}
+
+fn gen_vrf_section(
+    iface: &VrfInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let mut section = NetworkdSection::new("VRF");
+    if let Some(vrf_conf) = iface.vrf.as_ref() {
+        section.push("Table", vrf_conf.table_id);
+    }
+    Ok(section)
+}
+
+fn gen_wireguard_section(
+    iface: &WireGuardInterface,
+) -> Result<NetworkdSection, NmstateError> {
+    let mut section = NetworkdSection::new("WireGuard");
+    if let Some(wg_conf) = iface.wireguard.as_ref() {
+        if is_secret_hidden(wg_conf.private_key.as_deref()) {
+            return Err(secret_hidden(iface.base.name.as_str(), "private-key"));
+        }
+        section.push_opt("PrivateKey", wg_conf.private_key.as_ref());
+        section.push_opt("ListenPort", wg_conf.listen_port);
+        section.push_opt("FirewallMark", wg_conf.fwmark);
+    }
+    Ok(section)
+}
+
+fn gen_wireguard_peer_sections(
+    iface: &WireGuardInterface,
+) -> Result<Vec<NetworkdSection>, NmstateError> {
+    let mut ret = Vec::new();
+    for peer in iface
+        .wireguard
+        .as_ref()
+        .and_then(|c| c.peers.as_deref())
+        .unwrap_or_default()
+    {
+        if is_secret_hidden(peer.preshared_key.as_deref()) {
+            return Err(secret_hidden(
+                iface.base.name.as_str(),
+                "preshared-key",
+            ));
+        }
+        let mut section = NetworkdSection::new("WireGuardPeer");
+        section.push("PublicKey", peer.public_key.as_str());
+        section.push_opt("PresharedKey", peer.preshared_key.as_ref());
+        section.push_opt("Endpoint", peer.endpoint.as_ref());
+        section.push_opt(
+            "AllowedIPs",
+            peer.allowed_ips.as_ref().map(|ips| ips.join(",")),
+        );
+        section.push_opt("PersistentKeepalive", peer.persistent_keepalive);
+        ret.push(section);
+    }
+    Ok(ret)
+}
+
+fn is_secret_hidden(secret: Option<&str>) -> bool {
+    secret == Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+}
+
+// The systemd-networkd configuration files hold the real secrets, hence
+// cannot use the placeholder of hidden secrets.
+fn secret_hidden(iface_name: &str, prop: &str) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::InvalidArgument,
+        format!(
+            "The {prop} of WireGuard interface {iface_name} is hidden, \
+            please provide the real secret"
+        ),
+    );
+    log::error!("{}", e);
+    e
+}
+
+fn vlan_qos_map_to_networkd(qos_map: &[VlanQosMapping]) -> String {
+    qos_map
+        .iter()
+        .map(|m| format!("{}-{}", m.from, m.to))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn vlan_protocol_to_networkd(protocol: &VlanProtocol) -> &'static str {
+    match protocol {
+        VlanProtocol::Ieee8021Q => "802.1q",
+        VlanProtocol::Ieee8021Ad => "802.1ad",
+    }
+}
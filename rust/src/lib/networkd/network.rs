@@ -0,0 +1,571 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ip::is_ipv6_unicast_link_local, BaseInterface, BridgePortTunkTag,
+    BridgePortVlanConfig, BridgePortVlanMode, Dhcpv4ClientId, Dhcpv6Duid,
+    DnsClientState, ErrorKind, Interface, InterfaceIpAddr, InterfaceType,
+    Ipv6AddrGenMode, MergedNetworkState, NmstateError, RouteEntry,
+    RouteRuleAction, RouteRuleEntry, RouteScope, WaitIp,
+};
+
+use super::{
+    conf::{NetworkdConf, NetworkdSection},
+    gen_conf::not_supported,
+};
+
+// The all-zeros FDB entry used for head-end replication of VXLAN
+const VXLAN_FDB_REMOTE_MAC: &str = "00:00:00:00:00:00";
+
+pub(crate) fn gen_network(
+    iface: &Interface,
+    merged_state: &MergedNetworkState,
+) -> Result<NetworkdConf, NmstateError> {
+    let base_iface = iface.base_iface();
+    let iface_name = iface.name();
+    check_unsupported_props(base_iface)?;
+
+    let mut conf = NetworkdConf::default();
+    let mut match_section = NetworkdSection::new("Match");
+    match_section.push("Name", iface_name);
+    conf.push(match_section);
+
+    let mut link_section = NetworkdSection::new("Link");
+    link_section.push_opt("MTUBytes", base_iface.mtu);
+    link_section.push_opt("MACAddress", base_iface.mac_address.as_ref());
+    link_section
+        .push_opt_bool("Promiscuous", base_iface.accept_all_mac_addresses);
+    if iface.is_down() {
+        link_section.push("ActivationPolicy", "down");
+    }
+    link_section.push_opt(
+        "RequiredFamilyForOnline",
+        base_iface.wait_ip.as_ref().map(|w| match w {
+            WaitIp::Any => "any",
+            WaitIp::Ipv4 => "ipv4",
+            WaitIp::Ipv6 => "ipv6",
+            WaitIp::Ipv4AndIpv6 => "both",
+        }),
+    );
+    conf.push(link_section);
+
+    let mut network_section = NetworkdSection::new("Network");
+    let mut port_sections = Vec::new();
+    if let Some(ctrl_name) =
+        base_iface.controller.as_deref().filter(|c| !c.is_empty())
+    {
+        port_sections = gen_port_conf(
+            iface_name,
+            ctrl_name,
+            base_iface.controller_type.as_ref(),
+            merged_state,
+            &mut network_section,
+        )?;
+    }
+
+    let mut ip_sections = Vec::new();
+    if base_iface.can_have_ip() {
+        ip_sections = gen_ip_conf(base_iface, &mut network_section)?;
+    }
+
+    if let Some(lldp_conf) = base_iface.lldp.as_ref() {
+        network_section.push_bool("LLDP", lldp_conf.enabled);
+        network_section.push_bool("EmitLLDP", lldp_conf.enabled);
+    }
+
+    for child in get_stacked_ifaces(iface_name, merged_state) {
+        network_section.push(child.0, child.1);
+    }
+
+    conf.push(network_section);
+    for section in port_sections {
+        conf.push(section);
+    }
+    if let Interface::Vxlan(vxlan_iface) = iface {
+        for remote in vxlan_iface
+            .vxlan
+            .as_ref()
+            .and_then(|c| c.fdb_remotes.as_deref())
+            .unwrap_or_default()
+        {
+            let mut section = NetworkdSection::new("BridgeFDB");
+            section.push("MACAddress", VXLAN_FDB_REMOTE_MAC);
+            section.push("Destination", remote);
+            conf.push(section);
+        }
+    }
+    for section in ip_sections {
+        conf.push(section);
+    }
+    for rt in base_iface.routes.as_deref().unwrap_or_default() {
+        conf.push(gen_route_section(iface_name, rt)?);
+    }
+    for rule in base_iface
+        .ipv4
+        .as_ref()
+        .and_then(|i| i.rules.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .chain(
+            base_iface
+                .ipv6
+                .as_ref()
+                .and_then(|i| i.rules.as_deref())
+                .unwrap_or_default()
+                .iter(),
+        )
+    {
+        conf.push(gen_rule_section(iface_name, rule)?);
+    }
+    Ok(conf)
+}
+
+// The VLAN/VXLAN parent not mentioned in desired state only holds the
+// references to its VLAN/VXLAN interfaces.
+pub(crate) fn gen_stacked_parent_network(
+    iface_name: &str,
+    merged_state: &MergedNetworkState,
+) -> NetworkdConf {
+    let mut conf = NetworkdConf::default();
+    let mut match_section = NetworkdSection::new("Match");
+    match_section.push("Name", iface_name);
+    conf.push(match_section);
+    let mut network_section = NetworkdSection::new("Network");
+    for child in get_stacked_ifaces(iface_name, merged_state) {
+        network_section.push(child.0, child.1);
+    }
+    conf.push(network_section);
+    conf
+}
+
+fn check_unsupported_props(
+    base_iface: &BaseInterface,
+) -> Result<(), NmstateError> {
+    for (prop, is_set) in [
+        ("802.1x", base_iface.ieee8021x.is_some()),
+        ("ethtool", base_iface.ethtool.is_some()),
+        ("ovs-db", base_iface.ovsdb.is_some()),
+        ("copy-mac-from", base_iface.copy_mac_from.is_some()),
+        ("mptcp", base_iface.mptcp.is_some()),
+    ] {
+        if is_set {
+            return Err(not_supported(base_iface.name.as_str(), prop));
+        }
+    }
+    Ok(())
+}
+
+// Return sorted list of (`VLAN`, `VXLAN` or `Tunnel`, child_iface_name)
+fn get_stacked_ifaces<'a>(
+    iface_name: &str,
+    merged_state: &'a MergedNetworkState,
+) -> Vec<(&'static str, &'a str)> {
+    let mut ret: Vec<(&'static str, &'a str)> = merged_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .filter_map(|i| i.for_apply.as_ref())
+        .filter(|i| !i.is_absent() && i.parent() == Some(iface_name))
+        .filter_map(|i| match i {
+            Interface::Vlan(_) => Some(("VLAN", i.name())),
+            Interface::Vxlan(_) => Some(("VXLAN", i.name())),
+            Interface::IpTunnel(_) => Some(("Tunnel", i.name())),
+            _ => None,
+        })
+        .collect();
+    ret.sort_unstable();
+    ret
+}
+
+fn gen_port_conf(
+    iface_name: &str,
+    ctrl_name: &str,
+    ctrl_type: Option<&InterfaceType>,
+    merged_state: &MergedNetworkState,
+    network_section: &mut NetworkdSection,
+) -> Result<Vec<NetworkdSection>, NmstateError> {
+    let ctrl_iface = merged_state
+        .interfaces
+        .kernel_ifaces
+        .get(ctrl_name)
+        .and_then(|i| i.for_apply.as_ref().or(Some(&i.merged)));
+    let ctrl_type = match ctrl_type
+        .cloned()
+        .or_else(|| ctrl_iface.map(|i| i.iface_type()))
+    {
+        Some(t) => t,
+        None => {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "The controller {ctrl_name} of interface {iface_name} \
+                    is not defined in desired state"
+                ),
+            ));
+        }
+    };
+    let mut sections = Vec::new();
+    match ctrl_type {
+        InterfaceType::Bond => {
+            network_section.push("Bond", ctrl_name);
+            if let Some(Interface::Bond(bond_iface)) = ctrl_iface {
+                if bond_iface
+                    .bond
+                    .as_ref()
+                    .and_then(|b| b.options.as_ref())
+                    .and_then(|o| o.primary.as_deref())
+                    == Some(iface_name)
+                {
+                    network_section.push_bool("PrimarySlave", true);
+                }
+            }
+        }
+        InterfaceType::LinuxBridge => {
+            network_section.push("Bridge", ctrl_name);
+            if let Some(port_conf) = ctrl_iface.and_then(|i| {
+                if let Interface::LinuxBridge(br_iface) = i {
+                    br_iface
+                        .bridge
+                        .as_ref()
+                        .and_then(|b| b.port.as_deref())
+                        .and_then(|ports| {
+                            ports.iter().find(|p| p.name == iface_name)
+                        })
+                } else {
+                    None
+                }
+            }) {
+                let mut section = NetworkdSection::new("Bridge");
+                section.push_opt("Cost", port_conf.stp_path_cost);
+                section.push_opt("Priority", port_conf.stp_priority);
+                section.push_opt_bool("HairPin", port_conf.stp_hairpin_mode);
+                sections.push(section);
+                if let Some(vlan_conf) = port_conf.vlan.as_ref() {
+                    sections.extend(gen_bridge_vlan_sections(vlan_conf));
+                }
+            }
+        }
+        InterfaceType::Vrf => {
+            network_section.push("VRF", ctrl_name);
+        }
+        _ => {
+            return Err(NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "Attaching interface {iface_name} to {ctrl_type} \
+                    controller {ctrl_name} is not supported by \
+                    systemd-networkd"
+                ),
+            ));
+        }
+    }
+    Ok(sections)
+}
+
+fn gen_bridge_vlan_sections(
+    vlan_conf: &BridgePortVlanConfig,
+) -> Vec<NetworkdSection> {
+    let mut ret = Vec::new();
+    let is_trunk = vlan_conf.mode == Some(BridgePortVlanMode::Trunk);
+    if is_trunk {
+        let mut vids: Vec<u16> = Vec::new();
+        for trunk_tag in vlan_conf.trunk_tags.as_deref().unwrap_or_default() {
+            match trunk_tag {
+                BridgePortTunkTag::Id(id) => vids.push(*id),
+                BridgePortTunkTag::IdRange(range) => {
+                    vids.extend(range.min..=range.max)
+                }
+            }
+        }
+        for (start, end) in compress_vlan_ids(vids) {
+            let mut section = NetworkdSection::new("BridgeVLAN");
+            if start == end {
+                section.push("VLAN", start);
+            } else {
+                section.push("VLAN", format!("{start}-{end}"));
+            }
+            ret.push(section);
+        }
+    }
+    // Access port or native VLAN of trunk port
+    if let Some(tag) = vlan_conf
+        .tag
+        .filter(|_| !is_trunk || vlan_conf.enable_native == Some(true))
+    {
+        let mut section = NetworkdSection::new("BridgeVLAN");
+        section.push("PVID", tag);
+        section.push("EgressUntagged", tag);
+        ret.push(section);
+    }
+    ret
+}
+
+// Merge VLAN IDs into ranges of consecutive IDs
+fn compress_vlan_ids(mut vids: Vec<u16>) -> Vec<(u16, u16)> {
+    vids.sort_unstable();
+    vids.dedup();
+    let mut ret: Vec<(u16, u16)> = Vec::new();
+    for vid in vids {
+        match ret.last_mut() {
+            Some((_, end)) if *end + 1 == vid => *end = vid,
+            _ => ret.push((vid, vid)),
+        }
+    }
+    ret
+}
+
+// Return extra sections for DHCP and IPv6 autoconf options.
+// Undefined IP stack is treated as disabled, the same as NetworkManager
+// plugin does in gen_conf mode.
+fn gen_ip_conf(
+    base_iface: &BaseInterface,
+    network_section: &mut NetworkdSection,
+) -> Result<Vec<NetworkdSection>, NmstateError> {
+    let iface_name = base_iface.name.as_str();
+    let ipv4 = base_iface.ipv4.as_ref().filter(|i| i.enabled);
+    let ipv6 = base_iface.ipv6.as_ref().filter(|i| i.enabled);
+    let dhcpv4 = ipv4.and_then(|i| i.dhcp) == Some(true);
+    let dhcpv6 = ipv6.and_then(|i| i.dhcp) == Some(true);
+    let autoconf = ipv6.and_then(|i| i.autoconf) == Some(true);
+
+    match (dhcpv4, dhcpv6) {
+        (true, true) => network_section.push("DHCP", "yes"),
+        (true, false) => network_section.push("DHCP", "ipv4"),
+        (false, true) => network_section.push("DHCP", "ipv6"),
+        (false, false) => (),
+    }
+    if ipv6.is_none() {
+        network_section.push_bool("LinkLocalAddressing", false);
+    }
+    network_section.push_bool("IPv6AcceptRA", autoconf);
+
+    let mut dns_confs: Vec<&DnsClientState> = Vec::new();
+    let mut sections = Vec::new();
+    if let Some(ipv4) = ipv4 {
+        for addr in ipv4.addresses.as_deref().unwrap_or_default() {
+            push_address(iface_name, addr, network_section)?;
+        }
+        if let Some(dns_conf) = ipv4.dns.as_ref() {
+            dns_confs.push(dns_conf);
+        }
+        if dhcpv4 {
+            let mut section = NetworkdSection::new("DHCPv4");
+            section.push_opt_bool("UseDNS", ipv4.auto_dns);
+            section.push_opt_bool("UseRoutes", ipv4.auto_routes);
+            section.push_opt_bool("UseGateway", ipv4.auto_gateway);
+            section.push_opt("RouteTable", ipv4.auto_table_id);
+            section.push_opt("RouteMetric", ipv4.auto_route_metric);
+            match ipv4.dhcp_client_id.as_ref() {
+                Some(Dhcpv4ClientId::LinkLayerAddress) => {
+                    section.push("ClientIdentifier", "mac")
+                }
+                Some(Dhcpv4ClientId::IaidPlusDuid) => {
+                    section.push("ClientIdentifier", "duid")
+                }
+                Some(Dhcpv4ClientId::Other(v)) => {
+                    return Err(not_supported(
+                        iface_name,
+                        &format!("ipv4.dhcp-client-id: {v}"),
+                    ));
+                }
+                None => (),
+            }
+            sections.push(section);
+        }
+    }
+    if let Some(ipv6) = ipv6 {
+        for addr in ipv6.addresses.as_deref().unwrap_or_default() {
+            if let std::net::IpAddr::V6(ip) = &addr.ip {
+                if is_ipv6_unicast_link_local(ip) {
+                    continue;
+                }
+            }
+            push_address(iface_name, addr, network_section)?;
+        }
+        if let Some(dns_conf) = ipv6.dns.as_ref() {
+            dns_confs.push(dns_conf);
+        }
+        match ipv6.addr_gen_mode.as_ref() {
+            Some(Ipv6AddrGenMode::Eui64) => network_section
+                .push("IPv6LinkLocalAddressGenerationMode", "eui64"),
+            Some(Ipv6AddrGenMode::StablePrivacy) => network_section
+                .push("IPv6LinkLocalAddressGenerationMode", "stable-privacy"),
+            Some(Ipv6AddrGenMode::Other(v)) => {
+                return Err(not_supported(
+                    iface_name,
+                    &format!("ipv6.addr-gen-mode: {v}"),
+                ));
+            }
+            None => (),
+        }
+        if dhcpv6 {
+            let mut section = NetworkdSection::new("DHCPv6");
+            section.push_opt_bool("UseDNS", ipv6.auto_dns);
+            match ipv6.dhcp_duid.as_ref() {
+                Some(Dhcpv6Duid::LinkLayerAddressPlusTime) => {
+                    section.push("DUIDType", "link-layer-time")
+                }
+                Some(Dhcpv6Duid::EnterpriseNumber) => {
+                    section.push("DUIDType", "vendor")
+                }
+                Some(Dhcpv6Duid::LinkLayerAddress) => {
+                    section.push("DUIDType", "link-layer")
+                }
+                Some(Dhcpv6Duid::Uuid) => section.push("DUIDType", "uuid"),
+                Some(Dhcpv6Duid::Other(v)) => {
+                    return Err(not_supported(
+                        iface_name,
+                        &format!("ipv6.dhcp-duid: {v}"),
+                    ));
+                }
+                None => (),
+            }
+            // Without router advertisement, DHCPv6 client should be
+            // started explicitly.
+            if !autoconf {
+                section.push("WithoutRA", "solicit");
+            }
+            sections.push(section);
+        }
+        if autoconf {
+            let mut section = NetworkdSection::new("IPv6AcceptRA");
+            section.push_opt_bool("UseDNS", ipv6.auto_dns);
+            section.push_opt_bool("UseRoutePrefix", ipv6.auto_routes);
+            section.push_opt_bool("UseGateway", ipv6.auto_gateway);
+            section.push_opt("RouteTable", ipv6.auto_table_id);
+            section.push_opt("RouteMetric", ipv6.auto_route_metric);
+            section.push_opt(
+                "Token",
+                ipv6.token.as_deref().filter(|t| !t.is_empty()),
+            );
+            sections.push(section);
+        }
+    }
+
+    // The one with searches is preferred and holds smaller priority
+    dns_confs.sort_unstable_by_key(|c| c.priority.unwrap_or_default());
+    let mut searches: Vec<&str> = Vec::new();
+    for dns_conf in dns_confs {
+        for srv in dns_conf.server.as_deref().unwrap_or_default() {
+            network_section.push("DNS", srv);
+        }
+        searches.extend(
+            dns_conf
+                .search
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .map(|s| s.as_str()),
+        );
+    }
+    if !searches.is_empty() {
+        network_section.push("Domains", searches.join(" "));
+    }
+    Ok(sections)
+}
+
+fn push_address(
+    iface_name: &str,
+    addr: &InterfaceIpAddr,
+    network_section: &mut NetworkdSection,
+) -> Result<(), NmstateError> {
+    if addr.mptcp_flags.is_some() {
+        return Err(not_supported(iface_name, "mptcp-flags"));
+    }
+    network_section
+        .push("Address", format!("{}/{}", addr.ip, addr.prefix_length));
+    Ok(())
+}
+
+fn gen_route_section(
+    iface_name: &str,
+    rt: &RouteEntry,
+) -> Result<NetworkdSection, NmstateError> {
+    if rt.weight.is_some() {
+        return Err(not_supported(
+            iface_name,
+            &format!("weight of route {rt}"),
+        ));
+    }
+    let mut section = NetworkdSection::new("Route");
+    section.push_opt("Destination", rt.destination.as_ref());
+    section.push_opt("Gateway", rt.next_hop_addr.as_ref());
+    section.push_opt(
+        "Metric",
+        rt.metric.filter(|m| *m != RouteEntry::USE_DEFAULT_METRIC),
+    );
+    section.push_opt(
+        "Table",
+        rt.table_id
+            .filter(|t| *t != RouteEntry::USE_DEFAULT_ROUTE_TABLE),
+    );
+    section.push_opt("Type", rt.route_type.as_ref());
+    section.push_opt("PreferredSource", rt.source.as_ref());
+    section.push_opt("MTUBytes", rt.mtu);
+    section.push_opt("TCPAdvertisedMaximumSegmentSize", rt.advmss);
+    section.push_opt("InitialCongestionWindow", rt.initcwnd);
+    section.push_opt("InitialAdvertisedReceiveWindow", rt.initrwnd);
+    section.push_opt_bool("GatewayOnLink", rt.onlink);
+    section.push_opt(
+        "Scope",
+        rt.scope.map(|s| match s {
+            RouteScope::Universe => "global",
+            RouteScope::Site => "site",
+            RouteScope::Link => "link",
+            RouteScope::Host => "host",
+        }),
+    );
+    section.push_opt_bool("QuickAck", rt.quickack);
+    section.push_opt("Protocol", rt.protocol.as_ref());
+    Ok(section)
+}
+
+fn gen_rule_section(
+    iface_name: &str,
+    rule: &RouteRuleEntry,
+) -> Result<NetworkdSection, NmstateError> {
+    if rule.l3mdev == Some(true) {
+        return Err(not_supported(iface_name, "route rule l3mdev"));
+    }
+    if let Some(action @ (RouteRuleAction::Goto | RouteRuleAction::Nop)) =
+        rule.action
+    {
+        return Err(not_supported(
+            iface_name,
+            &format!("route rule action {action}"),
+        ));
+    }
+    let mut section = NetworkdSection::new("RoutingPolicyRule");
+    section.push_opt("From", rule.ip_from.as_ref());
+    section.push_opt("To", rule.ip_to.as_ref());
+    section.push_opt(
+        "Priority",
+        rule.priority
+            .filter(|p| *p != RouteRuleEntry::USE_DEFAULT_PRIORITY),
+    );
+    section.push_opt(
+        "Table",
+        rule.table_id
+            .filter(|t| *t != RouteRuleEntry::USE_DEFAULT_ROUTE_TABLE),
+    );
+    match (rule.fwmark, rule.fwmask) {
+        (Some(mark), Some(mask)) => {
+            section.push("FirewallMark", format!("{mark}/{mask}"))
+        }
+        (Some(mark), None) => section.push("FirewallMark", mark),
+        (None, Some(mask)) => section.push("FirewallMark", format!("0/{mask}")),
+        (None, None) => (),
+    }
+    section.push_opt("IncomingInterface", rule.iif.as_ref());
+    section.push_opt("OutgoingInterface", rule.oif.as_ref());
+    section.push_opt("User", rule.uid_range.as_ref());
+    section.push_opt("IPProtocol", rule.ip_proto);
+    section.push_opt("SourcePort", rule.sport.as_ref());
+    section.push_opt("DestinationPort", rule.dport.as_ref());
+    section.push_opt("TypeOfService", rule.tos);
+    section.push_opt_bool("InvertRule", rule.invert.filter(|i| *i));
+    section.push_opt("SuppressPrefixLength", rule.suppress_prefix_length);
+    section.push_opt("SuppressInterfaceGroup", rule.suppress_ifgroup);
+    section.push_opt("Type", rule.action.as_ref());
+    section.push("Family", if rule.is_ipv6() { "ipv6" } else { "ipv4" });
+    Ok(section)
+}
@@ -0,0 +1,587 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ErrorKind, NetworkState};
+
+fn gen_networkd_conf(yml: &str) -> Vec<(String, String)> {
+    let desired: NetworkState = serde_yaml::from_str(yml).unwrap();
+    desired
+        .gen_conf_for_backend("systemd-networkd")
+        .unwrap()
+        .remove("systemd-networkd")
+        .unwrap()
+}
+
+fn get_conf<'a>(confs: &'a [(String, String)], file_name: &str) -> &'a str {
+    confs
+        .iter()
+        .find(|(n, _)| n == file_name)
+        .map(|(_, c)| c.as_str())
+        .unwrap()
+}
+
+#[test]
+fn test_networkd_gen_conf_static_ip_route_dns() {
+    let confs = gen_networkd_conf(
+        r#"---
+dns-resolver:
+  config:
+    server:
+    - 192.0.2.1
+    search:
+    - example.com
+routes:
+  config:
+  - destination: 0.0.0.0/0
+    next-hop-address: 192.0.2.254
+    next-hop-interface: eth1
+  - destination: 198.51.100.0/24
+    next-hop-address: 192.0.2.253
+    next-hop-interface: eth1
+    metric: 50
+    table-id: 100
+route-rules:
+  config:
+  - ip-from: 192.0.2.0/24
+    priority: 1000
+    route-table: 100
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1400
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+  ipv6:
+    enabled: false
+"#,
+    );
+
+    assert_eq!(confs.len(), 1);
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-eth1.network"),
+        r#"[Match]
+Name=eth1
+
+[Link]
+MTUBytes=1400
+
+[Network]
+LinkLocalAddressing=no
+IPv6AcceptRA=no
+Address=192.0.2.2/24
+DNS=192.0.2.1
+Domains=example.com
+
+[Route]
+Destination=0.0.0.0/0
+Gateway=192.0.2.254
+
+[Route]
+Destination=198.51.100.0/24
+Gateway=192.0.2.253
+Metric=50
+Table=100
+
+[RoutingPolicyRule]
+From=192.0.2.0/24
+Priority=1000
+Table=100
+Family=ipv4
+"#
+    );
+}
+
+#[test]
+fn test_networkd_gen_conf_dhcp() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  ethernet:
+    speed: 1000
+    duplex: full
+    auto-negotiation: false
+  ipv4:
+    enabled: true
+    dhcp: true
+    auto-dns: false
+    auto-route-table-id: 100
+  ipv6:
+    enabled: true
+    dhcp: true
+    autoconf: false
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-eth1.link"),
+        r#"[Match]
+OriginalName=eth1
+
+[Link]
+AutoNegotiation=no
+BitsPerSecond=1000M
+Duplex=full
+"#
+    );
+    let network = get_conf(&confs, "10-nmstate-eth1.network");
+    assert!(network.contains("DHCP=yes\nIPv6AcceptRA=no\n"));
+    assert!(network.contains("[DHCPv4]\nUseDNS=no\n"));
+    assert!(network.contains("RouteTable=100\n"));
+    assert!(network.contains("WithoutRA=solicit\n"));
+}
+
+#[test]
+fn test_networkd_gen_conf_bond_and_bridge() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: bond0
+  type: bond
+  state: up
+  link-aggregation:
+    mode: active-backup
+    options:
+      miimon: 100
+      primary: eth1
+    port:
+    - eth1
+    - eth2
+- name: br0
+  type: linux-bridge
+  state: up
+  bridge:
+    options:
+      stp:
+        enabled: false
+    port:
+    - name: bond0
+      stp-path-cost: 10
+      vlan:
+        mode: trunk
+        trunk-tags:
+        - id: 100
+        - id: 101
+        - id-range:
+            min: 200
+            max: 299
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-bond0.netdev"),
+        r#"[NetDev]
+Name=bond0
+Kind=bond
+
+[Bond]
+Mode=active-backup
+MIIMonitorSec=100ms
+"#
+    );
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-eth1.network"),
+        r#"[Match]
+Name=eth1
+
+[Network]
+Bond=bond0
+PrimarySlave=yes
+"#
+    );
+    assert!(
+        !get_conf(&confs, "10-nmstate-eth2.network").contains("PrimarySlave")
+    );
+    assert!(get_conf(&confs, "10-nmstate-br0.netdev")
+        .contains("STP=no\nVLANFiltering=yes\n"));
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-bond0.network"),
+        r#"[Match]
+Name=bond0
+
+[Network]
+Bridge=br0
+
+[Bridge]
+Cost=10
+
+[BridgeVLAN]
+VLAN=100-101
+
+[BridgeVLAN]
+VLAN=200-299
+"#
+    );
+}
+
+#[test]
+fn test_networkd_gen_conf_vlan_parent_not_desired() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: eth1.10
+  type: vlan
+  state: up
+  vlan:
+    base-iface: eth1
+    id: 10
+- name: vxlan0
+  type: vxlan
+  state: up
+  vxlan:
+    base-iface: eth1
+    id: 100
+    remote: 192.0.2.100
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-eth1.10.netdev"),
+        r#"[NetDev]
+Name=eth1.10
+Kind=vlan
+
+[VLAN]
+Id=10
+"#
+    );
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-eth1.network"),
+        r#"[Match]
+Name=eth1
+
+[Network]
+VLAN=eth1.10
+VXLAN=vxlan0
+"#
+    );
+}
+
+#[test]
+fn test_networkd_gen_conf_not_supported() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: br0
+  type: ovs-bridge
+  state: up
+  bridge:
+    port:
+    - name: eth1
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf_for_backend("systemd-networkd");
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: bond0
+  type: bond
+  state: up
+  link-aggregation:
+    mode: balance-rr
+    options:
+      tlb_dynamic_lb: true
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf_for_backend("systemd-networkd");
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
+
+#[test]
+fn test_gen_conf_unknown_backend() {
+    let desired = NetworkState::new();
+    let result = desired.gen_conf_for_backend("ifupdown");
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_networkd_gen_conf_wireguard() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: wg0
+  type: wireguard
+  state: up
+  wireguard:
+    private-key: yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+    listen-port: 51820
+    peers:
+    - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+      endpoint: 198.51.100.1:51820
+      allowed-ips:
+      - 192.0.2.0/24
+      - 2001:db8::/64
+      persistent-keepalive: 25
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-wg0.netdev"),
+        r#"[NetDev]
+Name=wg0
+Kind=wireguard
+
+[WireGuard]
+PrivateKey=yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+ListenPort=51820
+
+[WireGuardPeer]
+PublicKey=gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+Endpoint=198.51.100.1:51820
+AllowedIPs=192.0.2.0/24,2001:db8::/64
+PersistentKeepalive=25
+"#
+    );
+
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: wg0
+  type: wireguard
+  state: up
+  wireguard:
+    private-key: <_password_hid_by_nmstate>
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf_for_backend("systemd-networkd");
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_networkd_gen_conf_ip_tunnel() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+- name: gre1
+  type: gre
+  state: up
+  ip-tunnel:
+    base-iface: eth1
+    local: 192.0.2.1
+    remote: 198.51.100.1
+    key: 100
+    ttl: 64
+- name: ip6tnl1
+  type: ip6tnl
+  state: up
+  ip-tunnel:
+    local: 2001:db8::1
+    remote: 2001:db8::2
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-gre1.netdev"),
+        r#"[NetDev]
+Name=gre1
+Kind=gre
+
+[Tunnel]
+Local=192.0.2.1
+Remote=198.51.100.1
+Key=100
+TTL=64
+"#
+    );
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-ip6tnl1.netdev"),
+        r#"[NetDev]
+Name=ip6tnl1
+Kind=ip6tnl
+
+[Tunnel]
+Mode=ip6ip6
+Local=2001:db8::1
+Remote=2001:db8::2
+Independent=yes
+"#
+    );
+    assert!(
+        get_conf(&confs, "10-nmstate-eth1.network").contains("Tunnel=gre1\n")
+    );
+}
+
+#[test]
+fn test_networkd_gen_conf_geneve() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: geneve1
+  type: geneve
+  state: up
+  geneve:
+    id: 102
+    remote: 192.0.2.2
+    ttl: 64
+    destination-port: 6082
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-geneve1.netdev"),
+        r#"[NetDev]
+Name=geneve1
+Kind=geneve
+
+[GENEVE]
+Id=102
+Remote=192.0.2.2
+TTL=64
+DestinationPort=6082
+"#
+    );
+}
+
+#[test]
+fn test_networkd_gen_conf_iface_less_route() {
+    let confs = gen_networkd_conf(
+        r#"---
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    route-type: blackhole
+"#,
+    );
+    let network = get_conf(&confs, "10-nmstate-lo.network");
+
+    assert!(network.contains("Name=lo\n"));
+    assert!(network.contains("Destination=198.51.100.0/24\n"));
+    assert!(network.contains("Type=blackhole\n"));
+}
+
+#[test]
+fn test_networkd_gen_conf_vxlan_opts() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: vxlan1
+  type: vxlan
+  state: up
+  vxlan:
+    base-iface: eth1
+    id: 101
+    remote: 239.1.1.1
+    destination-port: 4789
+    source-port-min: 10000
+    source-port-max: 20000
+    ttl: 64
+    tos: 8
+    learning: false
+    ageing: 300
+    limit: 100
+    proxy: true
+    rsc: true
+    l2-miss: true
+    l3-miss: false
+    udp-checksum: true
+    gbp: true
+    fdb-remotes:
+    - 192.0.2.2
+    - 192.0.2.3
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-vxlan1.netdev"),
+        r#"[NetDev]
+Name=vxlan1
+Kind=vxlan
+
+[VXLAN]
+VNI=101
+Group=239.1.1.1
+DestinationPort=4789
+PortRange=10000-20000
+TTL=64
+TOS=8
+MacLearning=no
+FDBAgeingSec=300
+MaximumFDBEntries=100
+ReduceARPProxy=yes
+RouteShortCircuit=yes
+L2MissNotification=yes
+L3MissNotification=no
+UDPChecksum=yes
+GroupPolicyExtension=yes
+"#
+    );
+    assert!(get_conf(&confs, "10-nmstate-vxlan1.network").ends_with(
+        r#"[BridgeFDB]
+MACAddress=00:00:00:00:00:00
+Destination=192.0.2.2
+
+[BridgeFDB]
+MACAddress=00:00:00:00:00:00
+Destination=192.0.2.3
+"#
+    ));
+}
+
+#[test]
+fn test_networkd_gen_conf_vlan_opts() {
+    let confs = gen_networkd_conf(
+        r#"---
+interfaces:
+- name: eth1.20
+  type: vlan
+  state: up
+  vlan:
+    base-iface: eth1
+    id: 20
+    reorder-headers: true
+    gvrp: false
+    mvrp: true
+    loose-binding: false
+    ingress-qos-map:
+    - from: 1
+      to: 2
+    - from: 3
+      to: 4
+    egress-qos-map:
+    - from: 5
+      to: 6
+"#,
+    );
+
+    assert_eq!(
+        get_conf(&confs, "10-nmstate-eth1.20.netdev"),
+        r#"[NetDev]
+Name=eth1.20
+Kind=vlan
+
+[VLAN]
+Id=20
+EgressQOSMaps=5-6
+IngressQOSMaps=1-2 3-4
+ReorderHeader=yes
+GVRP=no
+MVRP=yes
+LooseBinding=no
+"#
+    );
+}
@@ -0,0 +1,618 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ip::AddressFamily, ErrorKind, InterfaceType, NmstateError, Routes,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+#[serde(deny_unknown_fields)]
+/// Kernel nexthop objects and nexthop groups which could be referred by
+/// [crate::RouteEntry.nexthop_id].
+/// Only supported in kernel mode, NetworkManager cannot store nexthops.
+pub struct Nexthops {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Running nexthops from all protocols.
+    /// Ignored when applying.
+    pub running: Option<Vec<NexthopEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Static nexthops from protocols boot (often used by `iproute` command)
+    /// and static.
+    ///
+    /// When applying, `None` means preserve current nexthops.
+    /// This property is not overriding but adding or replacing (when holding
+    /// the same ID) specified nexthops to existing nexthops. To delete a
+    /// nexthop, please set [NexthopEntry.state] as [NexthopState::Absent].
+    /// Any property of absent [NexthopEntry] set to `None` means wildcard.
+    /// For example, this [crate::NetworkState] could remove all nexthops
+    /// using interface eth1(showing in yaml):
+    /// ```yaml
+    /// nexthops:
+    ///   config:
+    ///   - device: eth1
+    ///     state: absent
+    /// ```
+    pub config: Option<Vec<NexthopEntry>>,
+}
+
+impl Nexthops {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_none() && self.config.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum NexthopState {
+    /// Mark a nexthop as absent to remove it.
+    Absent,
+}
+
+impl Default for NexthopState {
+    fn default() -> Self {
+        Self::Absent
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+#[serde(deny_unknown_fields)]
+/// Nexthop entry. Could be one of these:
+///  * Gateway nexthop: `device` with optional `gateway`.
+///  * Blackhole nexthop: `blackhole` set to true.
+///  * Nexthop group: `group` with optional `resilient`.
+pub struct NexthopEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Only used for delete nexthop when applying.
+    pub state: Option<NexthopState>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Nexthop ID. Mandatory for every non-absent nexthops.
+    pub id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Address family of the nexthop. Only needed for nexthop without
+    /// `gateway`, default to [AddressFamily::IPv4]. IPv6 routes can only use
+    /// IPv6 nexthops. Not allowed for nexthop group.
+    pub family: Option<AddressFamily>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The next hop IP address.
+    pub gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The next hop interface name. Mandatory for nexthop which is neither
+    /// blackhole nor group.
+    pub device: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Silently discard the packets.
+    pub blackhole: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Members of nexthop group for ECMP(Equal-Cost Multi-Path) routing.
+    /// The member should be gateway or blackhole nexthop.
+    pub group: Option<Vec<NexthopGroupMember>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Use resilient hashing for nexthop group, so flows are only
+    /// redistributed to other members when the member they use is removed.
+    /// When not defined, the nexthop group is using hash-threshold
+    /// algorithm.
+    pub resilient: Option<NexthopResilientConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+#[serde(deny_unknown_fields)]
+pub struct NexthopGroupMember {
+    #[serde(deserialize_with = "crate::deserializer::u32_or_string")]
+    /// ID of member nexthop
+    pub id: u32,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// The valid range of this property is 1-256. Default to 1.
+    pub weight: Option<u16>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+#[serde(deny_unknown_fields)]
+pub struct NexthopResilientConfig {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u16_or_string"
+    )]
+    /// Number of hash buckets. Kernel default is 128.
+    /// Cannot be changed after nexthop group created.
+    pub buckets: Option<u16>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Seconds of idle time after which a hash bucket could be migrated to
+    /// another member. Kernel default is 120.
+    pub idle_timer: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Seconds of the nexthop group being unbalanced after which busy hash
+    /// buckets are migrated also. Kernel default is 0 which means disabled.
+    pub unbalanced_timer: Option<u32>,
+}
+
+impl NexthopEntry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn is_absent(&self) -> bool {
+        matches!(self.state, Some(NexthopState::Absent))
+    }
+
+    pub(crate) fn is_group(&self) -> bool {
+        self.group.is_some()
+    }
+
+    pub(crate) fn is_blackhole(&self) -> bool {
+        self.blackhole == Some(true)
+    }
+
+    pub(crate) fn is_match(&self, other: &Self) -> bool {
+        if self.id.is_some() && self.id != other.id {
+            return false;
+        }
+        if self.family.is_some()
+            && other.family.is_some()
+            && self.family != other.family
+        {
+            return false;
+        }
+        if self.gateway.is_some() && self.gateway != other.gateway {
+            return false;
+        }
+        if self.device.is_some() && self.device != other.device {
+            return false;
+        }
+        if self.blackhole.is_some()
+            && self.is_blackhole() != other.is_blackhole()
+        {
+            return false;
+        }
+        if let Some(members) = self.group.as_ref() {
+            match other.group.as_ref() {
+                Some(other_members) => {
+                    if sorted_group_members(members)
+                        != sorted_group_members(other_members)
+                    {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+        if let Some(res_conf) = self.resilient.as_ref() {
+            match other.resilient.as_ref() {
+                Some(other_res_conf) => {
+                    if !res_conf.is_match(other_res_conf) {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    pub(crate) fn sanitize(&mut self) -> Result<(), NmstateError> {
+        if let Some(gateway) = self.gateway.as_ref() {
+            let ip = gateway.parse::<IpAddr>()?;
+            let family = if ip.is_ipv6() {
+                AddressFamily::IPv6
+            } else {
+                AddressFamily::IPv4
+            };
+            if self.family.is_some() && self.family != Some(family) {
+                return Err(self.invalid(format!(
+                    "The gateway {gateway} mismatches with the family {}",
+                    self.family.unwrap_or_default()
+                )));
+            }
+            let new_gateway = ip.to_string();
+            if gateway != &new_gateway {
+                log::warn!(
+                    "Nexthop gateway {} sanitized to {}",
+                    gateway,
+                    new_gateway
+                );
+                self.gateway = Some(new_gateway);
+            }
+            self.family = Some(family);
+        }
+        if self.is_absent() {
+            return Ok(());
+        }
+        if self.id.unwrap_or_default() == 0 {
+            return Err(self.invalid(
+                "Nexthop ID is mandatory and should be bigger than 0"
+                    .to_string(),
+            ));
+        }
+        if self.is_group() {
+            self.sanitize_group()?;
+        } else {
+            if self.resilient.is_some() {
+                return Err(self.invalid(
+                    "The resilient option is only valid for nexthop group"
+                        .to_string(),
+                ));
+            }
+            if self.is_blackhole() {
+                if self.gateway.is_some() || self.device.is_some() {
+                    return Err(self.invalid(
+                        "Blackhole nexthop cannot have gateway or device"
+                            .to_string(),
+                    ));
+                }
+            } else if self.device.as_deref().unwrap_or_default().is_empty() {
+                return Err(self.invalid(
+                    "Device is mandatory for nexthop which is neither \
+                    blackhole nor group"
+                        .to_string(),
+                ));
+            }
+            if self.family.is_none() {
+                self.family = Some(AddressFamily::IPv4);
+            }
+        }
+        Ok(())
+    }
+
+    fn sanitize_group(&mut self) -> Result<(), NmstateError> {
+        if self.gateway.is_some()
+            || self.device.is_some()
+            || self.family.is_some()
+            || self.is_blackhole()
+        {
+            return Err(self.invalid(
+                "Nexthop group cannot have gateway, device, family or \
+                blackhole"
+                    .to_string(),
+            ));
+        }
+        let members = self.group.as_deref().unwrap_or_default();
+        if members.is_empty() {
+            return Err(self.invalid(
+                "Nexthop group should have at least one member".to_string(),
+            ));
+        }
+        let mut member_ids: HashSet<u32> = HashSet::new();
+        for member in members {
+            if Some(member.id) == self.id || !member_ids.insert(member.id) {
+                return Err(self.invalid(format!(
+                    "Nexthop group member {} is duplicate or referring to \
+                    the group itself",
+                    member.id
+                )));
+            }
+            if let Some(weight) = member.weight {
+                if !(1..=256).contains(&weight) {
+                    return Err(self.invalid(format!(
+                        "Invalid nexthop group member weight {weight}, \
+                        should be in the range of 1 to 256"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn invalid(&self, msg: String) -> NmstateError {
+        let e = NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("{msg}: {self}"),
+        );
+        log::error!("{}", e);
+        e
+    }
+}
+
+// Order insensitive and treat undefined weight as 1.
+fn sorted_group_members(members: &[NexthopGroupMember]) -> Vec<(u32, u16)> {
+    let mut ret: Vec<(u32, u16)> = members
+        .iter()
+        .map(|m| (m.id, m.weight.unwrap_or(1)))
+        .collect();
+    ret.sort_unstable();
+    ret
+}
+
+impl NexthopResilientConfig {
+    fn is_match(&self, other: &Self) -> bool {
+        (self.buckets.is_none() || self.buckets == other.buckets)
+            && (self.idle_timer.is_none()
+                || self.idle_timer == other.idle_timer)
+            && (self.unbalanced_timer.is_none()
+                || self.unbalanced_timer == other.unbalanced_timer)
+    }
+}
+
+impl std::fmt::Display for NexthopEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut props = Vec::new();
+        if self.is_absent() {
+            props.push("state: absent".to_string());
+        }
+        if let Some(v) = self.id {
+            props.push(format!("id: {v}"));
+        }
+        if let Some(v) = self.family {
+            props.push(format!("family: {v}"));
+        }
+        if let Some(v) = self.gateway.as_ref() {
+            props.push(format!("gateway: {v}"));
+        }
+        if let Some(v) = self.device.as_ref() {
+            props.push(format!("device: {v}"));
+        }
+        if let Some(v) = self.blackhole {
+            props.push(format!("blackhole: {v}"));
+        }
+        if let Some(members) = self.group.as_ref() {
+            props.push(format!(
+                "group: {}",
+                members
+                    .iter()
+                    .map(|m| match m.weight {
+                        Some(w) => format!("{},{w}", m.id),
+                        None => m.id.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join("/")
+            ));
+        }
+        if let Some(res_conf) = self.resilient.as_ref() {
+            let mut res_props = Vec::new();
+            if let Some(v) = res_conf.buckets {
+                res_props.push(format!("buckets {v}"));
+            }
+            if let Some(v) = res_conf.idle_timer {
+                res_props.push(format!("idle-timer {v}"));
+            }
+            if let Some(v) = res_conf.unbalanced_timer {
+                res_props.push(format!("unbalanced-timer {v}"));
+            }
+            props.push(format!("resilient: [{}]", res_props.join(", ")));
+        }
+        write!(f, "{}", props.join(" "))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct MergedNexthops {
+    pub(crate) desired: Nexthops,
+    pub(crate) current: Nexthops,
+    // The `for_apply` will hold two type of nexthops:
+    //  * Desired nexthops not matching current
+    //  * Current nexthops been marked as absent
+    pub(crate) for_apply: Vec<NexthopEntry>,
+}
+
+impl MergedNexthops {
+    pub(crate) fn new(
+        desired: Nexthops,
+        current: Nexthops,
+    ) -> Result<Self, NmstateError> {
+        let mut des_nhs: Vec<NexthopEntry> = Vec::new();
+        for nh in desired.config.as_deref().unwrap_or_default() {
+            let mut nh = nh.clone();
+            nh.sanitize()?;
+            des_nhs.push(nh);
+        }
+        let cur_nhs = current.config.as_deref().unwrap_or_default();
+
+        let mut for_apply: Vec<NexthopEntry> = Vec::new();
+        let mut merged_nhs: Vec<&NexthopEntry> = Vec::new();
+        for cur_nh in cur_nhs {
+            if des_nhs
+                .iter()
+                .any(|nh| nh.is_absent() && nh.is_match(cur_nh))
+            {
+                if !des_nhs
+                    .iter()
+                    .any(|nh| !nh.is_absent() && nh.id == cur_nh.id)
+                {
+                    let mut nh = cur_nh.clone();
+                    nh.state = Some(NexthopState::Absent);
+                    for_apply.push(nh);
+                }
+            } else if !des_nhs
+                .iter()
+                .any(|nh| !nh.is_absent() && nh.id == cur_nh.id)
+            {
+                merged_nhs.push(cur_nh);
+            }
+        }
+
+        let mut des_ids: HashSet<u32> = HashSet::new();
+        for nh in des_nhs.iter().filter(|nh| !nh.is_absent()) {
+            if let Some(id) = nh.id {
+                if !des_ids.insert(id) {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!("Found duplicate nexthop ID {id}"),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+            merged_nhs.push(nh);
+            if !cur_nhs.iter().any(|cur_nh| nh.is_match(cur_nh)) {
+                for_apply.push(nh.clone());
+            }
+        }
+
+        for nh in des_nhs.iter().filter(|nh| !nh.is_absent()) {
+            validate_group_members(nh, merged_nhs.as_slice())?;
+        }
+
+        Ok(Self {
+            desired,
+            current,
+            for_apply,
+        })
+    }
+
+    pub(crate) fn is_changed(&self) -> bool {
+        !self.for_apply.is_empty()
+    }
+
+    // Whether specified nexthop ID exists after apply
+    pub(crate) fn has_nexthop(&self, id: u32) -> bool {
+        let is_desired = self
+            .desired
+            .config
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .any(|nh| !nh.is_absent() && nh.id == Some(id));
+        let is_removed = self
+            .for_apply
+            .iter()
+            .any(|nh| nh.is_absent() && nh.id == Some(id));
+        is_desired
+            || (!is_removed
+                && self
+                    .current
+                    .config
+                    .as_deref()
+                    .unwrap_or_default()
+                    .iter()
+                    .any(|nh| nh.id == Some(id)))
+    }
+
+    pub(crate) fn validate_routes(
+        &self,
+        routes: &Routes,
+    ) -> Result<(), NmstateError> {
+        for rt in routes.config.as_deref().unwrap_or_default() {
+            if let Some(id) = rt.nexthop_id.filter(|_| !rt.is_absent()) {
+                if !self.has_nexthop(id) {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "The nexthop {id} used by route {rt} does not \
+                            exist"
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_nexthops_to_ignored_ifaces(
+        &mut self,
+        ignored_ifaces: &[(String, InterfaceType)],
+    ) {
+        let ignored_ifaces: Vec<&str> = ignored_ifaces
+            .iter()
+            .filter(|(_, t)| !t.is_userspace())
+            .map(|(n, _)| n.as_str())
+            .collect();
+
+        self.for_apply.retain(|nh| {
+            if let Some(device) = nh.device.as_ref() {
+                !ignored_ifaces.contains(&device.as_str())
+            } else {
+                true
+            }
+        })
+    }
+
+    // Return desired nexthops not exist in current and desired absent
+    // nexthops matching current.
+    pub(crate) fn gen_diff(&self) -> Result<Nexthops, NmstateError> {
+        let cur_nhs = self.current.config.as_deref().unwrap_or_default();
+        let mut changed_nhs = Vec::new();
+        for nh in self.desired.config.as_deref().unwrap_or_default() {
+            let mut nh = nh.clone();
+            nh.sanitize()?;
+            let is_changed = if nh.is_absent() {
+                cur_nhs.iter().any(|cur_nh| nh.is_match(cur_nh))
+            } else {
+                !cur_nhs.iter().any(|cur_nh| nh.is_match(cur_nh))
+            };
+            if is_changed {
+                changed_nhs.push(nh);
+            }
+        }
+        let mut ret = Nexthops::new();
+        if !changed_nhs.is_empty() {
+            ret.config = Some(changed_nhs);
+        }
+        Ok(ret)
+    }
+}
+
+// The member of nexthop group should exist and should not be a group.
+fn validate_group_members(
+    nh: &NexthopEntry,
+    merged_nhs: &[&NexthopEntry],
+) -> Result<(), NmstateError> {
+    for member in nh.group.as_deref().unwrap_or_default() {
+        match merged_nhs.iter().find(|n| n.id == Some(member.id)) {
+            Some(member_nh) if member_nh.is_group() => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Nexthop group {} cannot use another nexthop \
+                            group {} as member",
+                        nh.id.unwrap_or_default(),
+                        member.id
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            Some(_) => (),
+            None => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The member nexthop {} of nexthop group {} does \
+                            not exist",
+                        member.id,
+                        nh.id.unwrap_or_default(),
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
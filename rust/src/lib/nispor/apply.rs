@@ -1,10 +1,32 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::convert::TryFrom;
+
+use netlink_packet_route::nlas::link::{Info, InfoKind};
+
 use crate::{
     nispor::{
+        bond::apply_bond_conf,
+        geneve::nms_geneve_iface_to_nl,
+        hsr::nms_hsr_iface_to_nl,
+        infiniband::nms_ib_iface_to_nl,
         ip::{nmstate_ipv4_to_np, nmstate_ipv6_to_np},
+        ip_tunnel::nms_ip_tunnel_iface_to_nl,
+        ip_vlan::nms_ip_vlan_iface_to_nl,
+        linux_bridge::{apply_bridge_conf, apply_bridge_port_conf},
+        mac_vlan::{nms_mac_vlan_iface_to_nl, nms_mac_vtap_iface_to_nl},
+        macsec::nms_macsec_iface_to_nl,
+        netlink::{
+            create_link, get_iface_index, nl_error_to_nmstate, run_netlink,
+        },
+        nexthop::nispor_nexthop_apply,
+        route::apply_routes,
+        route_rule::apply_route_rules,
         veth::nms_veth_conf_to_np,
-        vlan::nms_vlan_conf_to_np,
+        vlan::{apply_vlan_confs, nms_vlan_conf_to_np},
+        vrf::nms_vrf_iface_to_nl,
+        vxlan::{nispor_vxlan_fdb_apply, nms_vxlan_iface_to_nl},
+        xfrm::nms_xfrm_iface_to_nl,
     },
     ErrorKind, Interface, InterfaceType, MergedInterface, MergedInterfaces,
     MergedNetworkState, NmstateError,
@@ -32,12 +54,19 @@ pub(crate) fn nispor_apply(
         }
     });
 
+    create_ifaces(&ifaces)?;
+    apply_controller_confs(&ifaces)?;
+    apply_vlan_confs(&ifaces)?;
+
     let mut np_ifaces: Vec<nispor::IfaceConf> = Vec::new();
     for merged_iface in ifaces.iter().filter(|i| {
         i.merged.iface_type() != InterfaceType::Unknown && !i.merged.is_absent()
     }) {
         if let Some(iface) = merged_iface.for_apply.as_ref() {
-            np_ifaces.push(nmstate_iface_to_np(iface)?);
+            np_ifaces.push(nmstate_iface_to_np(
+                iface,
+                merged_iface.current.as_ref(),
+            )?);
         }
     }
 
@@ -45,13 +74,26 @@ pub(crate) fn nispor_apply(
     net_conf.ifaces = Some(np_ifaces);
 
     if let Err(e) = net_conf.apply() {
-        Err(NmstateError::new(
+        return Err(NmstateError::new(
             ErrorKind::PluginFailure,
             format!("Unknown error from nipsor plugin: {}, {}", e.kind, e.msg),
-        ))
-    } else {
-        Ok(())
+        ));
     }
+
+    apply_bridge_port_confs(&ifaces)?;
+    apply_mtus(&ifaces)?;
+    nispor_vxlan_fdb_apply(&merged_state.interfaces)?;
+
+    if merged_state.nexthops.is_changed() {
+        nispor_nexthop_apply(&merged_state.nexthops)?;
+    }
+    if merged_state.routes.is_changed() {
+        apply_routes(&merged_state.routes)?;
+    }
+    if merged_state.rules.is_changed() {
+        apply_route_rules(&merged_state.rules)?;
+    }
+    Ok(())
 }
 
 fn nmstate_iface_type_to_np(
@@ -67,8 +109,10 @@ fn nmstate_iface_type_to_np(
     }
 }
 
+// The `cur_iface` is used to remove IP addresses not desired.
 fn nmstate_iface_to_np(
     nms_iface: &Interface,
+    cur_iface: Option<&Interface>,
 ) -> Result<nispor::IfaceConf, NmstateError> {
     let mut np_iface = nispor::IfaceConf::default();
 
@@ -90,12 +134,26 @@ fn nmstate_iface_to_np(
     np_iface.state = nispor::IfaceState::Up;
 
     let base_iface = &nms_iface.base_iface();
-    if let Some(ctrl_name) = &base_iface.controller {
+    // Empty controller means detaching from controller.
+    // The HSR ports are attached when creating HSR interface.
+    if let Some(ctrl_name) = base_iface
+        .controller
+        .as_ref()
+        .filter(|c| !c.is_empty())
+        .filter(|_| base_iface.controller_type != Some(InterfaceType::Hsr))
+    {
         np_iface.controller = Some(ctrl_name.to_string())
     }
     if base_iface.can_have_ip() {
-        np_iface.ipv4 = Some(nmstate_ipv4_to_np(base_iface.ipv4.as_ref()));
-        np_iface.ipv6 = Some(nmstate_ipv6_to_np(base_iface.ipv6.as_ref()));
+        let cur_base_iface = cur_iface.map(|i| i.base_iface());
+        np_iface.ipv4 = Some(nmstate_ipv4_to_np(
+            base_iface.ipv4.as_ref(),
+            cur_base_iface.and_then(|i| i.ipv4.as_ref()),
+        ));
+        np_iface.ipv6 = Some(nmstate_ipv6_to_np(
+            base_iface.ipv6.as_ref(),
+            cur_base_iface.and_then(|i| i.ipv6.as_ref()),
+        ));
     }
 
     np_iface.mac_address = base_iface.mac_address.clone();
@@ -109,6 +167,262 @@ fn nmstate_iface_to_np(
     Ok(np_iface)
 }
 
+// Create new interfaces one by one following the order of `up_priority`, so
+// parent or controller is always created before its child or port, except
+// HSR.
+// The controller, IP and link state will be set by follow up nispor apply.
+fn create_ifaces(ifaces: &[&MergedInterface]) -> Result<(), NmstateError> {
+    let mut created_ifaces: Vec<&str> = Vec::new();
+    let mut new_ifaces: Vec<&Interface> = ifaces
+        .iter()
+        .filter(|i| i.current.is_none() && !i.merged.is_absent())
+        .filter_map(|i| i.for_apply.as_ref())
+        .collect();
+    // Unlike other controllers, HSR interface can only be created after its
+    // ports exist.
+    new_ifaces.sort_by_key(|i| i.iface_type() == InterfaceType::Hsr);
+    for iface in new_ifaces {
+        if created_ifaces.contains(&iface.name()) {
+            continue;
+        }
+        match iface {
+            Interface::LinuxBridge(_)
+            | Interface::Bond(_)
+            | Interface::Vlan(_) => create_iface_by_np(iface)?,
+            Interface::Ethernet(eth_iface) => {
+                if let Some(veth_conf) = eth_iface.veth.as_ref() {
+                    create_iface_by_np(iface)?;
+                    created_ifaces.push(veth_conf.peer.as_str());
+                }
+            }
+            Interface::Dummy(_)
+            | Interface::Vrf(_)
+            | Interface::Vxlan(_)
+            | Interface::MacVlan(_)
+            | Interface::MacVtap(_)
+            | Interface::InfiniBand(_)
+            | Interface::WireGuard(_)
+            | Interface::IpTunnel(_)
+            | Interface::Geneve(_)
+            | Interface::Macsec(_)
+            | Interface::IpVlan(_)
+            | Interface::Tun(_)
+            | Interface::Hsr(_)
+            | Interface::Xfrm(_) => {
+                run_netlink(|handle| async move {
+                    create_iface_by_nl(&handle, iface).await
+                })?;
+            }
+            // Leave other interface types to nispor
+            _ => continue,
+        }
+        created_ifaces.push(iface.name());
+    }
+    Ok(())
+}
+
+// Bond and linux bridge options are applied before attaching ports, as some
+// of them(e.g. bond mode) cannot be changed when port attached.
+fn apply_controller_confs(
+    ifaces: &[&MergedInterface],
+) -> Result<(), NmstateError> {
+    run_netlink(|handle| async move {
+        for merged_iface in ifaces.iter().filter(|i| !i.merged.is_absent()) {
+            match merged_iface.for_apply.as_ref() {
+                Some(Interface::Bond(bond_iface)) => {
+                    apply_bond_conf(&handle, merged_iface, bond_iface).await?;
+                }
+                Some(Interface::LinuxBridge(br_iface)) => {
+                    apply_bridge_conf(&handle, merged_iface, br_iface).await?;
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    })
+}
+
+fn apply_bridge_port_confs(
+    ifaces: &[&MergedInterface],
+) -> Result<(), NmstateError> {
+    run_netlink(|handle| async move {
+        for merged_iface in ifaces.iter().filter(|i| !i.merged.is_absent()) {
+            if let Some(Interface::LinuxBridge(br_iface)) =
+                merged_iface.for_apply.as_ref()
+            {
+                for port_conf in br_iface
+                    .bridge
+                    .as_ref()
+                    .and_then(|b| b.port.as_deref())
+                    .unwrap_or_default()
+                {
+                    apply_bridge_port_conf(&handle, port_conf).await?;
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+// The nispor `IfaceConf` has no MTU support. The MTU of port or child
+// interface is limited by its controller or parent, hence we set MTU of
+// non-controller interfaces first following `up_priority`.
+fn apply_mtus(ifaces: &[&MergedInterface]) -> Result<(), NmstateError> {
+    let (ctrl_ifaces, other_ifaces): (Vec<&Interface>, Vec<&Interface>) =
+        ifaces
+            .iter()
+            .filter(|i| !i.merged.is_absent())
+            .filter_map(|i| {
+                let mtu = i.for_apply.as_ref()?.base_iface().mtu;
+                if mtu.is_some()
+                    && i.current.as_ref().and_then(|c| c.base_iface().mtu)
+                        != mtu
+                {
+                    i.for_apply.as_ref()
+                } else {
+                    None
+                }
+            })
+            .partition(|i| i.is_controller());
+
+    run_netlink(|handle| async move {
+        for iface in other_ifaces.iter().chain(ctrl_ifaces.iter()) {
+            if let Some(mtu) = iface.base_iface().mtu {
+                let mtu = u32::try_from(mtu).map_err(|_| {
+                    NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Invalid MTU {mtu} of interface {}",
+                            iface.name()
+                        ),
+                    )
+                })?;
+                log::info!(
+                    "Setting MTU of interface {} to {mtu}",
+                    iface.name()
+                );
+                handle
+                    .link()
+                    .set(get_iface_index(&handle, iface.name()).await?)
+                    .mtu(mtu)
+                    .execute()
+                    .await
+                    .map_err(|e| {
+                        nl_error_to_nmstate(
+                            e,
+                            &format!("set MTU of interface {}", iface.name()),
+                        )
+                    })?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn create_iface_by_np(iface: &Interface) -> Result<(), NmstateError> {
+    let mut np_iface = nmstate_iface_to_np(iface, None)?;
+    np_iface.state = nispor::IfaceState::Down;
+    np_iface.controller = None;
+    np_iface.ipv4 = None;
+    np_iface.ipv6 = None;
+
+    let mut net_conf = nispor::NetConf::default();
+    net_conf.ifaces = Some(vec![np_iface]);
+
+    if let Err(e) = net_conf.apply() {
+        Err(NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Unknown error from nipsor plugin: {}, {}", e.kind, e.msg),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn create_iface_by_nl(
+    handle: &rtnetlink::Handle,
+    iface: &Interface,
+) -> Result<(), NmstateError> {
+    let mut parent = iface.parent();
+    let link_info = match iface {
+        Interface::Dummy(_) => vec![Info::Kind(InfoKind::Dummy)],
+        Interface::Vrf(vrf_iface) => nms_vrf_iface_to_nl(vrf_iface)?,
+        Interface::Vxlan(vxlan_iface) => {
+            // VXLAN holds its base interface in IFLA_VXLAN_LINK instead of
+            // IFLA_LINK
+            let base_iface_index = match parent.take() {
+                Some(p) => Some(get_iface_index(handle, p).await?),
+                None => None,
+            };
+            nms_vxlan_iface_to_nl(vxlan_iface, base_iface_index)?
+        }
+        Interface::IpTunnel(tunnel_iface) => {
+            // IP tunnels hold their base interface in IFLA_GRE_LINK or
+            // IFLA_IPTUN_LINK instead of IFLA_LINK
+            let base_iface_index = match parent.take() {
+                Some(p) => Some(get_iface_index(handle, p).await?),
+                None => None,
+            };
+            nms_ip_tunnel_iface_to_nl(tunnel_iface, base_iface_index)
+        }
+        Interface::Xfrm(xfrm_iface) => {
+            // XFRM holds its base interface in IFLA_XFRM_LINK instead of
+            // IFLA_LINK
+            let base_iface_index = match parent.take() {
+                Some(p) => Some(get_iface_index(handle, p).await?),
+                None => None,
+            };
+            nms_xfrm_iface_to_nl(xfrm_iface, base_iface_index)
+        }
+        Interface::Geneve(iface) => nms_geneve_iface_to_nl(iface)?,
+        Interface::Hsr(iface) => nms_hsr_iface_to_nl(handle, iface).await?,
+        Interface::Macsec(iface) => nms_macsec_iface_to_nl(iface)?,
+        Interface::MacVlan(iface) => nms_mac_vlan_iface_to_nl(iface),
+        Interface::IpVlan(iface) => nms_ip_vlan_iface_to_nl(iface),
+        Interface::MacVtap(iface) => nms_mac_vtap_iface_to_nl(iface),
+        Interface::InfiniBand(iface) => nms_ib_iface_to_nl(iface)?,
+        Interface::WireGuard(wg_iface) => {
+            // The WireGuard keys and peers are configured via generic
+            // netlink which is not supported yet.
+            if wg_iface.wireguard.is_some() {
+                return Err(NmstateError::new(
+                    ErrorKind::NotSupportedError,
+                    format!(
+                        "Configuring WireGuard keys and peers of interface \
+                        {} is not supported in kernel mode",
+                        iface.name()
+                    ),
+                ));
+            }
+            vec![Info::Kind(InfoKind::Wireguard)]
+        }
+        Interface::Tun(_) => {
+            // Kernel only support creating TUN/TAP interface via ioctl()
+            // on /dev/net/tun.
+            return Err(NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "Creating TUN/TAP interface {} is not supported in \
+                    kernel mode",
+                    iface.name()
+                ),
+            ));
+        }
+        _ => {
+            return Err(NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "Creating {} interface {} is not supported in \
+                    kernel mode",
+                    iface.iface_type(),
+                    iface.name()
+                ),
+            ));
+        }
+    };
+    create_link(handle, iface.name(), parent, link_info).await
+}
+
 fn delete_ifaces(merged_ifaces: &MergedInterfaces) -> Result<(), NmstateError> {
     let mut deleted_veths: Vec<&str> = Vec::new();
     let mut np_ifaces: Vec<nispor::IfaceConf> = Vec::new();
@@ -133,7 +447,7 @@ fn delete_ifaces(merged_ifaces: &MergedInterfaces) -> Result<(), NmstateError> {
             }
         }
         if let Some(apply_iface) = iface.for_apply.as_ref() {
-            np_ifaces.push(nmstate_iface_to_np(apply_iface)?);
+            np_ifaces.push(nmstate_iface_to_np(apply_iface, None)?);
         }
     }
 
@@ -24,6 +24,20 @@ fn np_iface_type_to_nmstate(
         nispor::IfaceType::Vrf => InterfaceType::Vrf,
         nispor::IfaceType::Vxlan => InterfaceType::Vxlan,
         nispor::IfaceType::Ipoib => InterfaceType::InfiniBand,
+        nispor::IfaceType::Tun => InterfaceType::Tun,
+        // Nispor has no dedicated type for WireGuard yet
+        nispor::IfaceType::Other(t) if t.eq_ignore_ascii_case("wireguard") => {
+            InterfaceType::WireGuard
+        }
+        // The GRETAP tunnels are reported as ethernet by nispor, they are
+        // corrected with the IP tunnel information queried via rtnetlink.
+        // Only the GRE tunnels are reported with their netlink kind.
+        nispor::IfaceType::Other(t) if t == "GreTun" || t == "GreTun6" => {
+            InterfaceType::Gre
+        }
+        nispor::IfaceType::Other(t) if t == "IpTun" => InterfaceType::IpIp,
+        nispor::IfaceType::Other(t) if t == "SitTun" => InterfaceType::Sit,
+        nispor::IfaceType::Other(t) if t == "ip6tnl" => InterfaceType::Ip6Tnl,
         _ => InterfaceType::Other(format!("{np_iface_type:?}")),
     }
 }
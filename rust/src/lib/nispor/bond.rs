@@ -1,9 +1,17 @@
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
 use log::warn;
+use netlink_packet_route::nlas::link::{Info, InfoBond, InfoData, InfoKind};
 
+use super::netlink::{
+    change_link, get_iface_index, nl_error_to_nmstate, parse_mac_address,
+};
 use crate::{
     BaseInterface, BondAdSelect, BondAllPortsActive, BondArpAllTargets,
     BondArpValidate, BondConfig, BondFailOverMac, BondInterface, BondLacpRate,
-    BondMode, BondOptions, BondPrimaryReselect, BondXmitHashPolicy,
+    BondMode, BondOptions, BondPrimaryReselect, BondXmitHashPolicy, ErrorKind,
+    Interface, MergedInterface, NmstateError,
 };
 
 pub(crate) fn np_bond_to_nmstate(
@@ -186,3 +194,241 @@ fn np_bond_options_to_nmstate(np_iface: &nispor::Iface) -> BondOptions {
     }
     options
 }
+
+pub(crate) async fn apply_bond_conf(
+    handle: &rtnetlink::Handle,
+    merged_iface: &MergedInterface,
+    bond_iface: &BondInterface,
+) -> Result<(), NmstateError> {
+    let bond_conf = if let Some(c) = bond_iface.bond.as_ref() {
+        c
+    } else {
+        return Ok(());
+    };
+    let bond_name = bond_iface.base.name.as_str();
+    let bond_index = get_iface_index(handle, bond_name).await?;
+
+    // Kernel only allows changing bond mode when bond is down and has no port
+    let mut detached_ports: Vec<&str> = Vec::new();
+    if let Some(Interface::Bond(cur_iface)) = merged_iface.current.as_ref() {
+        let cur_mode = cur_iface.bond.as_ref().and_then(|c| c.mode);
+        if bond_conf.mode.is_some() && bond_conf.mode != cur_mode {
+            for port_name in cur_iface.ports().unwrap_or_default() {
+                let port_index = get_iface_index(handle, port_name).await?;
+                handle
+                    .link()
+                    .set(port_index)
+                    .nomaster()
+                    .execute()
+                    .await
+                    .map_err(|e| {
+                        nl_error_to_nmstate(
+                            e,
+                            &format!("detach {port_name} from {bond_name}"),
+                        )
+                    })?;
+                detached_ports.push(port_name);
+            }
+            handle
+                .link()
+                .set(bond_index)
+                .down()
+                .execute()
+                .await
+                .map_err(|e| {
+                    nl_error_to_nmstate(e, &format!("deactivate {bond_name}"))
+                })?;
+        }
+    }
+
+    change_link(
+        handle,
+        bond_name,
+        nms_bond_conf_to_nl(handle, bond_conf).await?,
+    )
+    .await?;
+
+    // Attach back the ports which are still desired
+    let merged_ports = merged_iface.merged.ports().unwrap_or_default();
+    for port_name in detached_ports
+        .into_iter()
+        .filter(|p| merged_ports.contains(p))
+    {
+        let port_index = get_iface_index(handle, port_name).await?;
+        for req in [
+            handle.link().set(port_index).down(),
+            handle.link().set(port_index).master(bond_index),
+            handle.link().set(port_index).up(),
+        ] {
+            req.execute().await.map_err(|e| {
+                nl_error_to_nmstate(
+                    e,
+                    &format!("attach {port_name} to {bond_name}"),
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+async fn nms_bond_conf_to_nl(
+    handle: &rtnetlink::Handle,
+    bond_conf: &BondConfig,
+) -> Result<Vec<Info>, NmstateError> {
+    let mut nl_infos = Vec::new();
+    if let Some(mode) = bond_conf.mode.as_ref() {
+        nl_infos.push(InfoBond::Mode(match mode {
+            BondMode::RoundRobin => 0,
+            BondMode::ActiveBackup => 1,
+            BondMode::XOR => 2,
+            BondMode::Broadcast => 3,
+            BondMode::LACP => 4,
+            BondMode::TLB => 5,
+            BondMode::ALB => 6,
+            BondMode::Unknown => {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    "Cannot apply unknown bond mode".to_string(),
+                ));
+            }
+        }));
+    }
+    if let Some(opts) = bond_conf.options.as_ref() {
+        nms_bond_options_to_nl(handle, opts, &mut nl_infos).await?;
+    }
+    Ok(vec![
+        Info::Kind(InfoKind::Bond),
+        Info::Data(InfoData::Bond(nl_infos)),
+    ])
+}
+
+async fn nms_bond_options_to_nl(
+    handle: &rtnetlink::Handle,
+    opts: &BondOptions,
+    nl_infos: &mut Vec<InfoBond>,
+) -> Result<(), NmstateError> {
+    if let Some(v) = opts.ad_actor_sys_prio {
+        nl_infos.push(InfoBond::AdActorSysPrio(v));
+    }
+    if let Some(v) = opts.ad_actor_system.as_deref() {
+        nl_infos.push(InfoBond::AdActorSystem(parse_mac_address(v)?));
+    }
+    if let Some(v) = opts.ad_select {
+        nl_infos.push(InfoBond::AdSelect(match v {
+            BondAdSelect::Stable => 0,
+            BondAdSelect::Bandwidth => 1,
+            BondAdSelect::Count => 2,
+        }));
+    }
+    if let Some(v) = opts.ad_user_port_key {
+        nl_infos.push(InfoBond::AdUserPortKey(v));
+    }
+    if let Some(v) = opts.all_slaves_active {
+        nl_infos.push(InfoBond::AllSlavesActive(match v {
+            BondAllPortsActive::Dropped => 0,
+            BondAllPortsActive::Delivered => 1,
+        }));
+    }
+    if let Some(v) = opts.arp_all_targets.as_ref() {
+        nl_infos.push(InfoBond::ArpAllTargets(match v {
+            BondArpAllTargets::Any => 0,
+            BondArpAllTargets::All => 1,
+        }));
+    }
+    if let Some(v) = opts.arp_interval {
+        nl_infos.push(InfoBond::ArpInterval(v));
+    }
+    if let Some(v) = opts.arp_ip_target.as_deref() {
+        let mut targets = Vec::new();
+        for target in v.split(',').filter(|t| !t.is_empty()) {
+            targets.push(Ipv4Addr::from_str(target.trim()).map_err(|e| {
+                NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("Invalid bond arp_ip_target {target}: {e}"),
+                )
+            })?);
+        }
+        nl_infos.push(InfoBond::ArpIpTarget(targets));
+    }
+    if let Some(v) = opts.arp_validate.as_ref() {
+        nl_infos.push(InfoBond::ArpValidate(match v {
+            BondArpValidate::None => 0,
+            BondArpValidate::Active => 1,
+            BondArpValidate::Backup => 2,
+            BondArpValidate::All => 3,
+            BondArpValidate::Filter => 4,
+            BondArpValidate::FilterActive => 5,
+            BondArpValidate::FilterBackup => 6,
+        }));
+    }
+    if let Some(v) = opts.downdelay {
+        nl_infos.push(InfoBond::DownDelay(v));
+    }
+    if let Some(v) = opts.fail_over_mac {
+        nl_infos.push(InfoBond::FailOverMac(match v {
+            BondFailOverMac::None => 0,
+            BondFailOverMac::Active => 1,
+            BondFailOverMac::Follow => 2,
+        }));
+    }
+    if let Some(v) = opts.lacp_rate.as_ref() {
+        nl_infos.push(InfoBond::AdLacpRate(match v {
+            BondLacpRate::Slow => 0,
+            BondLacpRate::Fast => 1,
+        }));
+    }
+    if let Some(v) = opts.lp_interval {
+        nl_infos.push(InfoBond::LpInterval(v));
+    }
+    if let Some(v) = opts.miimon {
+        nl_infos.push(InfoBond::MiiMon(v));
+    }
+    if let Some(v) = opts.min_links {
+        nl_infos.push(InfoBond::MinLinks(v));
+    }
+    // Both `num_grat_arp` and `num_unsol_na` are the same kernel option
+    if let Some(v) = opts.num_grat_arp.or(opts.num_unsol_na) {
+        nl_infos.push(InfoBond::NumPeerNotif(v));
+    }
+    if let Some(v) = opts.packets_per_slave {
+        nl_infos.push(InfoBond::PacketsPerSlave(v));
+    }
+    if let Some(v) = opts.primary.as_deref() {
+        if !v.is_empty() {
+            nl_infos.push(InfoBond::Primary(get_iface_index(handle, v).await?));
+        }
+    }
+    if let Some(v) = opts.primary_reselect.as_ref() {
+        nl_infos.push(InfoBond::PrimaryReselect(match v {
+            BondPrimaryReselect::Always => 0,
+            BondPrimaryReselect::Better => 1,
+            BondPrimaryReselect::Failure => 2,
+        }));
+    }
+    if let Some(v) = opts.resend_igmp {
+        nl_infos.push(InfoBond::ResendIgmp(v));
+    }
+    if let Some(v) = opts.tlb_dynamic_lb {
+        nl_infos.push(InfoBond::TlbDynamicLb(v.into()));
+    }
+    if let Some(v) = opts.updelay {
+        nl_infos.push(InfoBond::UpDelay(v));
+    }
+    if let Some(v) = opts.use_carrier {
+        nl_infos.push(InfoBond::UseCarrier(v.into()));
+    }
+    if let Some(v) = opts.xmit_hash_policy {
+        nl_infos.push(InfoBond::XmitHashPolicy(match v {
+            BondXmitHashPolicy::Layer2 => 0,
+            BondXmitHashPolicy::Layer34 => 1,
+            BondXmitHashPolicy::Layer23 => 2,
+            BondXmitHashPolicy::Encap23 => 3,
+            BondXmitHashPolicy::Encap34 => 4,
+            BondXmitHashPolicy::VlanSrcMac => 5,
+        }));
+    }
+    if opts.balance_slb.is_some() {
+        warn!("Bond option balance-slb is only supported by NetworkManager");
+    }
+    Ok(())
+}
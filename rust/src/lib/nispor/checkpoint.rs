@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// There is no daemon holding the checkpoint in kernel only mode, hence we
+// store the network state retrieved before the change into a file under
+// `/run`. Rollback is done by applying the reverse change generated from it.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ErrorKind, HostNameState, Interface, InterfaceState, NetworkState,
+    NexthopEntry, NexthopState, NmstateError, RouteEntry, RouteRuleEntry,
+    RouteRuleState, RouteState,
+};
+
+const CHECKPOINT_DIR: &str = "/run/nmstate/checkpoint";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct NisporCheckpoint {
+    // Seconds since UNIX epoch
+    pub(crate) created: u64,
+    pub(crate) timeout: u32,
+    pub(crate) desired: NetworkState,
+    pub(crate) current: NetworkState,
+    // The resolv.conf backed up for rollback
+    #[serde(default)]
+    pub(crate) resolv_conf: Option<String>,
+}
+
+impl NisporCheckpoint {
+    pub(crate) fn is_expired(&self) -> bool {
+        now_secs() >= self.created + u64::from(self.timeout)
+    }
+
+    // Generate the state reverting `current` to the state stored in
+    // checkpoint. Only interfaces mentioned by desired state (including their
+    // ports and veth peers) are reverted:
+    //  * Interfaces created after checkpoint are marked as absent.
+    //  * Interfaces changed or removed are restored to full checkpoint config
+    //    including IP addresses, MTU and controller.
+    // Nexthops, routes and route rules added after checkpoint are marked as
+    // absent while the ones in checkpoint are restored.
+    pub(crate) fn gen_revert_state(
+        &self,
+        current: &NetworkState,
+    ) -> NetworkState {
+        let mut ret = NetworkState::new();
+        let old = &self.current;
+
+        let mut iface_names: Vec<&str> = Vec::new();
+        for iface in self.desired.interfaces.kernel_ifaces.values() {
+            iface_names.push(iface.name());
+            if let Some(ports) = iface.ports() {
+                iface_names.extend(ports);
+            }
+            if let Interface::Ethernet(eth_iface) = iface {
+                if let Some(veth_conf) = eth_iface.veth.as_ref() {
+                    iface_names.push(veth_conf.peer.as_str());
+                }
+            }
+        }
+        iface_names.sort_unstable();
+        iface_names.dedup();
+
+        let mut absent_ifaces: Vec<&str> = Vec::new();
+        for iface_name in iface_names {
+            if let Some(old_iface) =
+                old.interfaces.kernel_ifaces.get(iface_name)
+            {
+                ret.interfaces.push(old_iface.clone());
+            } else if let Some(cur_iface) =
+                current.interfaces.kernel_ifaces.get(iface_name)
+            {
+                let mut iface = cur_iface.clone_name_type_only();
+                iface.base_iface_mut().state = InterfaceState::Absent;
+                ret.interfaces.push(iface);
+                absent_ifaces.push(iface_name);
+            }
+        }
+
+        if self.desired.nexthops.config.is_some() {
+            ret.nexthops.config = Some(gen_revert_nexthops(old, current));
+        }
+        if self.desired.routes.config.is_some() {
+            ret.routes.config =
+                Some(gen_revert_routes(old, current, absent_ifaces.as_slice()));
+        }
+        if self.desired.rules.config.is_some() {
+            ret.rules.config = Some(gen_revert_rules(old, current));
+        }
+        if let Some(running_hostname) = old
+            .hostname
+            .as_ref()
+            .and_then(|h| h.running.as_ref())
+            .filter(|_| self.desired.hostname.is_some())
+        {
+            ret.hostname = Some(HostNameState {
+                running: Some(running_hostname.to_string()),
+                ..Default::default()
+            });
+        }
+        ret
+    }
+}
+
+fn gen_revert_nexthops(
+    old: &NetworkState,
+    current: &NetworkState,
+) -> Vec<NexthopEntry> {
+    let old_nhs = old.nexthops.config.as_deref().unwrap_or_default();
+    let mut ret = Vec::new();
+    for nh in current
+        .nexthops
+        .config
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|nh| !old_nhs.iter().any(|o| o.id == nh.id))
+    {
+        let mut nh = nh.clone();
+        nh.state = Some(NexthopState::Absent);
+        ret.push(nh);
+    }
+    ret.extend_from_slice(old_nhs);
+    ret
+}
+
+fn gen_revert_routes(
+    old: &NetworkState,
+    current: &NetworkState,
+    absent_ifaces: &[&str],
+) -> Vec<RouteEntry> {
+    let old_routes = old.routes.config.as_deref().unwrap_or_default();
+    let mut ret = Vec::new();
+    for rt in current
+        .routes
+        .config
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|rt| !old_routes.contains(rt))
+    {
+        // Routes of removed interface will be purged by kernel
+        if let Some(iface) = rt.next_hop_iface.as_deref() {
+            if absent_ifaces.contains(&iface) {
+                continue;
+            }
+        }
+        let mut rt = rt.clone();
+        rt.state = Some(RouteState::Absent);
+        ret.push(rt);
+    }
+    ret.extend_from_slice(old_routes);
+    ret
+}
+
+fn gen_revert_rules(
+    old: &NetworkState,
+    current: &NetworkState,
+) -> Vec<RouteRuleEntry> {
+    let old_rules = old.rules.config.as_deref().unwrap_or_default();
+    let mut ret = Vec::new();
+    for rule in current
+        .rules
+        .config
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|rule| !old_rules.contains(rule))
+    {
+        let mut rule = rule.clone();
+        rule.state = Some(RouteRuleState::Absent);
+        ret.push(rule);
+    }
+    ret.extend_from_slice(old_rules);
+    ret
+}
+
+pub(crate) fn is_nispor_checkpoint(checkpoint: &str) -> bool {
+    checkpoint.starts_with(CHECKPOINT_DIR)
+}
+
+pub(crate) fn nispor_checkpoint_create(
+    desired: &NetworkState,
+    current: &NetworkState,
+    timeout: u32,
+    resolv_conf: Option<&str>,
+) -> Result<String, NmstateError> {
+    if let Some(checkpoint) = nispor_checkpoint_list()?.first() {
+        return Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Checkpoint {checkpoint} is still pending, please commit or \
+                rollback it first"
+            ),
+        ));
+    }
+    let cp = NisporCheckpoint {
+        created: now_secs(),
+        timeout,
+        desired: desired.clone(),
+        current: current.clone(),
+        resolv_conf: resolv_conf.map(|p| p.to_string()),
+    };
+    let content = serde_json::to_string(&cp).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::Bug,
+            format!("Failed to serialize checkpoint: {e}"),
+        )
+    })?;
+    std::fs::create_dir_all(CHECKPOINT_DIR).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PermissionError,
+            format!("Failed to create folder {CHECKPOINT_DIR}: {e}"),
+        )
+    })?;
+    let checkpoint = format!(
+        "{CHECKPOINT_DIR}/{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+    // The stored state contains secrets
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&checkpoint)
+        .and_then(|mut fd| fd.write_all(content.as_bytes()))
+        .map_err(|e| {
+            NmstateError::new(
+                ErrorKind::PermissionError,
+                format!("Failed to write checkpoint {checkpoint}: {e}"),
+            )
+        })?;
+    Ok(checkpoint)
+}
+
+pub(crate) fn nispor_checkpoint_get(
+    checkpoint: &str,
+) -> Result<NisporCheckpoint, NmstateError> {
+    let content = std::fs::read_to_string(checkpoint).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Failed to read checkpoint {checkpoint}: {e}"),
+        )
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::Bug,
+            format!("Failed to parse checkpoint {checkpoint}: {e}"),
+        )
+    })
+}
+
+pub(crate) fn nispor_checkpoint_destroy(
+    checkpoint: &str,
+) -> Result<(), NmstateError> {
+    std::fs::remove_file(checkpoint).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Failed to remove checkpoint {checkpoint}: {e}"),
+        )
+    })
+}
+
+// Exclusive lock on the checkpoint folder, released on drop. It prevents
+// the process waiting for timeout and the user committing or rolling back
+// from working on the same checkpoint at the same time.
+// No lock is taken if the folder does not exist as there is no checkpoint.
+pub(crate) struct NisporCheckpointLock {
+    _fd: Option<File>,
+}
+
+pub(crate) fn nispor_checkpoint_lock(
+) -> Result<NisporCheckpointLock, NmstateError> {
+    let fd = match File::open(CHECKPOINT_DIR) {
+        Ok(fd) => fd,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(NisporCheckpointLock { _fd: None });
+        }
+        Err(e) => {
+            return Err(NmstateError::new(
+                ErrorKind::PermissionError,
+                format!("Failed to open folder {CHECKPOINT_DIR}: {e}"),
+            ));
+        }
+    };
+    nix::fcntl::flock(fd.as_raw_fd(), nix::fcntl::FlockArg::LockExclusive)
+        .map_err(|e| {
+            NmstateError::new(
+                ErrorKind::PermissionError,
+                format!("Failed to lock folder {CHECKPOINT_DIR}: {e}"),
+            )
+        })?;
+    Ok(NisporCheckpointLock { _fd: Some(fd) })
+}
+
+// Return sorted checkpoint list, empty if none
+pub(crate) fn nispor_checkpoint_list() -> Result<Vec<String>, NmstateError> {
+    let dir = match std::fs::read_dir(CHECKPOINT_DIR) {
+        Ok(d) => d,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Vec::new());
+        }
+        Err(e) => {
+            return Err(NmstateError::new(
+                ErrorKind::PermissionError,
+                format!("Failed to read folder {CHECKPOINT_DIR}: {e}"),
+            ));
+        }
+    };
+    let mut ret: Vec<String> = dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().display().to_string())
+        .collect();
+    ret.sort_unstable();
+    Ok(ret)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Without NetworkManager, the DNS resolver config is stored directly in
+// resolv.conf. Lines other than `nameserver`, `search` and `domain` (e.g.
+// `options`) are preserved when writing.
+
+use crate::{
+    DnsClientState, DnsState, ErrorKind, MergedDnsState, NmstateError,
+};
+
+pub(crate) const DEFAULT_RESOLV_CONF_PATH: &str = "/etc/resolv.conf";
+
+const BACKUP_FILE_POSTFIX: &str = ".nmstate-backup";
+const RESOLV_CONF_HEADER: &str = "# Generated by nmstate";
+// The MAXNS of glibc
+const MAX_NAME_SERVERS: usize = 3;
+
+pub(crate) fn nispor_dns_retrieve(path: &str) -> DnsState {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::error!("Failed to read {}: {}", path, e);
+            }
+            return DnsState::new();
+        }
+    };
+    let conf = parse_resolv_conf(&content);
+    DnsState {
+        running: Some(conf.clone()),
+        config: Some(conf),
+    }
+}
+
+pub(crate) fn parse_resolv_conf(content: &str) -> DnsClientState {
+    let mut servers: Vec<String> = Vec::new();
+    let mut searches: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("nameserver") => {
+                if let Some(srv) = words.next() {
+                    servers.push(srv.to_string());
+                }
+            }
+            // The `search` and `domain` are mutually exclusive, the last
+            // instance wins.
+            Some("search") | Some("domain") => {
+                searches = words.map(|w| w.to_string()).collect();
+            }
+            _ => (),
+        }
+    }
+    let mut ret = DnsClientState::new();
+    if !servers.is_empty() {
+        ret.server = Some(servers);
+    }
+    if !searches.is_empty() {
+        ret.search = Some(searches);
+    }
+    ret
+}
+
+pub(crate) fn gen_resolv_conf(
+    servers: &[String],
+    searches: &[String],
+    old_content: &str,
+) -> String {
+    let mut lines = vec![RESOLV_CONF_HEADER.to_string()];
+    if !searches.is_empty() {
+        lines.push(format!("search {}", searches.join(" ")));
+    }
+    for srv in servers {
+        lines.push(format!("nameserver {srv}"));
+    }
+    for line in old_content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with(';')
+        {
+            continue;
+        }
+        if !matches!(
+            trimmed.split_whitespace().next(),
+            Some("nameserver") | Some("search") | Some("domain")
+        ) {
+            lines.push(line.to_string());
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+pub(crate) fn nispor_dns_apply(
+    merged_dns: &MergedDnsState,
+    path: &str,
+) -> Result<(), NmstateError> {
+    if !merged_dns.is_changed() {
+        return Ok(());
+    }
+    if merged_dns.servers.len() > MAX_NAME_SERVERS {
+        log::warn!(
+            "Only the first {} name servers will be used by glibc resolver",
+            MAX_NAME_SERVERS
+        );
+    }
+    let old_content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(NmstateError::new(
+                ErrorKind::PermissionError,
+                format!("Failed to read {path}: {e}"),
+            ));
+        }
+    };
+    let content = gen_resolv_conf(
+        merged_dns.servers.as_slice(),
+        merged_dns.searches.as_slice(),
+        &old_content,
+    );
+    log::info!("Writing DNS config to {}", path);
+    std::fs::write(path, content).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PermissionError,
+            format!("Failed to write {path}: {e}"),
+        )
+    })
+}
+
+// The backup file only exists when resolv.conf exists, so rollback could
+// remove the resolv.conf created by us.
+pub(crate) fn nispor_dns_backup(path: &str) -> Result<(), NmstateError> {
+    let backup_path = format!("{path}{BACKUP_FILE_POSTFIX}");
+    let result = if std::path::Path::new(path).exists() {
+        std::fs::copy(path, &backup_path).map(|_| ())
+    } else {
+        remove_file_if_exists(&backup_path)
+    };
+    result.map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PermissionError,
+            format!("Failed to backup {path} to {backup_path}: {e}"),
+        )
+    })
+}
+
+// Write the backup content instead of renaming, so a symbolic link of
+// resolv.conf is kept and its target restored.
+pub(crate) fn nispor_dns_restore(path: &str) -> Result<(), NmstateError> {
+    let backup_path = format!("{path}{BACKUP_FILE_POSTFIX}");
+    let result = if std::path::Path::new(&backup_path).exists() {
+        log::info!("Restoring {} from {}", path, backup_path);
+        std::fs::read(&backup_path)
+            .and_then(|content| std::fs::write(path, content))
+            .and_then(|_| std::fs::remove_file(&backup_path))
+    } else {
+        remove_file_if_exists(path)
+    };
+    result.map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PermissionError,
+            format!("Failed to restore {path} from {backup_path}: {e}"),
+        )
+    })
+}
+
+pub(crate) fn nispor_dns_remove_backup(path: &str) -> Result<(), NmstateError> {
+    let backup_path = format!("{path}{BACKUP_FILE_POSTFIX}");
+    remove_file_if_exists(&backup_path).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PermissionError,
+            format!("Failed to remove {backup_path}: {e}"),
+        )
+    })
+}
+
+fn remove_file_if_exists(path: &str) -> Result<(), std::io::Error> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Nispor 1.2 reports Geneve interfaces as ethernet without Geneve
+// configuration, hence we query the kernel via rtnetlink directly.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoKind, Nla as LinkNla},
+    LinkMessage,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, NlasIterator},
+    Emitable,
+};
+
+use super::netlink::{ip_to_nl_bytes, nl_bytes_to_ip};
+use crate::{ErrorKind, GeneveConfig, GeneveInterface, NmstateError};
+
+const IFLA_GENEVE_ID: u16 = 1;
+const IFLA_GENEVE_REMOTE: u16 = 2;
+const IFLA_GENEVE_TTL: u16 = 3;
+const IFLA_GENEVE_TOS: u16 = 4;
+const IFLA_GENEVE_PORT: u16 = 5;
+const IFLA_GENEVE_COLLECT_METADATA: u16 = 6;
+const IFLA_GENEVE_REMOTE6: u16 = 7;
+
+const GENEVE_KIND: &str = "geneve";
+
+// Return interface name to Geneve config of all Geneve interfaces in kernel.
+pub(crate) fn get_geneves(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, GeneveConfig> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        for nla in link.nlas.iter() {
+            if let LinkNla::Info(infos) = nla {
+                if let Some(conf) = nl_info_to_geneve(infos) {
+                    ret.insert(name.to_string(), conf);
+                }
+            }
+        }
+    }
+    ret
+}
+
+fn nl_info_to_geneve(infos: &[Info]) -> Option<GeneveConfig> {
+    if !infos.iter().any(
+        |i| matches!(i, Info::Kind(InfoKind::Other(k)) if k == GENEVE_KIND),
+    ) {
+        return None;
+    }
+    let mut conf = GeneveConfig::default();
+    for info in infos {
+        if let Info::Data(InfoData::Other(data)) = info {
+            for nla in NlasIterator::new(data).flatten() {
+                let value = nla.value();
+                match nla.kind() {
+                    IFLA_GENEVE_ID => {
+                        conf.id = <[u8; 4]>::try_from(value)
+                            .ok()
+                            .map(u32::from_ne_bytes);
+                    }
+                    IFLA_GENEVE_REMOTE | IFLA_GENEVE_REMOTE6 => {
+                        if let Some(ip) = nl_bytes_to_ip(value) {
+                            conf.remote = Some(ip);
+                        }
+                    }
+                    IFLA_GENEVE_TTL => conf.ttl = value.first().copied(),
+                    IFLA_GENEVE_TOS => conf.tos = value.first().copied(),
+                    IFLA_GENEVE_PORT => {
+                        conf.dst_port = <[u8; 2]>::try_from(value)
+                            .ok()
+                            .map(u16::from_be_bytes);
+                    }
+                    IFLA_GENEVE_COLLECT_METADATA => {
+                        conf.external = Some(true);
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    if conf.external == Some(true) {
+        // Kernel report these as 0 in external mode
+        conf.id = None;
+        conf.ttl = None;
+        conf.tos = None;
+    } else {
+        conf.external = Some(false);
+    }
+    Some(conf)
+}
+
+pub(crate) fn nms_geneve_iface_to_nl(
+    iface: &GeneveInterface,
+) -> Result<Vec<Info>, NmstateError> {
+    let conf = iface.geneve.clone().unwrap_or_default();
+    let mut nlas: Vec<DefaultNla> = Vec::new();
+    if conf.external == Some(true) {
+        nlas.push(DefaultNla::new(IFLA_GENEVE_COLLECT_METADATA, Vec::new()));
+    } else {
+        let id = match conf.id {
+            Some(i) => i,
+            None => {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Geneve ID undefined for new Geneve interface {}",
+                        iface.base.name
+                    ),
+                ));
+            }
+        };
+        nlas.push(DefaultNla::new(IFLA_GENEVE_ID, id.to_ne_bytes().to_vec()));
+    }
+    if let Some(remote) = conf.remote.as_ref() {
+        nlas.push(DefaultNla::new(
+            match remote {
+                IpAddr::V4(_) => IFLA_GENEVE_REMOTE,
+                IpAddr::V6(_) => IFLA_GENEVE_REMOTE6,
+            },
+            ip_to_nl_bytes(remote),
+        ));
+    }
+    if let Some(ttl) = conf.ttl {
+        nlas.push(DefaultNla::new(IFLA_GENEVE_TTL, vec![ttl]));
+    }
+    if let Some(tos) = conf.tos {
+        nlas.push(DefaultNla::new(IFLA_GENEVE_TOS, vec![tos]));
+    }
+    if let Some(port) = conf.dst_port {
+        nlas.push(DefaultNla::new(
+            IFLA_GENEVE_PORT,
+            port.to_be_bytes().to_vec(),
+        ));
+    }
+    let mut data = vec![0u8; nlas.as_slice().buffer_len()];
+    nlas.as_slice().emit(&mut data);
+
+    Ok(vec![
+        Info::Kind(InfoKind::Other(GENEVE_KIND.to_string())),
+        Info::Data(InfoData::Other(data)),
+    ])
+}
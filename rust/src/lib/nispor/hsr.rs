@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Nispor 1.2 has no HSR support, hence we query the kernel via rtnetlink
+// directly.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoKind, Nla as LinkNla},
+    LinkMessage,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, NlasIterator},
+    Emitable,
+};
+
+use super::netlink::get_iface_index;
+use crate::{HsrConfig, HsrInterface, HsrProtocol, NmstateError};
+
+const IFLA_HSR_SLAVE1: u16 = 1;
+const IFLA_HSR_SLAVE2: u16 = 2;
+const IFLA_HSR_MULTICAST_SPEC: u16 = 3;
+const IFLA_HSR_SUPERVISION_ADDR: u16 = 4;
+const IFLA_HSR_VERSION: u16 = 6;
+const IFLA_HSR_PROTOCOL: u16 = 7;
+
+const HSR_PROTOCOL_HSR: u8 = 0;
+const HSR_PROTOCOL_PRP: u8 = 1;
+
+const HSR_KIND: &str = "hsr";
+
+// Return interface name to HSR config of all HSR interfaces in kernel.
+pub(crate) fn get_hsrs(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, HsrConfig> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        for nla in link.nlas.iter() {
+            if let LinkNla::Info(infos) = nla {
+                if let Some(conf) = nl_info_to_hsr(infos, index_to_name) {
+                    ret.insert(name.to_string(), conf);
+                }
+            }
+        }
+    }
+    ret
+}
+
+fn nl_info_to_hsr(
+    infos: &[Info],
+    index_to_name: &HashMap<u32, String>,
+) -> Option<HsrConfig> {
+    if !infos
+        .iter()
+        .any(|i| matches!(i, Info::Kind(InfoKind::Other(k)) if k == HSR_KIND))
+    {
+        return None;
+    }
+    let mut conf = HsrConfig::default();
+    for info in infos {
+        if let Info::Data(InfoData::Other(data)) = info {
+            for nla in NlasIterator::new(data).flatten() {
+                let value = nla.value();
+                match nla.kind() {
+                    IFLA_HSR_SLAVE1 | IFLA_HSR_SLAVE2 => {
+                        let port = <[u8; 4]>::try_from(value)
+                            .ok()
+                            .map(u32::from_ne_bytes)
+                            .and_then(|i| index_to_name.get(&i))
+                            .cloned()
+                            .unwrap_or_default();
+                        if nla.kind() == IFLA_HSR_SLAVE1 {
+                            conf.port1 = port;
+                        } else {
+                            conf.port2 = port;
+                        }
+                    }
+                    IFLA_HSR_SUPERVISION_ADDR if value.len() == 6 => {
+                        conf.set_multicast_spec(value[5]);
+                    }
+                    IFLA_HSR_VERSION => conf.version = value.first().copied(),
+                    IFLA_HSR_PROTOCOL => {
+                        conf.protocol = match value.first() {
+                            Some(&HSR_PROTOCOL_HSR) => Some(HsrProtocol::Hsr),
+                            Some(&HSR_PROTOCOL_PRP) => Some(HsrProtocol::Prp),
+                            _ => None,
+                        };
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    // Kernel does not allow changing version of PRP
+    if conf.protocol == Some(HsrProtocol::Prp) {
+        conf.version = None;
+    }
+    Some(conf)
+}
+
+// The kernel requires both ports exist before creating HSR interface.
+pub(crate) async fn nms_hsr_iface_to_nl(
+    handle: &rtnetlink::Handle,
+    iface: &HsrInterface,
+) -> Result<Vec<Info>, NmstateError> {
+    let conf = iface.hsr.clone().unwrap_or_default();
+    let mut nlas: Vec<DefaultNla> = vec![
+        DefaultNla::new(
+            IFLA_HSR_SLAVE1,
+            get_iface_index(handle, conf.port1.as_str())
+                .await?
+                .to_ne_bytes()
+                .to_vec(),
+        ),
+        DefaultNla::new(
+            IFLA_HSR_SLAVE2,
+            get_iface_index(handle, conf.port2.as_str())
+                .await?
+                .to_ne_bytes()
+                .to_vec(),
+        ),
+    ];
+    if let Some(multicast_spec) = conf.multicast_spec() {
+        nlas.push(DefaultNla::new(
+            IFLA_HSR_MULTICAST_SPEC,
+            vec![multicast_spec],
+        ));
+    }
+    match conf.protocol.unwrap_or_default() {
+        HsrProtocol::Hsr => {
+            if let Some(version) = conf.version {
+                nlas.push(DefaultNla::new(IFLA_HSR_VERSION, vec![version]));
+            }
+        }
+        HsrProtocol::Prp => {
+            nlas.push(DefaultNla::new(
+                IFLA_HSR_PROTOCOL,
+                vec![HSR_PROTOCOL_PRP],
+            ));
+        }
+    }
+    let mut data = vec![0u8; nlas.as_slice().buffer_len()];
+    nlas.as_slice().emit(&mut data);
+
+    Ok(vec![
+        Info::Kind(InfoKind::Other(HSR_KIND.to_string())),
+        Info::Data(InfoData::Other(data)),
+    ])
+}
@@ -1,7 +1,13 @@
+use netlink_packet_route::nlas::link::{Info, InfoData, InfoIpoib, InfoKind};
+
 use crate::{
-    BaseInterface, InfiniBandConfig, InfiniBandInterface, InfiniBandMode,
+    BaseInterface, ErrorKind, InfiniBandConfig, InfiniBandInterface,
+    InfiniBandMode, NmstateError,
 };
 
+const IPOIB_MODE_DATAGRAM: u16 = 0;
+const IPOIB_MODE_CONNECTED: u16 = 1;
+
 impl From<nispor::IpoibMode> for InfiniBandMode {
     fn from(m: nispor::IpoibMode) -> Self {
         match m {
@@ -30,3 +36,37 @@ pub(crate) fn np_ib_to_nmstate(
         ib: ib_conf,
     }
 }
+
+// Only the IPoIB pkey child could be created, the base InfiniBand interface
+// is created by kernel driver.
+pub(crate) fn nms_ib_iface_to_nl(
+    iface: &InfiniBandInterface,
+) -> Result<Vec<Info>, NmstateError> {
+    let (mode, pkey) = match iface.ib.as_ref() {
+        Some(InfiniBandConfig {
+            mode,
+            base_iface: Some(_),
+            pkey: Some(pkey),
+        }) if *pkey != 0xffff => (mode, *pkey),
+        _ => {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Cannot create InfiniBand interface {} without \
+                    base-iface and pkey",
+                    iface.base.name
+                ),
+            ));
+        }
+    };
+    Ok(vec![
+        Info::Kind(InfoKind::Ipoib),
+        Info::Data(InfoData::Ipoib(vec![
+            InfoIpoib::Pkey(pkey),
+            InfoIpoib::Mode(match mode {
+                InfiniBandMode::Datagram => IPOIB_MODE_DATAGRAM,
+                InfiniBandMode::Connected => IPOIB_MODE_CONNECTED,
+            }),
+        ])),
+    ])
+}
@@ -1,10 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::net::IpAddr;
 use std::str::FromStr;
 
 use crate::{
-    nispor::mptcp::get_mptcp_flags, InterfaceIpAddr, InterfaceIpv4,
-    InterfaceIpv6,
+    ip::is_ipv6_unicast_link_local, nispor::mptcp::get_mptcp_flags,
+    InterfaceIpAddr, InterfaceIpv4, InterfaceIpv6,
 };
 
 pub(crate) fn np_ipv4_to_nmstate(
@@ -120,16 +121,30 @@ pub(crate) fn np_ipv6_to_nmstate(
 
 pub(crate) fn nmstate_ipv4_to_np(
     nms_ipv4: Option<&InterfaceIpv4>,
+    cur_ipv4: Option<&InterfaceIpv4>,
 ) -> nispor::IpConf {
     let mut np_ip_conf = nispor::IpConf::default();
     if let Some(nms_ipv4) = nms_ipv4 {
-        for nms_addr in nms_ipv4.addresses.as_deref().unwrap_or_default() {
-            np_ip_conf.addresses.push({
-                let mut ip_conf = nispor::IpAddrConf::default();
-                ip_conf.address = nms_addr.ip.to_string();
-                ip_conf.prefix_len = nms_addr.prefix_length;
-                ip_conf
-            });
+        let addrs = nms_ipv4.addresses.as_deref().unwrap_or_default();
+        for nms_addr in addrs {
+            np_ip_conf
+                .addresses
+                .push(nms_ip_addr_to_np(nms_addr, false));
+        }
+        // Remove static addresses not desired. Nispor stops processing on
+        // address already removed(e.g. secondary address removed along with
+        // primary), hence removal is placed after addition.
+        if !nms_ipv4.is_auto()
+            && (!nms_ipv4.enabled || nms_ipv4.addresses.is_some())
+        {
+            for cur_addr in cur_ipv4
+                .and_then(|i| i.addresses.as_deref())
+                .unwrap_or_default()
+                .iter()
+                .filter(|a| !nms_ipv4.enabled || !contains_ip_addr(addrs, a))
+            {
+                np_ip_conf.addresses.push(nms_ip_addr_to_np(cur_addr, true));
+            }
         }
     }
     np_ip_conf
@@ -137,17 +152,52 @@ pub(crate) fn nmstate_ipv4_to_np(
 
 pub(crate) fn nmstate_ipv6_to_np(
     nms_ipv6: Option<&InterfaceIpv6>,
+    cur_ipv6: Option<&InterfaceIpv6>,
 ) -> nispor::IpConf {
     let mut np_ip_conf = nispor::IpConf::default();
     if let Some(nms_ipv6) = nms_ipv6 {
-        for nms_addr in nms_ipv6.addresses.as_deref().unwrap_or_default() {
-            np_ip_conf.addresses.push({
-                let mut ip_conf = nispor::IpAddrConf::default();
-                ip_conf.address = nms_addr.ip.to_string();
-                ip_conf.prefix_len = nms_addr.prefix_length;
-                ip_conf
-            });
+        let addrs = nms_ipv6.addresses.as_deref().unwrap_or_default();
+        for nms_addr in addrs {
+            np_ip_conf
+                .addresses
+                .push(nms_ip_addr_to_np(nms_addr, false));
+        }
+        // Remove static addresses not desired after addition, IPv6 link
+        // local address is managed by kernel.
+        if !nms_ipv6.is_auto()
+            && (!nms_ipv6.enabled || nms_ipv6.addresses.is_some())
+        {
+            for cur_addr in cur_ipv6
+                .and_then(|i| i.addresses.as_deref())
+                .unwrap_or_default()
+                .iter()
+                .filter(|a| match a.ip {
+                    IpAddr::V6(ip) => !is_ipv6_unicast_link_local(&ip),
+                    IpAddr::V4(_) => false,
+                })
+                .filter(|a| !nms_ipv6.enabled || !contains_ip_addr(addrs, a))
+            {
+                np_ip_conf.addresses.push(nms_ip_addr_to_np(cur_addr, true));
+            }
         }
     }
     np_ip_conf
 }
+
+// The MPTCP flags are ignored
+fn contains_ip_addr(addrs: &[InterfaceIpAddr], addr: &InterfaceIpAddr) -> bool {
+    addrs
+        .iter()
+        .any(|a| a.ip == addr.ip && a.prefix_length == addr.prefix_length)
+}
+
+fn nms_ip_addr_to_np(
+    nms_addr: &InterfaceIpAddr,
+    remove: bool,
+) -> nispor::IpAddrConf {
+    let mut ip_conf = nispor::IpAddrConf::default();
+    ip_conf.address = nms_addr.ip.to_string();
+    ip_conf.prefix_len = nms_addr.prefix_length;
+    ip_conf.remove = remove;
+    ip_conf
+}
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Nispor 1.2 does not expose the IP tunnel configuration and reports GRETAP
+// tunnels as ethernet, hence we query the kernel via rtnetlink directly.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoKind, Nla as LinkNla},
+    LinkMessage,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, NlasIterator},
+    Emitable,
+};
+
+use super::netlink::{ip_to_nl_bytes, nl_bytes_to_ip};
+use crate::{InterfaceType, IpTunnelConfig, IpTunnelInterface};
+
+const IFLA_GRE_LINK: u16 = 1;
+const IFLA_GRE_IFLAGS: u16 = 2;
+const IFLA_GRE_OFLAGS: u16 = 3;
+const IFLA_GRE_IKEY: u16 = 4;
+const IFLA_GRE_OKEY: u16 = 5;
+const IFLA_GRE_LOCAL: u16 = 6;
+const IFLA_GRE_REMOTE: u16 = 7;
+const IFLA_GRE_TTL: u16 = 8;
+const IFLA_GRE_TOS: u16 = 9;
+const IFLA_GRE_PMTUDISC: u16 = 10;
+
+const IFLA_IPTUN_LINK: u16 = 1;
+const IFLA_IPTUN_LOCAL: u16 = 2;
+const IFLA_IPTUN_REMOTE: u16 = 3;
+const IFLA_IPTUN_TTL: u16 = 4;
+const IFLA_IPTUN_TOS: u16 = 5;
+const IFLA_IPTUN_PMTUDISC: u16 = 10;
+
+// The GRE_KEY flag in network byte order
+const GRE_KEY: [u8; 2] = [0x20, 0x00];
+
+const IP6TNL_KIND: &str = "ip6tnl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NlTunnelFamily {
+    Gre,
+    IpTun,
+}
+
+// Return interface name to (interface type, tunnel config) of all IP
+// tunnels in kernel.
+pub(crate) fn get_ip_tunnels(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, (InterfaceType, IpTunnelConfig)> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        for nla in link.nlas.iter() {
+            if let LinkNla::Info(infos) = nla {
+                if let Some((iface_type, conf)) =
+                    nl_info_to_ip_tunnel(infos, index_to_name)
+                {
+                    ret.insert(name.to_string(), (iface_type, conf));
+                }
+            }
+        }
+    }
+    ret
+}
+
+fn nl_info_to_ip_tunnel(
+    infos: &[Info],
+    index_to_name: &HashMap<u32, String>,
+) -> Option<(InterfaceType, IpTunnelConfig)> {
+    let mut iface_type = None;
+    let mut data: &[u8] = &[];
+    for info in infos {
+        match info {
+            Info::Kind(kind) => {
+                iface_type = match kind {
+                    InfoKind::GreTun | InfoKind::GreTun6 => {
+                        Some((InterfaceType::Gre, NlTunnelFamily::Gre))
+                    }
+                    InfoKind::GreTap | InfoKind::GreTap6 => {
+                        Some((InterfaceType::GreTap, NlTunnelFamily::Gre))
+                    }
+                    InfoKind::IpTun => {
+                        Some((InterfaceType::IpIp, NlTunnelFamily::IpTun))
+                    }
+                    InfoKind::SitTun => {
+                        Some((InterfaceType::Sit, NlTunnelFamily::IpTun))
+                    }
+                    InfoKind::Other(s) if s == IP6TNL_KIND => {
+                        Some((InterfaceType::Ip6Tnl, NlTunnelFamily::IpTun))
+                    }
+                    _ => None,
+                };
+            }
+            Info::Data(
+                InfoData::GreTun(d)
+                | InfoData::GreTun6(d)
+                | InfoData::GreTap(d)
+                | InfoData::GreTap6(d)
+                | InfoData::IpTun(d)
+                | InfoData::SitTun(d)
+                | InfoData::Other(d),
+            ) => {
+                data = d.as_slice();
+            }
+            _ => (),
+        }
+    }
+    let (iface_type, family) = iface_type?;
+    let is_ipv6 = matches!(
+        infos.iter().find(|i| matches!(i, Info::Kind(_))),
+        Some(Info::Kind(
+            InfoKind::GreTun6 | InfoKind::GreTap6 | InfoKind::Other(_)
+        ))
+    );
+
+    let mut conf = IpTunnelConfig::default();
+    let mut has_key = false;
+    let mut key = 0u32;
+    for nla in NlasIterator::new(data).flatten() {
+        let value = nla.value();
+        match (family, nla.kind()) {
+            (NlTunnelFamily::Gre, IFLA_GRE_LINK)
+            | (NlTunnelFamily::IpTun, IFLA_IPTUN_LINK) => {
+                conf.base_iface = parse_u32(value)
+                    .and_then(|i| index_to_name.get(&i))
+                    .cloned();
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_LOCAL)
+            | (NlTunnelFamily::IpTun, IFLA_IPTUN_LOCAL) => {
+                conf.local = nl_bytes_to_ip(value);
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_REMOTE)
+            | (NlTunnelFamily::IpTun, IFLA_IPTUN_REMOTE) => {
+                conf.remote = nl_bytes_to_ip(value);
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_TTL)
+            | (NlTunnelFamily::IpTun, IFLA_IPTUN_TTL) => {
+                conf.ttl = value.first().copied();
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_TOS)
+            | (NlTunnelFamily::IpTun, IFLA_IPTUN_TOS)
+                if !is_ipv6 =>
+            {
+                conf.tos = value.first().copied();
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_PMTUDISC)
+            | (NlTunnelFamily::IpTun, IFLA_IPTUN_PMTUDISC)
+                if !is_ipv6 =>
+            {
+                conf.pmtudisc = value.first().map(|v| *v > 0);
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_IFLAGS) => {
+                has_key = value.len() >= 2 && (value[0] & GRE_KEY[0]) > 0;
+            }
+            (NlTunnelFamily::Gre, IFLA_GRE_IKEY) => {
+                key = <[u8; 4]>::try_from(value)
+                    .map(u32::from_be_bytes)
+                    .unwrap_or_default();
+            }
+            _ => (),
+        }
+    }
+    if has_key {
+        conf.key = Some(key);
+    }
+    Some((iface_type, conf))
+}
+
+fn parse_u32(value: &[u8]) -> Option<u32> {
+    <[u8; 4]>::try_from(value)
+        .ok()
+        .map(u32::from_ne_bytes)
+        .filter(|i| *i > 0)
+}
+
+// The `base_iface_index` should be the kernel index of `base-iface` property.
+pub(crate) fn nms_ip_tunnel_iface_to_nl(
+    iface: &IpTunnelInterface,
+    base_iface_index: Option<u32>,
+) -> Vec<Info> {
+    let is_ipv6 = iface.is_ipv6();
+    let family = match iface.base.iface_type {
+        InterfaceType::IpIp | InterfaceType::Sit | InterfaceType::Ip6Tnl => {
+            NlTunnelFamily::IpTun
+        }
+        _ => NlTunnelFamily::Gre,
+    };
+    let mut nlas: Vec<DefaultNla> = Vec::new();
+    if let Some(index) = base_iface_index {
+        nlas.push(DefaultNla::new(
+            if family == NlTunnelFamily::Gre {
+                IFLA_GRE_LINK
+            } else {
+                IFLA_IPTUN_LINK
+            },
+            index.to_ne_bytes().to_vec(),
+        ));
+    }
+    if let Some(conf) = iface.ip_tunnel.as_ref() {
+        let (local, remote, ttl, tos, pmtudisc) = match family {
+            NlTunnelFamily::Gre => (
+                IFLA_GRE_LOCAL,
+                IFLA_GRE_REMOTE,
+                IFLA_GRE_TTL,
+                IFLA_GRE_TOS,
+                IFLA_GRE_PMTUDISC,
+            ),
+            NlTunnelFamily::IpTun => (
+                IFLA_IPTUN_LOCAL,
+                IFLA_IPTUN_REMOTE,
+                IFLA_IPTUN_TTL,
+                IFLA_IPTUN_TOS,
+                IFLA_IPTUN_PMTUDISC,
+            ),
+        };
+        if let Some(v) = conf.local.as_ref() {
+            nlas.push(DefaultNla::new(local, ip_to_nl_bytes(v)));
+        }
+        if let Some(v) = conf.remote.as_ref() {
+            nlas.push(DefaultNla::new(remote, ip_to_nl_bytes(v)));
+        }
+        if let Some(v) = conf.ttl {
+            nlas.push(DefaultNla::new(ttl, vec![v]));
+        }
+        // The IPv6 tunnels(e.g. ip6tnl) use the same attribute IDs for
+        // other purpose, only IPv4 tunnels support TOS and PMTU discovery.
+        if !is_ipv6 {
+            if let Some(v) = conf.tos {
+                nlas.push(DefaultNla::new(tos, vec![v]));
+            }
+            if let Some(v) = conf.pmtudisc {
+                nlas.push(DefaultNla::new(pmtudisc, vec![v.into()]));
+            }
+        }
+        if let Some(key) = conf.key {
+            for flag_kind in [IFLA_GRE_IFLAGS, IFLA_GRE_OFLAGS] {
+                nlas.push(DefaultNla::new(flag_kind, GRE_KEY.to_vec()));
+            }
+            for key_kind in [IFLA_GRE_IKEY, IFLA_GRE_OKEY] {
+                nlas.push(DefaultNla::new(
+                    key_kind,
+                    key.to_be_bytes().to_vec(),
+                ));
+            }
+        }
+    }
+    let mut data = vec![0u8; nlas.as_slice().buffer_len()];
+    nlas.as_slice().emit(&mut data);
+
+    let (kind, data) = match iface.base.iface_type {
+        InterfaceType::GreTap if is_ipv6 => {
+            (InfoKind::GreTap6, InfoData::GreTap6(data))
+        }
+        InterfaceType::GreTap => (InfoKind::GreTap, InfoData::GreTap(data)),
+        InterfaceType::IpIp => (InfoKind::IpTun, InfoData::IpTun(data)),
+        InterfaceType::Sit => (InfoKind::SitTun, InfoData::SitTun(data)),
+        InterfaceType::Ip6Tnl => (
+            InfoKind::Other(IP6TNL_KIND.to_string()),
+            InfoData::Other(data),
+        ),
+        _ if is_ipv6 => (InfoKind::GreTun6, InfoData::GreTun6(data)),
+        _ => (InfoKind::GreTun, InfoData::GreTun(data)),
+    };
+    vec![Info::Kind(kind), Info::Data(data)]
+}
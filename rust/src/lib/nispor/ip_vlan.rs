@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Nispor 1.2 reports IP VLAN interfaces as ethernet without IP VLAN
+// configuration, hence we query the kernel via rtnetlink directly.
+
+use std::collections::HashMap;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoIpVlan, InfoKind, Nla as LinkNla},
+    LinkMessage,
+};
+
+use crate::{IpVlanConfig, IpVlanInterface, IpVlanMode};
+
+const IPVLAN_MODE_L2: u16 = 0;
+const IPVLAN_MODE_L3: u16 = 1;
+const IPVLAN_MODE_L3S: u16 = 2;
+
+const IPVLAN_F_PRIVATE: u16 = 0x01;
+const IPVLAN_F_VEPA: u16 = 0x02;
+
+// Return interface name to IP VLAN config of all IP VLAN interfaces in kernel.
+pub(crate) fn get_ip_vlans(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, IpVlanConfig> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        let mut conf = None;
+        let mut base_iface = None;
+        for nla in link.nlas.iter() {
+            match nla {
+                LinkNla::Info(infos) => conf = nl_info_to_ip_vlan(infos),
+                LinkNla::Link(index) => base_iface = index_to_name.get(index),
+                _ => (),
+            }
+        }
+        if let Some(mut conf) = conf {
+            conf.base_iface = base_iface.cloned().unwrap_or_default();
+            ret.insert(name.to_string(), conf);
+        }
+    }
+    ret
+}
+
+fn nl_info_to_ip_vlan(infos: &[Info]) -> Option<IpVlanConfig> {
+    if !infos
+        .iter()
+        .any(|i| matches!(i, Info::Kind(InfoKind::IpVlan)))
+    {
+        return None;
+    }
+    let mut conf = IpVlanConfig::default();
+    for info in infos {
+        if let Info::Data(InfoData::IpVlan(nlas)) = info {
+            for nla in nlas {
+                match nla {
+                    InfoIpVlan::Mode(mode) => {
+                        conf.mode = match *mode {
+                            IPVLAN_MODE_L2 => Some(IpVlanMode::L2),
+                            IPVLAN_MODE_L3 => Some(IpVlanMode::L3),
+                            IPVLAN_MODE_L3S => Some(IpVlanMode::L3s),
+                            _ => {
+                                log::warn!("Unknown IP VLAN mode {}", mode);
+                                None
+                            }
+                        };
+                    }
+                    InfoIpVlan::Flags(flags) => {
+                        conf.private = Some(flags & IPVLAN_F_PRIVATE > 0);
+                        conf.vepa = Some(flags & IPVLAN_F_VEPA > 0);
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    Some(conf)
+}
+
+pub(crate) fn nms_ip_vlan_iface_to_nl(iface: &IpVlanInterface) -> Vec<Info> {
+    let mut nl_infos = Vec::new();
+    if let Some(conf) = iface.ipvlan.as_ref() {
+        if let Some(mode) = conf.mode {
+            nl_infos.push(InfoIpVlan::Mode(match mode {
+                IpVlanMode::L2 => IPVLAN_MODE_L2,
+                IpVlanMode::L3 => IPVLAN_MODE_L3,
+                IpVlanMode::L3s => IPVLAN_MODE_L3S,
+            }));
+        }
+        let mut flags = 0u16;
+        if conf.private == Some(true) {
+            flags |= IPVLAN_F_PRIVATE;
+        }
+        if conf.vepa == Some(true) {
+            flags |= IPVLAN_F_VEPA;
+        }
+        nl_infos.push(InfoIpVlan::Flags(flags));
+    }
+    vec![
+        Info::Kind(InfoKind::IpVlan),
+        Info::Data(InfoData::IpVlan(nl_infos)),
+    ]
+}
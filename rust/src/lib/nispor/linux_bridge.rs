@@ -1,14 +1,36 @@
 use std::convert::TryFrom;
 
 use log::warn;
+use netlink_packet_route::{
+    nlas::link::{Info, InfoBridge, InfoData, InfoKind, Nla as LinkNla},
+    AF_BRIDGE,
+};
+use netlink_packet_utils::nla::{DefaultNla, NLA_F_NESTED};
 
 use crate::{
-    nispor::linux_bridge_port_vlan::parse_port_vlan_conf, BaseInterface,
-    ErrorKind, LinuxBridgeConfig, LinuxBridgeInterface,
-    LinuxBridgeMulticastRouterType, LinuxBridgeOptions, LinuxBridgePortConfig,
-    LinuxBridgeStpOptions, NmstateError, VlanProtocol,
+    nispor::{
+        linux_bridge_port_vlan::{
+            nl_bridge_vlan_all, nms_port_vlan_conf_to_nl, parse_port_vlan_conf,
+        },
+        netlink::{
+            change_link, get_iface_index, nl_error_to_nmstate,
+            parse_mac_address,
+        },
+    },
+    BaseInterface, ErrorKind, Interface, LinuxBridgeConfig,
+    LinuxBridgeInterface, LinuxBridgeMulticastRouterType, LinuxBridgeOptions,
+    LinuxBridgePortConfig, LinuxBridgeStpOptions, MergedInterface,
+    NmstateError, VlanProtocol,
 };
 
+const IFLA_PROTINFO: u16 = 12;
+const IFLA_BRPORT_PRIORITY: u16 = 2;
+const IFLA_BRPORT_COST: u16 = 3;
+const IFLA_BRPORT_MODE: u16 = 4;
+
+const ETH_P_8021Q: u16 = 0x8100;
+const ETH_P_8021AD: u16 = 0x88A8;
+
 pub(crate) fn np_bridge_to_nmstate(
     np_iface: &nispor::Iface,
     base_iface: BaseInterface,
@@ -145,8 +167,16 @@ fn np_bridge_options_to_nmstate(
 //   * hello_time
 //   * max_age
 fn devide_by_user_hz(v: u32) -> Result<u32, NmstateError> {
-    let user_hz = match nix::unistd::sysconf(nix::unistd::SysconfVar::CLK_TCK) {
-        Ok(value) => value.unwrap_or_default() as u32,
+    Ok(v / get_user_hz()?)
+}
+
+fn multiply_by_user_hz(v: u32) -> Result<u32, NmstateError> {
+    Ok(v * get_user_hz()?)
+}
+
+fn get_user_hz() -> Result<u32, NmstateError> {
+    match nix::unistd::sysconf(nix::unistd::SysconfVar::CLK_TCK) {
+        Ok(value) => Ok(value.unwrap_or_default() as u32),
         Err(_) => {
             let e = NmstateError::new(
                 ErrorKind::KernelIntegerRoundedError,
@@ -154,10 +184,9 @@ fn devide_by_user_hz(v: u32) -> Result<u32, NmstateError> {
                     .to_string(),
             );
             log::error!("{}", e);
-            return Err(e);
+            Err(e)
         }
-    };
-    Ok(v / user_hz)
+    }
 }
 
 fn get_stp_options(
@@ -195,3 +224,216 @@ fn get_stp_options(
     stp_opt.priority = np_bridge.priority;
     Ok(stp_opt)
 }
+
+pub(crate) async fn apply_bridge_conf(
+    handle: &rtnetlink::Handle,
+    merged_iface: &MergedInterface,
+    br_iface: &LinuxBridgeInterface,
+) -> Result<(), NmstateError> {
+    let br_conf = if let Some(c) = br_iface.bridge.as_ref() {
+        c
+    } else {
+        return Ok(());
+    };
+    // Only touch VLAN filtering when changed, as kernel without
+    // CONFIG_BRIDGE_VLAN_FILTERING fails on any set request of it.
+    let mut vlan_filtering = None;
+    if let (Some(_), Interface::LinuxBridge(merged_br_iface)) =
+        (br_conf.port.as_ref(), &merged_iface.merged)
+    {
+        let cur_vlan_filtering = match merged_iface.current.as_ref() {
+            Some(Interface::LinuxBridge(cur_br_iface)) => {
+                cur_br_iface.vlan_filtering_is_enabled()
+            }
+            _ => false,
+        };
+        if merged_br_iface.vlan_filtering_is_enabled() != cur_vlan_filtering {
+            vlan_filtering = Some(merged_br_iface.vlan_filtering_is_enabled());
+        }
+    }
+    if br_conf.options.is_some() || vlan_filtering.is_some() {
+        change_link(
+            handle,
+            br_iface.base.name.as_str(),
+            nms_bridge_conf_to_nl(br_conf, vlan_filtering)?,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+// The `vlan_filtering` should be decided by merged interface as desired state
+// might only contains partial port list.
+fn nms_bridge_conf_to_nl(
+    br_conf: &LinuxBridgeConfig,
+    vlan_filtering: Option<bool>,
+) -> Result<Vec<Info>, NmstateError> {
+    let mut nl_infos = Vec::new();
+    if let Some(opts) = br_conf.options.as_ref() {
+        nms_bridge_options_to_nl(opts, &mut nl_infos)?;
+    }
+    if let Some(v) = vlan_filtering {
+        nl_infos.push(InfoBridge::VlanFiltering(v.into()));
+    }
+    Ok(vec![
+        Info::Kind(InfoKind::Bridge),
+        Info::Data(InfoData::Bridge(nl_infos)),
+    ])
+}
+
+fn nms_bridge_options_to_nl(
+    opts: &LinuxBridgeOptions,
+    nl_infos: &mut Vec<InfoBridge>,
+) -> Result<(), NmstateError> {
+    if let Some(stp_opts) = opts.stp.as_ref() {
+        if let Some(v) = stp_opts.enabled {
+            nl_infos.push(InfoBridge::StpState(v.into()));
+        }
+        if let Some(v) = stp_opts.forward_delay {
+            nl_infos
+                .push(InfoBridge::ForwardDelay(multiply_by_user_hz(v.into())?));
+        }
+        if let Some(v) = stp_opts.hello_time {
+            nl_infos
+                .push(InfoBridge::HelloTime(multiply_by_user_hz(v.into())?));
+        }
+        if let Some(v) = stp_opts.max_age {
+            nl_infos.push(InfoBridge::MaxAge(multiply_by_user_hz(v.into())?));
+        }
+        if let Some(v) = stp_opts.priority {
+            nl_infos.push(InfoBridge::Priority(v));
+        }
+    }
+    if let Some(v) = opts.group_addr.as_deref() {
+        nl_infos.push(InfoBridge::GroupAddr(parse_mac_address(v)?));
+    }
+    if let Some(v) = opts.group_fwd_mask.or(opts.group_forward_mask) {
+        nl_infos.push(InfoBridge::GroupFwdMask(v));
+    }
+    if let Some(v) = opts.hash_max {
+        nl_infos.push(InfoBridge::MulticastHashMax(v));
+    }
+    if let Some(v) = opts.mac_ageing_time {
+        nl_infos.push(InfoBridge::AgeingTime(multiply_by_user_hz(v)?));
+    }
+    if let Some(v) = opts.multicast_last_member_count {
+        nl_infos.push(InfoBridge::MulticastLastMemberCount(v));
+    }
+    if let Some(v) = opts.multicast_last_member_interval {
+        nl_infos.push(InfoBridge::MulticastLastMemberInterval(v));
+    }
+    if let Some(v) = opts.multicast_membership_interval {
+        nl_infos.push(InfoBridge::MulticastMembershipInterval(v));
+    }
+    if let Some(v) = opts.multicast_querier {
+        nl_infos.push(InfoBridge::MulticastQuerier(v.into()));
+    }
+    if let Some(v) = opts.multicast_querier_interval {
+        nl_infos.push(InfoBridge::MulticastQuerierInterval(v));
+    }
+    if let Some(v) = opts.multicast_query_interval {
+        nl_infos.push(InfoBridge::MulticastQueryInterval(v));
+    }
+    if let Some(v) = opts.multicast_query_response_interval {
+        nl_infos.push(InfoBridge::MulticastQueryResponseInterval(v));
+    }
+    if let Some(v) = opts.multicast_query_use_ifaddr {
+        nl_infos.push(InfoBridge::MulticastQueryUseIfaddr(v.into()));
+    }
+    if let Some(v) = opts.multicast_router.as_ref() {
+        nl_infos.push(InfoBridge::MulticastRouter(v.clone() as u8));
+    }
+    if let Some(v) = opts.multicast_snooping {
+        nl_infos.push(InfoBridge::MulticastSnooping(v.into()));
+    }
+    if let Some(v) = opts.multicast_startup_query_count {
+        nl_infos.push(InfoBridge::MulticastStartupQueryCount(v));
+    }
+    if let Some(v) = opts.multicast_startup_query_interval {
+        nl_infos.push(InfoBridge::MulticastStartupQueryInterval(v));
+    }
+    if let Some(v) = opts.vlan_protocol.as_ref() {
+        nl_infos.push(InfoBridge::VlanProtocol(match v {
+            VlanProtocol::Ieee8021Q => ETH_P_8021Q,
+            VlanProtocol::Ieee8021Ad => ETH_P_8021AD,
+        }));
+    }
+    Ok(())
+}
+
+// Apply the STP and VLAN filtering settings of specified bridge port, the
+// port should be already attached to the bridge.
+pub(crate) async fn apply_bridge_port_conf(
+    handle: &rtnetlink::Handle,
+    port_conf: &LinuxBridgePortConfig,
+) -> Result<(), NmstateError> {
+    let index = get_iface_index(handle, port_conf.name.as_str()).await?;
+
+    let mut protinfo = Vec::new();
+    if let Some(v) = port_conf.stp_priority {
+        append_nla_bytes(&mut protinfo, IFLA_BRPORT_PRIORITY, &v.to_ne_bytes());
+    }
+    if let Some(v) = port_conf.stp_path_cost {
+        append_nla_bytes(&mut protinfo, IFLA_BRPORT_COST, &v.to_ne_bytes());
+    }
+    if let Some(v) = port_conf.stp_hairpin_mode {
+        append_nla_bytes(&mut protinfo, IFLA_BRPORT_MODE, &[v.into()]);
+    }
+    if !protinfo.is_empty() {
+        let mut req = handle.link().set(index);
+        let nl_msg = req.message_mut();
+        nl_msg.header.interface_family = AF_BRIDGE as u8;
+        nl_msg.nlas.push(LinkNla::Other(DefaultNla::new(
+            IFLA_PROTINFO | NLA_F_NESTED,
+            protinfo,
+        )));
+        log::info!("Changing bridge port {} STP settings", port_conf.name);
+        req.execute().await.map_err(|e| {
+            nl_error_to_nmstate(
+                e,
+                &format!("change bridge port {} settings", port_conf.name),
+            )
+        })?;
+    }
+
+    if let Some(vlan_conf) = port_conf.vlan.as_ref() {
+        // Purge existing VLANs before adding desired ones
+        let mut req = handle.link().del(index);
+        let nl_msg = req.message_mut();
+        nl_msg.header.interface_family = AF_BRIDGE as u8;
+        nl_msg
+            .nlas
+            .push(LinkNla::AfSpecBridge(nl_bridge_vlan_all()));
+        req.execute().await.map_err(|e| {
+            nl_error_to_nmstate(
+                e,
+                &format!("purge VLANs of bridge port {}", port_conf.name),
+            )
+        })?;
+
+        let nl_vlans = nms_port_vlan_conf_to_nl(vlan_conf);
+        if !nl_vlans.is_empty() {
+            let mut req = handle.link().set(index);
+            let nl_msg = req.message_mut();
+            nl_msg.header.interface_family = AF_BRIDGE as u8;
+            nl_msg.nlas.push(LinkNla::AfSpecBridge(nl_vlans));
+            log::info!("Changing bridge port {} VLANs", port_conf.name);
+            req.execute().await.map_err(|e| {
+                nl_error_to_nmstate(
+                    e,
+                    &format!("change VLANs of bridge port {}", port_conf.name),
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+// Append netlink attribute with 4 bytes alignment
+fn append_nla_bytes(buffer: &mut Vec<u8>, kind: u16, value: &[u8]) {
+    let nla_len = 4 + value.len();
+    buffer.extend_from_slice(&(nla_len as u16).to_ne_bytes());
+    buffer.extend_from_slice(&kind.to_ne_bytes());
+    buffer.extend_from_slice(value);
+    buffer.resize(buffer.len() + (4 - nla_len % 4) % 4, 0);
+}
@@ -1,8 +1,32 @@
+use netlink_packet_route::{
+    nlas::link::{AfSpecBridge, BridgeVlanInfo},
+    BRIDGE_VLAN_INFO_PVID, BRIDGE_VLAN_INFO_RANGE_BEGIN,
+    BRIDGE_VLAN_INFO_RANGE_END, BRIDGE_VLAN_INFO_UNTAGGED,
+};
+
 use crate::{
     BridgePortTunkTag, BridgePortVlanConfig, BridgePortVlanMode,
     BridgePortVlanRange,
 };
 
+const VLAN_ID_MIN: u16 = 1;
+const VLAN_ID_MAX: u16 = 4094;
+
+// Used for purging all VLANs of bridge port
+pub(crate) fn nl_bridge_vlan_all() -> Vec<AfSpecBridge> {
+    vec![
+        nl_vlan_info(BRIDGE_VLAN_INFO_RANGE_BEGIN, VLAN_ID_MIN),
+        nl_vlan_info(BRIDGE_VLAN_INFO_RANGE_END, VLAN_ID_MAX),
+    ]
+}
+
+fn nl_vlan_info(flags: u16, vid: u16) -> AfSpecBridge {
+    let mut info = BridgeVlanInfo::default();
+    info.flags = flags;
+    info.vid = vid;
+    AfSpecBridge::VlanInfo(info)
+}
+
 pub(crate) fn parse_port_vlan_conf(
     np_vlan_entries: &[nispor::BridgeVlanEntry],
 ) -> Option<BridgePortVlanConfig> {
@@ -60,3 +84,29 @@ fn get_vlan_tag_range(np_vlan_entry: &nispor::BridgeVlanEntry) -> (u16, u16) {
         np_vlan_entry.vid.unwrap_or(1),
     ))
 }
+
+pub(crate) fn nms_port_vlan_conf_to_nl(
+    port_vlan_conf: &BridgePortVlanConfig,
+) -> Vec<AfSpecBridge> {
+    let mut ret = Vec::new();
+    if port_vlan_conf.mode == Some(BridgePortVlanMode::Trunk) {
+        for trunk_tag in port_vlan_conf.trunk_tags.as_deref().unwrap_or(&[]) {
+            let (vid_min, vid_max) = trunk_tag.get_vlan_tag_range();
+            if vid_min == vid_max {
+                ret.push(nl_vlan_info(0, vid_min));
+            } else {
+                ret.push(nl_vlan_info(BRIDGE_VLAN_INFO_RANGE_BEGIN, vid_min));
+                ret.push(nl_vlan_info(BRIDGE_VLAN_INFO_RANGE_END, vid_max));
+            }
+        }
+    }
+    if let Some(tag) = port_vlan_conf.tag {
+        if tag != 0 {
+            ret.push(nl_vlan_info(
+                BRIDGE_VLAN_INFO_PVID | BRIDGE_VLAN_INFO_UNTAGGED,
+                tag,
+            ));
+        }
+    }
+    ret
+}
@@ -1,3 +1,7 @@
+use netlink_packet_route::nlas::link::{
+    Info, InfoData, InfoKind, InfoMacVlan, InfoMacVtap,
+};
+
 use crate::{
     BaseInterface, MacVlanConfig, MacVlanInterface, MacVlanMode, MacVtapConfig,
     MacVtapInterface, MacVtapMode,
@@ -76,3 +80,35 @@ pub(crate) fn np_mac_vtap_to_nmstate(
         mac_vtap: vtap_conf,
     }
 }
+
+pub(crate) fn nms_mac_vlan_iface_to_nl(iface: &MacVlanInterface) -> Vec<Info> {
+    let mut nl_infos = Vec::new();
+    if let Some(conf) = iface.mac_vlan.as_ref() {
+        if conf.mode != MacVlanMode::Unknown {
+            nl_infos.push(InfoMacVlan::Mode(conf.mode.into()));
+        }
+        if conf.accept_all_mac == Some(false) {
+            nl_infos.push(InfoMacVlan::Flags(MACVLAN_FLAG_NOPROMISC));
+        }
+    }
+    vec![
+        Info::Kind(InfoKind::MacVlan),
+        Info::Data(InfoData::MacVlan(nl_infos)),
+    ]
+}
+
+pub(crate) fn nms_mac_vtap_iface_to_nl(iface: &MacVtapInterface) -> Vec<Info> {
+    let mut nl_infos = Vec::new();
+    if let Some(conf) = iface.mac_vtap.as_ref() {
+        if conf.mode != MacVtapMode::Unknown {
+            nl_infos.push(InfoMacVtap::Mode(conf.mode.into()));
+        }
+        if conf.accept_all_mac == Some(false) {
+            nl_infos.push(InfoMacVtap::Flags(MACVTAP_FLAG_NOPROMISC));
+        }
+    }
+    vec![
+        Info::Kind(InfoKind::MacVtap),
+        Info::Data(InfoData::MacVtap(nl_infos)),
+    ]
+}
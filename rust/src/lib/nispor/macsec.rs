@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Nispor 1.2 reports MACsec interfaces as ethernet without MACsec
+// configuration, hence we query the kernel via rtnetlink directly.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoKind, Nla as LinkNla},
+    LinkMessage,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, NlasIterator},
+    Emitable,
+};
+
+use crate::{
+    ErrorKind, MacsecConfig, MacsecInterface, MacsecValidation, NmstateError,
+};
+
+const IFLA_MACSEC_SCI: u16 = 1;
+const IFLA_MACSEC_PORT: u16 = 2;
+const IFLA_MACSEC_ENCRYPT: u16 = 7;
+const IFLA_MACSEC_INC_SCI: u16 = 9;
+const IFLA_MACSEC_VALIDATION: u16 = 13;
+
+const MACSEC_KIND: &str = "macsec";
+
+// Return interface name to MACsec config of all MACsec interfaces in kernel.
+pub(crate) fn get_macsecs(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, MacsecConfig> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        let mut conf = None;
+        let mut base_iface = None;
+        for nla in link.nlas.iter() {
+            match nla {
+                LinkNla::Info(infos) => conf = nl_info_to_macsec(infos),
+                LinkNla::Link(index) => base_iface = index_to_name.get(index),
+                _ => (),
+            }
+        }
+        if let Some(mut conf) = conf {
+            conf.base_iface = base_iface.cloned().unwrap_or_default();
+            ret.insert(name.to_string(), conf);
+        }
+    }
+    ret
+}
+
+fn nl_info_to_macsec(infos: &[Info]) -> Option<MacsecConfig> {
+    if !infos.iter().any(
+        |i| matches!(i, Info::Kind(InfoKind::Other(k)) if k == MACSEC_KIND),
+    ) {
+        return None;
+    }
+    let mut conf = MacsecConfig::default();
+    for info in infos {
+        if let Info::Data(InfoData::Other(data)) = info {
+            for nla in NlasIterator::new(data).flatten() {
+                let value = nla.value();
+                match nla.kind() {
+                    // The SCI is MAC address followed by port in network
+                    // byte order.
+                    IFLA_MACSEC_SCI if value.len() == 8 => {
+                        conf.port =
+                            Some(u16::from_be_bytes([value[6], value[7]]));
+                    }
+                    IFLA_MACSEC_ENCRYPT => {
+                        conf.encrypt = value.first().map(|v| *v > 0);
+                    }
+                    IFLA_MACSEC_INC_SCI => {
+                        conf.send_sci = value.first().map(|v| *v > 0);
+                    }
+                    IFLA_MACSEC_VALIDATION => {
+                        conf.validation = value
+                            .first()
+                            .and_then(|v| MacsecValidation::try_from(*v).ok());
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    Some(conf)
+}
+
+pub(crate) fn nms_macsec_iface_to_nl(
+    iface: &MacsecInterface,
+) -> Result<Vec<Info>, NmstateError> {
+    let conf = iface.macsec.clone().unwrap_or_default();
+    // The MKA is done by wpa_supplicant which is only supported via
+    // NetworkManager.
+    if conf.mka_cak.is_some() || conf.mka_ckn.is_some() {
+        let e = NmstateError::new(
+            ErrorKind::NotSupportedError,
+            format!(
+                "MACsec MKA of interface {} is not supported in kernel mode",
+                iface.base.name
+            ),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    let mut nlas: Vec<DefaultNla> = Vec::new();
+    if let Some(port) = conf.port {
+        nlas.push(DefaultNla::new(
+            IFLA_MACSEC_PORT,
+            port.to_be_bytes().to_vec(),
+        ));
+    }
+    if let Some(encrypt) = conf.encrypt {
+        nlas.push(DefaultNla::new(IFLA_MACSEC_ENCRYPT, vec![encrypt.into()]));
+    }
+    if let Some(send_sci) = conf.send_sci {
+        nlas.push(DefaultNla::new(IFLA_MACSEC_INC_SCI, vec![send_sci.into()]));
+    }
+    if let Some(validation) = conf.validation {
+        nlas.push(DefaultNla::new(
+            IFLA_MACSEC_VALIDATION,
+            vec![validation.into()],
+        ));
+    }
+    let mut data = vec![0u8; nlas.as_slice().buffer_len()];
+    nlas.as_slice().emit(&mut data);
+
+    Ok(vec![
+        Info::Kind(InfoKind::Other(MACSEC_KIND.to_string())),
+        Info::Data(InfoData::Other(data)),
+    ])
+}
@@ -1,24 +1,45 @@
 mod apply;
 mod base_iface;
 mod bond;
+mod checkpoint;
+mod dns;
 mod error;
 mod ethernet;
 mod ethtool;
+mod geneve;
 mod hostname;
+mod hsr;
 mod infiniband;
 mod ip;
+mod ip_tunnel;
+mod ip_vlan;
 mod linux_bridge;
 mod linux_bridge_port_vlan;
 mod mac_vlan;
+mod macsec;
 mod mptcp;
+mod netlink;
+mod nexthop;
 mod route;
 mod route_rule;
 mod show;
+mod tun;
+#[cfg(test)]
+mod unit_tests;
 mod veth;
 mod vlan;
 mod vrf;
 mod vxlan;
+mod xfrm;
 
 pub(crate) use apply::nispor_apply;
+pub(crate) use checkpoint::{
+    is_nispor_checkpoint, nispor_checkpoint_create, nispor_checkpoint_destroy,
+    nispor_checkpoint_get, nispor_checkpoint_list, nispor_checkpoint_lock,
+};
+pub(crate) use dns::{
+    nispor_dns_apply, nispor_dns_backup, nispor_dns_remove_backup,
+    nispor_dns_restore, nispor_dns_retrieve, DEFAULT_RESOLV_CONF_PATH,
+};
 pub(crate) use hostname::set_running_hostname;
 pub(crate) use show::nispor_retrieve;
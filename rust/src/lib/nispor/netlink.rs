@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// The nispor 1.2 `NetConf` only covers a small subset of what nmstate could
+// apply in kernel mode, hence we use rtnetlink directly for the rest.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use futures::{StreamExt, TryStreamExt};
+use netlink_packet_core::{
+    NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_REQUEST,
+};
+use netlink_packet_route::{
+    nlas::link::{Info, Nla as LinkNla},
+    LinkMessage, RtnlMessage,
+};
+
+use crate::{ErrorKind, NmstateError};
+
+pub(crate) fn run_netlink<F, Fut, T>(func: F) -> Result<T, NmstateError>
+where
+    F: FnOnce(rtnetlink::Handle) -> Fut,
+    Fut: Future<Output = Result<T, NmstateError>>,
+{
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .map_err(|e| {
+            NmstateError::new(
+                ErrorKind::Bug,
+                format!("Failed to create tokio runtime: {e}"),
+            )
+        })?;
+    rt.block_on(async {
+        let (connection, handle, _) =
+            rtnetlink::new_connection().map_err(|e| {
+                NmstateError::new(
+                    ErrorKind::PluginFailure,
+                    format!("Failed to create netlink connection: {e}"),
+                )
+            })?;
+        tokio::spawn(connection);
+        func(handle).await
+    })
+}
+
+// Return the negative errno if the netlink request failed with kernel error.
+pub(crate) fn nl_errno(e: &rtnetlink::Error) -> Option<i32> {
+    if let rtnetlink::Error::NetlinkError(e) = e {
+        Some(e.code)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn nl_error_to_nmstate(
+    e: rtnetlink::Error,
+    action: &str,
+) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::PluginFailure,
+        format!("Failed to {action}: {e}"),
+    );
+    log::error!("{}", e);
+    e
+}
+
+pub(crate) async fn get_iface_index(
+    handle: &rtnetlink::Handle,
+    iface_name: &str,
+) -> Result<u32, NmstateError> {
+    let mut links = handle
+        .link()
+        .get()
+        .match_name(iface_name.to_string())
+        .execute();
+    match links.try_next().await {
+        Ok(Some(link)) => Ok(link.header.index),
+        Ok(None) => Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Interface {iface_name} does not exist"),
+        )),
+        Err(e) => {
+            if nl_errno(&e) == Some(-(nix::errno::Errno::ENODEV as i32)) {
+                Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("Interface {iface_name} does not exist"),
+                ))
+            } else {
+                Err(nl_error_to_nmstate(
+                    e,
+                    &format!("query interface {iface_name}"),
+                ))
+            }
+        }
+    }
+}
+
+// Dump all interfaces, for the link info nispor does not support yet.
+pub(crate) fn get_links() -> Result<Vec<LinkMessage>, NmstateError> {
+    run_netlink(|handle| async move {
+        handle
+            .link()
+            .get()
+            .execute()
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| nl_error_to_nmstate(e, "query interfaces"))
+    })
+}
+
+pub(crate) fn get_link_index_to_name(
+    links: &[LinkMessage],
+) -> HashMap<u32, String> {
+    let mut ret = HashMap::new();
+    for link in links {
+        for nla in link.nlas.iter() {
+            if let LinkNla::IfName(name) = nla {
+                ret.insert(link.header.index, name.to_string());
+            }
+        }
+    }
+    ret
+}
+
+// Create interface in down state with specified link info, the `parent` will
+// be set as IFLA_LINK.
+pub(crate) async fn create_link(
+    handle: &rtnetlink::Handle,
+    iface_name: &str,
+    parent: Option<&str>,
+    link_info: Vec<Info>,
+) -> Result<(), NmstateError> {
+    let mut req = handle.link().add();
+    let nl_msg = req.message_mut();
+    nl_msg.nlas.push(LinkNla::IfName(iface_name.to_string()));
+    if let Some(parent) = parent {
+        nl_msg
+            .nlas
+            .push(LinkNla::Link(get_iface_index(handle, parent).await?));
+    }
+    nl_msg.nlas.push(LinkNla::Info(link_info));
+    log::info!("Creating interface {iface_name}");
+    req.execute().await.map_err(|e| {
+        nl_error_to_nmstate(e, &format!("create interface {iface_name}"))
+    })
+}
+
+// Send RTM_NEWLINK without NLM_F_EXCL or NLM_F_REPLACE to change the link
+// info of existing interface, the rtnetlink crate cannot do that yet.
+pub(crate) async fn change_link(
+    handle: &rtnetlink::Handle,
+    iface_name: &str,
+    link_info: Vec<Info>,
+) -> Result<(), NmstateError> {
+    let mut nl_msg = LinkMessage::default();
+    nl_msg.header.index = get_iface_index(handle, iface_name).await?;
+    nl_msg.nlas.push(LinkNla::Info(link_info));
+    let mut req = NetlinkMessage::from(RtnlMessage::NewLink(nl_msg));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+    log::info!("Changing interface {iface_name}");
+    let action = format!("change interface {iface_name}");
+    let mut response = handle
+        .clone()
+        .request(req)
+        .map_err(|e| nl_error_to_nmstate(e, &action))?;
+    while let Some(msg) = response.next().await {
+        if let NetlinkPayload::Error(e) = msg.payload {
+            return Err(nl_error_to_nmstate(
+                rtnetlink::Error::NetlinkError(e),
+                &action,
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_mac_address(mac: &str) -> Result<[u8; 6], NmstateError> {
+    let octets: Vec<u8> = mac
+        .split(':')
+        .map(|octet| u8::from_str_radix(octet, 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .unwrap_or_default();
+    <[u8; 6]>::try_from(octets.as_slice()).map_err(|_| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Invalid MAC address {mac}"),
+        )
+    })
+}
+
+pub(crate) fn ip_to_nl_bytes(ip: &IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(i) => i.octets().to_vec(),
+        IpAddr::V6(i) => i.octets().to_vec(),
+    }
+}
+
+// The unspecified address means any for tunnel endpoints, we treat it as
+// undefined.
+pub(crate) fn nl_bytes_to_ip(value: &[u8]) -> Option<IpAddr> {
+    let ip = if let Ok(octets) = <[u8; 4]>::try_from(value) {
+        IpAddr::V4(Ipv4Addr::from(octets))
+    } else if let Ok(octets) = <[u8; 16]>::try_from(value) {
+        IpAddr::V6(Ipv6Addr::from(octets))
+    } else {
+        return None;
+    };
+    if ip.is_unspecified() {
+        None
+    } else {
+        Some(ip)
+    }
+}
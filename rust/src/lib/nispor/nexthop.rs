@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Neither nispor nor rtnetlink support the nexthop object yet, hence we
+// compose the RTM_NEWNEXTHOP, RTM_DELNEXTHOP and RTM_GETNEXTHOP messages by
+// ourselves and send them via plain netlink socket.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use netlink_packet_core::{
+    NetlinkDeserializable, NetlinkHeader, NetlinkMessage, NetlinkPayload,
+    NetlinkSerializable, NLM_F_ACK, NLM_F_CREATE, NLM_F_DUMP, NLM_F_REPLACE,
+    NLM_F_REQUEST,
+};
+use netlink_packet_route::{
+    AF_INET, AF_INET6, AF_UNSPEC, RTPROT_BOOT, RTPROT_STATIC,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla, NlaBuffer, NlasIterator, NLA_F_NESTED},
+    DecodeError, Emitable, Parseable,
+};
+use netlink_sys::{protocols::NETLINK_ROUTE, Socket, SocketAddr};
+
+use crate::{
+    nispor::netlink::{
+        get_link_index_to_name, get_links, ip_to_nl_bytes, nl_bytes_to_ip,
+    },
+    AddressFamily, ErrorKind, MergedNexthops, NexthopEntry, NexthopGroupMember,
+    NexthopResilientConfig, NexthopState, Nexthops, NmstateError,
+};
+
+const RTM_NEWNEXTHOP: u16 = 104;
+const RTM_DELNEXTHOP: u16 = 105;
+const RTM_GETNEXTHOP: u16 = 106;
+
+const NHA_ID: u16 = 1;
+const NHA_GROUP: u16 = 2;
+const NHA_GROUP_TYPE: u16 = 3;
+const NHA_BLACKHOLE: u16 = 4;
+const NHA_OIF: u16 = 5;
+const NHA_GATEWAY: u16 = 6;
+const NHA_RES_GROUP: u16 = 12;
+
+const NHA_RES_GROUP_BUCKETS: u16 = 1;
+const NHA_RES_GROUP_IDLE_TIMER: u16 = 2;
+const NHA_RES_GROUP_UNBALANCED_TIMER: u16 = 3;
+
+const NEXTHOP_GRP_TYPE_MPATH: u16 = 0;
+const NEXTHOP_GRP_TYPE_RES: u16 = 1;
+
+// The size of `struct nhmsg`
+const NHMSG_LEN: usize = 8;
+// The size of `struct nexthop_grp`
+const NEXTHOP_GRP_LEN: usize = 8;
+
+// Kernel is using clock_t for the timers of resilient nexthop group
+const USER_HZ: u32 = 100;
+
+const SUPPORTED_STATIC_NEXTHOP_PROTOCOL: [u8; 2] = [RTPROT_BOOT, RTPROT_STATIC];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NexthopNla {
+    Id(u32),
+    // Tuple of (member ID, weight)
+    Group(Vec<(u32, u16)>),
+    GroupType(u16),
+    Blackhole,
+    Oif(u32),
+    Gateway(Vec<u8>),
+    ResGroup(Vec<DefaultNla>),
+    Other(u16),
+}
+
+impl Nla for NexthopNla {
+    fn value_len(&self) -> usize {
+        match self {
+            Self::Id(_) | Self::Oif(_) => 4,
+            Self::GroupType(_) => 2,
+            Self::Blackhole | Self::Other(_) => 0,
+            Self::Group(members) => members.len() * NEXTHOP_GRP_LEN,
+            Self::Gateway(v) => v.len(),
+            Self::ResGroup(nlas) => nlas.as_slice().buffer_len(),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Self::Id(_) => NHA_ID,
+            Self::Group(_) => NHA_GROUP,
+            Self::GroupType(_) => NHA_GROUP_TYPE,
+            Self::Blackhole => NHA_BLACKHOLE,
+            Self::Oif(_) => NHA_OIF,
+            Self::Gateway(_) => NHA_GATEWAY,
+            Self::ResGroup(_) => NHA_RES_GROUP | NLA_F_NESTED,
+            Self::Other(k) => *k,
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            Self::Id(v) | Self::Oif(v) => {
+                buffer[..4].copy_from_slice(&v.to_ne_bytes())
+            }
+            Self::GroupType(v) => buffer[..2].copy_from_slice(&v.to_ne_bytes()),
+            Self::Blackhole | Self::Other(_) => (),
+            Self::Group(members) => {
+                for (i, (id, weight)) in members.iter().enumerate() {
+                    let buf = &mut buffer
+                        [i * NEXTHOP_GRP_LEN..(i + 1) * NEXTHOP_GRP_LEN];
+                    buf.fill(0);
+                    buf[..4].copy_from_slice(&id.to_ne_bytes());
+                    // The weight field holds weight minus one
+                    buf[4] = weight.saturating_sub(1) as u8;
+                }
+            }
+            Self::Gateway(v) => buffer[..v.len()].copy_from_slice(v),
+            Self::ResGroup(nlas) => nlas.as_slice().emit(buffer),
+        }
+    }
+}
+
+impl NexthopNla {
+    fn parse(buf: &NlaBuffer<&[u8]>) -> Self {
+        let payload = buf.value();
+        match buf.kind() {
+            NHA_ID => parse_u32(payload).map(Self::Id),
+            NHA_OIF => parse_u32(payload).map(Self::Oif),
+            NHA_GROUP_TYPE => parse_u16(payload).map(Self::GroupType),
+            NHA_BLACKHOLE => Some(Self::Blackhole),
+            NHA_GATEWAY => Some(Self::Gateway(payload.to_vec())),
+            NHA_GROUP => Some(Self::Group(
+                payload
+                    .chunks_exact(NEXTHOP_GRP_LEN)
+                    .filter_map(|b| {
+                        parse_u32(b).map(|id| (id, u16::from(b[4]) + 1))
+                    })
+                    .collect(),
+            )),
+            NHA_RES_GROUP => Some(Self::ResGroup(
+                NlasIterator::new(payload)
+                    .filter_map(|nla| nla.ok())
+                    .filter_map(|nla| DefaultNla::parse(&nla).ok())
+                    .collect(),
+            )),
+            _ => None,
+        }
+        .unwrap_or_else(|| Self::Other(buf.kind()))
+    }
+}
+
+fn parse_u32(payload: &[u8]) -> Option<u32> {
+    payload
+        .get(..4)
+        .and_then(|b| <[u8; 4]>::try_from(b).ok())
+        .map(u32::from_ne_bytes)
+}
+
+fn parse_u16(payload: &[u8]) -> Option<u16> {
+    payload
+        .get(..2)
+        .and_then(|b| <[u8; 2]>::try_from(b).ok())
+        .map(u16::from_ne_bytes)
+}
+
+fn nla_value(nla: &DefaultNla) -> Vec<u8> {
+    let mut ret = vec![0u8; nla.value_len()];
+    nla.emit_value(&mut ret);
+    ret
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NexthopMessage {
+    message_type: u16,
+    family: u8,
+    protocol: u8,
+    nlas: Vec<NexthopNla>,
+}
+
+impl NexthopMessage {
+    fn new(message_type: u16) -> Self {
+        Self {
+            message_type,
+            family: AF_UNSPEC as u8,
+            protocol: 0,
+            nlas: Vec::new(),
+        }
+    }
+}
+
+impl NetlinkSerializable for NexthopMessage {
+    fn message_type(&self) -> u16 {
+        self.message_type
+    }
+
+    fn buffer_len(&self) -> usize {
+        NHMSG_LEN + self.nlas.as_slice().buffer_len()
+    }
+
+    fn serialize(&self, buffer: &mut [u8]) {
+        buffer[..NHMSG_LEN].fill(0);
+        buffer[0] = self.family;
+        buffer[2] = self.protocol;
+        self.nlas.as_slice().emit(&mut buffer[NHMSG_LEN..]);
+    }
+}
+
+impl NetlinkDeserializable for NexthopMessage {
+    type Error = DecodeError;
+
+    fn deserialize(
+        header: &NetlinkHeader,
+        payload: &[u8],
+    ) -> Result<Self, Self::Error> {
+        if payload.len() < NHMSG_LEN {
+            return Err(DecodeError::from(format!(
+                "Invalid nexthop message length {}",
+                payload.len()
+            )));
+        }
+        let mut nlas = Vec::new();
+        for nla in NlasIterator::new(&payload[NHMSG_LEN..]) {
+            nlas.push(NexthopNla::parse(&nla?));
+        }
+        Ok(Self {
+            message_type: header.message_type,
+            family: payload[0],
+            protocol: payload[2],
+            nlas,
+        })
+    }
+}
+
+fn nl_io_error_to_nmstate(e: std::io::Error, action: &str) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::PluginFailure,
+        format!("Failed to {action}: {e}"),
+    );
+    log::error!("{}", e);
+    e
+}
+
+// Send the request and return the replied nexthop messages, the request
+// should be either dump or asking for ACK.
+// The error of kernel is returned as std::io::Error, so caller could check
+// its errno.
+fn nexthop_request(
+    msg: NexthopMessage,
+    flags: u16,
+) -> Result<Vec<NexthopMessage>, std::io::Error> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    socket.bind_auto()?;
+    socket.connect(&SocketAddr::new(0, 0))?;
+
+    let mut nl_msg = NetlinkMessage::new(
+        NetlinkHeader::default(),
+        NetlinkPayload::InnerMessage(msg),
+    );
+    nl_msg.header.flags = flags;
+    nl_msg.header.sequence_number = 1;
+    nl_msg.finalize();
+    let mut buffer = vec![0u8; nl_msg.buffer_len()];
+    nl_msg.serialize(&mut buffer);
+    socket.send(&buffer, 0)?;
+
+    let mut ret = Vec::new();
+    loop {
+        let (data, _) = socket.recv_from_full()?;
+        let mut offset = 0;
+        while offset < data.len() {
+            let reply =
+                NetlinkMessage::<NexthopMessage>::deserialize(&data[offset..])
+                    .map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+                    })?;
+            let length = reply.header.length as usize;
+            match reply.payload {
+                NetlinkPayload::InnerMessage(m) => ret.push(m),
+                NetlinkPayload::Error(e) => {
+                    return Err(std::io::Error::from_raw_os_error(-e.code));
+                }
+                NetlinkPayload::Done | NetlinkPayload::Ack(_) => {
+                    return Ok(ret);
+                }
+                _ => (),
+            }
+            if length == 0 {
+                break;
+            }
+            // Netlink messages are aligned to 4 bytes
+            offset += (length + 3) & !3;
+        }
+    }
+}
+
+pub(crate) fn get_nexthops(
+    index_to_name: &HashMap<u32, String>,
+    running_config_only: bool,
+) -> Nexthops {
+    let mut ret = Nexthops::new();
+    let nl_msgs = match nexthop_request(
+        NexthopMessage::new(RTM_GETNEXTHOP),
+        NLM_F_REQUEST | NLM_F_DUMP,
+    ) {
+        Ok(m) => m,
+        Err(e) => {
+            // Kernel older than 5.3 does not support nexthop object
+            log::info!("Failed to retrieve nexthops: {e}");
+            return ret;
+        }
+    };
+    let mut running_nhs = Vec::new();
+    let mut config_nhs = Vec::new();
+    for nl_msg in nl_msgs {
+        let nh = nl_msg_to_nmstate(&nl_msg, index_to_name);
+        if SUPPORTED_STATIC_NEXTHOP_PROTOCOL.contains(&nl_msg.protocol) {
+            config_nhs.push(nh.clone());
+        }
+        running_nhs.push(nh);
+    }
+    if !running_config_only {
+        ret.running = Some(running_nhs);
+    }
+    ret.config = Some(config_nhs);
+    ret
+}
+
+fn nl_msg_to_nmstate(
+    nl_msg: &NexthopMessage,
+    index_to_name: &HashMap<u32, String>,
+) -> NexthopEntry {
+    let mut ret = NexthopEntry::new();
+    let mut group_type = NEXTHOP_GRP_TYPE_MPATH;
+    let mut res_conf = NexthopResilientConfig::default();
+    for nla in nl_msg.nlas.as_slice() {
+        match nla {
+            NexthopNla::Id(v) => ret.id = Some(*v),
+            NexthopNla::Group(members) => {
+                ret.group = Some(
+                    members
+                        .iter()
+                        .map(|(id, weight)| NexthopGroupMember {
+                            id: *id,
+                            weight: Some(*weight),
+                        })
+                        .collect(),
+                )
+            }
+            NexthopNla::GroupType(v) => group_type = *v,
+            NexthopNla::Blackhole => ret.blackhole = Some(true),
+            NexthopNla::Oif(v) => ret.device = index_to_name.get(v).cloned(),
+            NexthopNla::Gateway(v) => {
+                ret.gateway = nl_bytes_to_ip(v).map(|i| i.to_string())
+            }
+            NexthopNla::ResGroup(nlas) => {
+                for nla in nlas {
+                    let value = nla_value(nla);
+                    match nla.kind() {
+                        NHA_RES_GROUP_BUCKETS => {
+                            res_conf.buckets = parse_u16(&value)
+                        }
+                        NHA_RES_GROUP_IDLE_TIMER => {
+                            res_conf.idle_timer =
+                                parse_u32(&value).map(|v| v / USER_HZ)
+                        }
+                        NHA_RES_GROUP_UNBALANCED_TIMER => {
+                            res_conf.unbalanced_timer =
+                                parse_u32(&value).map(|v| v / USER_HZ)
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            NexthopNla::Other(_) => (),
+        }
+    }
+    if group_type == NEXTHOP_GRP_TYPE_RES {
+        ret.resilient = Some(res_conf);
+    }
+    // Only show family when cannot be determined by gateway
+    if ret.gateway.is_none() && !ret.is_group() {
+        match AddressFamily::from(nl_msg.family) {
+            AddressFamily::Unknown => (),
+            family => ret.family = Some(family),
+        }
+    }
+    ret
+}
+
+fn nmstate_nexthop_to_nl(
+    nh: &NexthopEntry,
+    name_to_index: &HashMap<&str, u32>,
+    message_type: u16,
+) -> Result<NexthopMessage, NmstateError> {
+    let mut nl_msg = NexthopMessage::new(message_type);
+    if let Some(id) = nh.id {
+        nl_msg.nlas.push(NexthopNla::Id(id));
+    }
+    if message_type == RTM_DELNEXTHOP {
+        return Ok(nl_msg);
+    }
+    nl_msg.protocol = RTPROT_STATIC;
+    if let Some(members) = nh.group.as_ref() {
+        nl_msg.nlas.push(NexthopNla::Group(
+            members
+                .iter()
+                .map(|m| (m.id, m.weight.unwrap_or(1)))
+                .collect(),
+        ));
+        if let Some(res_conf) = nh.resilient.as_ref() {
+            nl_msg
+                .nlas
+                .push(NexthopNla::GroupType(NEXTHOP_GRP_TYPE_RES));
+            let mut res_nlas = Vec::new();
+            if let Some(v) = res_conf.buckets {
+                res_nlas.push(DefaultNla::new(
+                    NHA_RES_GROUP_BUCKETS,
+                    v.to_ne_bytes().to_vec(),
+                ));
+            }
+            if let Some(v) = res_conf.idle_timer {
+                res_nlas.push(DefaultNla::new(
+                    NHA_RES_GROUP_IDLE_TIMER,
+                    v.saturating_mul(USER_HZ).to_ne_bytes().to_vec(),
+                ));
+            }
+            if let Some(v) = res_conf.unbalanced_timer {
+                res_nlas.push(DefaultNla::new(
+                    NHA_RES_GROUP_UNBALANCED_TIMER,
+                    v.saturating_mul(USER_HZ).to_ne_bytes().to_vec(),
+                ));
+            }
+            nl_msg.nlas.push(NexthopNla::ResGroup(res_nlas));
+        } else {
+            nl_msg
+                .nlas
+                .push(NexthopNla::GroupType(NEXTHOP_GRP_TYPE_MPATH));
+        }
+        return Ok(nl_msg);
+    }
+
+    nl_msg.family = match nh.family {
+        Some(AddressFamily::IPv6) => AF_INET6 as u8,
+        _ => AF_INET as u8,
+    };
+    if nh.is_blackhole() {
+        nl_msg.nlas.push(NexthopNla::Blackhole);
+        return Ok(nl_msg);
+    }
+    if let Some(iface_name) = nh.device.as_deref() {
+        match name_to_index.get(iface_name) {
+            Some(index) => nl_msg.nlas.push(NexthopNla::Oif(*index)),
+            None => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The device {iface_name} of nexthop {nh} does not \
+                        exist"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+    }
+    if let Some(gateway) = nh.gateway.as_deref() {
+        let gateway = gateway.parse::<IpAddr>()?;
+        nl_msg
+            .nlas
+            .push(NexthopNla::Gateway(ip_to_nl_bytes(&gateway)));
+    }
+    Ok(nl_msg)
+}
+
+// Whether kernel refuses to replace current nexthop with desired one.
+fn need_recreate(nh: &NexthopEntry, cur_nh: &NexthopEntry) -> bool {
+    nh.is_group() != cur_nh.is_group()
+        || nh.resilient.is_some() != cur_nh.resilient.is_some()
+}
+
+// The nexthop groups are removed before their members and created after
+// their members.
+pub(crate) fn nispor_nexthop_apply(
+    merged_nhs: &MergedNexthops,
+) -> Result<(), NmstateError> {
+    let links = get_links()?;
+    let index_to_name = get_link_index_to_name(&links);
+    let name_to_index: HashMap<&str, u32> = index_to_name
+        .iter()
+        .map(|(index, name)| (name.as_str(), *index))
+        .collect();
+    let cur_nhs = merged_nhs.current.config.as_deref().unwrap_or_default();
+
+    let mut del_nhs: Vec<NexthopEntry> = merged_nhs
+        .for_apply
+        .iter()
+        .filter(|nh| nh.is_absent())
+        .cloned()
+        .collect();
+    for nh in merged_nhs.for_apply.iter().filter(|nh| !nh.is_absent()) {
+        if let Some(cur_nh) = cur_nhs
+            .iter()
+            .find(|cur_nh| cur_nh.id == nh.id && need_recreate(nh, cur_nh))
+        {
+            log::warn!(
+                "Recreating nexthop {} as kernel cannot change nexthop \
+                type, routes using it will be removed by kernel",
+                nh.id.unwrap_or_default()
+            );
+            let mut cur_nh = cur_nh.clone();
+            cur_nh.state = Some(NexthopState::Absent);
+            del_nhs.push(cur_nh);
+        }
+    }
+    del_nhs.sort_by_key(|nh| !nh.is_group());
+
+    for nh in del_nhs.as_slice() {
+        log::info!("Removing nexthop {nh}");
+        let nl_msg = nmstate_nexthop_to_nl(nh, &name_to_index, RTM_DELNEXTHOP)?;
+        if let Err(e) = nexthop_request(nl_msg, NLM_F_REQUEST | NLM_F_ACK) {
+            if e.raw_os_error() != Some(nix::errno::Errno::ENOENT as i32) {
+                return Err(nl_io_error_to_nmstate(
+                    e,
+                    &format!("remove nexthop {nh}"),
+                ));
+            }
+        }
+    }
+
+    let mut add_nhs: Vec<&NexthopEntry> = merged_nhs
+        .for_apply
+        .iter()
+        .filter(|nh| !nh.is_absent())
+        .collect();
+    add_nhs.sort_by_key(|nh| nh.is_group());
+    for nh in add_nhs {
+        log::info!("Setting nexthop {nh}");
+        let nl_msg = nmstate_nexthop_to_nl(nh, &name_to_index, RTM_NEWNEXTHOP)?;
+        nexthop_request(
+            nl_msg,
+            NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | NLM_F_REPLACE,
+        )
+        .map_err(|e| nl_io_error_to_nmstate(e, &format!("set nexthop {nh}")))?;
+    }
+    Ok(())
+}
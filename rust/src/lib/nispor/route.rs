@@ -1,6 +1,32 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use futures::TryStreamExt;
 use log::warn;
+use netlink_packet_route::{
+    nlas::route::{Metrics, Nla},
+    RouteMessage, AF_INET, AF_INET6, RTA_GATEWAY, RTNH_F_ONLINK, RTN_BLACKHOLE,
+    RTN_LOCAL, RTN_PROHIBIT, RTN_THROW, RTN_UNICAST, RTN_UNREACHABLE,
+    RT_SCOPE_HOST, RT_SCOPE_LINK, RT_SCOPE_NOWHERE, RT_SCOPE_UNIVERSE,
+    RT_TABLE_MAIN, RT_TABLE_UNSPEC,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, Nla as _},
+    Emitable,
+};
+use rtnetlink::IpVersion;
 
-use crate::{RouteEntry, Routes};
+use crate::{
+    nispor::netlink::{
+        get_iface_index, ip_to_nl_bytes, nl_bytes_to_ip, nl_errno,
+        nl_error_to_nmstate, run_netlink,
+    },
+    ErrorKind, InterfaceIpAddr, MergedRoutes, NmstateError, RouteEntry,
+    RouteProtocol, RouteScope, RouteType, Routes,
+};
 
 const SUPPORTED_ROUTE_SCOPE: [nispor::RouteScope; 2] =
     [nispor::RouteScope::Universe, nispor::RouteScope::Link];
@@ -23,7 +49,15 @@ const IPV6_DEFAULT_GATEWAY: &str = "::/0";
 const IPV4_EMPTY_NEXT_HOP_ADDRESS: &str = "0.0.0.0";
 const IPV6_EMPTY_NEXT_HOP_ADDRESS: &str = "::";
 
-pub(crate) fn get_routes(running_config_only: bool) -> Routes {
+const RTA_NH_ID: u16 = 30;
+
+// Tuple of (destination, table, metric) identifying a route
+type RouteKey = (String, u32, Option<u32>);
+
+pub(crate) fn get_routes(
+    running_config_only: bool,
+    has_nexthop: bool,
+) -> Routes {
     let mut ret = Routes::new();
     let mut np_routes: Vec<nispor::Route> = Vec::new();
 
@@ -54,42 +88,155 @@ pub(crate) fn get_routes(running_config_only: bool) -> Routes {
         }
     }
 
+    // Nispor shows the routes using nexthop object as normal routes, hence we
+    // query their nexthop ID by ourselves.
+    let nh_route_ids = if has_nexthop {
+        get_nexthop_route_ids().unwrap_or_else(|e| {
+            log::warn!("Failed to retrieve nexthop ID of routes: {}", e);
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+
     if !running_config_only {
         let mut running_routes = Vec::new();
-        for np_route in np_routes.iter().filter(|np_route| {
-            SUPPORTED_ROUTE_SCOPE.contains(&np_route.scope)
-                && np_route.oif.as_ref() != Some(&"lo".to_string())
-        }) {
-            if is_multipath(np_route) {
-                for route in flat_multipath_route(np_route) {
-                    running_routes.push(route);
-                }
-            } else if np_route.oif.is_some() {
-                running_routes.push(np_route_to_nmstate(np_route));
-            }
+        for np_route in np_routes.iter() {
+            running_routes
+                .extend(np_route_to_nmstate_routes(np_route, &nh_route_ids));
         }
         ret.running = Some(running_routes);
     }
 
     let mut config_routes = Vec::new();
     for np_route in np_routes.iter().filter(|np_route| {
-        SUPPORTED_ROUTE_SCOPE.contains(&np_route.scope)
-            && SUPPORTED_STATIC_ROUTE_PROTOCOL.contains(&np_route.protocol)
-            && np_route.oif.as_ref() != Some(&"lo".to_string())
+        SUPPORTED_STATIC_ROUTE_PROTOCOL.contains(&np_route.protocol)
     }) {
-        if is_multipath(np_route) {
-            for route in flat_multipath_route(np_route) {
-                config_routes.push(route);
-            }
-        } else if np_route.oif.is_some() {
-            config_routes.push(np_route_to_nmstate(np_route));
-        }
+        config_routes
+            .extend(np_route_to_nmstate_routes(np_route, &nh_route_ids));
     }
     ret.config = Some(config_routes);
     ret
 }
 
-fn np_route_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
+fn np_route_to_nmstate_routes(
+    np_route: &nispor::Route,
+    nh_route_ids: &HashMap<RouteKey, u32>,
+) -> Vec<RouteEntry> {
+    let nexthop_id = if np_route.route_type == nispor::RouteType::Unicast {
+        np_route_key(np_route).and_then(|k| nh_route_ids.get(&k).copied())
+    } else {
+        None
+    };
+    if nexthop_id.is_some() {
+        vec![np_route_to_nmstate(np_route, nexthop_id)]
+    } else if !is_supported_np_route(np_route) {
+        Vec::new()
+    } else if is_multipath(np_route) {
+        flat_multipath_route(np_route)
+    } else {
+        vec![np_route_to_nmstate(np_route, None)]
+    }
+}
+
+fn np_route_key(np_route: &nispor::Route) -> Option<RouteKey> {
+    let dst = match np_route.dst.as_deref() {
+        Some(d) => d.to_string(),
+        None => match np_route.address_family {
+            nispor::AddressFamily::IPv4 => IPV4_DEFAULT_GATEWAY.to_string(),
+            nispor::AddressFamily::IPv6 => IPV6_DEFAULT_GATEWAY.to_string(),
+            _ => return None,
+        },
+    };
+    Some((dst, np_route.table, np_route.metric))
+}
+
+fn get_nexthop_route_ids() -> Result<HashMap<RouteKey, u32>, NmstateError> {
+    let nl_msgs = run_netlink(|handle| async move {
+        let mut ret = Vec::new();
+        for ip_version in [IpVersion::V4, IpVersion::V6] {
+            let mut routes = handle.route().get(ip_version).execute();
+            while let Some(nl_msg) = routes
+                .try_next()
+                .await
+                .map_err(|e| nl_error_to_nmstate(e, "query routes"))?
+            {
+                ret.push(nl_msg);
+            }
+        }
+        Ok(ret)
+    })?;
+    let mut ret = HashMap::new();
+    for nl_msg in nl_msgs {
+        let mut nexthop_id = None;
+        let mut dst = None;
+        let mut table = u32::from(nl_msg.header.table);
+        let mut metric = None;
+        for nla in nl_msg.nlas.iter() {
+            match nla {
+                Nla::Other(nla) if nla.kind() == RTA_NH_ID => {
+                    let mut value = [0u8; 4];
+                    if nla.value_len() == value.len() {
+                        nla.emit_value(&mut value);
+                        nexthop_id = Some(u32::from_ne_bytes(value));
+                    }
+                }
+                Nla::Destination(v) => dst = nl_bytes_to_ip(v),
+                Nla::Table(v) => table = *v,
+                Nla::Priority(v) => metric = Some(*v),
+                _ => (),
+            }
+        }
+        if let Some(nexthop_id) = nexthop_id {
+            let dst = match dst {
+                Some(ip) => {
+                    format!("{ip}/{}", nl_msg.header.destination_prefix_length)
+                }
+                None if nl_msg.header.address_family == AF_INET6 as u8 => {
+                    IPV6_DEFAULT_GATEWAY.to_string()
+                }
+                None => IPV4_DEFAULT_GATEWAY.to_string(),
+            };
+            ret.insert((dst, table, metric), nexthop_id);
+        }
+    }
+    Ok(ret)
+}
+
+fn is_supported_np_route(np_route: &nispor::Route) -> bool {
+    let is_lo =
+        np_route.oif.as_deref() == Some(RouteEntry::LOOPBACK_IFACE_NAME);
+    match np_route.route_type {
+        nispor::RouteType::Unicast => {
+            SUPPORTED_ROUTE_SCOPE.contains(&np_route.scope)
+                && !is_lo
+                && (np_route.oif.is_some() || is_multipath(np_route))
+        }
+        // Kernel use loopback interface for IPv6 routes of these types
+        nispor::RouteType::BlackHole
+        | nispor::RouteType::Unreachable
+        | nispor::RouteType::Prohibit
+        | nispor::RouteType::Throw => true,
+        nispor::RouteType::Local => np_route.oif.is_some() && !is_lo,
+        _ => false,
+    }
+}
+
+fn np_route_type_to_nmstate(np_route: &nispor::Route) -> Option<RouteType> {
+    match np_route.route_type {
+        nispor::RouteType::BlackHole => Some(RouteType::Blackhole),
+        nispor::RouteType::Unreachable => Some(RouteType::Unreachable),
+        nispor::RouteType::Prohibit => Some(RouteType::Prohibit),
+        nispor::RouteType::Throw => Some(RouteType::Throw),
+        nispor::RouteType::Local => Some(RouteType::Local),
+        _ => None,
+    }
+}
+
+fn np_route_to_nmstate(
+    np_route: &nispor::Route,
+    nexthop_id: Option<u32>,
+) -> RouteEntry {
     let destination = match &np_route.dst {
         Some(dst) => Some(dst.to_string()),
         None => match np_route.address_family {
@@ -137,6 +284,36 @@ fn np_route_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
     route_entry.next_hop_addr = next_hop_addr;
     route_entry.metric = np_route.metric.map(i64::from);
     route_entry.table_id = Some(np_route.table);
+    route_entry.route_type = np_route_type_to_nmstate(np_route);
+    if let Some(route_type) = route_entry.route_type {
+        route_entry.next_hop_addr = None;
+        if route_type.is_iface_less() {
+            route_entry.next_hop_iface = None;
+        }
+    }
+    if nexthop_id.is_some() {
+        route_entry.nexthop_id = nexthop_id;
+        route_entry.next_hop_iface = None;
+        route_entry.next_hop_addr = None;
+    }
+    route_entry.source = np_route.prefered_src.clone();
+    route_entry.mtu = np_route.mtu;
+    route_entry.advmss = np_route.advmss;
+    route_entry.initcwnd = np_route.initcwnd;
+    route_entry.initrwnd = np_route.initrwnd;
+    route_entry.quickack = np_route.quickack.filter(|v| *v > 0).map(|_| true);
+    // Only show scope and protocol when they are not the default ones
+    if let Ok(scope) = RouteScope::try_from(u8::from(&np_route.scope)) {
+        if scope != route_entry.effective_scope() {
+            route_entry.scope = Some(scope);
+        }
+    }
+    if let Ok(protocol) = RouteProtocol::try_from(u8::from(&np_route.protocol))
+    {
+        if protocol != route_entry.effective_protocol() {
+            route_entry.protocol = Some(protocol);
+        }
+    }
 
     route_entry
 }
@@ -156,7 +333,7 @@ fn flat_multipath_route(np_route: &nispor::Route) -> Vec<RouteEntry> {
             let mut new_np_route = np_route.clone();
             new_np_route.via = Some(mp_route.via.to_string());
             new_np_route.oif = Some(mp_route.iface.to_string());
-            let mut route = np_route_to_nmstate(&new_np_route);
+            let mut route = np_route_to_nmstate(&new_np_route, None);
             if np_route.address_family == nispor::AddressFamily::IPv4 {
                 route.weight = Some(mp_route.weight);
             }
@@ -165,3 +342,363 @@ fn flat_multipath_route(np_route: &nispor::Route) -> Vec<RouteEntry> {
     }
     ret
 }
+
+pub(crate) fn apply_routes(
+    merged_routes: &MergedRoutes,
+) -> Result<(), NmstateError> {
+    let changed_ifaces = merged_routes.route_changed_ifaces.as_slice();
+    apply_routes_with_filter(merged_routes, |rt| {
+        rt.nexthop_id.is_some()
+            || rt
+                .iface_for_store()
+                .map(|i| changed_ifaces.iter().any(|c| c == i))
+                .unwrap_or_default()
+    })
+}
+
+// Apply the desired routes and remove the current routes matching desired
+// absent routes. Only routes passing the `filter` are touched.
+// Routes with ECMP weight are grouped by destination, route table and metric
+// and replaced as a whole, because kernel cannot append nexthop to existing
+// IPv4 multipath route.
+fn apply_routes_with_filter<F>(
+    merged_routes: &MergedRoutes,
+    filter: F,
+) -> Result<(), NmstateError>
+where
+    F: Fn(&RouteEntry) -> bool,
+{
+    let is_changed_iface = |rt: &&RouteEntry| filter(rt);
+
+    let mut des_routes = Vec::new();
+    if let Some(rts) = merged_routes.desired.config.as_ref() {
+        for rt in rts {
+            let mut rt = rt.clone();
+            rt.sanitize()?;
+            des_routes.push(rt);
+        }
+    }
+    let cur_routes =
+        merged_routes.current.config.as_deref().unwrap_or_default();
+
+    let add_routes: Vec<&RouteEntry> = des_routes
+        .iter()
+        .filter(|rt| !rt.is_absent())
+        .filter(is_changed_iface)
+        .collect();
+    let del_routes: Vec<&RouteEntry> = cur_routes
+        .iter()
+        .filter(|cur_rt| {
+            des_routes
+                .iter()
+                .any(|rt| rt.is_absent() && rt.is_match(cur_rt))
+                && !add_routes.contains(cur_rt)
+        })
+        .filter(is_changed_iface)
+        .collect();
+
+    let mut ecmp_keys: Vec<(&str, u32, i64)> = Vec::new();
+    for rt in add_routes
+        .iter()
+        .chain(del_routes.iter())
+        .filter(|rt| rt.weight.is_some())
+    {
+        let key = ecmp_route_key(rt);
+        if !ecmp_keys.contains(&key) {
+            ecmp_keys.push(key);
+        }
+    }
+    let mut ecmp_routes: HashMap<(&str, u32, i64), Vec<&RouteEntry>> =
+        HashMap::new();
+    for rt in cur_routes
+        .iter()
+        .filter(|rt| rt.weight.is_some() && !del_routes.contains(rt))
+        .chain(add_routes.iter().copied().filter(|rt| rt.weight.is_some()))
+    {
+        let key = ecmp_route_key(rt);
+        if ecmp_keys.contains(&key) {
+            let rts = ecmp_routes.entry(key).or_default();
+            if !rts.contains(&rt) {
+                rts.push(rt);
+            }
+        }
+    }
+
+    run_netlink(|handle| async move {
+        for rt in del_routes.iter().filter(|rt| rt.weight.is_none()) {
+            log::info!("Removing route {rt}");
+            let nl_msg = nmstate_route_to_nl(&handle, rt, true).await?;
+            del_route(&handle, nl_msg, rt).await?;
+        }
+        for rt in add_routes.iter().filter(|rt| rt.weight.is_none()) {
+            log::info!("Adding route {rt}");
+            // The netlink library cannot set RTNH_F_ONLINK to route header,
+            // hence we use multipath route with single next hop instead.
+            let nl_msg = if rt.onlink == Some(true) {
+                nmstate_ecmp_routes_to_nl(&handle, &[rt]).await?
+            } else {
+                nmstate_route_to_nl(&handle, rt, false).await?
+            };
+            // Replace existing route to change its attributes
+            add_route(&handle, nl_msg, rt, rt.has_extended_attrs()).await?;
+        }
+        for key in ecmp_keys.as_slice() {
+            match ecmp_routes.get(key) {
+                Some(rts) if !rts.is_empty() => {
+                    log::info!(
+                        "Setting ECMP route {} with {} next hops",
+                        key.0,
+                        rts.len()
+                    );
+                    let nl_msg =
+                        nmstate_ecmp_routes_to_nl(&handle, rts).await?;
+                    add_route(&handle, nl_msg, rts[0], true).await?;
+                }
+                _ => {
+                    if let Some(rt) =
+                        del_routes.iter().find(|rt| ecmp_route_key(rt) == *key)
+                    {
+                        log::info!("Removing ECMP route {}", key.0);
+                        let mut rt = (*rt).clone();
+                        rt.next_hop_iface = None;
+                        rt.next_hop_addr = None;
+                        let nl_msg =
+                            nmstate_route_to_nl(&handle, &rt, true).await?;
+                        del_route(&handle, nl_msg, &rt).await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn ecmp_route_key(rt: &RouteEntry) -> (&str, u32, i64) {
+    (
+        rt.destination.as_deref().unwrap_or_default(),
+        route_table_id(rt),
+        rt.metric.filter(|m| *m >= 0).unwrap_or_default(),
+    )
+}
+
+fn route_table_id(rt: &RouteEntry) -> u32 {
+    match rt.table_id {
+        None | Some(RouteEntry::USE_DEFAULT_ROUTE_TABLE) => {
+            RT_TABLE_MAIN.into()
+        }
+        Some(t) => t,
+    }
+}
+
+fn route_gateway(rt: &RouteEntry) -> Result<Option<IpAddr>, NmstateError> {
+    match rt.next_hop_addr.as_deref() {
+        None | Some("") => Ok(None),
+        Some(via) => {
+            let via = via.parse::<IpAddr>()?;
+            if via.is_unspecified() {
+                Ok(None)
+            } else {
+                Ok(Some(via))
+            }
+        }
+    }
+}
+
+async fn nmstate_route_to_nl(
+    handle: &rtnetlink::Handle,
+    rt: &RouteEntry,
+    for_removal: bool,
+) -> Result<RouteMessage, NmstateError> {
+    let oif_index = match rt.next_hop_iface.as_deref() {
+        Some(iface_name) => Some(get_iface_index(handle, iface_name).await?),
+        None => None,
+    };
+    nmstate_route_to_nl_msg(rt, oif_index, for_removal)
+}
+
+// The `oif_index` should be the kernel index of `next-hop-interface`.
+pub(crate) fn nmstate_route_to_nl_msg(
+    rt: &RouteEntry,
+    oif_index: Option<u32>,
+    for_removal: bool,
+) -> Result<RouteMessage, NmstateError> {
+    let mut nl_msg = RouteMessage::default();
+    let dst = match rt.destination.as_deref() {
+        Some(d) => InterfaceIpAddr::try_from(d)?,
+        None => {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Route destination is mandatory: {rt}"),
+            ));
+        }
+    };
+    nl_msg.header.address_family = if dst.ip.is_ipv6() {
+        AF_INET6 as u8
+    } else {
+        AF_INET as u8
+    };
+    nl_msg.header.destination_prefix_length = dst.prefix_length;
+    if dst.prefix_length > 0 {
+        nl_msg.nlas.push(Nla::Destination(ip_to_nl_bytes(&dst.ip)));
+    }
+    let table_id = route_table_id(rt);
+    nl_msg.header.table = u8::try_from(table_id).unwrap_or(RT_TABLE_UNSPEC);
+    nl_msg.nlas.push(Nla::Table(table_id));
+
+    if let Some(metric) = rt.metric.filter(|m| *m >= 0) {
+        nl_msg.nlas.push(Nla::Priority(metric as u32));
+    }
+    if let Some(index) = oif_index {
+        nl_msg.nlas.push(Nla::Oif(index));
+    }
+    let gateway = route_gateway(rt)?;
+    if let Some(via) = gateway.as_ref() {
+        nl_msg.nlas.push(Nla::Gateway(ip_to_nl_bytes(via)));
+    }
+    if let Some(nexthop_id) = rt.nexthop_id {
+        nl_msg.nlas.push(Nla::Other(DefaultNla::new(
+            RTA_NH_ID,
+            nexthop_id.to_ne_bytes().to_vec(),
+        )));
+    }
+
+    if for_removal {
+        // Kernel treat zero protocol and type as wildcard on removal
+        nl_msg.header.scope = RT_SCOPE_NOWHERE;
+        if let Some(route_type) = rt.route_type {
+            nl_msg.header.kind = route_type_to_nl(route_type);
+        }
+        if let Some(protocol) = rt.protocol {
+            nl_msg.header.protocol = protocol.into();
+        }
+    } else {
+        nl_msg.header.protocol = rt.effective_protocol().into();
+        nl_msg.header.kind =
+            rt.route_type.map(route_type_to_nl).unwrap_or(RTN_UNICAST);
+        nl_msg.header.scope = match (rt.scope, rt.route_type) {
+            (Some(scope), _) => scope.into(),
+            (None, Some(RouteType::Local)) => RT_SCOPE_HOST,
+            (None, Some(_)) => RT_SCOPE_UNIVERSE,
+            (None, None) if gateway.is_some() || rt.nexthop_id.is_some() => {
+                RT_SCOPE_UNIVERSE
+            }
+            (None, None) => RT_SCOPE_LINK,
+        };
+        if let Some(src) = rt.source.as_deref() {
+            let src = src.parse::<IpAddr>()?;
+            nl_msg.nlas.push(Nla::PrefSource(ip_to_nl_bytes(&src)));
+        }
+        let metrics = gen_route_metrics(rt);
+        if !metrics.is_empty() {
+            let mut data = vec![0u8; metrics.as_slice().buffer_len()];
+            metrics.as_slice().emit(&mut data);
+            nl_msg.nlas.push(Nla::Metrics(data));
+        }
+    }
+    Ok(nl_msg)
+}
+
+fn gen_route_metrics(rt: &RouteEntry) -> Vec<Metrics> {
+    let mut ret = Vec::new();
+    if let Some(v) = rt.mtu {
+        ret.push(Metrics::Mtu(v));
+    }
+    if let Some(v) = rt.advmss {
+        ret.push(Metrics::Advmss(v));
+    }
+    if let Some(v) = rt.initcwnd {
+        ret.push(Metrics::InitCwnd(v));
+    }
+    if let Some(v) = rt.initrwnd {
+        ret.push(Metrics::InitRwnd(v));
+    }
+    if let Some(v) = rt.quickack {
+        ret.push(Metrics::QuickAck(v.into()));
+    }
+    ret
+}
+
+fn route_type_to_nl(route_type: RouteType) -> u8 {
+    match route_type {
+        RouteType::Blackhole => RTN_BLACKHOLE,
+        RouteType::Unreachable => RTN_UNREACHABLE,
+        RouteType::Prohibit => RTN_PROHIBIT,
+        RouteType::Throw => RTN_THROW,
+        RouteType::Local => RTN_LOCAL,
+    }
+}
+
+async fn nmstate_ecmp_routes_to_nl(
+    handle: &rtnetlink::Handle,
+    rts: &[&RouteEntry],
+) -> Result<RouteMessage, NmstateError> {
+    let mut first_rt = rts[0].clone();
+    first_rt.next_hop_iface = None;
+    first_rt.next_hop_addr = None;
+    let mut nl_msg = nmstate_route_to_nl(handle, &first_rt, false).await?;
+    if first_rt.scope.is_none() {
+        nl_msg.header.scope = RT_SCOPE_UNIVERSE;
+    }
+
+    // Each next hop is a `struct rtnexthop` followed by its own attributes
+    let mut nexthops: Vec<u8> = Vec::new();
+    for rt in rts {
+        let index = match rt.next_hop_iface.as_deref() {
+            Some(iface_name) => get_iface_index(handle, iface_name).await?,
+            None => 0,
+        };
+        let gateway = route_gateway(rt)?.map(|i| ip_to_nl_bytes(&i));
+        let gateway_nla_len =
+            gateway.as_ref().map(|g| 4 + g.len()).unwrap_or_default();
+        nexthops
+            .extend_from_slice(&((8 + gateway_nla_len) as u16).to_ne_bytes());
+        // rtnh_flags
+        nexthops.push(if rt.onlink == Some(true) {
+            RTNH_F_ONLINK
+        } else {
+            0
+        });
+        // rtnh_hops holds weight minus one
+        nexthops.push(rt.weight.unwrap_or(1).saturating_sub(1) as u8);
+        nexthops.extend_from_slice(&index.to_ne_bytes());
+        if let Some(gateway) = gateway {
+            nexthops.extend_from_slice(&(gateway_nla_len as u16).to_ne_bytes());
+            nexthops.extend_from_slice(&RTA_GATEWAY.to_ne_bytes());
+            nexthops.extend_from_slice(gateway.as_slice());
+        }
+    }
+    nl_msg.nlas.push(Nla::MultiPath(nexthops));
+    Ok(nl_msg)
+}
+
+async fn add_route(
+    handle: &rtnetlink::Handle,
+    nl_msg: RouteMessage,
+    rt: &RouteEntry,
+    replace: bool,
+) -> Result<(), NmstateError> {
+    let mut req = handle.route().add();
+    if replace {
+        req = req.replace();
+    }
+    *req.message_mut() = nl_msg;
+    if let Err(e) = req.execute().await {
+        if nl_errno(&e) != Some(-(nix::errno::Errno::EEXIST as i32)) {
+            return Err(nl_error_to_nmstate(e, &format!("add route {rt}")));
+        }
+    }
+    Ok(())
+}
+
+async fn del_route(
+    handle: &rtnetlink::Handle,
+    nl_msg: RouteMessage,
+    rt: &RouteEntry,
+) -> Result<(), NmstateError> {
+    if let Err(e) = handle.route().del(nl_msg).execute().await {
+        if nl_errno(&e) != Some(-(nix::errno::Errno::ESRCH as i32)) {
+            return Err(nl_error_to_nmstate(e, &format!("remove route {rt}")));
+        }
+    }
+    Ok(())
+}
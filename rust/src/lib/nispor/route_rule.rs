@@ -1,8 +1,33 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::convert::TryFrom;
+
+use futures::stream::TryStreamExt;
 use log::warn;
+use netlink_packet_route::{
+    nlas::rule::Nla, RuleMessage, AF_INET, AF_INET6, FIB_RULE_INVERT,
+    FR_ACT_TO_TBL, RTPROT_STATIC, RT_TABLE_MAIN, RT_TABLE_UNSPEC,
+};
+use rtnetlink::IpVersion;
+
+use crate::{
+    nispor::netlink::{
+        ip_to_nl_bytes, nl_errno, nl_error_to_nmstate, run_netlink,
+    },
+    AddressFamily, InterfaceIpAddr, MergedRouteRules, NmstateError,
+    RouteRuleAction, RouteRuleEntry, RouteRulePortRange, RouteRuleUidRange,
+    RouteRules,
+};
 
-use crate::{AddressFamily, RouteRuleAction, RouteRuleEntry, RouteRules};
+// Selectors not provided by nispor:
+// (family, priority, uid_range, sport, dport)
+type RuleRangeSelectors = (
+    u8,
+    Option<u32>,
+    Option<RouteRuleUidRange>,
+    Option<RouteRulePortRange>,
+    Option<RouteRulePortRange>,
+);
 
 // Due to a bug in NetworkManager all route rules added using NetworkManager are
 // using RTM_PROTOCOL UnSpec. Therefore, we need to support it until it is
@@ -37,11 +62,35 @@ pub(crate) fn get_route_rules(
         SUPPORTED_ROUTE_PROTOCOL.as_slice()
     };
 
-    for np_rule in np_rules {
+    // Nispor dumps IPv6 route rules before IPv4 ones, we do the same, hence
+    // the range selectors can be paired with nispor route rules by index.
+    let range_selectors = match get_rule_range_selectors() {
+        Ok(s) if s.len() == np_rules.len() => s,
+        Ok(_) => {
+            log::info!(
+                "Route rules changed during query, ignoring uid-range, \
+                sport and dport"
+            );
+            Vec::new()
+        }
+        Err(e) => {
+            log::info!(
+                "Failed to query uid-range, sport and dport of route \
+                rules: {e}"
+            );
+            Vec::new()
+        }
+    };
+
+    for (i, np_rule) in np_rules.iter().enumerate() {
         let mut rule = RouteRuleEntry::new();
-        // We only support route rules with 'table' action
         match np_rule.action {
             nispor::RuleAction::Table => (),
+            nispor::RuleAction::Goto => {
+                rule.action = Some(RouteRuleAction::Goto);
+                rule.goto_target = np_rule.goto;
+            }
+            nispor::RuleAction::Nop => rule.action = Some(RouteRuleAction::Nop),
             nispor::RuleAction::Blackhole => {
                 rule.action = Some(RouteRuleAction::Blackhole)
             }
@@ -63,6 +112,26 @@ pub(crate) fn get_route_rules(
             }
         }
         rule.iif = np_rule.iif.clone();
+        rule.oif = np_rule.oif.clone();
+        rule.ip_proto = np_rule.ip_proto.as_ref().map(np_family_to_u8);
+        rule.tos = Some(np_rule.tos).filter(|t| *t != 0);
+        rule.l3mdev = np_rule.l3mdev.filter(|l| *l);
+        rule.suppress_prefix_length = np_rule.suppress_prefix_len;
+        rule.suppress_ifgroup = np_rule.suppress_ifgroup;
+        if np_rule.flags & FIB_RULE_INVERT > 0 {
+            rule.invert = Some(true);
+        }
+        if let Some((family, priority, uid_range, sport, dport)) =
+            range_selectors.get(i)
+        {
+            if *family == np_family_to_u8(&np_rule.address_family)
+                && *priority == np_rule.priority
+            {
+                rule.uid_range = *uid_range;
+                rule.sport = *sport;
+                rule.dport = *dport;
+            }
+        }
         rule.ip_to = np_rule.dst.clone();
         rule.ip_from = np_rule.src.clone();
         rule.table_id = np_rule.table;
@@ -86,3 +155,204 @@ pub(crate) fn get_route_rules(
 
     ret
 }
+
+// Nispor stores the IP protocol number of route rule as AddressFamily also,
+// convert it back to number.
+fn np_family_to_u8(family: &nispor::AddressFamily) -> u8 {
+    match family {
+        nispor::AddressFamily::IPv4 => AF_INET as u8,
+        nispor::AddressFamily::IPv6 => AF_INET6 as u8,
+        nispor::AddressFamily::Other(d) => *d,
+        _ => 0,
+    }
+}
+
+fn get_rule_range_selectors() -> Result<Vec<RuleRangeSelectors>, NmstateError> {
+    let nl_msgs = run_netlink(|handle| async move {
+        let mut ret = Vec::new();
+        for ip_version in [IpVersion::V6, IpVersion::V4] {
+            let mut rules = handle.rule().get(ip_version).execute();
+            while let Some(nl_msg) = rules
+                .try_next()
+                .await
+                .map_err(|e| nl_error_to_nmstate(e, "query route rules"))?
+            {
+                ret.push(nl_msg);
+            }
+        }
+        Ok(ret)
+    })?;
+    let mut ret = Vec::new();
+    for nl_msg in nl_msgs {
+        let mut priority = None;
+        let mut uid_range = None;
+        let mut sport = None;
+        let mut dport = None;
+        for nla in nl_msg.nlas.iter() {
+            match nla {
+                Nla::Priority(v) => priority = Some(*v),
+                Nla::UidRange(v) if v.len() == 8 => {
+                    uid_range = Some(RouteRuleUidRange::new(
+                        u32::from_ne_bytes([v[0], v[1], v[2], v[3]]),
+                        u32::from_ne_bytes([v[4], v[5], v[6], v[7]]),
+                    ));
+                }
+                Nla::SourcePortRange(v) if v.len() == 4 => {
+                    sport = Some(nl_bytes_to_port_range(v));
+                }
+                Nla::DestinationPortRange(v) if v.len() == 4 => {
+                    dport = Some(nl_bytes_to_port_range(v));
+                }
+                _ => (),
+            }
+        }
+        ret.push((nl_msg.header.family, priority, uid_range, sport, dport));
+    }
+    Ok(ret)
+}
+
+fn nl_bytes_to_port_range(v: &[u8]) -> RouteRulePortRange {
+    RouteRulePortRange::new(
+        u16::from_ne_bytes([v[0], v[1]]),
+        u16::from_ne_bytes([v[2], v[3]]),
+    )
+}
+
+fn port_range_to_nl_bytes(range: &RouteRulePortRange) -> Vec<u8> {
+    let mut ret = range.start.to_ne_bytes().to_vec();
+    ret.extend_from_slice(&range.end.to_ne_bytes());
+    ret
+}
+
+// The `for_apply` of MergedRouteRules holds both the full current route rules
+// marked as absent and the desired route rules with priority set.
+pub(crate) fn apply_route_rules(
+    merged_rules: &MergedRouteRules,
+) -> Result<(), NmstateError> {
+    let rules = merged_rules.for_apply.as_slice();
+    run_netlink(|handle| async move {
+        for rule in rules.iter().filter(|r| r.is_absent()) {
+            log::info!("Removing route rule {rule}");
+            let nl_msg = nmstate_rule_to_nl(rule, true)?;
+            if let Err(e) = handle.rule().del(nl_msg).execute().await {
+                if nl_errno(&e) != Some(-(nix::errno::Errno::ENOENT as i32)) {
+                    return Err(nl_error_to_nmstate(
+                        e,
+                        &format!("remove route rule {rule}"),
+                    ));
+                }
+            }
+        }
+        for rule in rules.iter().filter(|r| !r.is_absent()) {
+            log::info!("Adding route rule {rule}");
+            let mut req = handle.rule().add();
+            *req.message_mut() = nmstate_rule_to_nl(rule, false)?;
+            if let Err(e) = req.execute().await {
+                if nl_errno(&e) != Some(-(nix::errno::Errno::EEXIST as i32)) {
+                    return Err(nl_error_to_nmstate(
+                        e,
+                        &format!("add route rule {rule}"),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+pub(crate) fn nmstate_rule_to_nl(
+    rule: &RouteRuleEntry,
+    for_removal: bool,
+) -> Result<RuleMessage, NmstateError> {
+    let mut nl_msg = RuleMessage::default();
+    nl_msg.header.family = if rule.is_ipv6() {
+        AF_INET6 as u8
+    } else {
+        AF_INET as u8
+    };
+    if let Some(ip) = rule.ip_from.as_deref().filter(|i| !i.is_empty()) {
+        let ip = InterfaceIpAddr::try_from(ip)?;
+        nl_msg.header.src_len = ip.prefix_length;
+        nl_msg.nlas.push(Nla::Source(ip_to_nl_bytes(&ip.ip)));
+    }
+    if let Some(ip) = rule.ip_to.as_deref().filter(|i| !i.is_empty()) {
+        let ip = InterfaceIpAddr::try_from(ip)?;
+        nl_msg.header.dst_len = ip.prefix_length;
+        nl_msg.nlas.push(Nla::Destination(ip_to_nl_bytes(&ip.ip)));
+    }
+    match rule.action {
+        Some(RouteRuleAction::Goto) => {
+            nl_msg.header.action = RouteRuleAction::Goto.into();
+            if let Some(target) = rule.goto_target {
+                nl_msg.nlas.push(Nla::Goto(target));
+            }
+        }
+        Some(action) => nl_msg.header.action = action.into(),
+        None if rule.l3mdev == Some(true) => {
+            nl_msg.header.action = FR_ACT_TO_TBL;
+            nl_msg.nlas.push(Nla::L3MDev(1));
+        }
+        None => {
+            nl_msg.header.action = FR_ACT_TO_TBL;
+            let table_id = match rule.table_id {
+                None | Some(RouteRuleEntry::USE_DEFAULT_ROUTE_TABLE) => {
+                    RT_TABLE_MAIN.into()
+                }
+                Some(t) => t,
+            };
+            nl_msg.header.table =
+                u8::try_from(table_id).unwrap_or(RT_TABLE_UNSPEC);
+            nl_msg.nlas.push(Nla::Table(table_id));
+        }
+    }
+    if let Some(priority) = rule.priority.filter(|p| *p >= 0) {
+        nl_msg.nlas.push(Nla::Priority(priority as u32));
+    }
+    if let Some(fwmark) = rule.fwmark {
+        nl_msg.nlas.push(Nla::FwMark(fwmark));
+    }
+    if let Some(fwmask) = rule.fwmask {
+        nl_msg.nlas.push(Nla::FwMask(fwmask));
+    }
+    if let Some(iif) = rule.iif.as_deref() {
+        nl_msg.nlas.push(Nla::Iifname(iif.to_string()));
+    }
+    if let Some(oif) = rule.oif.as_deref() {
+        nl_msg.nlas.push(Nla::OifName(oif.to_string()));
+    }
+    if let Some(uid_range) = rule.uid_range.as_ref() {
+        let mut value = uid_range.start.to_ne_bytes().to_vec();
+        value.extend_from_slice(&uid_range.end.to_ne_bytes());
+        nl_msg.nlas.push(Nla::UidRange(value));
+    }
+    if let Some(ip_proto) = rule.ip_proto {
+        nl_msg.nlas.push(Nla::IpProto(ip_proto));
+    }
+    if let Some(sport) = rule.sport.as_ref() {
+        nl_msg
+            .nlas
+            .push(Nla::SourcePortRange(port_range_to_nl_bytes(sport)));
+    }
+    if let Some(dport) = rule.dport.as_ref() {
+        nl_msg
+            .nlas
+            .push(Nla::DestinationPortRange(port_range_to_nl_bytes(dport)));
+    }
+    if let Some(tos) = rule.tos {
+        nl_msg.header.tos = tos;
+    }
+    if rule.invert == Some(true) {
+        nl_msg.header.flags |= FIB_RULE_INVERT;
+    }
+    if let Some(v) = rule.suppress_prefix_length {
+        nl_msg.nlas.push(Nla::SuppressPrefixLen(v));
+    }
+    if let Some(v) = rule.suppress_ifgroup {
+        nl_msg.nlas.push(Nla::SuppressIfGroup(v));
+    }
+    // Kernel treat unset protocol as wildcard on removal
+    if !for_removal {
+        nl_msg.nlas.push(Nla::Protocol(RTPROT_STATIC));
+    }
+    Ok(nl_msg)
+}
@@ -8,19 +8,30 @@ use crate::{
         bond::np_bond_to_nmstate,
         error::np_error_to_nmstate,
         ethernet::np_ethernet_to_nmstate,
+        geneve::get_geneves,
         hostname::get_hostname_state,
+        hsr::get_hsrs,
         infiniband::np_ib_to_nmstate,
+        ip_tunnel::get_ip_tunnels,
+        ip_vlan::get_ip_vlans,
         linux_bridge::{append_bridge_port_config, np_bridge_to_nmstate},
         mac_vlan::{np_mac_vlan_to_nmstate, np_mac_vtap_to_nmstate},
+        macsec::get_macsecs,
+        netlink::{get_link_index_to_name, get_links},
+        nexthop::get_nexthops,
         route::get_routes,
         route_rule::get_route_rules,
+        tun::np_tun_to_nmstate,
         veth::np_veth_to_nmstate,
-        vlan::np_vlan_to_nmstate,
+        vlan::{get_vlan_qos_maps, np_vlan_to_nmstate},
         vrf::np_vrf_to_nmstate,
-        vxlan::np_vxlan_to_nmstate,
+        vxlan::{get_vxlan_fdb_remotes, np_vxlan_to_nmstate},
+        xfrm::get_xfrms,
     },
-    DummyInterface, Interface, InterfaceType, Interfaces, LoopbackInterface,
-    NetworkState, NmstateError, OvsInterface, UnknownInterface,
+    DummyInterface, GeneveInterface, HsrInterface, Interface, InterfaceType,
+    Interfaces, IpTunnelInterface, IpVlanInterface, LoopbackInterface,
+    MacsecInterface, NetworkState, NmstateError, OvsInterface,
+    UnknownInterface, WireGuardInterface, XfrmInterface,
 };
 
 pub(crate) fn nispor_retrieve(
@@ -28,7 +39,13 @@ pub(crate) fn nispor_retrieve(
 ) -> Result<NetworkState, NmstateError> {
     let mut net_state = NetworkState {
         hostname: get_hostname_state(),
-        prop_list: vec!["interfaces", "routes", "rules", "hostname"],
+        prop_list: vec![
+            "interfaces",
+            "nexthops",
+            "routes",
+            "rules",
+            "hostname",
+        ],
         ..Default::default()
     };
     let mut filter = nispor::NetStateFilter::default();
@@ -37,9 +54,54 @@ pub(crate) fn nispor_retrieve(
     filter.route = None;
     let np_state = nispor::NetState::retrieve_with_filter(&filter)
         .map_err(np_error_to_nmstate)?;
+    let nl_links = get_links()?;
+    let index_to_name = get_link_index_to_name(&nl_links);
+    let mut ip_tunnels = get_ip_tunnels(&nl_links, &index_to_name);
+    let mut geneves = get_geneves(&nl_links, &index_to_name);
+    let mut macsecs = get_macsecs(&nl_links, &index_to_name);
+    let mut ip_vlans = get_ip_vlans(&nl_links, &index_to_name);
+    let mut hsrs = get_hsrs(&nl_links, &index_to_name);
+    let mut xfrms = get_xfrms(&nl_links, &index_to_name);
+    let mut vlan_qos_maps = get_vlan_qos_maps(&nl_links, &index_to_name);
+    let mut vxlan_fdb_remotes = if np_state
+        .ifaces
+        .values()
+        .any(|i| i.iface_type == nispor::IfaceType::Vxlan)
+    {
+        get_vxlan_fdb_remotes()?
+    } else {
+        HashMap::new()
+    };
 
     for (_, np_iface) in np_state.ifaces.iter() {
-        let base_iface = np_iface_to_base_iface(np_iface, running_config_only);
+        let mut base_iface =
+            np_iface_to_base_iface(np_iface, running_config_only);
+        let ip_tunnel_conf = ip_tunnels.remove(np_iface.name.as_str()).map(
+            |(iface_type, conf)| {
+                base_iface.iface_type = iface_type;
+                conf
+            },
+        );
+        let geneve_conf = geneves.remove(np_iface.name.as_str());
+        if geneve_conf.is_some() {
+            base_iface.iface_type = InterfaceType::Geneve;
+        }
+        let macsec_conf = macsecs.remove(np_iface.name.as_str());
+        if macsec_conf.is_some() {
+            base_iface.iface_type = InterfaceType::Macsec;
+        }
+        let ip_vlan_conf = ip_vlans.remove(np_iface.name.as_str());
+        if ip_vlan_conf.is_some() {
+            base_iface.iface_type = InterfaceType::IpVlan;
+        }
+        let hsr_conf = hsrs.remove(np_iface.name.as_str());
+        if hsr_conf.is_some() {
+            base_iface.iface_type = InterfaceType::Hsr;
+        }
+        let xfrm_conf = xfrms.remove(np_iface.name.as_str());
+        if xfrm_conf.is_some() {
+            base_iface.iface_type = InterfaceType::Xfrm;
+        }
         // The `ovs-system` is reserved for OVS kernel datapath
         if np_iface.name == "ovs-system" {
             continue;
@@ -70,12 +132,59 @@ pub(crate) fn nispor_retrieve(
             InterfaceType::Veth => {
                 Interface::Ethernet(np_veth_to_nmstate(np_iface, base_iface))
             }
-            InterfaceType::Vlan => {
-                Interface::Vlan(np_vlan_to_nmstate(np_iface, base_iface))
-            }
-            InterfaceType::Vxlan => {
-                Interface::Vxlan(np_vxlan_to_nmstate(np_iface, base_iface))
+            InterfaceType::Vlan => Interface::Vlan(np_vlan_to_nmstate(
+                np_iface,
+                base_iface,
+                vlan_qos_maps.remove(np_iface.name.as_str()),
+            )),
+            InterfaceType::Vxlan => Interface::Vxlan(np_vxlan_to_nmstate(
+                np_iface,
+                base_iface,
+                vxlan_fdb_remotes.remove(&np_iface.index),
+            )),
+            InterfaceType::Gre
+            | InterfaceType::GreTap
+            | InterfaceType::IpIp
+            | InterfaceType::Sit
+            | InterfaceType::Ip6Tnl => Interface::IpTunnel({
+                let mut iface = IpTunnelInterface::new();
+                iface.base = base_iface;
+                iface.ip_tunnel = ip_tunnel_conf;
+                iface
+            }),
+            InterfaceType::Geneve => Interface::Geneve({
+                let mut iface = GeneveInterface::new();
+                iface.base = base_iface;
+                iface.geneve = geneve_conf;
+                iface
+            }),
+            InterfaceType::Macsec => Interface::Macsec({
+                let mut iface = MacsecInterface::new();
+                iface.base = base_iface;
+                iface.macsec = macsec_conf;
+                iface
+            }),
+            InterfaceType::Tun => {
+                Interface::Tun(np_tun_to_nmstate(np_iface, base_iface))
             }
+            InterfaceType::IpVlan => Interface::IpVlan({
+                let mut iface = IpVlanInterface::new();
+                iface.base = base_iface;
+                iface.ipvlan = ip_vlan_conf;
+                iface
+            }),
+            InterfaceType::Hsr => Interface::Hsr({
+                let mut iface = HsrInterface::new();
+                iface.base = base_iface;
+                iface.hsr = hsr_conf;
+                iface
+            }),
+            InterfaceType::Xfrm => Interface::Xfrm({
+                let mut iface = XfrmInterface::new();
+                iface.base = base_iface;
+                iface.xfrm = xfrm_conf;
+                iface
+            }),
             InterfaceType::Dummy => Interface::Dummy({
                 let mut iface = DummyInterface::new();
                 iface.base = base_iface;
@@ -110,6 +219,13 @@ pub(crate) fn nispor_retrieve(
             InterfaceType::Loopback => {
                 Interface::Loopback(LoopbackInterface { base: base_iface })
             }
+            // The WireGuard configuration is not available via rtnetlink,
+            // we depend on NetworkManager plugin to provide them.
+            InterfaceType::WireGuard => Interface::WireGuard({
+                let mut iface = WireGuardInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
             _ => {
                 log::info!(
                     "Got unsupported interface {} type {:?}",
@@ -125,13 +241,40 @@ pub(crate) fn nispor_retrieve(
         };
         net_state.append_interface_data(iface);
     }
+    set_hsr_ports_controller(&mut net_state.interfaces);
     set_controller_type(&mut net_state.interfaces);
-    net_state.routes = get_routes(running_config_only);
+    net_state.nexthops = get_nexthops(&index_to_name, running_config_only);
+    let has_nexthop = net_state
+        .nexthops
+        .running
+        .as_ref()
+        .or(net_state.nexthops.config.as_ref())
+        .map(|nhs| !nhs.is_empty())
+        .unwrap_or_default();
+    net_state.routes = get_routes(running_config_only, has_nexthop);
     net_state.rules = get_route_rules(&np_state.rules, running_config_only);
 
     Ok(net_state)
 }
 
+// The kernel does not set IFLA_MASTER for HSR ports.
+fn set_hsr_ports_controller(ifaces: &mut Interfaces) {
+    let mut pending_changes: Vec<(String, String)> = Vec::new();
+    for iface in ifaces.kernel_ifaces.values() {
+        if let Interface::Hsr(hsr_iface) = iface {
+            for port_name in hsr_iface.ports().unwrap_or_default() {
+                pending_changes
+                    .push((port_name.to_string(), iface.name().to_string()));
+            }
+        }
+    }
+    for (iface_name, ctrl_name) in pending_changes {
+        if let Some(iface) = ifaces.kernel_ifaces.get_mut(&iface_name) {
+            iface.base_iface_mut().controller = Some(ctrl_name);
+        }
+    }
+}
+
 fn set_controller_type(ifaces: &mut Interfaces) {
     let mut ctrl_to_type: HashMap<String, InterfaceType> = HashMap::new();
     for iface in ifaces.to_vec() {
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{BaseInterface, TunConfig, TunInterface, TunMode};
+
+pub(crate) fn np_tun_to_nmstate(
+    np_iface: &nispor::Iface,
+    base_iface: BaseInterface,
+) -> TunInterface {
+    let tun_conf = np_iface.tun.as_ref().map(|np_tun_info| TunConfig {
+        mode: match np_tun_info.mode {
+            nispor::TunMode::Tun => Some(TunMode::Tun),
+            nispor::TunMode::Tap => Some(TunMode::Tap),
+            _ => None,
+        },
+        owner: np_tun_info.owner,
+        group: np_tun_info.group,
+        multi_queue: Some(np_tun_info.multi_queue),
+        vnet_hdr: Some(np_tun_info.vnet_hdr),
+        persist: Some(np_tun_info.persist),
+    });
+
+    TunInterface {
+        base: base_iface,
+        tun: tun_conf,
+    }
+}
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    nispor::checkpoint::NisporCheckpoint, InterfaceType, NetworkState,
+    RouteEntry, RouteProtocol,
+};
+
+fn gen_test_checkpoint(desired: &str, old: &str) -> NisporCheckpoint {
+    NisporCheckpoint {
+        created: 0,
+        timeout: 60,
+        desired: serde_yaml::from_str(desired).unwrap(),
+        current: serde_yaml::from_str(old).unwrap(),
+        resolv_conf: None,
+    }
+}
+
+#[test]
+fn test_checkpoint_revert_created_iface() {
+    let cp = gen_test_checkpoint(
+        r#"---
+interfaces:
+- name: br0
+  type: linux-bridge
+  bridge:
+    port:
+    - name: eth1
+"#,
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+"#,
+    );
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: br0
+  type: linux-bridge
+  state: up
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+  controller: br0
+"#,
+    )
+    .unwrap();
+
+    let revert_state = cp.gen_revert_state(&current);
+
+    let br0 = revert_state
+        .interfaces
+        .get_iface("br0", InterfaceType::LinuxBridge)
+        .unwrap();
+    assert!(br0.is_absent());
+    let eth1 = revert_state
+        .interfaces
+        .get_iface("eth1", InterfaceType::Ethernet)
+        .unwrap();
+    assert!(!eth1.is_absent());
+    assert_eq!(eth1.base_iface().controller, None);
+    assert_eq!(eth1.base_iface().mtu, Some(1500));
+}
+
+#[test]
+fn test_checkpoint_revert_changed_iface() {
+    let cp = gen_test_checkpoint(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 9000
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+"#,
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+- name: eth2
+  type: ethernet
+  state: up
+"#,
+    );
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+- name: eth2
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+
+    let revert_state = cp.gen_revert_state(&current);
+
+    assert_eq!(revert_state.interfaces.to_vec().len(), 1);
+    let eth1 = revert_state
+        .interfaces
+        .get_iface("eth1", InterfaceType::Ethernet)
+        .unwrap();
+    assert_eq!(eth1.base_iface().mtu, Some(1500));
+    let addrs = eth1
+        .base_iface()
+        .ipv4
+        .as_ref()
+        .and_then(|i| i.addresses.as_deref())
+        .unwrap();
+    assert_eq!(addrs.len(), 1);
+    assert_eq!(
+        addrs[0].ip,
+        "192.0.2.1".parse::<std::net::IpAddr>().unwrap()
+    );
+}
+
+#[test]
+fn test_checkpoint_revert_routes() {
+    let cp = gen_test_checkpoint(
+        r#"---
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+"#,
+        r#"---
+routes:
+  config:
+  - destination: 203.0.113.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+"#,
+    );
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+routes:
+  config:
+  - destination: 203.0.113.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+"#,
+    )
+    .unwrap();
+
+    let revert_state = cp.gen_revert_state(&current);
+    let routes: &[RouteEntry] = revert_state.routes.config.as_deref().unwrap();
+
+    assert_eq!(routes.len(), 2);
+    assert!(routes[0].is_absent());
+    assert_eq!(routes[0].destination.as_deref(), Some("198.51.100.0/24"));
+    assert!(!routes[1].is_absent());
+    assert_eq!(routes[1].destination.as_deref(), Some("203.0.113.0/24"));
+}
+
+#[test]
+fn test_checkpoint_revert_route_attributes() {
+    let cp = gen_test_checkpoint(
+        r#"---
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+    mtu: 1400
+    protocol: boot
+"#,
+        r#"---
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+"#,
+    );
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.254
+    mtu: 1400
+    protocol: boot
+"#,
+    )
+    .unwrap();
+
+    let revert_state = cp.gen_revert_state(&current);
+    let routes: &[RouteEntry] = revert_state.routes.config.as_deref().unwrap();
+
+    assert_eq!(routes.len(), 2);
+    assert!(routes[0].is_absent());
+    assert_eq!(routes[0].mtu, Some(1400));
+    assert_eq!(routes[0].protocol, Some(RouteProtocol::Boot));
+    assert!(!routes[1].is_absent());
+    assert_eq!(routes[1].destination.as_deref(), Some("198.51.100.0/24"));
+    assert_eq!(routes[1].mtu, None);
+    assert_eq!(routes[1].protocol, None);
+}
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nispor::dns::{
+    gen_resolv_conf, nispor_dns_backup, nispor_dns_restore, parse_resolv_conf,
+};
+
+#[test]
+fn test_parse_resolv_conf() {
+    let conf = parse_resolv_conf(
+        r#"# Generated by NetworkManager
+domain example.com
+search example.org example.net
+nameserver 192.0.2.250
+nameserver 2001:db8:1::250
+options edns0 trust-ad
+"#,
+    );
+
+    assert_eq!(
+        conf.server,
+        Some(vec![
+            "192.0.2.250".to_string(),
+            "2001:db8:1::250".to_string()
+        ])
+    );
+    assert_eq!(
+        conf.search,
+        Some(vec!["example.org".to_string(), "example.net".to_string()])
+    );
+}
+
+#[test]
+fn test_parse_empty_resolv_conf() {
+    let conf = parse_resolv_conf("# Generated by NetworkManager\n");
+
+    assert!(conf.server.is_none());
+    assert!(conf.search.is_none());
+}
+
+#[test]
+fn test_gen_resolv_conf_preserve_options() {
+    let content = gen_resolv_conf(
+        &["192.0.2.251".to_string()],
+        &["example.com".to_string()],
+        r#"# Generated by NetworkManager
+search example.org
+nameserver 192.0.2.250
+options edns0 trust-ad
+"#,
+    );
+
+    assert_eq!(
+        content,
+        r#"# Generated by nmstate
+search example.com
+nameserver 192.0.2.251
+options edns0 trust-ad
+"#
+    );
+}
+
+#[test]
+fn test_dns_restore_keep_symlink() {
+    let dir = std::env::temp_dir()
+        .join(format!("nmstate-test-dns-restore-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let target = dir.join("stub-resolv.conf");
+    let link = dir.join("resolv.conf");
+    let link_path = link.display().to_string();
+    std::fs::write(&target, "nameserver 192.0.2.1\n").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    nispor_dns_backup(&link_path).unwrap();
+    std::fs::write(&link, "nameserver 192.0.2.2\n").unwrap();
+    nispor_dns_restore(&link_path).unwrap();
+
+    let is_symlink = std::fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink();
+    let content = std::fs::read_to_string(&target).unwrap();
+    let backup_exists =
+        std::path::Path::new(&format!("{link_path}.nmstate-backup")).exists();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(is_symlink);
+    assert_eq!(content, "nameserver 192.0.2.1\n");
+    assert!(!backup_exists);
+}
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{nispor::ip::nmstate_ipv4_to_np, InterfaceIpv4};
+
+#[test]
+fn test_nmstate_ipv4_to_np_remove_undesired_addresses() {
+    let desired: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+address:
+- ip: 192.0.2.1
+  prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let current: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+address:
+- ip: 192.0.2.1
+  prefix-length: 24
+- ip: 192.0.2.2
+  prefix-length: 24
+"#,
+    )
+    .unwrap();
+
+    let np_ip_conf = nmstate_ipv4_to_np(Some(&desired), Some(&current));
+
+    assert_eq!(np_ip_conf.addresses.len(), 2);
+    assert_eq!(np_ip_conf.addresses[0].address, "192.0.2.1");
+    assert!(!np_ip_conf.addresses[0].remove);
+    assert_eq!(np_ip_conf.addresses[1].address, "192.0.2.2");
+    assert!(np_ip_conf.addresses[1].remove);
+}
+
+#[test]
+fn test_nmstate_ipv4_to_np_keep_addresses_when_not_mentioned() {
+    let desired: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+"#,
+    )
+    .unwrap();
+    let current: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+address:
+- ip: 192.0.2.1
+  prefix-length: 24
+"#,
+    )
+    .unwrap();
+
+    let np_ip_conf = nmstate_ipv4_to_np(Some(&desired), Some(&current));
+
+    assert!(np_ip_conf.addresses.is_empty());
+}
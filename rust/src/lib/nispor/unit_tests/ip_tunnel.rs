@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use netlink_packet_route::nlas::link::{Info, InfoData, InfoKind};
+use netlink_packet_utils::nla::NlasIterator;
+
+use crate::{nispor::ip_tunnel::nms_ip_tunnel_iface_to_nl, IpTunnelInterface};
+
+fn get_nl_kind_and_attrs(infos: &[Info]) -> (InfoKind, Vec<(u16, Vec<u8>)>) {
+    let mut kind = None;
+    let mut attrs = Vec::new();
+    for info in infos {
+        match info {
+            Info::Kind(k) => kind = Some(k.clone()),
+            Info::Data(
+                InfoData::GreTun(d)
+                | InfoData::IpTun(d)
+                | InfoData::SitTun(d)
+                | InfoData::Other(d),
+            ) => {
+                for nla in NlasIterator::new(d.as_slice()).flatten() {
+                    attrs.push((nla.kind(), nla.value().to_vec()));
+                }
+            }
+            _ => (),
+        }
+    }
+    (kind.unwrap(), attrs)
+}
+
+#[test]
+fn test_ip_tunnel_ipip_to_nl() {
+    let iface: IpTunnelInterface = serde_yaml::from_str(
+        r#"---
+name: ipip1
+type: ipip
+ip-tunnel:
+  local: 192.0.2.1
+  remote: 192.0.2.2
+  ttl: 64
+  tos: 8
+  pmtudisc: true
+"#,
+    )
+    .unwrap();
+    let (kind, attrs) =
+        get_nl_kind_and_attrs(&nms_ip_tunnel_iface_to_nl(&iface, Some(2)));
+
+    assert_eq!(kind, InfoKind::IpTun);
+    assert_eq!(
+        attrs,
+        vec![
+            (1, 2u32.to_ne_bytes().to_vec()),
+            (2, vec![192, 0, 2, 1]),
+            (3, vec![192, 0, 2, 2]),
+            (4, vec![64]),
+            (5, vec![8]),
+            (10, vec![1]),
+        ]
+    );
+}
+
+#[test]
+fn test_ip_tunnel_ip6tnl_to_nl_no_tos_pmtudisc() {
+    let iface: IpTunnelInterface = serde_yaml::from_str(
+        r#"---
+name: ip6tnl1
+type: ip6tnl
+ip-tunnel:
+  local: 2001:db8::1
+  remote: 2001:db8::2
+  ttl: 64
+  tos: 8
+  pmtudisc: true
+"#,
+    )
+    .unwrap();
+    let (kind, attrs) =
+        get_nl_kind_and_attrs(&nms_ip_tunnel_iface_to_nl(&iface, None));
+
+    assert_eq!(kind, InfoKind::Other("ip6tnl".to_string()));
+    let kinds: Vec<u16> = attrs.iter().map(|(k, _)| *k).collect();
+    assert_eq!(kinds, vec![2, 3, 4]);
+}
+
+#[test]
+fn test_ip_tunnel_gre_key_to_nl() {
+    let iface: IpTunnelInterface = serde_yaml::from_str(
+        r#"---
+name: gre1
+type: gre
+ip-tunnel:
+  local: 192.0.2.1
+  remote: 192.0.2.2
+  key: 1000
+"#,
+    )
+    .unwrap();
+    let (kind, attrs) =
+        get_nl_kind_and_attrs(&nms_ip_tunnel_iface_to_nl(&iface, None));
+
+    assert_eq!(kind, InfoKind::GreTun);
+    assert_eq!(
+        attrs,
+        vec![
+            (6, vec![192, 0, 2, 1]),
+            (7, vec![192, 0, 2, 2]),
+            (2, vec![0x20, 0x00]),
+            (3, vec![0x20, 0x00]),
+            (4, 1000u32.to_be_bytes().to_vec()),
+            (5, 1000u32.to_be_bytes().to_vec()),
+        ]
+    );
+}
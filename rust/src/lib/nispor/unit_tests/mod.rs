@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod checkpoint;
+#[cfg(test)]
+mod dns;
+#[cfg(test)]
+mod ip;
+#[cfg(test)]
+mod ip_tunnel;
+#[cfg(test)]
+mod route;
+#[cfg(test)]
+mod route_rule;
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use netlink_packet_route::{
+    route::Nla, RouteMessage, AF_INET, AF_INET6, RTN_BLACKHOLE, RTN_UNICAST,
+    RTPROT_STATIC, RT_SCOPE_LINK, RT_SCOPE_NOWHERE, RT_SCOPE_UNIVERSE,
+    RT_TABLE_MAIN, RT_TABLE_UNSPEC,
+};
+
+use crate::{nispor::route::nmstate_route_to_nl_msg, RouteEntry};
+
+fn gen_nl_msg(
+    yml: &str,
+    oif_index: Option<u32>,
+    for_removal: bool,
+) -> RouteMessage {
+    let rt: RouteEntry = serde_yaml::from_str(yml).unwrap();
+    nmstate_route_to_nl_msg(&rt, oif_index, for_removal).unwrap()
+}
+
+#[test]
+fn test_route_to_nl_ipv4_gateway() {
+    let nl_msg = gen_nl_msg(
+        r#"---
+destination: 198.51.100.0/24
+next-hop-interface: eth1
+next-hop-address: 192.0.2.1
+metric: 150
+table-id: 100
+"#,
+        Some(2),
+        false,
+    );
+
+    assert_eq!(nl_msg.header.address_family, AF_INET as u8);
+    assert_eq!(nl_msg.header.destination_prefix_length, 24);
+    assert_eq!(nl_msg.header.table, 100);
+    assert_eq!(nl_msg.header.protocol, RTPROT_STATIC);
+    assert_eq!(nl_msg.header.kind, RTN_UNICAST);
+    assert_eq!(nl_msg.header.scope, RT_SCOPE_UNIVERSE);
+    assert_eq!(
+        nl_msg.nlas,
+        vec![
+            Nla::Destination(vec![198, 51, 100, 0]),
+            Nla::Table(100),
+            Nla::Priority(150),
+            Nla::Oif(2),
+            Nla::Gateway(vec![192, 0, 2, 1]),
+        ]
+    );
+}
+
+#[test]
+fn test_route_to_nl_ipv6_iface_only() {
+    let nl_msg = gen_nl_msg(
+        r#"---
+destination: 2001:db8:1::/64
+next-hop-interface: eth1
+"#,
+        Some(3),
+        false,
+    );
+
+    assert_eq!(nl_msg.header.address_family, AF_INET6 as u8);
+    assert_eq!(nl_msg.header.destination_prefix_length, 64);
+    assert_eq!(nl_msg.header.table, RT_TABLE_MAIN);
+    assert_eq!(nl_msg.header.scope, RT_SCOPE_LINK);
+    assert!(nl_msg.nlas.contains(&Nla::Oif(3)));
+    assert!(!nl_msg.nlas.iter().any(|n| matches!(n, Nla::Gateway(_))));
+    assert!(!nl_msg.nlas.iter().any(|n| matches!(n, Nla::Priority(_))));
+}
+
+#[test]
+fn test_route_to_nl_large_table_id() {
+    let nl_msg = gen_nl_msg(
+        r#"---
+destination: 0.0.0.0/0
+route-type: blackhole
+table-id: 1000
+"#,
+        None,
+        false,
+    );
+
+    assert_eq!(nl_msg.header.destination_prefix_length, 0);
+    assert_eq!(nl_msg.header.table, RT_TABLE_UNSPEC);
+    assert_eq!(nl_msg.header.kind, RTN_BLACKHOLE);
+    assert_eq!(nl_msg.header.scope, RT_SCOPE_UNIVERSE);
+    assert_eq!(nl_msg.nlas, vec![Nla::Table(1000)]);
+}
+
+#[test]
+fn test_route_to_nl_for_removal() {
+    let nl_msg = gen_nl_msg(
+        r#"---
+destination: 198.51.100.0/24
+next-hop-interface: eth1
+next-hop-address: 192.0.2.1
+state: absent
+"#,
+        Some(2),
+        true,
+    );
+
+    assert_eq!(nl_msg.header.scope, RT_SCOPE_NOWHERE);
+    assert_eq!(nl_msg.header.protocol, 0);
+    assert_eq!(nl_msg.header.kind, 0);
+    assert!(nl_msg.nlas.contains(&Nla::Gateway(vec![192, 0, 2, 1])));
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use netlink_packet_route::{
+    rule::Nla, AF_INET, AF_INET6, FR_ACT_TO_TBL, RT_TABLE_MAIN,
+};
+
+use crate::{
+    nispor::route_rule::nmstate_rule_to_nl, RouteRuleAction, RouteRuleEntry,
+};
+
+#[test]
+fn test_route_rule_to_nl_table() {
+    let rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"---
+ip-from: 192.0.2.0/24
+priority: 1000
+route-table: 100
+fwmark: 16
+fwmask: 255
+"#,
+    )
+    .unwrap();
+    let nl_msg = nmstate_rule_to_nl(&rule, false).unwrap();
+
+    assert_eq!(nl_msg.header.family, AF_INET as u8);
+    assert_eq!(nl_msg.header.src_len, 24);
+    assert_eq!(nl_msg.header.action, FR_ACT_TO_TBL);
+    assert_eq!(nl_msg.header.table, 100);
+    assert!(nl_msg.nlas.contains(&Nla::Source(vec![192, 0, 2, 0])));
+    assert!(nl_msg.nlas.contains(&Nla::Table(100)));
+    assert!(nl_msg.nlas.contains(&Nla::Priority(1000)));
+    assert!(nl_msg.nlas.contains(&Nla::FwMark(16)));
+    assert!(nl_msg.nlas.contains(&Nla::FwMask(255)));
+}
+
+#[test]
+fn test_route_rule_to_nl_default_table() {
+    let rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"---
+ip-to: 2001:db8:1::/64
+"#,
+    )
+    .unwrap();
+    let nl_msg = nmstate_rule_to_nl(&rule, false).unwrap();
+
+    assert_eq!(nl_msg.header.family, AF_INET6 as u8);
+    assert_eq!(nl_msg.header.dst_len, 64);
+    assert_eq!(nl_msg.header.table, RT_TABLE_MAIN);
+    assert!(nl_msg.nlas.contains(&Nla::Table(RT_TABLE_MAIN.into())));
+    assert!(!nl_msg.nlas.iter().any(|n| matches!(n, Nla::Priority(_))));
+}
+
+#[test]
+fn test_route_rule_to_nl_action() {
+    let rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"---
+ip-from: 192.0.2.1
+priority: 500
+action: blackhole
+"#,
+    )
+    .unwrap();
+    let nl_msg = nmstate_rule_to_nl(&rule, false).unwrap();
+
+    assert_eq!(nl_msg.header.action, u8::from(RouteRuleAction::Blackhole));
+    assert!(!nl_msg.nlas.iter().any(|n| matches!(n, Nla::Table(_))));
+    assert!(nl_msg.nlas.contains(&Nla::Priority(500)));
+}
@@ -1,11 +1,36 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{BaseInterface, VlanConfig, VlanInterface, VlanProtocol};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoKind, InfoVlan, Nla as LinkNla},
+    LinkMessage,
+};
+use netlink_packet_utils::{
+    nla::{DefaultNla, NlasIterator},
+    Emitable,
+};
+
+use super::netlink::{change_link, run_netlink};
+use crate::{
+    BaseInterface, Interface, MergedInterface, NmstateError, VlanConfig,
+    VlanInterface, VlanProtocol, VlanQosMapping,
+};
+
+const IFLA_VLAN_QOS_MAPPING: u16 = 1;
+
+const VLAN_FLAG_REORDER_HDR: u32 = 0x1;
+const VLAN_FLAG_GVRP: u32 = 0x2;
+const VLAN_FLAG_LOOSE_BINDING: u32 = 0x4;
+const VLAN_FLAG_MVRP: u32 = 0x8;
 
 pub(crate) fn np_vlan_to_nmstate(
     np_iface: &nispor::Iface,
     base_iface: BaseInterface,
+    qos_maps: Option<(Vec<VlanQosMapping>, Vec<VlanQosMapping>)>,
 ) -> VlanInterface {
+    let (ingress_qos_map, egress_qos_map) = qos_maps.unwrap_or_default();
     let vlan_conf = np_iface.vlan.as_ref().map(|np_vlan_info| VlanConfig {
         id: np_vlan_info.vlan_id,
         base_iface: np_vlan_info.base_iface.clone(),
@@ -20,6 +45,12 @@ pub(crate) fn np_vlan_to_nmstate(
                 None
             }
         },
+        reorder_headers: Some(np_vlan_info.is_reorder_hdr),
+        gvrp: Some(np_vlan_info.is_gvrp),
+        mvrp: Some(np_vlan_info.is_mvrp),
+        loose_binding: Some(np_vlan_info.is_loose_binding),
+        ingress_qos_map: Some(ingress_qos_map),
+        egress_qos_map: Some(egress_qos_map),
     });
 
     VlanInterface {
@@ -38,3 +69,179 @@ pub(crate) fn nms_vlan_conf_to_np(
         np_vlan_conf
     })
 }
+
+// Nispor 1.2 does not report VLAN QoS mappings, hence we query the kernel via
+// rtnetlink directly.
+// Return interface name to ingress and egress QoS mappings of all VLAN
+// interfaces in kernel.
+pub(crate) fn get_vlan_qos_maps(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, (Vec<VlanQosMapping>, Vec<VlanQosMapping>)> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        for nla in link.nlas.iter() {
+            if let LinkNla::Info(infos) = nla {
+                for info in infos {
+                    if let Info::Data(InfoData::Vlan(vlan_nlas)) = info {
+                        ret.insert(
+                            name.to_string(),
+                            nl_vlan_to_qos_maps(vlan_nlas),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    ret
+}
+
+fn nl_vlan_to_qos_maps(
+    nlas: &[InfoVlan],
+) -> (Vec<VlanQosMapping>, Vec<VlanQosMapping>) {
+    let mut ingress = Vec::new();
+    let mut egress = Vec::new();
+    for nla in nlas {
+        match nla {
+            InfoVlan::IngressQos(data) => ingress = parse_qos_map(data),
+            InfoVlan::EgressQos(data) => egress = parse_qos_map(data),
+            _ => (),
+        }
+    }
+    (ingress, egress)
+}
+
+fn parse_qos_map(data: &[u8]) -> Vec<VlanQosMapping> {
+    let mut ret = Vec::new();
+    for nla in NlasIterator::new(data).flatten() {
+        let value = nla.value();
+        if nla.kind() != IFLA_VLAN_QOS_MAPPING || value.len() != 8 {
+            continue;
+        }
+        if let (Ok(from), Ok(to)) = (
+            <[u8; 4]>::try_from(&value[..4]),
+            <[u8; 4]>::try_from(&value[4..]),
+        ) {
+            let mapping = VlanQosMapping::new(
+                u32::from_ne_bytes(from),
+                u32::from_ne_bytes(to),
+            );
+            if mapping.to != 0 {
+                ret.push(mapping);
+            }
+        }
+    }
+    ret.sort_unstable();
+    ret
+}
+
+// Nispor 1.2 cannot set VLAN flags and QoS mappings, hence we change them via
+// rtnetlink after VLAN interface created.
+pub(crate) fn apply_vlan_confs(
+    ifaces: &[&MergedInterface],
+) -> Result<(), NmstateError> {
+    let mut changes: Vec<(&str, Vec<InfoVlan>)> = Vec::new();
+    for merged_iface in ifaces.iter().filter(|i| !i.merged.is_absent()) {
+        if let Some(Interface::Vlan(vlan_iface)) =
+            merged_iface.for_apply.as_ref()
+        {
+            let cur_conf = match merged_iface.current.as_ref() {
+                Some(Interface::Vlan(cur_iface)) => cur_iface.vlan.as_ref(),
+                _ => None,
+            };
+            if let Some(vlan_conf) = vlan_iface.vlan.as_ref() {
+                let nlas = nms_vlan_conf_to_nl(vlan_conf, cur_conf);
+                if !nlas.is_empty() {
+                    changes.push((vlan_iface.base.name.as_str(), nlas));
+                }
+            }
+        }
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+    run_netlink(|handle| async move {
+        for (iface_name, nlas) in changes {
+            change_link(
+                &handle,
+                iface_name,
+                vec![
+                    Info::Kind(InfoKind::Vlan),
+                    Info::Data(InfoData::Vlan(nlas)),
+                ],
+            )
+            .await?;
+        }
+        Ok(())
+    })
+}
+
+fn nms_vlan_conf_to_nl(
+    vlan_conf: &VlanConfig,
+    cur_conf: Option<&VlanConfig>,
+) -> Vec<InfoVlan> {
+    let mut ret = Vec::new();
+    let mut flags = 0u32;
+    let mut mask = 0u32;
+    for (flag, value) in [
+        (VLAN_FLAG_REORDER_HDR, vlan_conf.reorder_headers),
+        (VLAN_FLAG_GVRP, vlan_conf.gvrp),
+        (VLAN_FLAG_LOOSE_BINDING, vlan_conf.loose_binding),
+        (VLAN_FLAG_MVRP, vlan_conf.mvrp),
+    ] {
+        if let Some(v) = value {
+            mask |= flag;
+            if v {
+                flags |= flag;
+            }
+        }
+    }
+    if mask != 0 {
+        ret.push(InfoVlan::Flags((flags, mask)));
+    }
+    if let Some(qos_map) = vlan_conf.ingress_qos_map.as_deref() {
+        let cur_qos_map = cur_conf
+            .and_then(|c| c.ingress_qos_map.as_deref())
+            .unwrap_or_default();
+        ret.push(InfoVlan::IngressQos(gen_qos_map_nl_bytes(
+            qos_map,
+            cur_qos_map,
+        )));
+    }
+    if let Some(qos_map) = vlan_conf.egress_qos_map.as_deref() {
+        let cur_qos_map = cur_conf
+            .and_then(|c| c.egress_qos_map.as_deref())
+            .unwrap_or_default();
+        ret.push(InfoVlan::EgressQos(gen_qos_map_nl_bytes(
+            qos_map,
+            cur_qos_map,
+        )));
+    }
+    ret
+}
+
+// Kernel only add or change the mappings, so we reset undesired current
+// mappings to 0.
+fn gen_qos_map_nl_bytes(
+    qos_map: &[VlanQosMapping],
+    cur_qos_map: &[VlanQosMapping],
+) -> Vec<u8> {
+    let mut nlas: Vec<DefaultNla> = Vec::new();
+    for mapping in cur_qos_map
+        .iter()
+        .filter(|c| !qos_map.iter().any(|m| m.from == c.from))
+        .map(|c| VlanQosMapping::new(c.from, 0))
+        .chain(qos_map.iter().copied())
+    {
+        let mut value = mapping.from.to_ne_bytes().to_vec();
+        value.extend_from_slice(&mapping.to.to_ne_bytes());
+        nlas.push(DefaultNla::new(IFLA_VLAN_QOS_MAPPING, value));
+    }
+    let mut data = vec![0u8; nlas.as_slice().buffer_len()];
+    nlas.as_slice().emit(&mut data);
+    data
+}
@@ -1,4 +1,6 @@
-use crate::{BaseInterface, VrfConfig, VrfInterface};
+use netlink_packet_route::nlas::link::{Info, InfoData, InfoKind, InfoVrf};
+
+use crate::{BaseInterface, ErrorKind, NmstateError, VrfConfig, VrfInterface};
 
 pub(crate) fn np_vrf_to_nmstate(
     np_iface: &nispor::Iface,
@@ -18,3 +20,22 @@ pub(crate) fn np_vrf_to_nmstate(
         vrf: vrf_conf,
     }
 }
+
+pub(crate) fn nms_vrf_iface_to_nl(
+    iface: &VrfInterface,
+) -> Result<Vec<Info>, NmstateError> {
+    match iface.vrf.as_ref().map(|v| v.table_id) {
+        Some(table_id) if table_id > 0 => Ok(vec![
+            Info::Kind(InfoKind::Vrf),
+            Info::Data(InfoData::Vrf(vec![InfoVrf::TableId(table_id)])),
+        ]),
+        _ => Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Route table ID undefined or 0 is not allowed for \
+                new VRF interface {}",
+                iface.base.name
+            ),
+        )),
+    }
+}
@@ -1,20 +1,78 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::str::FromStr;
 
-use crate::{BaseInterface, VxlanConfig, VxlanInterface};
+use futures::{StreamExt, TryStreamExt};
+use netlink_packet_core::{
+    NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_APPEND, NLM_F_CREATE,
+    NLM_F_REQUEST,
+};
+use netlink_packet_route::{
+    constants::{
+        AF_BRIDGE, IFLA_VXLAN_GBP, IFLA_VXLAN_PORT_RANGE, NTF_SELF, NUD_NOARP,
+        NUD_PERMANENT,
+    },
+    neighbour::{NeighbourMessage, Nla as NeighbourNla},
+    nlas::link::{Info, InfoData, InfoKind, InfoVxlan},
+    RtnlMessage,
+};
+use netlink_packet_utils::{nla::DefaultNla, Emitable};
+
+use super::netlink::{
+    get_iface_index, ip_to_nl_bytes, nl_bytes_to_ip, nl_errno,
+    nl_error_to_nmstate, run_netlink,
+};
+use crate::{
+    BaseInterface, ErrorKind, Interface, MergedInterfaces, NmstateError,
+    VxlanConfig, VxlanInterface,
+};
+
+// The FDB entry with all-zeros MAC address is used for flooding broadcast,
+// unknown unicast and multicast traffic.
+const VXLAN_FDB_ALL_ZEROS_MAC: [u8; 6] = [0u8; 6];
 
 pub(crate) fn np_vxlan_to_nmstate(
     np_iface: &nispor::Iface,
     base_iface: BaseInterface,
+    fdb_remotes: Option<Vec<IpAddr>>,
 ) -> VxlanInterface {
-    let vxlan_conf = np_iface.vxlan.as_ref().map(|np_vxlan_info| VxlanConfig {
-        id: np_vxlan_info.vxlan_id,
-        base_iface: np_vxlan_info.base_iface.clone(),
-        learning: Some(np_vxlan_info.learning),
-        local: std::net::IpAddr::from_str(np_vxlan_info.local.as_str()).ok(),
-        remote: std::net::IpAddr::from_str(np_vxlan_info.remote.as_str()).ok(),
-        dst_port: Some(np_vxlan_info.dst_port),
+    let vxlan_conf = np_iface.vxlan.as_ref().map(|np_vxlan_info| {
+        let remote = IpAddr::from_str(np_vxlan_info.remote.as_str()).ok();
+        // The `remote` is also stored as all-zeros FDB entry by kernel
+        let mut fdb_remotes: Vec<IpAddr> = fdb_remotes
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|ip| Some(ip) != remote.as_ref())
+            .collect();
+        fdb_remotes.sort_unstable();
+        VxlanConfig {
+            id: np_vxlan_info.vxlan_id,
+            base_iface: np_vxlan_info.base_iface.clone(),
+            learning: Some(np_vxlan_info.learning),
+            local: IpAddr::from_str(np_vxlan_info.local.as_str()).ok(),
+            remote,
+            dst_port: Some(np_vxlan_info.dst_port),
+            ttl: Some(np_vxlan_info.ttl),
+            tos: Some(np_vxlan_info.tos),
+            // netlink-packet-route parse the port range in native endian,
+            // but kernel is using network byte order.
+            source_port_min: Some(u16::from_be(np_vxlan_info.src_port_min)),
+            source_port_max: Some(u16::from_be(np_vxlan_info.src_port_max)),
+            ageing: Some(np_vxlan_info.ageing),
+            limit: Some(np_vxlan_info.max_address),
+            proxy: Some(np_vxlan_info.proxy),
+            rsc: Some(np_vxlan_info.rsc),
+            l2_miss: Some(np_vxlan_info.l2miss),
+            l3_miss: Some(np_vxlan_info.l3miss),
+            udp_checksum: Some(np_vxlan_info.udp_check_sum),
+            udp6_zero_checksum_tx: Some(np_vxlan_info.udp6_zero_check_sum_tx),
+            udp6_zero_checksum_rx: Some(np_vxlan_info.udp6_zero_check_sum_rx),
+            gbp: Some(np_vxlan_info.gbp),
+            external: Some(np_vxlan_info.collect_metadata),
+            fdb_remotes: Some(fdb_remotes),
+        }
     });
 
     VxlanInterface {
@@ -22,3 +80,241 @@ pub(crate) fn np_vxlan_to_nmstate(
         vxlan: vxlan_conf,
     }
 }
+
+// The `base_iface_index` should be the kernel index of `base-iface` property.
+pub(crate) fn nms_vxlan_iface_to_nl(
+    iface: &VxlanInterface,
+    base_iface_index: Option<u32>,
+) -> Result<Vec<Info>, NmstateError> {
+    let vxlan_conf = if let Some(v) = iface.vxlan.as_ref() {
+        v
+    } else {
+        return Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "VXLAN ID undefined for new VXLAN interface {}",
+                iface.base.name
+            ),
+        ));
+    };
+    let mut nl_infos = vec![InfoVxlan::Id(vxlan_conf.id)];
+    if let Some(index) = base_iface_index {
+        nl_infos.push(InfoVxlan::Link(index));
+    }
+    if let Some(local) = vxlan_conf.local.as_ref() {
+        nl_infos.push(match local {
+            IpAddr::V4(_) => InfoVxlan::Local(ip_to_nl_bytes(local)),
+            IpAddr::V6(_) => InfoVxlan::Local6(ip_to_nl_bytes(local)),
+        });
+    }
+    if let Some(remote) = vxlan_conf.remote.as_ref() {
+        nl_infos.push(match remote {
+            IpAddr::V4(_) => InfoVxlan::Group(ip_to_nl_bytes(remote)),
+            IpAddr::V6(_) => InfoVxlan::Group6(ip_to_nl_bytes(remote)),
+        });
+    }
+    if let Some(learning) = vxlan_conf.learning {
+        nl_infos.push(InfoVxlan::Learning(learning.into()));
+    }
+    if let Some(dst_port) = vxlan_conf.dst_port {
+        // netlink-packet-route emit this in native endian, but kernel
+        // expect network byte order.
+        nl_infos.push(InfoVxlan::Port(dst_port.to_be()));
+    }
+    if let Some(ttl) = vxlan_conf.ttl {
+        nl_infos.push(InfoVxlan::Ttl(ttl));
+    }
+    if let Some(tos) = vxlan_conf.tos {
+        nl_infos.push(InfoVxlan::Tos(tos));
+    }
+    if let Some(ageing) = vxlan_conf.ageing {
+        nl_infos.push(InfoVxlan::Ageing(ageing));
+    }
+    if let Some(limit) = vxlan_conf.limit {
+        nl_infos.push(InfoVxlan::Limit(limit));
+    }
+    if let Some(proxy) = vxlan_conf.proxy {
+        nl_infos.push(InfoVxlan::Proxy(proxy.into()));
+    }
+    if let Some(rsc) = vxlan_conf.rsc {
+        nl_infos.push(InfoVxlan::Rsc(rsc.into()));
+    }
+    if let Some(l2_miss) = vxlan_conf.l2_miss {
+        nl_infos.push(InfoVxlan::L2Miss(l2_miss.into()));
+    }
+    if let Some(l3_miss) = vxlan_conf.l3_miss {
+        nl_infos.push(InfoVxlan::L3Miss(l3_miss.into()));
+    }
+    if let Some(v) = vxlan_conf.udp_checksum {
+        nl_infos.push(InfoVxlan::UDPCsum(v.into()));
+    }
+    if let Some(v) = vxlan_conf.udp6_zero_checksum_tx {
+        nl_infos.push(InfoVxlan::UDPZeroCsumTX(v.into()));
+    }
+    if let Some(v) = vxlan_conf.udp6_zero_checksum_rx {
+        nl_infos.push(InfoVxlan::UDPZeroCsumRX(v.into()));
+    }
+    if let Some(external) = vxlan_conf.external {
+        nl_infos.push(InfoVxlan::CollectMetadata(external.into()));
+    }
+
+    let mut data = vec![0u8; nl_infos.as_slice().buffer_len()];
+    nl_infos.as_slice().emit(&mut data);
+
+    // netlink-packet-route emit the port range incorrectly and GBP as
+    // u8 instead of flag, hence we emit them by ourselves.
+    let mut extra_nlas: Vec<DefaultNla> = Vec::new();
+    if let (Some(min), Some(max)) =
+        (vxlan_conf.source_port_min, vxlan_conf.source_port_max)
+    {
+        let mut value = min.to_be_bytes().to_vec();
+        value.extend_from_slice(&max.to_be_bytes());
+        extra_nlas.push(DefaultNla::new(IFLA_VXLAN_PORT_RANGE, value));
+    }
+    if vxlan_conf.gbp == Some(true) {
+        extra_nlas.push(DefaultNla::new(IFLA_VXLAN_GBP, Vec::new()));
+    }
+    let mut extra_data = vec![0u8; extra_nlas.as_slice().buffer_len()];
+    extra_nlas.as_slice().emit(&mut extra_data);
+    data.extend_from_slice(&extra_data);
+
+    Ok(vec![
+        Info::Kind(InfoKind::Vxlan),
+        Info::Data(InfoData::Other(data)),
+    ])
+}
+
+// Return interface index to the remote IP addresses of all-zeros FDB
+// entries of VXLAN interfaces.
+pub(crate) fn get_vxlan_fdb_remotes(
+) -> Result<HashMap<u32, Vec<IpAddr>>, NmstateError> {
+    let nl_msgs = run_netlink(|handle| async move {
+        let mut req = handle.neighbours().get();
+        req.message_mut().header.family = AF_BRIDGE as u8;
+        req.execute()
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| nl_error_to_nmstate(e, "query FDB entries"))
+    })?;
+    let mut ret: HashMap<u32, Vec<IpAddr>> = HashMap::new();
+    for nl_msg in nl_msgs {
+        if nl_msg.header.flags & NTF_SELF == 0 {
+            continue;
+        }
+        let mut is_all_zeros_mac = false;
+        let mut remote = None;
+        for nla in nl_msg.nlas.iter() {
+            match nla {
+                NeighbourNla::LinkLocalAddress(mac) => {
+                    is_all_zeros_mac =
+                        mac.as_slice() == VXLAN_FDB_ALL_ZEROS_MAC;
+                }
+                NeighbourNla::Destination(ip) => remote = nl_bytes_to_ip(ip),
+                _ => (),
+            }
+        }
+        if let (true, Some(remote)) = (is_all_zeros_mac, remote) {
+            ret.entry(nl_msg.header.ifindex).or_default().push(remote);
+        }
+    }
+    Ok(ret)
+}
+
+// Add all desired FDB remotes and remove undesired ones.
+// Adding existing FDB remote is no-op due to NLM_F_APPEND.
+pub(crate) fn nispor_vxlan_fdb_apply(
+    merged_ifaces: &MergedInterfaces,
+) -> Result<(), NmstateError> {
+    let mut changes: Vec<(&str, &[IpAddr], &[IpAddr])> = Vec::new();
+    for merged_iface in merged_ifaces
+        .kernel_ifaces
+        .values()
+        .filter(|i| i.is_changed() && !i.merged.is_absent())
+    {
+        if let Some(Interface::Vxlan(iface)) = merged_iface.for_apply.as_ref() {
+            if let Some(desired) =
+                iface.vxlan.as_ref().and_then(|c| c.fdb_remotes.as_deref())
+            {
+                let current = match merged_iface.current.as_ref() {
+                    Some(Interface::Vxlan(cur_iface)) => cur_iface
+                        .vxlan
+                        .as_ref()
+                        .and_then(|c| c.fdb_remotes.as_deref())
+                        .unwrap_or_default(),
+                    _ => &[],
+                };
+                changes.push((iface.base.name.as_str(), desired, current));
+            }
+        }
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+    run_netlink(|handle| async move {
+        for (iface_name, desired, current) in changes {
+            let index = get_iface_index(&handle, iface_name).await?;
+            for remote in current.iter().filter(|r| !desired.contains(r)) {
+                log::info!(
+                    "Removing FDB remote {remote} from VXLAN interface \
+                    {iface_name}"
+                );
+                let nl_msg = gen_fdb_remote_nl_msg(index, remote);
+                if let Err(e) = handle.neighbours().del(nl_msg).execute().await
+                {
+                    if nl_errno(&e) != Some(-(nix::errno::Errno::ENOENT as i32))
+                    {
+                        return Err(nl_error_to_nmstate(
+                            e,
+                            &format!(
+                                "remove FDB remote {remote} from VXLAN \
+                                interface {iface_name}"
+                            ),
+                        ));
+                    }
+                }
+            }
+            for remote in desired {
+                log::info!(
+                    "Adding FDB remote {remote} to VXLAN interface \
+                    {iface_name}"
+                );
+                let mut req = NetlinkMessage::from(RtnlMessage::NewNeighbour(
+                    gen_fdb_remote_nl_msg(index, remote),
+                ));
+                req.header.flags =
+                    NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | NLM_F_APPEND;
+                let action = format!(
+                    "add FDB remote {remote} to VXLAN interface {iface_name}"
+                );
+                let mut response = handle
+                    .clone()
+                    .request(req)
+                    .map_err(|e| nl_error_to_nmstate(e, &action))?;
+                while let Some(msg) = response.next().await {
+                    if let NetlinkPayload::Error(e) = msg.payload {
+                        return Err(nl_error_to_nmstate(
+                            rtnetlink::Error::NetlinkError(e),
+                            &action,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn gen_fdb_remote_nl_msg(index: u32, remote: &IpAddr) -> NeighbourMessage {
+    let mut nl_msg = NeighbourMessage::default();
+    nl_msg.header.family = AF_BRIDGE as u8;
+    nl_msg.header.ifindex = index;
+    nl_msg.header.state = NUD_PERMANENT | NUD_NOARP;
+    nl_msg.header.flags = NTF_SELF;
+    nl_msg.nlas.push(NeighbourNla::LinkLocalAddress(
+        VXLAN_FDB_ALL_ZEROS_MAC.to_vec(),
+    ));
+    nl_msg
+        .nlas
+        .push(NeighbourNla::Destination(ip_to_nl_bytes(remote)));
+    nl_msg
+}
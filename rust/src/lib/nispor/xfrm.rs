@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Nispor 1.2 has no XFRM support, hence we query the kernel via rtnetlink
+// directly.
+
+use std::collections::HashMap;
+
+use netlink_packet_route::{
+    nlas::link::{Info, InfoData, InfoKind, InfoXfrmTun, Nla as LinkNla},
+    LinkMessage,
+};
+
+use crate::{XfrmConfig, XfrmInterface};
+
+// Return interface name to XFRM config of all XFRM interfaces in kernel.
+pub(crate) fn get_xfrms(
+    links: &[LinkMessage],
+    index_to_name: &HashMap<u32, String>,
+) -> HashMap<String, XfrmConfig> {
+    let mut ret = HashMap::new();
+    for link in links {
+        let name = match index_to_name.get(&link.header.index) {
+            Some(n) => n,
+            None => continue,
+        };
+        for nla in link.nlas.iter() {
+            if let LinkNla::Info(infos) = nla {
+                if let Some(conf) = nl_info_to_xfrm(infos, index_to_name) {
+                    ret.insert(name.to_string(), conf);
+                }
+            }
+        }
+    }
+    ret
+}
+
+fn nl_info_to_xfrm(
+    infos: &[Info],
+    index_to_name: &HashMap<u32, String>,
+) -> Option<XfrmConfig> {
+    if !infos
+        .iter()
+        .any(|i| matches!(i, Info::Kind(InfoKind::Xfrm)))
+    {
+        return None;
+    }
+    let mut conf = XfrmConfig::default();
+    for info in infos {
+        if let Info::Data(InfoData::Xfrm(nlas)) = info {
+            for nla in nlas {
+                match nla {
+                    InfoXfrmTun::Link(index) => {
+                        conf.base_iface = index_to_name
+                            .get(index)
+                            .cloned()
+                            .unwrap_or_default();
+                    }
+                    InfoXfrmTun::IfId(if_id) => conf.if_id = *if_id,
+                    _ => (),
+                }
+            }
+        }
+    }
+    Some(conf)
+}
+
+pub(crate) fn nms_xfrm_iface_to_nl(
+    iface: &XfrmInterface,
+    base_iface_index: Option<u32>,
+) -> Vec<Info> {
+    let mut nl_infos = Vec::new();
+    if let Some(index) = base_iface_index {
+        nl_infos.push(InfoXfrmTun::Link(index));
+    }
+    if let Some(conf) = iface.xfrm.as_ref() {
+        nl_infos.push(InfoXfrmTun::IfId(conf.if_id));
+    }
+    vec![
+        Info::Kind(InfoKind::Xfrm),
+        Info::Data(InfoData::Xfrm(nl_infos)),
+    ]
+}
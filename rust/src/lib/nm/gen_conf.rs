@@ -3,13 +3,24 @@
 use crate::{ErrorKind, MergedNetworkState, NmstateError};
 
 use super::{
-    dns::store_dns_config_to_iface, profile::perpare_nm_conns,
-    route::store_route_config, route_rule::store_route_rule_config,
+    dns::store_dns_config_to_iface,
+    profile::{
+        check_nm_nexthop_support, check_nm_route_protocol_support,
+        check_nm_unsupported_ifaces, check_nm_vxlan_fdb_support,
+        perpare_nm_conns,
+    },
+    route::store_route_config,
+    route_rule::store_route_rule_config,
 };
 
 pub(crate) fn nm_gen_conf(
     merged_state: &MergedNetworkState,
 ) -> Result<Vec<(String, String)>, NmstateError> {
+    check_nm_unsupported_ifaces(merged_state)?;
+    check_nm_nexthop_support(merged_state)?;
+    check_nm_vxlan_fdb_support(merged_state)?;
+    check_nm_route_protocol_support(merged_state)?;
+
     if merged_state
         .hostname
         .desired
@@ -0,0 +1,839 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Convert NetworkManager keyfiles into NetworkState for migrating existing
+// NetworkManager configurations to nmstate.
+// Unlike `nm_retrieve()` which query type specific configuration from kernel
+// via nispor, everything here is parsed from the NetworkManager connections.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use super::{
+    error::nm_error_to_nmstate,
+    nm_dbus::{
+        NmConnection, NmSettingBridgePort, NmSettingBridgeVlanRange,
+        NmVlanProtocol, NM_SETTING_IPVLAN_MODE_L2, NM_SETTING_IPVLAN_MODE_L3,
+        NM_SETTING_IPVLAN_MODE_L3S, NM_SETTING_TUN_MODE_TAP,
+        NM_SETTING_TUN_MODE_TUN, NM_VLAN_FLAG_GVRP, NM_VLAN_FLAG_LOOSE_BINDING,
+        NM_VLAN_FLAG_MVRP, NM_VLAN_FLAG_REORDER_HEADERS,
+    },
+    query_apply::{device::nm_iface_type_to_nmstate, is_lldp_enabled},
+    show::iface_get,
+};
+use crate::{
+    BaseInterface, BondConfig, BondInterface, BondMode, BondOptions,
+    BridgePortTunkTag, BridgePortVlanConfig, BridgePortVlanMode,
+    BridgePortVlanRange, DnsClientState, EthernetConfig, EthernetDuplex,
+    EthernetInterface, HsrConfig, HsrInterface, HsrProtocol, Interface,
+    InterfaceIpAddr, IpTunnelConfig, IpTunnelInterface, IpVlanConfig,
+    IpVlanInterface, IpVlanMode, LinuxBridgeConfig, LinuxBridgeInterface,
+    LinuxBridgeMulticastRouterType, LinuxBridgeOptions, LinuxBridgePortConfig,
+    LinuxBridgeStpOptions, MacVlanConfig, MacVlanInterface, MacVlanMode,
+    MacVtapConfig, MacVtapInterface, MacVtapMode, MacsecInterface,
+    MacsecValidation, NetworkState, NmstateError, RouteEntry, RouteRuleEntry,
+    RouteScope, RouteType, TunConfig, TunInterface, TunMode, VethConfig,
+    VlanConfig, VlanInterface, VlanProtocol, VlanQosMapping, VrfConfig,
+    VxlanConfig, VxlanInterface,
+};
+
+// NetworkManager treat 0 as default DNS priority which is 100 for non-VPN
+// connections.
+const NM_DEFAULT_DNS_PRIORITY: i32 = 100;
+
+const NM_802_1_AD: &str = "802.1ad";
+
+pub(crate) fn nm_keyfiles_to_nmstate(
+    keyfiles: &[String],
+) -> Result<NetworkState, NmstateError> {
+    let mut nm_conns = Vec::new();
+    for keyfile in keyfiles {
+        nm_conns.push(
+            NmConnection::from_keyfile(keyfile).map_err(nm_error_to_nmstate)?,
+        );
+    }
+
+    // Controller and parent could be referred by UUID or interface name
+    let mut uuid_to_name: HashMap<&str, &str> = HashMap::new();
+    for nm_conn in nm_conns.as_slice() {
+        if let (Some(uuid), Some(name)) = (nm_conn.uuid(), nm_conn.iface_name())
+        {
+            uuid_to_name.insert(uuid, name);
+        }
+    }
+    let resolve_name = |name: &str| -> String {
+        uuid_to_name.get(name).copied().unwrap_or(name).to_string()
+    };
+
+    let mut net_state = NetworkState::new();
+    let mut routes: Vec<RouteEntry> = Vec::new();
+    let mut rules: Vec<RouteRuleEntry> = Vec::new();
+    let mut dns_confs: Vec<DnsClientState> = Vec::new();
+    // (controller, port, bridge port setting)
+    let mut ports: Vec<(String, String, Option<&NmSettingBridgePort>)> =
+        Vec::new();
+
+    for nm_conn in nm_conns.as_slice() {
+        let conn_id = nm_conn.id().unwrap_or_default();
+        let nm_iface_type = nm_conn.iface_type().unwrap_or_default();
+        if nm_iface_type.starts_with("ovs-") {
+            log::warn!(
+                "Ignoring connection {}: OpenvSwitch keyfile is not \
+                supported yet",
+                conn_id
+            );
+            continue;
+        }
+        let iface_name = if let Some(n) = nm_conn.iface_name() {
+            n
+        } else {
+            log::warn!(
+                "Ignoring connection {} which has no interface-name",
+                conn_id
+            );
+            continue;
+        };
+        let is_mac_vtap =
+            nm_conn.mac_vlan.as_ref().and_then(|s| s.tap) == Some(true);
+        let ip_tunnel_mode = nm_conn
+            .ip_tunnel
+            .as_ref()
+            .and_then(|s| s.mode)
+            .unwrap_or_default();
+        let iface_type = nm_iface_type_to_nmstate(
+            nm_iface_type,
+            is_mac_vtap,
+            ip_tunnel_mode,
+        );
+        let lldp_neighbors = if is_lldp_enabled(nm_conn) {
+            Some(Vec::new())
+        } else {
+            None
+        };
+        let mut iface = if let Some(i) =
+            iface_get(iface_type, nm_conn, Some(nm_conn), lldp_neighbors)
+        {
+            i
+        } else {
+            log::warn!(
+                "Ignoring connection {}: interface type {} is not supported",
+                conn_id,
+                nm_iface_type
+            );
+            continue;
+        };
+
+        nm_conn_to_iface_config(&mut iface, nm_conn, &resolve_name);
+
+        let base_iface = iface.base_iface_mut();
+        if let Some(ctrl) = base_iface.controller.take() {
+            ports.push((
+                resolve_name(&ctrl),
+                iface_name.to_string(),
+                nm_conn.bridge_port.as_ref(),
+            ));
+        }
+        nm_ip_to_nmstate(
+            base_iface,
+            nm_conn,
+            &mut routes,
+            &mut rules,
+            &mut dns_confs,
+        )?;
+
+        net_state.append_interface_data(iface);
+    }
+
+    for (ctrl_name, port_name, nm_br_port_set) in ports {
+        match net_state.interfaces.kernel_ifaces.get_mut(&ctrl_name) {
+            Some(Interface::Bond(iface)) => {
+                iface
+                    .bond
+                    .get_or_insert_with(BondConfig::new)
+                    .port
+                    .get_or_insert_with(Vec::new)
+                    .push(port_name);
+            }
+            Some(Interface::LinuxBridge(iface)) => {
+                iface
+                    .bridge
+                    .get_or_insert_with(LinuxBridgeConfig::new)
+                    .port
+                    .get_or_insert_with(Vec::new)
+                    .push(nm_br_port_to_nmstate(port_name, nm_br_port_set));
+            }
+            Some(Interface::Vrf(iface)) => {
+                iface
+                    .vrf
+                    .get_or_insert_with(VrfConfig::default)
+                    .port
+                    .get_or_insert_with(Vec::new)
+                    .push(port_name);
+            }
+            _ => {
+                log::warn!(
+                    "Ignoring controller {} of interface {}: controller \
+                    not found or not supported",
+                    ctrl_name,
+                    port_name
+                );
+            }
+        }
+    }
+
+    if !routes.is_empty() {
+        net_state.routes.config = Some(routes);
+    }
+    if !rules.is_empty() {
+        net_state.rules.config = Some(rules);
+    }
+    net_state.dns.config = merge_dns_confs(dns_confs);
+
+    Ok(net_state)
+}
+
+fn nm_conn_to_iface_config(
+    iface: &mut Interface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    nm_conn_to_base_iface_config(iface.base_iface_mut(), nm_conn);
+    match iface {
+        Interface::Ethernet(iface) => nm_conn_to_eth_config(iface, nm_conn),
+        Interface::Bond(iface) => nm_conn_to_bond_config(iface, nm_conn),
+        Interface::LinuxBridge(iface) => {
+            nm_conn_to_bridge_config(iface, nm_conn)
+        }
+        Interface::Vlan(iface) => {
+            nm_conn_to_vlan_config(iface, nm_conn, resolve_name)
+        }
+        Interface::Vxlan(iface) => {
+            nm_conn_to_vxlan_config(iface, nm_conn, resolve_name)
+        }
+        Interface::IpTunnel(iface) => {
+            nm_conn_to_ip_tunnel_config(iface, nm_conn, resolve_name)
+        }
+        Interface::Vrf(iface) => {
+            if let Some(table_id) = nm_conn.vrf.as_ref().and_then(|s| s.table) {
+                iface.vrf.get_or_insert_with(VrfConfig::default).table_id =
+                    table_id;
+            }
+        }
+        Interface::MacVlan(iface) => {
+            nm_conn_to_mac_vlan_config(iface, nm_conn, resolve_name)
+        }
+        Interface::MacVtap(iface) => {
+            nm_conn_to_mac_vtap_config(iface, nm_conn, resolve_name)
+        }
+        Interface::Macsec(iface) => {
+            nm_conn_to_macsec_config(iface, nm_conn, resolve_name)
+        }
+        Interface::IpVlan(iface) => {
+            nm_conn_to_ip_vlan_config(iface, nm_conn, resolve_name)
+        }
+        Interface::Tun(iface) => nm_conn_to_tun_config(iface, nm_conn),
+        Interface::Hsr(iface) => nm_conn_to_hsr_config(iface, nm_conn),
+        _ => (),
+    }
+}
+
+fn nm_conn_to_base_iface_config(
+    base_iface: &mut BaseInterface,
+    nm_conn: &NmConnection,
+) {
+    if let Some(nm_set) = nm_conn.wired.as_ref() {
+        base_iface.mtu = nm_set.mtu.map(u64::from);
+        base_iface.mac_address = nm_set.cloned_mac_address.clone();
+        base_iface.accept_all_mac_addresses =
+            nm_set.accept_all_mac_addresses.map(|i| i > 0);
+    }
+    if let Some(mtu) = nm_conn.loopback.as_ref().and_then(|s| s.mtu) {
+        base_iface.mtu = Some(mtu.into());
+    }
+    if let Some(mtu) = nm_conn.ip_tunnel.as_ref().and_then(|s| s.mtu) {
+        base_iface.mtu = Some(mtu.into());
+    }
+}
+
+fn nm_conn_to_eth_config(
+    iface: &mut EthernetInterface,
+    nm_conn: &NmConnection,
+) {
+    if let Some(nm_set) = nm_conn.wired.as_ref() {
+        if nm_set.auto_negotiate.is_some()
+            || nm_set.speed.is_some()
+            || nm_set.duplex.is_some()
+        {
+            let mut eth_conf = EthernetConfig::new();
+            eth_conf.auto_neg = nm_set.auto_negotiate;
+            eth_conf.speed = nm_set.speed;
+            eth_conf.duplex = match nm_set.duplex.as_deref() {
+                Some("full") => Some(EthernetDuplex::Full),
+                Some("half") => Some(EthernetDuplex::Half),
+                _ => None,
+            };
+            iface.ethernet = Some(eth_conf);
+        }
+    }
+    if let Some(peer) = nm_conn.veth.as_ref().and_then(|s| s.peer.as_ref()) {
+        iface.veth = Some(VethConfig {
+            peer: peer.to_string(),
+        });
+    }
+}
+
+fn nm_conn_to_bond_config(iface: &mut BondInterface, nm_conn: &NmConnection) {
+    let nm_set = if let Some(s) = nm_conn.bond.as_ref() {
+        s
+    } else {
+        return;
+    };
+    let bond_conf = iface.bond.get_or_insert_with(BondConfig::new);
+    if let Some(mode) = nm_set.options.get("mode") {
+        match serde_json::from_value::<BondMode>(serde_json::Value::String(
+            mode.to_string(),
+        )) {
+            Ok(m) => bond_conf.mode = Some(m),
+            Err(e) => {
+                log::warn!("Ignoring invalid bond mode {}: {}", mode, e);
+            }
+        }
+    }
+
+    // Drop the options nmstate does not support instead of failing the whole
+    // bond options.
+    let mut opts = serde_json::Map::new();
+    for (k, v) in nm_set.options.iter().filter(|(k, _)| k.as_str() != "mode") {
+        let mut opt = serde_json::Map::new();
+        opt.insert(k.to_string(), serde_json::Value::String(v.to_string()));
+        if let Err(e) = serde_json::from_value::<BondOptions>(
+            serde_json::Value::Object(opt),
+        ) {
+            log::warn!("Ignoring unsupported bond option {}={}: {}", k, v, e);
+        } else {
+            opts.insert(
+                k.to_string(),
+                serde_json::Value::String(v.to_string()),
+            );
+        }
+    }
+    if !opts.is_empty() {
+        match serde_json::from_value::<BondOptions>(serde_json::Value::Object(
+            opts,
+        )) {
+            Ok(mut bond_opts) => {
+                bond_opts.balance_slb =
+                    bond_conf.options.as_ref().and_then(|o| o.balance_slb);
+                bond_conf.options = Some(bond_opts);
+            }
+            Err(e) => {
+                log::warn!("Ignoring invalid bond options: {}", e);
+            }
+        }
+    }
+}
+
+fn nm_conn_to_bridge_config(
+    iface: &mut LinuxBridgeInterface,
+    nm_conn: &NmConnection,
+) {
+    let nm_set = if let Some(s) = nm_conn.bridge.as_ref() {
+        s
+    } else {
+        return;
+    };
+    let mut opts = LinuxBridgeOptions::new();
+    let mut stp_opts = LinuxBridgeStpOptions::new();
+    stp_opts.enabled = nm_set.stp;
+    stp_opts.forward_delay =
+        nm_set.forward_delay.and_then(|v| u8::try_from(v).ok());
+    stp_opts.hello_time = nm_set.hello_time.and_then(|v| u8::try_from(v).ok());
+    stp_opts.max_age = nm_set.max_age.and_then(|v| u8::try_from(v).ok());
+    stp_opts.priority = nm_set.priority.and_then(|v| u16::try_from(v).ok());
+    opts.stp = Some(stp_opts);
+    opts.group_addr = nm_set.group_address.clone();
+    opts.group_forward_mask = nm_set
+        .group_forward_mask
+        .and_then(|v| u16::try_from(v).ok());
+    opts.hash_max = nm_set.multicast_hash_max;
+    opts.mac_ageing_time = nm_set.ageing_time;
+    opts.multicast_last_member_count = nm_set.multicast_last_member_count;
+    opts.multicast_last_member_interval = nm_set.multicast_last_member_interval;
+    opts.multicast_membership_interval = nm_set.multicast_membership_interval;
+    opts.multicast_querier = nm_set.multicast_querier;
+    opts.multicast_querier_interval = nm_set.multicast_querier_interval;
+    opts.multicast_query_interval = nm_set.multicast_query_interval;
+    opts.multicast_query_response_interval =
+        nm_set.multicast_query_response_interval;
+    opts.multicast_query_use_ifaddr = nm_set.multicast_query_use_ifaddr;
+    opts.multicast_router = nm_set
+        .multicast_router
+        .as_deref()
+        .and_then(|v| LinuxBridgeMulticastRouterType::from_str(v).ok());
+    opts.multicast_snooping = nm_set.multicast_snooping;
+    opts.multicast_startup_query_count = nm_set.multicast_startup_query_count;
+    opts.multicast_startup_query_interval =
+        nm_set.multicast_startup_query_interval;
+    opts.vlan_protocol = nm_set.vlan_protocol.map(|p| match p {
+        NmVlanProtocol::Dot1Ad => VlanProtocol::Ieee8021Ad,
+        _ => VlanProtocol::Ieee8021Q,
+    });
+    iface
+        .bridge
+        .get_or_insert_with(LinuxBridgeConfig::new)
+        .options = Some(opts);
+}
+
+fn nm_br_port_to_nmstate(
+    port_name: String,
+    nm_set: Option<&NmSettingBridgePort>,
+) -> LinuxBridgePortConfig {
+    let mut port_conf = LinuxBridgePortConfig::new();
+    port_conf.name = port_name;
+    if let Some(nm_set) = nm_set {
+        port_conf.stp_hairpin_mode = nm_set.hairpin_mode;
+        port_conf.stp_path_cost = nm_set.path_cost;
+        port_conf.stp_priority =
+            nm_set.priority.and_then(|v| u16::try_from(v).ok());
+        port_conf.vlan =
+            nm_set.vlans.as_deref().and_then(nm_vlan_ranges_to_nmstate);
+    }
+    port_conf
+}
+
+// Reverse of `nmstate_port_vlans_to_nm_vlan_range()`: the native VLAN is
+// stored as PVID and untagged VLAN.
+fn nm_vlan_ranges_to_nmstate(
+    nm_ranges: &[NmSettingBridgeVlanRange],
+) -> Option<BridgePortVlanConfig> {
+    if nm_ranges.is_empty() {
+        return None;
+    }
+    let mut vlan_conf = BridgePortVlanConfig::new();
+    let mut trunk_tags = Vec::new();
+    for nm_range in nm_ranges {
+        if nm_range.pvid
+            && nm_range.untagged
+            && nm_range.vid_start == nm_range.vid_end
+        {
+            vlan_conf.tag = Some(nm_range.vid_start);
+        } else if nm_range.vid_start == nm_range.vid_end {
+            trunk_tags.push(BridgePortTunkTag::Id(nm_range.vid_start));
+        } else {
+            trunk_tags.push(BridgePortTunkTag::IdRange(BridgePortVlanRange {
+                min: nm_range.vid_start,
+                max: nm_range.vid_end,
+            }));
+        }
+    }
+    if trunk_tags.is_empty() {
+        vlan_conf.mode = Some(BridgePortVlanMode::Access);
+    } else {
+        vlan_conf.mode = Some(BridgePortVlanMode::Trunk);
+        vlan_conf.enable_native = Some(vlan_conf.tag.is_some());
+        vlan_conf.trunk_tags = Some(trunk_tags);
+    }
+    Some(vlan_conf)
+}
+
+fn nm_vlan_protocol_to_nmstate(protocol: &str) -> VlanProtocol {
+    if protocol == NM_802_1_AD {
+        VlanProtocol::Ieee8021Ad
+    } else {
+        VlanProtocol::Ieee8021Q
+    }
+}
+
+fn nm_conn_to_vlan_config(
+    iface: &mut VlanInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.vlan.as_ref() {
+        iface.vlan = Some(VlanConfig {
+            base_iface: resolve_name(
+                nm_set.parent.as_deref().unwrap_or_default(),
+            ),
+            id: nm_set
+                .id
+                .and_then(|i| u16::try_from(i).ok())
+                .unwrap_or_default(),
+            protocol: nm_set
+                .protocol
+                .as_deref()
+                .map(nm_vlan_protocol_to_nmstate),
+            reorder_headers: nm_set
+                .flags
+                .map(|f| f & NM_VLAN_FLAG_REORDER_HEADERS > 0),
+            gvrp: nm_set.flags.map(|f| f & NM_VLAN_FLAG_GVRP > 0),
+            mvrp: nm_set.flags.map(|f| f & NM_VLAN_FLAG_MVRP > 0),
+            loose_binding: nm_set
+                .flags
+                .map(|f| f & NM_VLAN_FLAG_LOOSE_BINDING > 0),
+            ingress_qos_map: nm_set
+                .ingress_priority_map
+                .as_deref()
+                .map(nm_qos_map_to_nmstate),
+            egress_qos_map: nm_set
+                .egress_priority_map
+                .as_deref()
+                .map(nm_qos_map_to_nmstate),
+        });
+    }
+}
+
+// NetworkManager store the priority mapping in the format of `from:to`
+fn nm_qos_map_to_nmstate(nm_qos_map: &[String]) -> Vec<VlanQosMapping> {
+    let mut ret = Vec::new();
+    for mapping in nm_qos_map {
+        match mapping
+            .split_once(':')
+            .and_then(|(f, t)| Some((f.parse().ok()?, t.parse().ok()?)))
+        {
+            Some((from, to)) => ret.push(VlanQosMapping::new(from, to)),
+            None => log::warn!("Ignoring invalid VLAN priority map {mapping}"),
+        }
+    }
+    ret
+}
+
+fn nm_conn_to_vxlan_config(
+    iface: &mut VxlanInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.vxlan.as_ref() {
+        iface.vxlan = Some(VxlanConfig {
+            base_iface: resolve_name(
+                nm_set.parent.as_deref().unwrap_or_default(),
+            ),
+            id: nm_set.id.unwrap_or_default(),
+            learning: nm_set.learning,
+            local: nm_set
+                .local
+                .as_deref()
+                .and_then(|i| std::net::IpAddr::from_str(i).ok()),
+            remote: nm_set
+                .remote
+                .as_deref()
+                .and_then(|i| std::net::IpAddr::from_str(i).ok()),
+            dst_port: nm_set.dst_port.and_then(|p| u16::try_from(p).ok()),
+            ttl: nm_set.ttl.and_then(|v| u8::try_from(v).ok()),
+            tos: nm_set.tos.and_then(|v| u8::try_from(v).ok()),
+            source_port_min: nm_set
+                .source_port_min
+                .and_then(|p| u16::try_from(p).ok()),
+            source_port_max: nm_set
+                .source_port_max
+                .and_then(|p| u16::try_from(p).ok()),
+            ageing: nm_set.ageing,
+            limit: nm_set.limit,
+            proxy: nm_set.proxy,
+            rsc: nm_set.rsc,
+            l2_miss: nm_set.l2_miss,
+            l3_miss: nm_set.l3_miss,
+            ..Default::default()
+        });
+    }
+}
+
+fn nm_conn_to_ip_tunnel_config(
+    iface: &mut IpTunnelInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.ip_tunnel.as_ref() {
+        iface.ip_tunnel = Some(IpTunnelConfig {
+            base_iface: nm_set
+                .parent
+                .as_deref()
+                .filter(|p| !p.is_empty())
+                .map(resolve_name),
+            local: nm_set
+                .local
+                .as_deref()
+                .and_then(|i| std::net::IpAddr::from_str(i).ok()),
+            remote: nm_set
+                .remote
+                .as_deref()
+                .and_then(|i| std::net::IpAddr::from_str(i).ok()),
+            key: nm_set
+                .input_key
+                .as_deref()
+                .and_then(|k| u32::from_str(k).ok()),
+            ttl: nm_set.ttl.and_then(|t| u8::try_from(t).ok()),
+            tos: nm_set.tos.and_then(|t| u8::try_from(t).ok()),
+            pmtudisc: nm_set.path_mtu_discovery,
+        });
+    }
+}
+
+fn nm_conn_to_mac_vlan_config(
+    iface: &mut MacVlanInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.mac_vlan.as_ref() {
+        iface.mac_vlan = Some(MacVlanConfig {
+            base_iface: resolve_name(
+                nm_set.parent.as_deref().unwrap_or_default(),
+            ),
+            mode: match nm_set.mode {
+                Some(1) => MacVlanMode::Vepa,
+                Some(2) => MacVlanMode::Bridge,
+                Some(3) => MacVlanMode::Private,
+                Some(4) => MacVlanMode::Passthru,
+                Some(5) => MacVlanMode::Source,
+                _ => MacVlanMode::Unknown,
+            },
+            accept_all_mac: nm_set.accept_all_mac,
+        });
+    }
+}
+
+fn nm_conn_to_mac_vtap_config(
+    iface: &mut MacVtapInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.mac_vlan.as_ref() {
+        iface.mac_vtap = Some(MacVtapConfig {
+            base_iface: resolve_name(
+                nm_set.parent.as_deref().unwrap_or_default(),
+            ),
+            mode: match nm_set.mode {
+                Some(1) => MacVtapMode::Vepa,
+                Some(2) => MacVtapMode::Bridge,
+                Some(3) => MacVtapMode::Private,
+                Some(4) => MacVtapMode::Passthru,
+                Some(5) => MacVtapMode::Source,
+                _ => MacVtapMode::Unknown,
+            },
+            accept_all_mac: nm_set.accept_all_mac,
+        });
+    }
+}
+
+fn nm_conn_to_ip_vlan_config(
+    iface: &mut IpVlanInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.ip_vlan.as_ref() {
+        iface.ipvlan = Some(IpVlanConfig {
+            base_iface: resolve_name(
+                nm_set.parent.as_deref().unwrap_or_default(),
+            ),
+            mode: match nm_set.mode {
+                Some(NM_SETTING_IPVLAN_MODE_L2) => Some(IpVlanMode::L2),
+                Some(NM_SETTING_IPVLAN_MODE_L3) => Some(IpVlanMode::L3),
+                Some(NM_SETTING_IPVLAN_MODE_L3S) => Some(IpVlanMode::L3s),
+                _ => None,
+            },
+            private: nm_set.private,
+            vepa: nm_set.vepa,
+        });
+    }
+}
+
+fn nm_conn_to_tun_config(iface: &mut TunInterface, nm_conn: &NmConnection) {
+    if let Some(nm_set) = nm_conn.tun.as_ref() {
+        iface.tun = Some(TunConfig {
+            mode: match nm_set.mode {
+                Some(NM_SETTING_TUN_MODE_TUN) => Some(TunMode::Tun),
+                Some(NM_SETTING_TUN_MODE_TAP) => Some(TunMode::Tap),
+                _ => None,
+            },
+            owner: nm_set.owner.as_deref().and_then(|v| v.parse().ok()),
+            group: nm_set.group.as_deref().and_then(|v| v.parse().ok()),
+            multi_queue: nm_set.multi_queue,
+            vnet_hdr: nm_set.vnet_hdr,
+            // NetworkManager always create persistent TUN/TAP interface
+            persist: Some(true),
+        });
+    }
+}
+
+fn nm_conn_to_hsr_config(iface: &mut HsrInterface, nm_conn: &NmConnection) {
+    if let Some(nm_set) = nm_conn.hsr.as_ref() {
+        let mut conf = HsrConfig {
+            port1: nm_set.port1.clone().unwrap_or_default(),
+            port2: nm_set.port2.clone().unwrap_or_default(),
+            protocol: Some(if nm_set.prp == Some(true) {
+                HsrProtocol::Prp
+            } else {
+                HsrProtocol::Hsr
+            }),
+            ..Default::default()
+        };
+        if let Some(v) =
+            nm_set.multicast_spec.and_then(|v| u8::try_from(v).ok())
+        {
+            conf.set_multicast_spec(v);
+        }
+        iface.hsr = Some(conf);
+    }
+}
+
+// The MKA keys are already set by `iface_get()`.
+fn nm_conn_to_macsec_config(
+    iface: &mut MacsecInterface,
+    nm_conn: &NmConnection,
+    resolve_name: &dyn Fn(&str) -> String,
+) {
+    if let Some(nm_set) = nm_conn.macsec.as_ref() {
+        let conf = iface.macsec.get_or_insert_with(Default::default);
+        conf.base_iface =
+            resolve_name(nm_set.parent.as_deref().unwrap_or_default());
+        conf.port = nm_set.port.and_then(|p| u16::try_from(p).ok());
+        conf.encrypt = nm_set.encrypt;
+        conf.validation = nm_set
+            .validation
+            .and_then(|v| u8::try_from(v).ok())
+            .and_then(|v| MacsecValidation::try_from(v).ok());
+        conf.send_sci = nm_set.send_sci;
+    }
+}
+
+// The `nm_conn_to_base_iface()` does not include IP addresses and routes as
+// they are retrieved from kernel in `nm_retrieve()`. The DNS and route rules
+// are moved from interface to global level.
+fn nm_ip_to_nmstate(
+    base_iface: &mut BaseInterface,
+    nm_conn: &NmConnection,
+    routes: &mut Vec<RouteEntry>,
+    rules: &mut Vec<RouteRuleEntry>,
+    dns_confs: &mut Vec<DnsClientState>,
+) -> Result<(), NmstateError> {
+    let iface_name = base_iface.name.clone();
+    if let (Some(ip_conf), Some(nm_set)) =
+        (base_iface.ipv4.as_mut(), nm_conn.ipv4.as_ref())
+    {
+        ip_conf.addresses = nm_ip_addresses_to_nmstate(&nm_set.addresses)?;
+        routes.extend(nm_routes_to_nmstate(&iface_name, nm_conn, false));
+        rules.extend(ip_conf.rules.take().unwrap_or_default());
+        if let Some(dns_conf) = ip_conf.dns.take() {
+            dns_confs.push(dns_conf);
+        }
+        ip_conf.sanitize(false)?;
+    }
+    if let (Some(ip_conf), Some(nm_set)) =
+        (base_iface.ipv6.as_mut(), nm_conn.ipv6.as_ref())
+    {
+        ip_conf.addresses = nm_ip_addresses_to_nmstate(&nm_set.addresses)?;
+        routes.extend(nm_routes_to_nmstate(&iface_name, nm_conn, true));
+        rules.extend(ip_conf.rules.take().unwrap_or_default());
+        if let Some(dns_conf) = ip_conf.dns.take() {
+            dns_confs.push(dns_conf);
+        }
+        ip_conf.sanitize(false)?;
+    }
+    Ok(())
+}
+
+fn nm_ip_addresses_to_nmstate(
+    nm_addrs: &[String],
+) -> Result<Option<Vec<InterfaceIpAddr>>, NmstateError> {
+    if nm_addrs.is_empty() {
+        return Ok(None);
+    }
+    let mut addrs = Vec::new();
+    for nm_addr in nm_addrs {
+        addrs.push(InterfaceIpAddr::try_from(nm_addr.as_str())?);
+    }
+    Ok(Some(addrs))
+}
+
+fn nm_routes_to_nmstate(
+    iface_name: &str,
+    nm_conn: &NmConnection,
+    is_ipv6: bool,
+) -> Vec<RouteEntry> {
+    let mut ret = Vec::new();
+    let nm_set = if let Some(s) = if is_ipv6 {
+        nm_conn.ipv6.as_ref()
+    } else {
+        nm_conn.ipv4.as_ref()
+    } {
+        s
+    } else {
+        return ret;
+    };
+    if let Some(gateway) = nm_set.gateway.as_ref() {
+        let mut rt = RouteEntry::new();
+        rt.destination =
+            Some(if is_ipv6 { "::/0" } else { "0.0.0.0/0" }.to_string());
+        rt.next_hop_iface = Some(iface_name.to_string());
+        rt.next_hop_addr = Some(gateway.to_string());
+        rt.table_id = nm_set.route_table;
+        ret.push(rt);
+    }
+    for nm_route in nm_set.routes.as_slice() {
+        let mut rt = RouteEntry::new();
+        if let (Some(dest), Some(prefix)) =
+            (nm_route.dest.as_ref(), nm_route.prefix)
+        {
+            rt.destination = Some(format!("{dest}/{prefix}"));
+        }
+        rt.next_hop_iface = Some(iface_name.to_string());
+        rt.next_hop_addr = nm_route.next_hop.clone();
+        rt.metric = nm_route.metric.map(i64::from);
+        rt.table_id = nm_route.table.or(nm_set.route_table);
+        rt.weight = nm_route.weight.and_then(|w| u16::try_from(w).ok());
+        if let Some(route_type) =
+            nm_route.route_type.as_deref().filter(|t| *t != "unicast")
+        {
+            match RouteType::from_str(route_type) {
+                Ok(t) => {
+                    rt.route_type = Some(t);
+                    if t.is_iface_less() {
+                        rt.next_hop_iface = None;
+                    }
+                }
+                Err(e) => log::warn!("Ignoring route {rt}: {e}"),
+            }
+        }
+        rt.source = nm_route.src.clone();
+        rt.mtu = nm_route.mtu;
+        rt.advmss = nm_route.advmss;
+        rt.initcwnd = nm_route.initcwnd;
+        rt.initrwnd = nm_route.initrwnd;
+        rt.onlink = nm_route.onlink;
+        rt.scope = nm_route.scope.and_then(|s| RouteScope::try_from(s).ok());
+        rt.quickack = nm_route.quickack;
+        ret.push(rt);
+    }
+    ret
+}
+
+// Merge DNS configurations of all connections sorted by DNS priority.
+fn merge_dns_confs(
+    mut dns_confs: Vec<DnsClientState>,
+) -> Option<DnsClientState> {
+    dns_confs.sort_by_key(|c| match c.priority {
+        Some(0) | None => NM_DEFAULT_DNS_PRIORITY,
+        Some(p) => p,
+    });
+    let mut servers: Vec<String> = Vec::new();
+    let mut searches: Vec<String> = Vec::new();
+    for dns_conf in dns_confs {
+        for srv in dns_conf.server.unwrap_or_default() {
+            if !servers.contains(&srv) {
+                servers.push(srv);
+            }
+        }
+        for search in dns_conf.search.unwrap_or_default() {
+            if !searches.contains(&search) {
+                searches.push(search);
+            }
+        }
+    }
+    if servers.is_empty() && searches.is_empty() {
+        None
+    } else {
+        let mut ret = DnsClientState::new();
+        ret.server = Some(servers);
+        ret.search = Some(searches);
+        Some(ret)
+    }
+}
@@ -11,6 +11,8 @@ pub(crate) mod dns;
 mod error;
 #[cfg(feature = "gen_conf")]
 mod gen_conf;
+#[cfg(feature = "query_apply")]
+mod keyfile;
 mod nm_dbus;
 mod profile;
 #[cfg(feature = "query_apply")]
@@ -32,6 +34,8 @@ pub(crate) use checkpoint::{
 #[cfg(feature = "gen_conf")]
 pub(crate) use gen_conf::nm_gen_conf;
 #[cfg(feature = "query_apply")]
-pub(crate) use query_apply::nm_apply;
+pub(crate) use keyfile::nm_keyfiles_to_nmstate;
+#[cfg(feature = "query_apply")]
+pub(crate) use query_apply::{nm_apply, nm_apply_plan};
 #[cfg(feature = "query_apply")]
 pub(crate) use show::nm_retrieve;
@@ -12,23 +12,29 @@ use super::super::{
     connection::bond::NmSettingBond,
     connection::bridge::{NmSettingBridge, NmSettingBridgePort},
     connection::ethtool::NmSettingEthtool,
+    connection::hsr::NmSettingHsr,
     connection::ieee8021x::NmSetting8021X,
     connection::infiniband::NmSettingInfiniBand,
     connection::ip::NmSettingIp,
+    connection::ip_tunnel::NmSettingIpTunnel,
+    connection::ip_vlan::NmSettingIpVlan,
     connection::loopback::NmSettingLoopback,
     connection::mac_vlan::NmSettingMacVlan,
+    connection::macsec::NmSettingMacsec,
     connection::ovs::{
         NmSettingOvsBridge, NmSettingOvsDpdk, NmSettingOvsExtIds,
         NmSettingOvsIface, NmSettingOvsOtherConfig, NmSettingOvsPatch,
         NmSettingOvsPort,
     },
     connection::sriov::NmSettingSriov,
+    connection::tun::NmSettingTun,
     connection::user::NmSettingUser,
     connection::veth::NmSettingVeth,
     connection::vlan::NmSettingVlan,
     connection::vrf::NmSettingVrf,
     connection::vxlan::NmSettingVxlan,
     connection::wired::NmSettingWired,
+    connection::wireguard::NmSettingWireGuard,
     convert::ToDbusValue,
     NmError,
 };
@@ -102,6 +108,12 @@ pub struct NmConnection {
     pub ethtool: Option<NmSettingEthtool>,
     pub infiniband: Option<NmSettingInfiniBand>,
     pub loopback: Option<NmSettingLoopback>,
+    pub wireguard: Option<NmSettingWireGuard>,
+    pub ip_tunnel: Option<NmSettingIpTunnel>,
+    pub macsec: Option<NmSettingMacsec>,
+    pub ip_vlan: Option<NmSettingIpVlan>,
+    pub tun: Option<NmSettingTun>,
+    pub hsr: Option<NmSettingHsr>,
     #[serde(skip)]
     pub obj_path: String,
     #[serde(skip)]
@@ -176,6 +188,16 @@ impl TryFrom<NmConnectionDbusOwnedValue> for NmConnection {
                 NmSettingInfiniBand::try_from
             )?,
             loopback: _from_map!(v, "loopback", NmSettingLoopback::try_from)?,
+            wireguard: _from_map!(
+                v,
+                "wireguard",
+                NmSettingWireGuard::try_from
+            )?,
+            ip_tunnel: _from_map!(v, "ip-tunnel", NmSettingIpTunnel::try_from)?,
+            macsec: _from_map!(v, "macsec", NmSettingMacsec::try_from)?,
+            ip_vlan: _from_map!(v, "ipvlan", NmSettingIpVlan::try_from)?,
+            tun: _from_map!(v, "tun", NmSettingTun::try_from)?,
+            hsr: _from_map!(v, "hsr", NmSettingHsr::try_from)?,
             _other: v,
             ..Default::default()
         })
@@ -281,6 +303,24 @@ impl NmConnection {
         if let Some(v) = &self.loopback {
             ret.insert("loopback", v.to_value()?);
         }
+        if let Some(v) = &self.wireguard {
+            ret.insert("wireguard", v.to_value()?);
+        }
+        if let Some(v) = &self.ip_tunnel {
+            ret.insert("ip-tunnel", v.to_value()?);
+        }
+        if let Some(v) = &self.macsec {
+            ret.insert("macsec", v.to_value()?);
+        }
+        if let Some(v) = &self.ip_vlan {
+            ret.insert("ipvlan", v.to_value()?);
+        }
+        if let Some(v) = &self.tun {
+            ret.insert("tun", v.to_value()?);
+        }
+        if let Some(v) = &self.hsr {
+            ret.insert("hsr", v.to_value()?);
+        }
         for (key, setting_value) in &self._other {
             let mut other_setting_value: HashMap<&str, zvariant::Value> =
                 HashMap::new();
@@ -308,6 +348,15 @@ impl NmConnection {
         if let Some(setting) = self.mac_vlan.as_mut() {
             setting.parent = Some(parent.to_string());
         }
+        if let Some(setting) = self.ip_tunnel.as_mut() {
+            setting.parent = Some(parent.to_string());
+        }
+        if let Some(setting) = self.macsec.as_mut() {
+            setting.parent = Some(parent.to_string());
+        }
+        if let Some(setting) = self.ip_vlan.as_mut() {
+            setting.parent = Some(parent.to_string());
+        }
     }
 
     pub fn uuid(&self) -> Option<&str> {
@@ -415,6 +464,7 @@ impl NmSettingConnection {
         match val {
             Some(NM_AUTOCONENCT_PORT_YES) => Some(true),
             Some(NM_AUTOCONENCT_PORT_NO) => Some(false),
+            Some(NM_AUTOCONENCT_PORT_DEFAULT) => None,
             Some(v) => {
                 warn!("Unknown autoconnect-ports value {}", v);
                 None
@@ -447,6 +497,25 @@ pub(crate) fn nm_con_get_from_obj_path(
             }
         }
     }
+    if let Some(wg_conf) = nm_conn.wireguard.as_mut() {
+        if let Ok(nm_secrets) = proxy.call::<&str, NmConnectionDbusOwnedValue>(
+            "GetSecrets",
+            &"wireguard",
+        ) {
+            if let Some(nm_secret) = nm_secrets.get("wireguard") {
+                wg_conf.fill_secrets(nm_secret);
+            }
+        }
+    }
+    if let Some(macsec_conf) = nm_conn.macsec.as_mut() {
+        if let Ok(nm_secrets) = proxy
+            .call::<&str, NmConnectionDbusOwnedValue>("GetSecrets", &"macsec")
+        {
+            if let Some(nm_secret) = nm_secrets.get("macsec") {
+                macsec_conf.fill_secrets(nm_secret);
+            }
+        }
+    }
     if let Ok(flags) = proxy.get_property::<u32>("Flags") {
         nm_conn.flags = from_u32_to_vec_nm_conn_flags(flags);
     }
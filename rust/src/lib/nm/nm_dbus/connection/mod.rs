@@ -21,21 +21,27 @@ mod bridge;
 mod conn;
 mod dns;
 mod ethtool;
+mod hsr;
 mod ieee8021x;
 mod infiniband;
 mod ip;
+mod ip_tunnel;
+mod ip_vlan;
 mod loopback;
 mod mac_vlan;
+mod macsec;
 mod ovs;
 mod route;
 mod route_rule;
 mod sriov;
+mod tun;
 mod user;
 mod veth;
 mod vlan;
 mod vrf;
 mod vxlan;
 mod wired;
+mod wireguard;
 
 pub use self::bond::NmSettingBond;
 pub use self::bridge::{
@@ -45,11 +51,25 @@ pub use self::conn::{
     NmConnection, NmRange, NmSettingConnection, NmSettingsConnectionFlag,
 };
 pub use self::ethtool::NmSettingEthtool;
+pub use self::hsr::NmSettingHsr;
 pub use self::ieee8021x::NmSetting8021X;
 pub use self::infiniband::NmSettingInfiniBand;
 pub use self::ip::{NmSettingIp, NmSettingIpMethod};
+pub use self::ip_tunnel::{
+    NmSettingIpTunnel, NM_IP_TUNNEL_MODE_GRE, NM_IP_TUNNEL_MODE_GRETAP,
+    NM_IP_TUNNEL_MODE_IP6GRE, NM_IP_TUNNEL_MODE_IP6GRETAP,
+    NM_IP_TUNNEL_MODE_IP6IP6, NM_IP_TUNNEL_MODE_IPIP, NM_IP_TUNNEL_MODE_IPIP6,
+    NM_IP_TUNNEL_MODE_SIT,
+};
+pub use self::ip_vlan::{
+    NmSettingIpVlan, NM_SETTING_IPVLAN_MODE_L2, NM_SETTING_IPVLAN_MODE_L3,
+    NM_SETTING_IPVLAN_MODE_L3S,
+};
 pub use self::loopback::NmSettingLoopback;
 pub use self::mac_vlan::NmSettingMacVlan;
+pub use self::macsec::{
+    NmSettingMacsec, NM_SETTING_MACSEC_MODE_EAP, NM_SETTING_MACSEC_MODE_PSK,
+};
 pub use self::ovs::{
     NmSettingOvsBridge, NmSettingOvsDpdk, NmSettingOvsExtIds,
     NmSettingOvsIface, NmSettingOvsOtherConfig, NmSettingOvsPatch,
@@ -58,15 +78,25 @@ pub use self::ovs::{
 pub use self::route::NmIpRoute;
 pub use self::route_rule::{NmIpRouteRule, NmIpRouteRuleAction};
 pub use self::sriov::{NmSettingSriov, NmSettingSriovVf, NmSettingSriovVfVlan};
+pub use self::tun::{
+    NmSettingTun, NM_SETTING_TUN_MODE_TAP, NM_SETTING_TUN_MODE_TUN,
+};
 pub use self::user::NmSettingUser;
 pub use self::veth::NmSettingVeth;
-pub use self::vlan::{NmSettingVlan, NmVlanProtocol};
+pub use self::vlan::{
+    NmSettingVlan, NmVlanProtocol, NM_VLAN_FLAG_GVRP,
+    NM_VLAN_FLAG_LOOSE_BINDING, NM_VLAN_FLAG_MVRP,
+    NM_VLAN_FLAG_REORDER_HEADERS,
+};
 pub use self::vrf::NmSettingVrf;
 pub use self::vxlan::NmSettingVxlan;
 pub use self::wired::NmSettingWired;
+pub use self::wireguard::{NmSettingWireGuard, NmSettingWireGuardPeer};
 
 pub(crate) use self::conn::DbusDictionary;
 #[cfg(feature = "query_apply")]
-pub(crate) use self::conn::{nm_con_get_from_obj_path, NmConnectionDbusValue};
+pub(crate) use self::conn::{
+    nm_con_get_from_obj_path, NmConnectionDbusOwnedValue, NmConnectionDbusValue,
+};
 #[cfg(feature = "query_apply")]
 pub(crate) use self::macros::_from_map;
@@ -16,6 +16,15 @@ pub struct NmIpRoute {
     pub table: Option<u32>,
     pub metric: Option<u32>,
     pub weight: Option<u32>,
+    pub route_type: Option<String>,
+    pub src: Option<String>,
+    pub mtu: Option<u32>,
+    pub advmss: Option<u32>,
+    pub initcwnd: Option<u32>,
+    pub initrwnd: Option<u32>,
+    pub onlink: Option<bool>,
+    pub scope: Option<u8>,
+    pub quickack: Option<bool>,
     _other: DbusDictionary,
 }
 
@@ -35,6 +44,15 @@ impl TryFrom<DbusDictionary> for NmIpRoute {
             table: _from_map!(v, "table", u32::try_from)?,
             metric: _from_map!(v, "metric", u32::try_from)?,
             weight,
+            route_type: _from_map!(v, "type", String::try_from)?,
+            src: _from_map!(v, "src", String::try_from)?,
+            mtu: _from_map!(v, "mtu", u32::try_from)?,
+            advmss: _from_map!(v, "advmss", u32::try_from)?,
+            initcwnd: _from_map!(v, "initcwnd", u32::try_from)?,
+            initrwnd: _from_map!(v, "initrwnd", u32::try_from)?,
+            onlink: _from_map!(v, "onlink", bool::try_from)?,
+            scope: _from_map!(v, "scope", u8::try_from)?,
+            quickack: _from_map!(v, "quickack", bool::try_from)?,
             _other: v,
         })
     }
@@ -82,6 +100,47 @@ impl NmIpRoute {
                 zvariant::Value::new(zvariant::Value::new(v)),
             )?;
         }
+        if let Some(v) = &self.route_type {
+            ret.append(
+                zvariant::Value::new("type"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.src {
+            ret.append(
+                zvariant::Value::new("src"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        for (key, value) in [
+            ("mtu", self.mtu),
+            ("advmss", self.advmss),
+            ("initcwnd", self.initcwnd),
+            ("initrwnd", self.initrwnd),
+        ] {
+            if let Some(v) = value {
+                ret.append(
+                    zvariant::Value::new(key),
+                    zvariant::Value::new(zvariant::Value::new(v)),
+                )?;
+            }
+        }
+        for (key, value) in
+            [("onlink", self.onlink), ("quickack", self.quickack)]
+        {
+            if let Some(v) = value {
+                ret.append(
+                    zvariant::Value::new(key),
+                    zvariant::Value::new(zvariant::Value::new(v)),
+                )?;
+            }
+        }
+        if let Some(v) = &self.scope {
+            ret.append(
+                zvariant::Value::new("scope"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
 
         for (key, value) in self._other.iter() {
             ret.append(
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use super::super::{connection::DbusDictionary, NmError, ToDbusValue};
+
+pub const NM_SETTING_TUN_MODE_TUN: u32 = 1;
+pub const NM_SETTING_TUN_MODE_TAP: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+#[non_exhaustive]
+pub struct NmSettingTun {
+    pub mode: Option<u32>,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    pub multi_queue: Option<bool>,
+    pub vnet_hdr: Option<bool>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingTun {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            mode: _from_map!(v, "mode", u32::try_from)?,
+            owner: _from_map!(v, "owner", String::try_from)?,
+            group: _from_map!(v, "group", String::try_from)?,
+            multi_queue: _from_map!(v, "multi-queue", bool::try_from)?,
+            vnet_hdr: _from_map!(v, "vnet-hdr", bool::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl ToDbusValue for NmSettingTun {
+    fn to_value(&self) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = self.mode {
+            ret.insert("mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.owner {
+            ret.insert("owner", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = &self.group {
+            ret.insert("group", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = self.multi_queue {
+            ret.insert("multi-queue", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.vnet_hdr {
+            ret.insert("vnet-hdr", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+}
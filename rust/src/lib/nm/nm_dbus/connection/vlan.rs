@@ -8,6 +8,11 @@ use super::super::{
     connection::DbusDictionary, ErrorKind, NmError, ToDbusValue,
 };
 
+pub const NM_VLAN_FLAG_REORDER_HEADERS: u32 = 0x1;
+pub const NM_VLAN_FLAG_GVRP: u32 = 0x2;
+pub const NM_VLAN_FLAG_LOOSE_BINDING: u32 = 0x4;
+pub const NM_VLAN_FLAG_MVRP: u32 = 0x8;
+
 #[derive(Debug, Clone, PartialEq, Default, Deserialize)]
 #[serde(try_from = "DbusDictionary")]
 #[non_exhaustive]
@@ -15,6 +20,11 @@ pub struct NmSettingVlan {
     pub parent: Option<String>,
     pub id: Option<u32>,
     pub protocol: Option<String>,
+    pub flags: Option<u32>,
+    // In the format of `from:to`
+    pub ingress_priority_map: Option<Vec<String>>,
+    // In the format of `from:to`
+    pub egress_priority_map: Option<Vec<String>>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -25,6 +35,17 @@ impl TryFrom<DbusDictionary> for NmSettingVlan {
             parent: _from_map!(v, "parent", String::try_from)?,
             id: _from_map!(v, "id", u32::try_from)?,
             protocol: _from_map!(v, "protocol", String::try_from)?,
+            flags: _from_map!(v, "flags", u32::try_from)?,
+            ingress_priority_map: _from_map!(
+                v,
+                "ingress-priority-map",
+                <Vec<String>>::try_from
+            )?,
+            egress_priority_map: _from_map!(
+                v,
+                "egress-priority-map",
+                <Vec<String>>::try_from
+            )?,
             _other: v,
         })
     }
@@ -42,6 +63,15 @@ impl ToDbusValue for NmSettingVlan {
         if let Some(protocol) = self.protocol.as_ref() {
             ret.insert("protocol", zvariant::Value::new(protocol));
         }
+        if let Some(v) = self.flags {
+            ret.insert("flags", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ingress_priority_map.as_ref() {
+            ret.insert("ingress-priority-map", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.egress_priority_map.as_ref() {
+            ret.insert("egress-priority-map", zvariant::Value::new(v));
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
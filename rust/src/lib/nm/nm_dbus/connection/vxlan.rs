@@ -15,6 +15,16 @@ pub struct NmSettingVxlan {
     pub local: Option<String>,
     pub remote: Option<String>,
     pub dst_port: Option<u32>,
+    pub ttl: Option<u32>,
+    pub tos: Option<u32>,
+    pub source_port_min: Option<u32>,
+    pub source_port_max: Option<u32>,
+    pub ageing: Option<u32>,
+    pub limit: Option<u32>,
+    pub proxy: Option<bool>,
+    pub rsc: Option<bool>,
+    pub l2_miss: Option<bool>,
+    pub l3_miss: Option<bool>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -28,6 +38,16 @@ impl TryFrom<DbusDictionary> for NmSettingVxlan {
             local: _from_map!(v, "local", String::try_from)?,
             remote: _from_map!(v, "remote", String::try_from)?,
             dst_port: _from_map!(v, "destination-port", u32::try_from)?,
+            ttl: _from_map!(v, "ttl", u32::try_from)?,
+            tos: _from_map!(v, "tos", u32::try_from)?,
+            source_port_min: _from_map!(v, "source-port-min", u32::try_from)?,
+            source_port_max: _from_map!(v, "source-port-max", u32::try_from)?,
+            ageing: _from_map!(v, "ageing", u32::try_from)?,
+            limit: _from_map!(v, "limit", u32::try_from)?,
+            proxy: _from_map!(v, "proxy", bool::try_from)?,
+            rsc: _from_map!(v, "rsc", bool::try_from)?,
+            l2_miss: _from_map!(v, "l2-miss", bool::try_from)?,
+            l3_miss: _from_map!(v, "l3-miss", bool::try_from)?,
             _other: v,
         })
     }
@@ -56,6 +76,36 @@ impl ToDbusValue for NmSettingVxlan {
         if let Some(v) = self.dst_port {
             ret.insert("destination-port", zvariant::Value::new(v));
         }
+        if let Some(v) = self.ttl {
+            ret.insert("ttl", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.tos {
+            ret.insert("tos", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.source_port_min {
+            ret.insert("source-port-min", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.source_port_max {
+            ret.insert("source-port-max", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ageing {
+            ret.insert("ageing", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.limit {
+            ret.insert("limit", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.proxy {
+            ret.insert("proxy", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.rsc {
+            ret.insert("rsc", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.l2_miss {
+            ret.insert("l2-miss", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.l3_miss {
+            ret.insert("l3-miss", zvariant::Value::new(v));
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
@@ -55,12 +55,27 @@ impl NmConnection {
         if let Some(vxlan) = &self.vxlan {
             sections.push(("vxlan", vxlan.to_keyfile()?));
         }
+        if let Some(ip_tunnel) = &self.ip_tunnel {
+            sections.push(("ip-tunnel", ip_tunnel.to_keyfile()?));
+        }
         if let Some(sriov) = &self.sriov {
             sections.push(("sriov", sriov.to_keyfile()?));
         }
         if let Some(mac_vlan) = &self.mac_vlan {
             sections.push(("macvlan", mac_vlan.to_keyfile()?));
         }
+        if let Some(ip_vlan) = &self.ip_vlan {
+            sections.push(("ipvlan", ip_vlan.to_keyfile()?));
+        }
+        if let Some(tun) = &self.tun {
+            sections.push(("tun", tun.to_keyfile()?));
+        }
+        if let Some(hsr) = &self.hsr {
+            sections.push(("hsr", hsr.to_keyfile()?));
+        }
+        if let Some(macsec) = &self.macsec {
+            sections.push(("macsec", macsec.to_keyfile()?));
+        }
         if let Some(vrf) = &self.vrf {
             sections.push(("vrf", vrf.to_keyfile()?));
         }
@@ -85,6 +100,27 @@ impl NmConnection {
         if let Some(ovs_other_cfgs) = &self.ovs_other_config {
             sections.push(("ovs-other-config", ovs_other_cfgs.to_keyfile()?));
         }
+        if let Some(wg) = &self.wireguard {
+            sections.push(("wireguard", wg.to_keyfile()?));
+        }
+        let wg_peers: Vec<(String, HashMap<String, zvariant::Value>)> = self
+            .wireguard
+            .as_ref()
+            .and_then(|wg| wg.peers.as_ref())
+            .map(|peers| {
+                peers
+                    .iter()
+                    .filter_map(|peer| {
+                        peer.public_key.as_ref().map(|k| {
+                            (format!("wireguard-peer.{k}"), peer.to_keyfile())
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (section_name, data) in wg_peers.as_slice() {
+            sections.push((section_name.as_str(), data.clone()));
+        }
 
         keyfile_sections_to_string(&sections)
     }
@@ -59,13 +59,16 @@ fn zvariant_value_to_string(
         zvariant::Value::Array(a) => {
             let mut ret = String::new();
             for item in a.get() {
-                ret += &zvariant_value_to_string(item)?;
+                ret += &match item {
+                    zvariant::Value::Str(s) => keyfile_escape(s.as_str(), true),
+                    _ => zvariant_value_to_string(item)?,
+                };
                 ret += DEFAULT_SEPARATOR;
             }
             ret.pop();
             Ok(ret)
         }
-        zvariant::Value::Str(s) => Ok(s.as_str().to_string()),
+        zvariant::Value::Str(s) => Ok(keyfile_escape(s.as_str(), false)),
         _ => {
             let e = NmError::new(
                 ErrorKind::Bug,
@@ -77,6 +80,24 @@ fn zvariant_value_to_string(
     }
 }
 
+// Escape string in the way of GKeyFile. The list separator is only escaped
+// for items of list.
+fn keyfile_escape(value: &str, is_list_item: bool) -> String {
+    let mut ret = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            ' ' if i == 0 => ret += "\\s",
+            '\n' => ret += "\\n",
+            '\t' => ret += "\\t",
+            '\r' => ret += "\\r",
+            '\\' => ret += "\\\\",
+            ';' if is_list_item => ret += "\\;",
+            _ => ret.push(c),
+        }
+    }
+    ret
+}
+
 pub(crate) trait ToKeyfile: ToDbusValue {
     fn to_keyfile(&self) -> Result<HashMap<String, zvariant::Value>, NmError> {
         let mut ret = HashMap::new();
@@ -4,20 +4,26 @@ mod bond;
 mod bridge;
 mod conn;
 mod ethtool;
+mod hsr;
 mod ieee8021x;
 mod infiniband;
 mod ip;
+mod ip_tunnel;
+mod ip_vlan;
 mod keyfile;
 mod mac_vlan;
+mod macsec;
 mod ovs;
 mod route;
 mod route_rule;
 mod sriov;
+mod tun;
 mod user;
 mod veth;
 mod vlan;
 mod vrf;
 mod vxlan;
 mod wired;
+mod wireguard;
 
 pub(crate) use keyfile::ToKeyfile;
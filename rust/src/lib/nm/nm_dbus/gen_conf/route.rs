@@ -20,10 +20,36 @@ impl NmIpRoute {
                 (None, None) => vec![dest],
             };
             ret.insert("".to_string(), rt_line.join(","));
-            ret.insert(
-                "options".to_string(),
-                format!("table={}", self.table.unwrap_or(DEFAULT_ROUTE_TABLE)),
-            );
+            let mut opts = vec![format!(
+                "table={}",
+                self.table.unwrap_or(DEFAULT_ROUTE_TABLE)
+            )];
+            if let Some(v) = self.route_type.as_ref() {
+                opts.push(format!("type={v}"));
+            }
+            if let Some(v) = self.src.as_ref() {
+                opts.push(format!("src={v}"));
+            }
+            for (key, value) in [
+                ("mtu", self.mtu),
+                ("advmss", self.advmss),
+                ("initcwnd", self.initcwnd),
+                ("initrwnd", self.initrwnd),
+            ] {
+                if let Some(v) = value {
+                    opts.push(format!("{key}={v}"));
+                }
+            }
+            if let Some(v) = self.onlink {
+                opts.push(format!("onlink={v}"));
+            }
+            if let Some(v) = self.scope {
+                opts.push(format!("scope={v}"));
+            }
+            if let Some(v) = self.quickack {
+                opts.push(format!("quickack={v}"));
+            }
+            ret.insert("options".to_string(), opts.join(","));
         }
         ret
     }
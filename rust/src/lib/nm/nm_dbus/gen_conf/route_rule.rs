@@ -16,6 +16,10 @@ impl NmIpRouteRule {
             let prio_str = format!("priority {priority}");
             keys.push(prio_str);
 
+            if self.invert == Some(true) {
+                keys.push("not".to_string());
+            }
+
             let to_str = match (self.to.as_ref(), self.to_len.as_ref()) {
                 (Some(t), Some(t_len)) => format!("to {t}/{t_len}"),
                 (Some(t), None) => format!("to {t}"),
@@ -49,6 +53,39 @@ impl NmIpRouteRule {
                 keys.push(from_str);
             }
 
+            if let Some(tos) = self.tos {
+                keys.push(format!("tos {tos:#04x}"));
+            }
+            if let Some(ipproto) = self.ipproto {
+                keys.push(format!("ipproto {ipproto}"));
+            }
+            if let Some(sport) = self.sport_start {
+                keys.push(format!(
+                    "sport {}",
+                    port_range_to_keyfile(sport, self.sport_end)
+                ));
+            }
+            if let Some(dport) = self.dport_start {
+                keys.push(format!(
+                    "dport {}",
+                    port_range_to_keyfile(dport, self.dport_end)
+                ));
+            }
+            if let Some(iif) = self.iifname.as_ref() {
+                keys.push(format!("iif {iif}"));
+            }
+            if let Some(oif) = self.oifname.as_ref() {
+                keys.push(format!("oif {oif}"));
+            }
+            if let (Some(start), Some(end)) =
+                (self.uid_range_start, self.uid_range_end)
+            {
+                keys.push(format!("uidrange {start}-{end}"));
+            }
+            if let Some(v) = self.suppress_prefixlength.filter(|v| *v >= 0) {
+                keys.push(format!("suppress_prefixlength {v}"));
+            }
+
             let mut table_str = format!("table {DEFAULT_ROUTE_TABLE}");
             if let Some(table) = self.table {
                 table_str = format!("table {table}");
@@ -70,3 +107,10 @@ impl NmIpRouteRule {
         ret
     }
 }
+
+fn port_range_to_keyfile(start: u16, end: Option<u16>) -> String {
+    match end {
+        Some(end) if end != start => format!("{start}-{end}"),
+        _ => start.to_string(),
+    }
+}
@@ -0,0 +1,5 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::{NmSettingTun, ToKeyfile};
+
+impl ToKeyfile for NmSettingTun {}
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use super::super::{
+    NmError, NmSettingWireGuard, NmSettingWireGuardPeer, ToDbusValue, ToKeyfile,
+};
+
+impl ToKeyfile for NmSettingWireGuard {
+    fn to_keyfile(&self) -> Result<HashMap<String, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        for (k, v) in self.to_value()?.drain() {
+            // Peers are stored in `[wireguard-peer.<public_key>]` sections
+            if k != "peers" {
+                ret.insert(k.to_string(), v);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl NmSettingWireGuardPeer {
+    pub(crate) fn to_keyfile(&self) -> HashMap<String, zvariant::Value> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.preshared_key {
+            ret.insert("preshared-key".to_string(), zvariant::Value::new(v));
+            // Store the preshared key in keyfile instead of secret agent
+            ret.insert(
+                "preshared-key-flags".to_string(),
+                zvariant::Value::U32(0),
+            );
+        }
+        if let Some(v) = &self.endpoint {
+            ret.insert("endpoint".to_string(), zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.allowed_ips {
+            ret.insert("allowed-ips".to_string(), zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.persistent_keepalive {
+            ret.insert(
+                "persistent-keepalive".to_string(),
+                zvariant::Value::new(v),
+            );
+        }
+        ret
+    }
+}
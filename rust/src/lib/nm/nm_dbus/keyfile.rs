@@ -0,0 +1,1022 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// Parse NetworkManager keyfile(`*.nmconnection`) into `NmConnection`.
+// Plain properties are converted into the same D-Bus value types used by
+// NetworkManager daemon, so we could reuse the
+// `TryFrom<NmConnectionDbusOwnedValue>` of `NmConnection`. Properties stored
+// in keyfile specific format(e.g. `address1=192.0.2.1/24`) are parsed
+// separately and set to the `NmConnection` afterwards.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use super::{
+    connection::NmConnectionDbusOwnedValue, convert::mac_str_to_u8_array,
+    ErrorKind, NmConnection, NmError, NmIpRoute, NmIpRouteRule,
+    NmIpRouteRuleAction, NmSetting8021X, NmSettingBond,
+    NmSettingBridgeVlanRange, NmSettingIp, NmSettingOvsExtIds,
+    NmSettingOvsOtherConfig, NmSettingSriovVf, NmSettingSriovVfVlan,
+    NmSettingUser, NmSettingWireGuardPeer, NmVlanProtocol,
+};
+
+const DEFAULT_SEPARATOR: char = ';';
+const KEYFILE_DATA_PREFIX: &str = "data.";
+const KEYFILE_SRIOV_VF_PREFIX: &str = "vf.";
+const KEYFILE_WIREGUARD_PEER_PREFIX: &str = "wireguard-peer.";
+
+const AF_INET: i32 = 2;
+const AF_INET6: i32 = 10;
+
+const ADDR_GEN_MODE_EUI64: i32 = 0;
+const ADDR_GEN_MODE_STABLE_PRIVACY: i32 = 1;
+const ADDR_GEN_MODE_STABLE_DEFAULT_OR_EUI64: i32 = 2;
+const ADDR_GEN_MODE_STABLE_DEFAULT: i32 = 3;
+
+type KeyfileSection = (String, Vec<(String, String)>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyfileValueType {
+    Bool,
+    I32,
+    U32,
+    I64,
+    U64,
+    Mac,
+}
+
+impl NmConnection {
+    pub fn from_keyfile(content: &str) -> Result<Self, NmError> {
+        let sections = parse_keyfile_sections(content)?;
+
+        let mut dbus_value = NmConnectionDbusOwnedValue::new();
+        for (section_name, props) in sections.as_slice() {
+            // WireGuard peers are stored as array in `wireguard` setting
+            if section_name.starts_with(KEYFILE_WIREGUARD_PEER_PREFIX) {
+                continue;
+            }
+            let setting =
+                dbus_value.entry(section_name.to_string()).or_default();
+            for (key, value) in props {
+                if !is_keyfile_special_prop(section_name, key) {
+                    setting.insert(
+                        key.to_string(),
+                        keyfile_value_to_dbus(section_name, key, value)?,
+                    );
+                }
+            }
+        }
+        let mut nm_conn = NmConnection::try_from(dbus_value)?;
+
+        for (section_name, props) in sections.as_slice() {
+            apply_keyfile_special_props(&mut nm_conn, section_name, props)?;
+        }
+        Ok(nm_conn)
+    }
+}
+
+fn parse_keyfile_sections(
+    content: &str,
+) -> Result<Vec<KeyfileSection>, NmError> {
+    let mut ret: Vec<KeyfileSection> = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        // Like GKeyFile, only leading whitespace of line is ignored, trailing
+        // whitespace is part of the value.
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.trim_end().ends_with(']') {
+            let line = line.trim_end();
+            let name = &line[1..line.len() - 1];
+            ret.push((
+                keyfile_section_name_to_nm(name).to_string(),
+                Vec::new(),
+            ));
+            continue;
+        }
+        let (key, value) = match (line.split_once('='), ret.last_mut()) {
+            (Some((k, v)), Some(_)) => (k.trim(), v.trim_start()),
+            _ => {
+                let e = NmError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Invalid keyfile line {}: {line}, expecting \
+                        `[section]` or `key=value` in a section",
+                        line_no + 1
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        };
+        if let Some((section_name, props)) = ret.last_mut() {
+            // The connection type is using alias in keyfile
+            if section_name == "connection" && key == "type" {
+                props.push((
+                    key.to_string(),
+                    keyfile_section_name_to_nm(value).to_string(),
+                ));
+            } else if section_name == "connection" {
+                // NetworkManager 1.46+ write `controller` and `port-type`
+                let key = match key {
+                    "controller" => "master",
+                    "port-type" => "slave-type",
+                    _ => key,
+                };
+                props.push((key.to_string(), value.to_string()));
+            } else {
+                props.push((key.to_string(), value.to_string()));
+            }
+        }
+    }
+    Ok(ret)
+}
+
+fn keyfile_section_name_to_nm(name: &str) -> &str {
+    match name {
+        "ethernet" => "802-3-ethernet",
+        "wifi" => "802-11-wireless",
+        "wifi-security" => "802-11-wireless-security",
+        _ => name,
+    }
+}
+
+fn keyfile_value_type(section: &str, key: &str) -> Option<KeyfileValueType> {
+    match (section, key) {
+        ("connection", "autoconnect") => Some(KeyfileValueType::Bool),
+        ("connection", "autoconnect-slaves") | ("connection", "lldp") => {
+            Some(KeyfileValueType::I32)
+        }
+        ("connection", "mptcp-flags") => Some(KeyfileValueType::U32),
+        ("ipv4" | "ipv6", "ignore-auto-dns")
+        | ("ipv4" | "ipv6", "ignore-auto-routes")
+        | ("ipv4" | "ipv6", "never-default")
+        | ("ipv4" | "ipv6", "may-fail") => Some(KeyfileValueType::Bool),
+        ("ipv4" | "ipv6", "dns-priority")
+        | ("ipv4" | "ipv6", "dhcp-timeout")
+        | ("ipv4" | "ipv6", "ra-timeout")
+        | ("ipv4" | "ipv6", "addr-gen-mode") => Some(KeyfileValueType::I32),
+        ("ipv4" | "ipv6", "route-table") => Some(KeyfileValueType::U32),
+        ("ipv4" | "ipv6", "route-metric") => Some(KeyfileValueType::I64),
+        ("802-3-ethernet", "cloned-mac-address")
+        | ("bridge", "group-address") => Some(KeyfileValueType::Mac),
+        ("802-3-ethernet", "mtu") | ("802-3-ethernet", "speed") => {
+            Some(KeyfileValueType::U32)
+        }
+        ("802-3-ethernet", "accept-all-mac-addresses") => {
+            Some(KeyfileValueType::I32)
+        }
+        ("802-3-ethernet", "auto-negotiate") => Some(KeyfileValueType::Bool),
+        ("bridge", "multicast-querier")
+        | ("bridge", "multicast-query-use-ifaddr")
+        | ("bridge", "multicast-snooping")
+        | ("bridge", "stp")
+        | ("bridge", "vlan-filtering")
+        | ("bridge", "vlan-stats-enabled") => Some(KeyfileValueType::Bool),
+        ("bridge", "multicast-last-member-interval")
+        | ("bridge", "multicast-membership-interval")
+        | ("bridge", "multicast-querier-interval")
+        | ("bridge", "multicast-query-interval")
+        | ("bridge", "multicast-query-response-interval")
+        | ("bridge", "multicast-startup-query-interval") => {
+            Some(KeyfileValueType::U64)
+        }
+        ("bridge", "ageing-time")
+        | ("bridge", "forward-delay")
+        | ("bridge", "group-forward-mask")
+        | ("bridge", "hello-time")
+        | ("bridge", "max-age")
+        | ("bridge", "multicast-hash-max")
+        | ("bridge", "multicast-last-member-count")
+        | ("bridge", "multicast-startup-query-count")
+        | ("bridge", "priority")
+        | ("bridge", "vlan-default-pvid")
+        | ("bridge-port", "path-cost")
+        | ("bridge-port", "priority") => Some(KeyfileValueType::U32),
+        ("ethtool", k)
+            if k.starts_with("feature-") || k.starts_with("pause-") =>
+        {
+            Some(KeyfileValueType::Bool)
+        }
+        ("ethtool", k)
+            if k.starts_with("coalesce-") || k.starts_with("ring-") =>
+        {
+            Some(KeyfileValueType::U32)
+        }
+        ("hsr", "multicast-spec") => Some(KeyfileValueType::U32),
+        ("hsr", "prp") => Some(KeyfileValueType::Bool),
+        ("infiniband", "p-key") => Some(KeyfileValueType::I32),
+        ("infiniband" | "loopback", "mtu") => Some(KeyfileValueType::U32),
+        ("ip-tunnel", "mode")
+        | ("ip-tunnel", "ttl")
+        | ("ip-tunnel", "tos")
+        | ("ip-tunnel", "encapsulation-limit")
+        | ("ip-tunnel", "flow-label")
+        | ("ip-tunnel", "flags")
+        | ("ip-tunnel", "mtu") => Some(KeyfileValueType::U32),
+        ("ip-tunnel", "path-mtu-discovery") => Some(KeyfileValueType::Bool),
+        ("ipvlan", "mode") => Some(KeyfileValueType::U32),
+        ("ipvlan", "private") | ("ipvlan", "vepa") => {
+            Some(KeyfileValueType::Bool)
+        }
+        ("macsec", "mode") | ("macsec", "port") | ("macsec", "validation") => {
+            Some(KeyfileValueType::I32)
+        }
+        ("macsec", "encrypt") | ("macsec", "send-sci") => {
+            Some(KeyfileValueType::Bool)
+        }
+        ("macsec", "mka-cak-flags") => Some(KeyfileValueType::U32),
+        ("macvlan", "mode") => Some(KeyfileValueType::U32),
+        ("macvlan", "promiscuous") | ("macvlan", "tap") => {
+            Some(KeyfileValueType::Bool)
+        }
+        ("ovs-bridge", "stp-enable")
+        | ("ovs-bridge", "mcast-snooping-enable")
+        | ("ovs-bridge", "rstp-enable") => Some(KeyfileValueType::Bool),
+        ("ovs-port", "bond-updelay")
+        | ("ovs-port", "bond-downdelay")
+        | ("ovs-port", "tag")
+        | ("ovs-dpdk", "n-rxq")
+        | ("ovs-dpdk", "n-rxq-desc")
+        | ("ovs-dpdk", "n-txq-desc") => Some(KeyfileValueType::U32),
+        ("sriov", "autoprobe-drivers") => Some(KeyfileValueType::I32),
+        ("sriov", "total-vfs") => Some(KeyfileValueType::U32),
+        ("tun", "mode") => Some(KeyfileValueType::U32),
+        ("tun", "multi-queue") | ("tun", "pi") | ("tun", "vnet-hdr") => {
+            Some(KeyfileValueType::Bool)
+        }
+        ("vlan", "id") | ("vlan", "flags") | ("vrf", "table") => {
+            Some(KeyfileValueType::U32)
+        }
+        ("vxlan", "id")
+        | ("vxlan", "destination-port")
+        | ("vxlan", "ttl")
+        | ("vxlan", "tos")
+        | ("vxlan", "source-port-min")
+        | ("vxlan", "source-port-max")
+        | ("vxlan", "ageing")
+        | ("vxlan", "limit") => Some(KeyfileValueType::U32),
+        ("vxlan", "learning")
+        | ("vxlan", "proxy")
+        | ("vxlan", "rsc")
+        | ("vxlan", "l2-miss")
+        | ("vxlan", "l3-miss") => Some(KeyfileValueType::Bool),
+        ("wireguard", "listen-port")
+        | ("wireguard", "fwmark")
+        | ("wireguard", "mtu")
+        | ("wireguard", "private-key-flags") => Some(KeyfileValueType::U32),
+        ("wireguard", "peer-routes") => Some(KeyfileValueType::Bool),
+        _ => None,
+    }
+}
+
+fn keyfile_value_to_dbus(
+    section: &str,
+    key: &str,
+    value: &str,
+) -> Result<zvariant::OwnedValue, NmError> {
+    let value = keyfile_unescape(value);
+    let value = value.as_str();
+    let value_type = match keyfile_value_type(section, key) {
+        Some(t) => t,
+        None => return Ok(zvariant::Value::new(value).into()),
+    };
+    let dbus_value = match value_type {
+        KeyfileValueType::Bool => {
+            parse_keyfile_bool(value).map(zvariant::Value::new)
+        }
+        KeyfileValueType::I32 => match (section, key) {
+            ("connection", "lldp") => parse_keyfile_bool(value)
+                .map(i32::from)
+                .or_else(|| value.parse::<i32>().ok())
+                .map(zvariant::Value::new),
+            ("ipv6", "addr-gen-mode") => parse_addr_gen_mode(value)
+                .or_else(|| value.parse::<i32>().ok())
+                .map(zvariant::Value::new),
+            _ => value.parse::<i32>().ok().map(zvariant::Value::new),
+        },
+        KeyfileValueType::U32 => {
+            value.parse::<u32>().ok().map(zvariant::Value::new)
+        }
+        KeyfileValueType::I64 => {
+            value.parse::<i64>().ok().map(zvariant::Value::new)
+        }
+        KeyfileValueType::U64 => {
+            value.parse::<u64>().ok().map(zvariant::Value::new)
+        }
+        KeyfileValueType::Mac => {
+            let bytes = mac_str_to_u8_array(value);
+            if bytes.is_empty() {
+                None
+            } else {
+                Some(zvariant::Value::new(bytes))
+            }
+        }
+    };
+    match dbus_value {
+        Some(v) => Ok(v.into()),
+        None => {
+            let e = NmError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Invalid value for {section}.{key}: {value}, \
+                    expecting {value_type:?}"
+                ),
+            );
+            log::error!("{}", e);
+            Err(e)
+        }
+    }
+}
+
+fn parse_keyfile_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_addr_gen_mode(value: &str) -> Option<i32> {
+    match value {
+        "eui64" => Some(ADDR_GEN_MODE_EUI64),
+        "stable-privacy" => Some(ADDR_GEN_MODE_STABLE_PRIVACY),
+        "default-or-eui64" => Some(ADDR_GEN_MODE_STABLE_DEFAULT_OR_EUI64),
+        "default" => Some(ADDR_GEN_MODE_STABLE_DEFAULT),
+        _ => None,
+    }
+}
+
+fn is_keyfile_special_prop(section: &str, key: &str) -> bool {
+    match section {
+        "ipv4" | "ipv6" => {
+            ["dns", "dns-search", "addresses", "routes"].contains(&key)
+                || keyfile_indexed_key(key, "address").is_some()
+                || keyfile_indexed_key(key, "route").is_some()
+                || keyfile_indexed_key(key, "routing-rule").is_some()
+                || key
+                    .strip_suffix("_options")
+                    .and_then(|k| keyfile_indexed_key(k, "route"))
+                    .is_some()
+        }
+        "bond" | "user" | "ovs-external-ids" | "ovs-other-config" => true,
+        "bridge" => key == "vlans",
+        "bridge-port" => key == "vlans" || key == "hairpin-mode",
+        "802-1x" => {
+            ["eap", "private-key", "client-cert", "ca-cert"].contains(&key)
+        }
+        "sriov" => key.starts_with(KEYFILE_SRIOV_VF_PREFIX),
+        "ovs-port" => key == "trunks",
+        "vlan" => key == "ingress-priority-map" || key == "egress-priority-map",
+        _ => false,
+    }
+}
+
+// Return the index of keys like `address1` or `route2`.
+fn keyfile_indexed_key(key: &str, prefix: &str) -> Option<u32> {
+    key.strip_prefix(prefix).and_then(|i| i.parse::<u32>().ok())
+}
+
+fn apply_keyfile_special_props(
+    nm_conn: &mut NmConnection,
+    section: &str,
+    props: &[(String, String)],
+) -> Result<(), NmError> {
+    match section {
+        "ipv4" | "ipv6" => {
+            let is_ipv6 = section == "ipv6";
+            let nm_ip_set = if is_ipv6 {
+                nm_conn.ipv6.get_or_insert_with(Default::default)
+            } else {
+                nm_conn.ipv4.get_or_insert_with(Default::default)
+            };
+            apply_keyfile_ip_props(nm_ip_set, is_ipv6, props)?;
+        }
+        "bond" => {
+            let nm_set =
+                nm_conn.bond.get_or_insert_with(NmSettingBond::default);
+            for (k, v) in props {
+                // The `bond.interface-name` is deprecated
+                if k != "interface-name" {
+                    nm_set.options.insert(k.to_string(), keyfile_unescape(v));
+                }
+            }
+        }
+        "user" => {
+            let nm_set =
+                nm_conn.user.get_or_insert_with(NmSettingUser::default);
+            nm_set.data = Some(
+                props
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), keyfile_unescape(v)))
+                    .collect(),
+            );
+        }
+        "ovs-external-ids" => {
+            let nm_set = nm_conn
+                .ovs_ext_ids
+                .get_or_insert_with(NmSettingOvsExtIds::default);
+            nm_set.data = Some(keyfile_data_props(props));
+        }
+        "ovs-other-config" => {
+            let nm_set = nm_conn
+                .ovs_other_config
+                .get_or_insert_with(NmSettingOvsOtherConfig::default);
+            nm_set.data = Some(keyfile_data_props(props));
+        }
+        "bridge" => {
+            if let (Some(nm_set), Some(vlans)) =
+                (nm_conn.bridge.as_mut(), get_prop(props, "vlans"))
+            {
+                nm_set.vlans = Some(parse_keyfile_bridge_vlans(vlans)?);
+            }
+        }
+        "bridge-port" => {
+            if let Some(nm_set) = nm_conn.bridge_port.as_mut() {
+                if let Some(vlans) = get_prop(props, "vlans") {
+                    nm_set.vlans = Some(parse_keyfile_bridge_vlans(vlans)?);
+                }
+                if let Some(v) = get_prop(props, "hairpin-mode") {
+                    nm_set.hairpin_mode = parse_keyfile_bool(v);
+                }
+            }
+        }
+        "802-1x" => {
+            let nm_set = nm_conn
+                .ieee8021x
+                .get_or_insert_with(NmSetting8021X::default);
+            if let Some(v) = get_prop(props, "eap") {
+                nm_set.eap = Some(parse_keyfile_list(v));
+            }
+            if let Some(v) = get_prop(props, "private-key") {
+                nm_set.private_key = Some(keyfile_cert_to_glib_bytes(v));
+            }
+            if let Some(v) = get_prop(props, "client-cert") {
+                nm_set.client_cert = Some(keyfile_cert_to_glib_bytes(v));
+            }
+            if let Some(v) = get_prop(props, "ca-cert") {
+                nm_set.ca_cert = Some(keyfile_cert_to_glib_bytes(v));
+            }
+        }
+        "sriov" => {
+            let mut vfs = Vec::new();
+            for (k, v) in props {
+                if let Some(index) = k
+                    .strip_prefix(KEYFILE_SRIOV_VF_PREFIX)
+                    .and_then(|i| i.parse::<u32>().ok())
+                {
+                    vfs.push(parse_keyfile_sriov_vf(index, v)?);
+                }
+            }
+            if !vfs.is_empty() {
+                vfs.sort_unstable_by_key(|vf| vf.index);
+                if let Some(nm_set) = nm_conn.sriov.as_mut() {
+                    nm_set.vfs = Some(vfs);
+                }
+            }
+        }
+        _ if section.starts_with(KEYFILE_WIREGUARD_PEER_PREFIX) => {
+            let public_key = &section[KEYFILE_WIREGUARD_PEER_PREFIX.len()..];
+            nm_conn
+                .wireguard
+                .get_or_insert_with(Default::default)
+                .peers
+                .get_or_insert_with(Vec::new)
+                .push(parse_keyfile_wireguard_peer(public_key, props)?);
+        }
+        "vlan" => {
+            let nm_set = nm_conn.vlan.get_or_insert_with(Default::default);
+            if let Some(v) = get_prop(props, "ingress-priority-map") {
+                nm_set.ingress_priority_map = Some(parse_keyfile_list(v));
+            }
+            if let Some(v) = get_prop(props, "egress-priority-map") {
+                nm_set.egress_priority_map = Some(parse_keyfile_list(v));
+            }
+        }
+        "ovs-port" if get_prop(props, "trunks").is_some() => {
+            log::warn!(
+                "Ignoring unsupported ovs-port.trunks in keyfile of \
+                connection {}",
+                nm_conn.id().unwrap_or_default()
+            );
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn get_prop<'a>(props: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    props
+        .iter()
+        .rev()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn keyfile_data_props(props: &[(String, String)]) -> HashMap<String, String> {
+    props
+        .iter()
+        .filter_map(|(k, v)| {
+            k.strip_prefix(KEYFILE_DATA_PREFIX)
+                .map(|k| (k.to_string(), keyfile_unescape(v)))
+        })
+        .collect()
+}
+
+// Split the list on separators not escaped by `\;`, then unescape each item
+fn parse_keyfile_list(value: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    let mut item = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                item.push(c);
+                if let Some(next) = chars.next() {
+                    item.push(next);
+                }
+            }
+            DEFAULT_SEPARATOR => items.push(std::mem::take(&mut item)),
+            _ => item.push(c),
+        }
+    }
+    items.push(item);
+    items
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(keyfile_unescape)
+        .collect()
+}
+
+// Unescape the GKeyFile escape sequences: `\s`, `\n`, `\t`, `\r`, `\\`
+// and `\;`. Unknown escape sequences are kept as it is.
+fn keyfile_unescape(value: &str) -> String {
+    let mut ret = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => ret.push(' '),
+            Some('n') => ret.push('\n'),
+            Some('t') => ret.push('\t'),
+            Some('r') => ret.push('\r'),
+            Some('\\') => ret.push('\\'),
+            Some(DEFAULT_SEPARATOR) => ret.push(DEFAULT_SEPARATOR),
+            Some(other) => {
+                ret.push('\\');
+                ret.push(other);
+            }
+            None => ret.push('\\'),
+        }
+    }
+    ret
+}
+
+fn keyfile_cert_to_glib_bytes(value: &str) -> Vec<u8> {
+    let value = keyfile_unescape(value);
+    NmSetting8021X::file_path_to_glib_bytes(
+        value.strip_prefix("file://").unwrap_or(&value),
+    )
+}
+
+fn apply_keyfile_ip_props(
+    nm_ip_set: &mut NmSettingIp,
+    is_ipv6: bool,
+    props: &[(String, String)],
+) -> Result<(), NmError> {
+    let mut addresses: Vec<(u32, String)> = Vec::new();
+    let mut routes: Vec<(u32, NmIpRoute)> = Vec::new();
+    let mut route_opts: HashMap<u32, &str> = HashMap::new();
+    let mut rules: Vec<(u32, NmIpRouteRule)> = Vec::new();
+
+    for (key, value) in props {
+        if key == "dns" {
+            nm_ip_set.dns = Some(parse_keyfile_list(value));
+        } else if key == "dns-search" {
+            nm_ip_set.dns_search = Some(parse_keyfile_list(value));
+        } else if let Some(i) = keyfile_indexed_key(key, "address") {
+            let (addr, gateway) = parse_keyfile_address(value, is_ipv6)?;
+            if let Some(gw) = gateway {
+                nm_ip_set.gateway.get_or_insert(gw);
+            }
+            addresses.push((i, addr));
+        } else if let Some(i) = keyfile_indexed_key(key, "route") {
+            routes.push((i, parse_keyfile_route(value, is_ipv6)?));
+        } else if let Some(i) = key
+            .strip_suffix("_options")
+            .and_then(|k| keyfile_indexed_key(k, "route"))
+        {
+            route_opts.insert(i, value.as_str());
+        } else if let Some(i) = keyfile_indexed_key(key, "routing-rule") {
+            rules.push((i, parse_keyfile_route_rule(value, is_ipv6)?));
+        } else if key == "addresses" || key == "routes" {
+            log::warn!(
+                "Ignoring deprecated keyfile property {}, please use \
+                `address1` or `route1` instead",
+                key
+            );
+        }
+    }
+
+    for (i, route) in routes.iter_mut() {
+        if let Some(opts) = route_opts.get(i) {
+            apply_keyfile_route_options(route, opts)?;
+        }
+    }
+
+    addresses.sort_unstable_by_key(|(i, _)| *i);
+    routes.sort_unstable_by_key(|(i, _)| *i);
+    rules.sort_unstable_by_key(|(i, _)| *i);
+    nm_ip_set.addresses = addresses.into_iter().map(|(_, a)| a).collect();
+    nm_ip_set.routes = routes.into_iter().map(|(_, r)| r).collect();
+    nm_ip_set.route_rules = rules.into_iter().map(|(_, r)| r).collect();
+    Ok(())
+}
+
+fn invalid_keyfile_value(prop: &str, value: &str) -> NmError {
+    let e = NmError::new(
+        ErrorKind::InvalidArgument,
+        format!("Invalid keyfile {prop} value: {value}"),
+    );
+    log::error!("{}", e);
+    e
+}
+
+// Split `192.0.2.1/24` into `192.0.2.1` and `24`, the prefix is optional.
+fn parse_keyfile_ip_prefix(
+    value: &str,
+    is_ipv6: bool,
+) -> Result<(String, u32), NmError> {
+    let (ip, prefix) = match value.split_once('/') {
+        Some((ip, prefix)) => (
+            ip,
+            prefix
+                .parse::<u32>()
+                .map_err(|_| invalid_keyfile_value("IP prefix", value))?,
+        ),
+        None => (value, if is_ipv6 { 128 } else { 32 }),
+    };
+    let is_valid = if is_ipv6 {
+        ip.parse::<std::net::Ipv6Addr>().is_ok() && prefix <= 128
+    } else {
+        ip.parse::<std::net::Ipv4Addr>().is_ok() && prefix <= 32
+    };
+    if is_valid {
+        Ok((ip.to_string(), prefix))
+    } else {
+        Err(invalid_keyfile_value("IP address", value))
+    }
+}
+
+// Format: `address/prefix[,gateway]`
+fn parse_keyfile_address(
+    value: &str,
+    is_ipv6: bool,
+) -> Result<(String, Option<String>), NmError> {
+    let mut items = value.split(',').map(|s| s.trim());
+    let (ip, prefix) =
+        parse_keyfile_ip_prefix(items.next().unwrap_or_default(), is_ipv6)?;
+    let gateway = items.next().filter(|g| !g.is_empty()).map(String::from);
+    Ok((format!("{ip}/{prefix}"), gateway))
+}
+
+// Format: `dest/prefix[,next_hop[,metric]]`
+fn parse_keyfile_route(
+    value: &str,
+    is_ipv6: bool,
+) -> Result<NmIpRoute, NmError> {
+    let mut items = value.split(',').map(|s| s.trim());
+    let (dest, prefix) =
+        parse_keyfile_ip_prefix(items.next().unwrap_or_default(), is_ipv6)?;
+    let mut route = NmIpRoute::default();
+    route.dest = Some(dest);
+    route.prefix = Some(prefix);
+    if let Some(next_hop) = items.next().filter(|n| !n.is_empty()) {
+        route.next_hop = Some(next_hop.to_string());
+    }
+    if let Some(metric) = items.next().filter(|m| !m.is_empty()) {
+        route.metric = Some(
+            metric
+                .parse::<u32>()
+                .map_err(|_| invalid_keyfile_value("route metric", value))?,
+        );
+    }
+    Ok(route)
+}
+
+// Format: `key1=value1,key2=value2`
+fn apply_keyfile_route_options(
+    route: &mut NmIpRoute,
+    value: &str,
+) -> Result<(), NmError> {
+    for opt in value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        match opt.split_once('=') {
+            Some(("table", v)) => {
+                route.table = Some(v.parse::<u32>().map_err(|_| {
+                    invalid_keyfile_value("route table", value)
+                })?);
+            }
+            Some(("weight", v)) => {
+                route.weight = Some(v.parse::<u32>().map_err(|_| {
+                    invalid_keyfile_value("route weight", value)
+                })?);
+            }
+            Some(("type", v)) => {
+                route.route_type = Some(v.to_string());
+            }
+            Some(("src", v)) => {
+                route.src = Some(v.to_string());
+            }
+            Some((key @ ("mtu" | "advmss" | "initcwnd" | "initrwnd"), v)) => {
+                let v = Some(v.parse::<u32>().map_err(|_| {
+                    invalid_keyfile_value(&format!("route {key}"), value)
+                })?);
+                match key {
+                    "mtu" => route.mtu = v,
+                    "advmss" => route.advmss = v,
+                    "initcwnd" => route.initcwnd = v,
+                    _ => route.initrwnd = v,
+                }
+            }
+            Some((key @ ("onlink" | "quickack"), v)) => {
+                let v = Some(parse_keyfile_bool(v).ok_or_else(|| {
+                    invalid_keyfile_value(&format!("route {key}"), value)
+                })?);
+                if key == "onlink" {
+                    route.onlink = v;
+                } else {
+                    route.quickack = v;
+                }
+            }
+            Some(("scope", v)) => {
+                route.scope = Some(v.parse::<u8>().map_err(|_| {
+                    invalid_keyfile_value("route scope", value)
+                })?);
+            }
+            _ => {
+                log::warn!("Ignoring unsupported route option {}", opt);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Format: `priority 100 from 192.0.2.0/24 to 198.51.100.0/24 table 100`
+fn parse_keyfile_route_rule(
+    value: &str,
+    is_ipv6: bool,
+) -> Result<NmIpRouteRule, NmError> {
+    let mut rule = NmIpRouteRule::default();
+    rule.family = Some(if is_ipv6 { AF_INET6 } else { AF_INET });
+    let mut words = value.split_whitespace();
+    while let Some(word) = words.next() {
+        // The `not` is the only keyword without argument
+        if word == "not" {
+            rule.invert = Some(true);
+            continue;
+        }
+        let arg = if let Some(a) = words.next() {
+            a
+        } else {
+            return Err(invalid_keyfile_value("routing rule", value));
+        };
+        match word {
+            "priority" => {
+                rule.priority = Some(arg.parse::<u32>().map_err(|_| {
+                    invalid_keyfile_value("routing rule", value)
+                })?);
+            }
+            "from" | "to" => {
+                let (ip, prefix) = parse_keyfile_ip_prefix(arg, is_ipv6)?;
+                // Rule without from and to is stored with `from 0.0.0.0/0`
+                if prefix != 0 {
+                    let prefix = u8::try_from(prefix).map_err(|_| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?;
+                    if word == "from" {
+                        rule.from = Some(ip);
+                        rule.from_len = Some(prefix);
+                    } else {
+                        rule.to = Some(ip);
+                        rule.to_len = Some(prefix);
+                    }
+                }
+            }
+            "table" => {
+                rule.table = Some(arg.parse::<u32>().map_err(|_| {
+                    invalid_keyfile_value("routing rule", value)
+                })?);
+            }
+            "fwmark" => {
+                let (mark, mask) = match arg.split_once('/') {
+                    Some((mark, mask)) => (mark, Some(mask)),
+                    None => (arg, None),
+                };
+                rule.fw_mark =
+                    Some(parse_keyfile_u32(mark).ok_or_else(|| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?);
+                if let Some(mask) = mask {
+                    rule.fw_mask =
+                        Some(parse_keyfile_u32(mask).ok_or_else(|| {
+                            invalid_keyfile_value("routing rule", value)
+                        })?);
+                }
+            }
+            "iif" => {
+                rule.iifname = Some(arg.to_string());
+            }
+            "oif" => {
+                rule.oifname = Some(arg.to_string());
+            }
+            "uidrange" => {
+                let (start, end) = arg.split_once('-').unwrap_or((arg, arg));
+                rule.uid_range_start =
+                    Some(start.parse::<u32>().map_err(|_| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?);
+                rule.uid_range_end =
+                    Some(end.parse::<u32>().map_err(|_| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?);
+            }
+            "ipproto" => {
+                rule.ipproto = Some(arg.parse::<u8>().map_err(|_| {
+                    invalid_keyfile_value("routing rule", value)
+                })?);
+            }
+            "sport" | "dport" => {
+                let (start, end) =
+                    parse_keyfile_port_range(arg).ok_or_else(|| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?;
+                if word == "sport" {
+                    rule.sport_start = Some(start);
+                    rule.sport_end = Some(end);
+                } else {
+                    rule.dport_start = Some(start);
+                    rule.dport_end = Some(end);
+                }
+            }
+            "suppress_prefixlength" => {
+                rule.suppress_prefixlength =
+                    Some(arg.parse::<i32>().map_err(|_| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?);
+            }
+            "tos" => {
+                rule.tos = Some(
+                    parse_keyfile_u32(arg)
+                        .and_then(|t| u8::try_from(t).ok())
+                        .ok_or_else(|| {
+                        invalid_keyfile_value("routing rule", value)
+                    })?,
+                );
+            }
+            "type" => {
+                rule.action = Some(match arg {
+                    "blackhole" => NmIpRouteRuleAction::Blackhole,
+                    "unreachable" => NmIpRouteRuleAction::Unreachable,
+                    "prohibit" => NmIpRouteRuleAction::Prohibit,
+                    _ => {
+                        return Err(invalid_keyfile_value(
+                            "routing rule",
+                            value,
+                        ));
+                    }
+                });
+            }
+            _ => {
+                log::warn!(
+                    "Ignoring unsupported routing rule property {} {}",
+                    word,
+                    arg
+                );
+            }
+        }
+    }
+    Ok(rule)
+}
+
+// Format: `80` or `1000-2000`
+fn parse_keyfile_port_range(value: &str) -> Option<(u16, u16)> {
+    let (start, end) = value.split_once('-').unwrap_or((value, value));
+    Some((start.parse::<u16>().ok()?, end.parse::<u16>().ok()?))
+}
+
+fn parse_keyfile_u32(value: &str) -> Option<u32> {
+    if let Some(hex) = value.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16).ok()
+    } else {
+        value.parse::<u32>().ok()
+    }
+}
+
+// Format: `100 pvid untagged,200-300`
+fn parse_keyfile_bridge_vlans(
+    value: &str,
+) -> Result<Vec<NmSettingBridgeVlanRange>, NmError> {
+    let mut ret = Vec::new();
+    for vlan_str in value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty())
+    {
+        let mut words = vlan_str.split_whitespace();
+        let range = words.next().unwrap_or_default();
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        let mut vlan_range = NmSettingBridgeVlanRange::default();
+        vlan_range.vid_start = start
+            .parse::<u16>()
+            .map_err(|_| invalid_keyfile_value("bridge vlans", value))?;
+        vlan_range.vid_end = end
+            .parse::<u16>()
+            .map_err(|_| invalid_keyfile_value("bridge vlans", value))?;
+        for flag in words {
+            match flag {
+                "pvid" => vlan_range.pvid = true,
+                "untagged" => vlan_range.untagged = true,
+                _ => {
+                    return Err(invalid_keyfile_value("bridge vlans", value));
+                }
+            }
+        }
+        ret.push(vlan_range);
+    }
+    Ok(ret)
+}
+
+// Format: `mac=00:11:22:33:44:55 trust=true vlans=100.0.q;200.0.ad`
+fn parse_keyfile_sriov_vf(
+    index: u32,
+    value: &str,
+) -> Result<NmSettingSriovVf, NmError> {
+    let mut vf = NmSettingSriovVf::default();
+    vf.index = Some(index);
+    for item in value.split_whitespace() {
+        let (k, v) = item
+            .split_once('=')
+            .ok_or_else(|| invalid_keyfile_value("SR-IOV VF", value))?;
+        match k {
+            "mac" => vf.mac = Some(v.to_string()),
+            "spoof-check" => vf.spoof_check = parse_keyfile_bool(v),
+            "trust" => vf.trust = parse_keyfile_bool(v),
+            "min-tx-rate" => vf.min_tx_rate = v.parse::<u32>().ok(),
+            "max-tx-rate" => vf.max_tx_rate = v.parse::<u32>().ok(),
+            "vlans" => {
+                let mut vlans = Vec::new();
+                for vlan_str in parse_keyfile_list(v) {
+                    vlans.push(parse_keyfile_sriov_vf_vlan(&vlan_str)?);
+                }
+                vf.vlans = Some(vlans);
+            }
+            _ => {
+                log::warn!("Ignoring unsupported SR-IOV VF property {}", item);
+            }
+        }
+    }
+    Ok(vf)
+}
+
+// Format: `id[.qos[.protocol]]`
+fn parse_keyfile_sriov_vf_vlan(
+    value: &str,
+) -> Result<NmSettingSriovVfVlan, NmError> {
+    let mut items = value.split('.');
+    let mut vlan = NmSettingSriovVfVlan::default();
+    vlan.id = items
+        .next()
+        .and_then(|i| i.parse::<u32>().ok())
+        .ok_or_else(|| invalid_keyfile_value("SR-IOV VF VLAN", value))?;
+    if let Some(qos) = items.next() {
+        vlan.qos = qos
+            .parse::<u32>()
+            .map_err(|_| invalid_keyfile_value("SR-IOV VF VLAN", value))?;
+    }
+    vlan.protocol = match items.next() {
+        Some("ad") => NmVlanProtocol::Dot1Ad,
+        Some("q") | None => NmVlanProtocol::Dot1Q,
+        Some(_) => {
+            return Err(invalid_keyfile_value("SR-IOV VF VLAN", value));
+        }
+    };
+    Ok(vlan)
+}
+
+fn parse_keyfile_wireguard_peer(
+    public_key: &str,
+    props: &[(String, String)],
+) -> Result<NmSettingWireGuardPeer, NmError> {
+    let mut peer = NmSettingWireGuardPeer::default();
+    peer.public_key = Some(public_key.to_string());
+    peer.preshared_key = get_prop(props, "preshared-key").map(String::from);
+    peer.endpoint = get_prop(props, "endpoint").map(String::from);
+    peer.allowed_ips = get_prop(props, "allowed-ips").map(parse_keyfile_list);
+    if let Some(v) = get_prop(props, "persistent-keepalive") {
+        peer.persistent_keepalive = Some(v.parse::<u32>().map_err(|_| {
+            invalid_keyfile_value("WireGuard persistent-keepalive", v)
+        })?);
+    }
+    Ok(peer)
+}
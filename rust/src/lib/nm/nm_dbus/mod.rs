@@ -12,6 +12,8 @@ mod device;
 mod dns;
 mod error;
 #[cfg(feature = "query_apply")]
+mod keyfile;
+#[cfg(feature = "query_apply")]
 mod lldp;
 #[cfg(feature = "query_apply")]
 mod nm_api;
@@ -28,12 +30,22 @@ pub use self::connection::{
     NmConnection, NmIpRoute, NmIpRouteRule, NmIpRouteRuleAction, NmRange,
     NmSetting8021X, NmSettingBond, NmSettingBridge, NmSettingBridgePort,
     NmSettingBridgeVlanRange, NmSettingConnection, NmSettingEthtool,
-    NmSettingInfiniBand, NmSettingIp, NmSettingIpMethod, NmSettingLoopback,
-    NmSettingMacVlan, NmSettingOvsBridge, NmSettingOvsDpdk, NmSettingOvsExtIds,
+    NmSettingHsr, NmSettingInfiniBand, NmSettingIp, NmSettingIpMethod,
+    NmSettingIpTunnel, NmSettingIpVlan, NmSettingLoopback, NmSettingMacVlan,
+    NmSettingMacsec, NmSettingOvsBridge, NmSettingOvsDpdk, NmSettingOvsExtIds,
     NmSettingOvsIface, NmSettingOvsOtherConfig, NmSettingOvsPatch,
     NmSettingOvsPort, NmSettingSriov, NmSettingSriovVf, NmSettingSriovVfVlan,
-    NmSettingUser, NmSettingVeth, NmSettingVlan, NmSettingVrf, NmSettingVxlan,
-    NmSettingWired, NmSettingsConnectionFlag, NmVlanProtocol,
+    NmSettingTun, NmSettingUser, NmSettingVeth, NmSettingVlan, NmSettingVrf,
+    NmSettingVxlan, NmSettingWireGuard, NmSettingWireGuardPeer, NmSettingWired,
+    NmSettingsConnectionFlag, NmVlanProtocol, NM_IP_TUNNEL_MODE_GRE,
+    NM_IP_TUNNEL_MODE_GRETAP, NM_IP_TUNNEL_MODE_IP6GRE,
+    NM_IP_TUNNEL_MODE_IP6GRETAP, NM_IP_TUNNEL_MODE_IP6IP6,
+    NM_IP_TUNNEL_MODE_IPIP, NM_IP_TUNNEL_MODE_IPIP6, NM_IP_TUNNEL_MODE_SIT,
+    NM_SETTING_IPVLAN_MODE_L2, NM_SETTING_IPVLAN_MODE_L3,
+    NM_SETTING_IPVLAN_MODE_L3S, NM_SETTING_MACSEC_MODE_EAP,
+    NM_SETTING_MACSEC_MODE_PSK, NM_SETTING_TUN_MODE_TAP,
+    NM_SETTING_TUN_MODE_TUN, NM_VLAN_FLAG_GVRP, NM_VLAN_FLAG_LOOSE_BINDING,
+    NM_VLAN_FLAG_MVRP, NM_VLAN_FLAG_REORDER_HEADERS,
 };
 pub use self::device::{NmDevice, NmDeviceState, NmDeviceStateReason};
 #[cfg(feature = "query_apply")]
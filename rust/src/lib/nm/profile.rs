@@ -6,7 +6,10 @@ use super::settings::{
     use_uuid_for_controller_reference, use_uuid_for_parent_reference,
 };
 
-use crate::{InterfaceType, MergedInterface, MergedNetworkState, NmstateError};
+use crate::{
+    ErrorKind, Interface, InterfaceType, MergedInterface, MergedNetworkState,
+    NmstateError, RouteProtocol,
+};
 
 #[allow(dead_code)]
 pub(crate) struct PerparedNmConnections {
@@ -126,3 +129,135 @@ pub(crate) fn perpare_nm_conns(
         to_deactivate: nm_conns_to_deactivate,
     })
 }
+
+// NetworkManager has no Geneve or XFRM support, user should use kernel mode
+// instead.
+const NM_UNSUPPORTED_IFACE_TYPES: [InterfaceType; 2] =
+    [InterfaceType::Geneve, InterfaceType::Xfrm];
+
+// NetworkManager has no setting for nexthop object. Applying nexthops and the
+// routes using them to kernel directly cannot be reverted by NetworkManager
+// checkpoint, hence they are only supported in kernel mode.
+pub(crate) fn check_nm_nexthop_support(
+    merged_state: &MergedNetworkState,
+) -> Result<(), NmstateError> {
+    if !merged_state.nexthops.is_changed()
+        && !merged_state.routes.nexthop_route_changed
+    {
+        return Ok(());
+    }
+    let e = NmstateError::new(
+        ErrorKind::NotSupportedError,
+        "NetworkManager cannot persist or rollback nexthops and routes using \
+        nexthop-id, please use kernel mode instead"
+            .to_string(),
+    );
+    log::error!("{}", e);
+    Err(e)
+}
+
+// NetworkManager has no setting for static FDB entries. Applying them to
+// kernel directly cannot be reverted by NetworkManager checkpoint, hence only
+// unchanged `fdb-remotes` are allowed.
+pub(crate) fn check_nm_vxlan_fdb_support(
+    merged_state: &MergedNetworkState,
+) -> Result<(), NmstateError> {
+    for iface in merged_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .filter(|i| i.is_changed() && !i.merged.is_absent())
+    {
+        let des_remotes =
+            if let Some(Interface::Vxlan(des_iface)) = iface.desired.as_ref() {
+                if let Some(r) = des_iface
+                    .vxlan
+                    .as_ref()
+                    .and_then(|c| c.fdb_remotes.as_ref())
+                {
+                    r
+                } else {
+                    continue;
+                }
+            } else {
+                continue;
+            };
+        let mut des_remotes = des_remotes.clone();
+        let mut cur_remotes = match iface.current.as_ref() {
+            Some(Interface::Vxlan(cur_iface)) => cur_iface
+                .vxlan
+                .as_ref()
+                .and_then(|c| c.fdb_remotes.clone())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        des_remotes.sort_unstable();
+        cur_remotes.sort_unstable();
+        if des_remotes != cur_remotes {
+            let e = NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "NetworkManager cannot persist or rollback the \
+                    fdb-remotes of VXLAN interface {}, please use kernel \
+                    mode instead",
+                    iface.merged.name()
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+// NetworkManager always installs routes with protocol static.
+pub(crate) fn check_nm_route_protocol_support(
+    merged_state: &MergedNetworkState,
+) -> Result<(), NmstateError> {
+    if let Some(rt) = merged_state
+        .routes
+        .desired
+        .config
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .find(|rt| {
+            !rt.is_absent()
+                && rt.protocol.is_some()
+                && rt.protocol != Some(RouteProtocol::Static)
+        })
+    {
+        let e = NmstateError::new(
+            ErrorKind::NotSupportedError,
+            format!(
+                "NetworkManager only supports route protocol static, \
+                please use kernel mode instead: {rt}"
+            ),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    Ok(())
+}
+
+pub(crate) fn check_nm_unsupported_ifaces(
+    merged_state: &MergedNetworkState,
+) -> Result<(), NmstateError> {
+    if let Some(iface) = merged_state.interfaces.iter().find(|i| {
+        i.is_changed()
+            && NM_UNSUPPORTED_IFACE_TYPES.contains(&i.merged.iface_type())
+    }) {
+        let e = NmstateError::new(
+            ErrorKind::NotSupportedError,
+            format!(
+                "{} interface {} is not supported by NetworkManager \
+                backend, please use kernel mode instead",
+                iface.merged.iface_type(),
+                iface.merged.name()
+            ),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    Ok(())
+}
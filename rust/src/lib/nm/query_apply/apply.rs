@@ -4,28 +4,24 @@ use std::collections::HashSet;
 
 use super::super::{
     device::create_index_for_nm_devs,
-    dns::{cur_dns_ifaces_still_valid_for_dns, store_dns_config_to_iface},
     error::nm_error_to_nmstate,
-    nm_dbus::{NmApi, NmConnection},
-    profile::{perpare_nm_conns, PerparedNmConnections},
+    nm_dbus::{NmApi, NmConnection, NmDevice},
     query_apply::{
         activate_nm_profiles, create_index_for_nm_conns_by_name_type,
-        deactivate_nm_profiles, delete_exist_profiles, delete_orphan_ovs_ports,
+        deactivate_nm_profiles,
         dns::{purge_global_dns_config, store_dns_config_via_global_api},
-        is_mptcp_flags_changed, is_mptcp_supported, is_route_removed,
-        is_veth_peer_changed, is_vlan_changed, is_vrf_table_id_changed,
-        is_vxlan_changed, save_nm_profiles,
+        is_ip_tunnel_changed, is_macsec_changed, is_mptcp_flags_changed,
+        is_route_removed, is_veth_peer_changed, is_vlan_changed,
+        is_vrf_table_id_changed, is_vxlan_changed,
+        plan::{query_nm_apply_plan, NmApplyPlan},
+        profile::delete_profiles,
+        save_nm_profiles,
     },
-    route::store_route_config,
-    route_rule::store_route_rule_config,
     settings::{iface_type_to_nm, NM_SETTING_OVS_PORT_SETTING_NAME},
 };
 
-use crate::{InterfaceType, MergedNetworkState, NmstateError};
+use crate::{Interface, InterfaceType, MergedNetworkState, NmstateError};
 
-// There is plan to simply the `add_net_state`, `chg_net_state`, `del_net_state`
-// `cur_net_state`, `des_net_state` into single struct. Suppress the clippy
-// warning for now
 pub(crate) fn nm_apply(
     merged_state: &MergedNetworkState,
     checkpoint: &str,
@@ -35,127 +31,66 @@ pub(crate) fn nm_apply(
     nm_api.set_checkpoint(checkpoint, timeout);
     nm_api.set_checkpoint_auto_refresh(true);
 
-    if !merged_state.memory_only {
-        delete_ifaces(&mut nm_api, merged_state)?;
-    }
+    let plan = query_nm_apply_plan(&mut nm_api, merged_state)?;
+    execute_nm_apply_plan(&mut nm_api, &plan)
+}
 
-    if let Some(hostname) = merged_state
-        .hostname
-        .desired
-        .as_ref()
-        .and_then(|c| c.config.as_ref())
-    {
-        if merged_state.memory_only {
-            log::debug!(
-                "NM: Cannot change configure hostname in memory only mode, \
-                ignoring"
-            );
-        } else {
-            nm_api.hostname_set(hostname).map_err(nm_error_to_nmstate)?;
+fn execute_nm_apply_plan(
+    nm_api: &mut NmApi,
+    plan: &NmApplyPlan,
+) -> Result<(), NmstateError> {
+    for uuid in plan.absent_uuids.iter() {
+        log::info!("Deleting NM connection {uuid}");
+        nm_api
+            .connection_delete(uuid)
+            .map_err(nm_error_to_nmstate)?;
+    }
+    for nm_dev in plan.devs_to_delete.iter() {
+        log::info!(
+            "Deleting interface {}/{}: {}",
+            &nm_dev.name,
+            &nm_dev.iface_type,
+            &nm_dev.obj_path
+        );
+        // There might be an race with on-going profile/connection
+        // deletion, verification will raise error for it later.
+        if let Err(e) = nm_api.device_delete(&nm_dev.obj_path) {
+            log::debug!("Failed to delete interface {:?}", e);
         }
     }
 
-    let mptcp_supported = is_mptcp_supported(&nm_api);
-
-    let exist_nm_conns =
-        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
-    let nm_acs = nm_api
-        .active_connections_get()
-        .map_err(nm_error_to_nmstate)?;
-    let nm_devs = nm_api.devices_get().map_err(nm_error_to_nmstate)?;
-
-    let mut merged_state = merged_state.clone();
-
-    store_route_config(&mut merged_state)?;
-
-    store_route_rule_config(&mut merged_state)?;
-
-    if merged_state.dns.is_changed()
-        || !cur_dns_ifaces_still_valid_for_dns(&merged_state.interfaces)
-    {
-        purge_global_dns_config(&mut nm_api)?;
+    if let Some(hostname) = plan.hostname.as_deref() {
+        nm_api.hostname_set(hostname).map_err(nm_error_to_nmstate)?;
     }
 
-    if let Err(e) =
-        store_dns_config_to_iface(&mut merged_state, &nm_acs, &nm_devs)
-    {
-        log::warn!(
-            "Cannot store DNS to NetworkManager interface connection: {e}"
-        );
-        store_dns_config_via_global_api(
-            &mut nm_api,
-            merged_state.dns.servers.as_slice(),
-            merged_state.dns.searches.as_slice(),
-        )?;
+    if plan.purge_global_dns {
+        purge_global_dns_config(nm_api)?;
+    }
+    if let Some((servers, searches)) = plan.global_dns.as_ref() {
+        store_dns_config_via_global_api(nm_api, servers, searches)?;
     }
 
-    let PerparedNmConnections {
-        to_store: nm_conns_to_store,
-        to_activate: nm_conns_to_activate,
-        to_deactivate: nm_conns_to_deactivate,
-    } = perpare_nm_conns(
-        &merged_state,
-        exist_nm_conns.as_slice(),
-        nm_acs.as_slice(),
-        mptcp_supported,
-        false,
-    )?;
-
-    let nm_ac_uuids: Vec<&str> =
-        nm_acs.iter().map(|nm_ac| &nm_ac.uuid as &str).collect();
-    let activated_nm_conns: Vec<&NmConnection> = exist_nm_conns
-        .iter()
-        .filter(|c| {
-            if let Some(uuid) = c.uuid() {
-                nm_ac_uuids.contains(&uuid)
-            } else {
-                false
-            }
-        })
-        .collect();
-    let nm_conns_to_deactivate_first = gen_nm_conn_need_to_deactivate_first(
-        nm_conns_to_activate.as_slice(),
-        activated_nm_conns.as_slice(),
-    );
-    deactivate_nm_profiles(
-        &mut nm_api,
-        nm_conns_to_deactivate_first.as_slice(),
-    )?;
+    deactivate_nm_profiles(nm_api, plan.to_deactivate_first.as_slice())?;
 
-    save_nm_profiles(
-        &mut nm_api,
-        nm_conns_to_store.as_slice(),
-        merged_state.memory_only,
-    )?;
-    if !merged_state.memory_only {
-        delete_exist_profiles(
-            &mut nm_api,
-            &exist_nm_conns,
-            &nm_conns_to_store,
-        )?;
-        delete_orphan_ovs_ports(
-            &mut nm_api,
-            &merged_state.interfaces,
-            &exist_nm_conns,
-            &nm_conns_to_activate,
-        )?;
-    }
+    save_nm_profiles(nm_api, plan.to_store.as_slice(), plan.memory_only)?;
+    let uuids_to_delete: Vec<&str> =
+        plan.uuids_to_delete.iter().map(|u| u.as_str()).collect();
+    delete_profiles(nm_api, uuids_to_delete.as_slice())?;
 
-    activate_nm_profiles(&mut nm_api, nm_conns_to_activate.as_slice())?;
+    activate_nm_profiles(nm_api, plan.to_activate.as_slice())?;
 
-    deactivate_nm_profiles(&mut nm_api, nm_conns_to_deactivate.as_slice())?;
+    deactivate_nm_profiles(nm_api, plan.to_deactivate.as_slice())?;
 
     Ok(())
 }
 
-fn delete_ifaces(
-    nm_api: &mut NmApi,
+// Return UUIDs of NM connections should be deleted for absent interfaces
+pub(crate) fn gen_absent_iface_nm_conn_uuids<'a>(
     merged_state: &MergedNetworkState,
-) -> Result<(), NmstateError> {
-    let all_nm_conns = nm_api.connections_get().map_err(nm_error_to_nmstate)?;
-
+    all_nm_conns: &'a [NmConnection],
+) -> Result<HashSet<&'a str>, NmstateError> {
     let nm_conns_name_type_index =
-        create_index_for_nm_conns_by_name_type(&all_nm_conns);
+        create_index_for_nm_conns_by_name_type(all_nm_conns);
     let mut uuids_to_delete: HashSet<&str> = HashSet::new();
 
     for iface in merged_state
@@ -170,7 +105,6 @@ fn delete_ifaces(
             if iface.iface_type() == InterfaceType::Unknown {
                 Some(
                     all_nm_conns
-                        .as_slice()
                         .iter()
                         .filter(|c| c.iface_name() == Some(iface.name()))
                         .collect(),
@@ -212,26 +146,17 @@ fn delete_ifaces(
             }
         }
     }
-
-    for uuid in &uuids_to_delete {
-        nm_api
-            .connection_delete(uuid)
-            .map_err(nm_error_to_nmstate)?;
-    }
-
-    delete_orphan_ports(nm_api, &uuids_to_delete)?;
-    delete_remain_virtual_interface_as_desired(nm_api, merged_state)?;
-    Ok(())
+    Ok(uuids_to_delete)
 }
 
-fn delete_remain_virtual_interface_as_desired(
-    nm_api: &mut NmApi,
-    merged_state: &MergedNetworkState,
-) -> Result<(), NmstateError> {
-    let nm_devs = nm_api.devices_get().map_err(nm_error_to_nmstate)?;
-    let nm_devs_indexed = create_index_for_nm_devs(&nm_devs);
-    // Interfaces created by non-NM tools will not be deleted by connection
-    // deletion, remove manually.
+// Interfaces created by non-NM tools will not be deleted by connection
+// deletion, remove manually.
+pub(crate) fn gen_virtual_ifaces_to_delete<'a, 'b>(
+    merged_state: &'a MergedNetworkState,
+    nm_devs: &'b [NmDevice],
+) -> Result<Vec<(&'a Interface, &'b NmDevice)>, NmstateError> {
+    let nm_devs_indexed = create_index_for_nm_devs(nm_devs);
+    let mut ret = Vec::new();
     for iface in merged_state
         .interfaces
         .kernel_ifaces
@@ -246,37 +171,31 @@ fn delete_remain_virtual_interface_as_desired(
                 iface.name().to_string(),
                 iface_type_to_nm(&iface.iface_type())?,
             )) {
-                log::info!(
-                    "Deleting interface {}/{}: {}",
-                    &iface.name(),
-                    &iface.iface_type(),
-                    &nm_dev.obj_path
-                );
-                // There might be an race with on-going profile/connection
-                // deletion, verification will raise error for it later.
-                if let Err(e) = nm_api.device_delete(&nm_dev.obj_path) {
-                    log::debug!("Failed to delete interface {:?}", e);
-                }
+                ret.push((iface, *nm_dev));
             }
         }
     }
-    Ok(())
+    Ok(ret)
 }
 
 // If any connection still referring to deleted UUID, we should delete it also
-fn delete_orphan_ports(
-    nm_api: &mut NmApi,
+pub(crate) fn gen_orphan_port_uuids<'a>(
+    all_nm_conns: &'a [NmConnection],
     uuids_deleted: &HashSet<&str>,
-) -> Result<(), NmstateError> {
+) -> Vec<&'a str> {
     let mut uuids_to_delete = Vec::new();
-    let all_nm_conns = nm_api.connections_get().map_err(nm_error_to_nmstate)?;
-    for nm_conn in &all_nm_conns {
+    for nm_conn in all_nm_conns {
         if nm_conn.iface_type() != Some(NM_SETTING_OVS_PORT_SETTING_NAME) {
             continue;
         }
         if let Some(ctrl_uuid) = nm_conn.controller() {
             if uuids_deleted.contains(ctrl_uuid) {
                 if let Some(uuid) = nm_conn.uuid() {
+                    // The deleted profile might still show up in the cached
+                    // connection list
+                    if uuids_deleted.contains(uuid) {
+                        continue;
+                    }
                     log::info!(
                         "Deleting NM orphan profile {}/{}: {}",
                         nm_conn.iface_name().unwrap_or(""),
@@ -288,12 +207,7 @@ fn delete_orphan_ports(
             }
         }
     }
-    for uuid in &uuids_to_delete {
-        nm_api
-            .connection_delete(uuid)
-            .map_err(nm_error_to_nmstate)?;
-    }
-    Ok(())
+    uuids_to_delete
 }
 
 // * NM has problem on remove routes, we need to deactivate it first
@@ -301,8 +215,10 @@ fn delete_orphan_ports(
 // * NM cannot change VRF table ID, so we deactivate first
 // * VLAN config changed.
 // * Veth peer changed.
+// * IP tunnel config changed.
+// * MACsec config changed.
 // * NM cannot reapply changes to MPTCP flags.
-fn gen_nm_conn_need_to_deactivate_first(
+pub(crate) fn gen_nm_conn_need_to_deactivate_first(
     nm_conns_to_activate: &[NmConnection],
     activated_nm_conns: &[&NmConnection],
 ) -> Vec<NmConnection> {
@@ -323,6 +239,8 @@ fn gen_nm_conn_need_to_deactivate_first(
                     || is_vlan_changed(nm_conn, activated_nm_con)
                     || is_vxlan_changed(nm_conn, activated_nm_con)
                     || is_veth_peer_changed(nm_conn, activated_nm_con)
+                    || is_ip_tunnel_changed(nm_conn, activated_nm_con)
+                    || is_macsec_changed(nm_conn, activated_nm_con)
                     || is_mptcp_flags_changed(nm_conn, activated_nm_con)
                 {
                     ret.push((*activated_nm_con).clone());
@@ -1,21 +1,43 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    nm::nm_dbus::NmDevice,
+    nm::nm_dbus::{
+        NmDevice, NM_IP_TUNNEL_MODE_GRE, NM_IP_TUNNEL_MODE_GRETAP,
+        NM_IP_TUNNEL_MODE_IP6GRE, NM_IP_TUNNEL_MODE_IP6GRETAP,
+        NM_IP_TUNNEL_MODE_IP6IP6, NM_IP_TUNNEL_MODE_IPIP,
+        NM_IP_TUNNEL_MODE_IPIP6, NM_IP_TUNNEL_MODE_SIT,
+    },
     nm::settings::{
         NM_SETTING_BOND_SETTING_NAME, NM_SETTING_BRIDGE_SETTING_NAME,
-        NM_SETTING_DUMMY_SETTING_NAME, NM_SETTING_INFINIBAND_SETTING_NAME,
-        NM_SETTING_LOOPBACK_SETTING_NAME, NM_SETTING_MACVLAN_SETTING_NAME,
+        NM_SETTING_DUMMY_SETTING_NAME, NM_SETTING_HSR_SETTING_NAME,
+        NM_SETTING_INFINIBAND_SETTING_NAME, NM_SETTING_IPVLAN_SETTING_NAME,
+        NM_SETTING_IP_TUNNEL_SETTING_NAME, NM_SETTING_LOOPBACK_SETTING_NAME,
+        NM_SETTING_MACSEC_SETTING_NAME, NM_SETTING_MACVLAN_SETTING_NAME,
         NM_SETTING_OVS_BRIDGE_SETTING_NAME, NM_SETTING_OVS_IFACE_SETTING_NAME,
-        NM_SETTING_VETH_SETTING_NAME, NM_SETTING_VLAN_SETTING_NAME,
-        NM_SETTING_VRF_SETTING_NAME, NM_SETTING_VXLAN_SETTING_NAME,
-        NM_SETTING_WIRED_SETTING_NAME,
+        NM_SETTING_TUN_SETTING_NAME, NM_SETTING_VETH_SETTING_NAME,
+        NM_SETTING_VLAN_SETTING_NAME, NM_SETTING_VRF_SETTING_NAME,
+        NM_SETTING_VXLAN_SETTING_NAME, NM_SETTING_WIRED_SETTING_NAME,
+        NM_SETTING_WIREGUARD_SETTING_NAME,
     },
     InterfaceType,
 };
 
 pub(crate) fn nm_dev_iface_type_to_nmstate(nm_dev: &NmDevice) -> InterfaceType {
-    match nm_dev.iface_type.as_str() {
+    nm_iface_type_to_nmstate(
+        nm_dev.iface_type.as_str(),
+        nm_dev.is_mac_vtap,
+        nm_dev.ip_tunnel_mode,
+    )
+}
+
+// The `ip_tunnel_mode` is the NM_IP_TUNNEL_MODE_* of ip-tunnel device or
+// profile, ignored for other interface types.
+pub(crate) fn nm_iface_type_to_nmstate(
+    nm_iface_type: &str,
+    is_mac_vtap: bool,
+    ip_tunnel_mode: u32,
+) -> InterfaceType {
+    match nm_iface_type {
         NM_SETTING_WIRED_SETTING_NAME => InterfaceType::Ethernet,
         NM_SETTING_VETH_SETTING_NAME => InterfaceType::Ethernet,
         NM_SETTING_BOND_SETTING_NAME => InterfaceType::Bond,
@@ -27,7 +49,7 @@ pub(crate) fn nm_dev_iface_type_to_nmstate(nm_dev: &NmDevice) -> InterfaceType {
         NM_SETTING_VLAN_SETTING_NAME => InterfaceType::Vlan,
         NM_SETTING_VXLAN_SETTING_NAME => InterfaceType::Vxlan,
         NM_SETTING_MACVLAN_SETTING_NAME => {
-            if nm_dev.is_mac_vtap {
+            if is_mac_vtap {
                 InterfaceType::MacVtap
             } else {
                 InterfaceType::MacVlan
@@ -35,6 +57,25 @@ pub(crate) fn nm_dev_iface_type_to_nmstate(nm_dev: &NmDevice) -> InterfaceType {
         }
         NM_SETTING_LOOPBACK_SETTING_NAME => InterfaceType::Loopback,
         NM_SETTING_INFINIBAND_SETTING_NAME => InterfaceType::InfiniBand,
-        _ => InterfaceType::Other(nm_dev.iface_type.to_string()),
+        NM_SETTING_WIREGUARD_SETTING_NAME => InterfaceType::WireGuard,
+        NM_SETTING_MACSEC_SETTING_NAME => InterfaceType::Macsec,
+        NM_SETTING_IPVLAN_SETTING_NAME => InterfaceType::IpVlan,
+        NM_SETTING_TUN_SETTING_NAME => InterfaceType::Tun,
+        NM_SETTING_HSR_SETTING_NAME => InterfaceType::Hsr,
+        NM_SETTING_IP_TUNNEL_SETTING_NAME => match ip_tunnel_mode {
+            NM_IP_TUNNEL_MODE_GRE | NM_IP_TUNNEL_MODE_IP6GRE => {
+                InterfaceType::Gre
+            }
+            NM_IP_TUNNEL_MODE_GRETAP | NM_IP_TUNNEL_MODE_IP6GRETAP => {
+                InterfaceType::GreTap
+            }
+            NM_IP_TUNNEL_MODE_IPIP => InterfaceType::IpIp,
+            NM_IP_TUNNEL_MODE_SIT => InterfaceType::Sit,
+            NM_IP_TUNNEL_MODE_IP6IP6 | NM_IP_TUNNEL_MODE_IPIP6 => {
+                InterfaceType::Ip6Tnl
+            }
+            _ => InterfaceType::Other(nm_iface_type.to_string()),
+        },
+        _ => InterfaceType::Other(nm_iface_type.to_string()),
     }
 }
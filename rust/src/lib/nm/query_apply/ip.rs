@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::convert::TryFrom;
 use std::ops::BitXor;
 
 use super::super::nm_dbus::{
@@ -10,7 +11,8 @@ use super::dns::nm_dns_to_nmstate;
 
 use crate::{
     AddressFamily, Dhcpv4ClientId, Dhcpv6Duid, InterfaceIpv4, InterfaceIpv6,
-    Ipv6AddrGenMode, RouteRuleAction, RouteRuleEntry, WaitIp,
+    Ipv6AddrGenMode, RouteRuleAction, RouteRuleEntry, RouteRulePortRange,
+    RouteRuleUidRange, WaitIp,
 };
 
 const ADDR_GEN_MODE_EUI64: i32 = 0;
@@ -262,6 +264,34 @@ fn nm_rules_to_nmstate(
         if let Some(v) = nm_rule.iifname.as_ref() {
             rule.iif = Some(v.to_string());
         }
+        if let Some(v) = nm_rule.oifname.as_ref() {
+            rule.oif = Some(v.to_string());
+        }
+        if let (Some(start), Some(end)) =
+            (nm_rule.uid_range_start, nm_rule.uid_range_end)
+        {
+            rule.uid_range = Some(RouteRuleUidRange::new(start, end));
+        }
+        rule.ip_proto = nm_rule.ipproto.filter(|p| *p != 0);
+        if let Some(start) = nm_rule.sport_start.filter(|p| *p != 0) {
+            rule.sport = Some(RouteRulePortRange::new(
+                start,
+                nm_rule.sport_end.unwrap_or(start),
+            ));
+        }
+        if let Some(start) = nm_rule.dport_start.filter(|p| *p != 0) {
+            rule.dport = Some(RouteRulePortRange::new(
+                start,
+                nm_rule.dport_end.unwrap_or(start),
+            ));
+        }
+        rule.tos = nm_rule.tos.filter(|t| *t != 0);
+        if nm_rule.invert == Some(true) {
+            rule.invert = Some(true);
+        }
+        rule.suppress_prefix_length = nm_rule
+            .suppress_prefixlength
+            .and_then(|v| u32::try_from(v).ok());
         if let Some(v) = nm_rule.action.as_ref() {
             rule.action = Some(match v {
                 NmIpRouteRuleAction::Blackhole => RouteRuleAction::Blackhole,
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::NmConnection;
+
+// NetworkManager cannot reapply the tunnel properties, the tunnel device need
+// to be recreated.
+pub(crate) fn is_ip_tunnel_changed(
+    new_nm_conn: &NmConnection,
+    cur_nm_conn: &NmConnection,
+) -> bool {
+    if let (Some(new_conf), Some(cur_conf)) = (
+        new_nm_conn.ip_tunnel.as_ref(),
+        cur_nm_conn.ip_tunnel.as_ref(),
+    ) {
+        new_conf.mode != cur_conf.mode
+            || new_conf.parent != cur_conf.parent
+            || new_conf.local != cur_conf.local
+            || new_conf.remote != cur_conf.remote
+            || new_conf.input_key != cur_conf.input_key
+            || new_conf.output_key != cur_conf.output_key
+            || new_conf.ttl != cur_conf.ttl
+            || new_conf.tos != cur_conf.tos
+            || new_conf.path_mtu_discovery != cur_conf.path_mtu_discovery
+    } else {
+        false
+    }
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::{NmConnection, NmSettingMacsec};
+
+use crate::MacsecConfig;
+
+// Other MACsec properties are retrieved from kernel, NetworkManager only
+// provides the MKA pre-shared keys.
+pub(crate) fn nm_macsec_to_nmstate(
+    nm_setting: &NmSettingMacsec,
+) -> Option<MacsecConfig> {
+    if nm_setting.mka_cak.is_none() && nm_setting.mka_ckn.is_none() {
+        None
+    } else {
+        Some(MacsecConfig {
+            mka_cak: nm_setting.mka_cak.clone(),
+            mka_ckn: nm_setting.mka_ckn.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+// The MACsec device need to be recreated and MKA restarted when any MACsec
+// property changed.
+pub(crate) fn is_macsec_changed(
+    new_nm_conn: &NmConnection,
+    cur_nm_conn: &NmConnection,
+) -> bool {
+    if let (Some(new_conf), Some(cur_conf)) =
+        (new_nm_conn.macsec.as_ref(), cur_nm_conn.macsec.as_ref())
+    {
+        new_conf.parent != cur_conf.parent
+            || new_conf.mode != cur_conf.mode
+            || new_conf.port != cur_conf.port
+            || new_conf.encrypt != cur_conf.encrypt
+            || new_conf.validation != cur_conf.validation
+            || new_conf.send_sci != cur_conf.send_sci
+            || new_conf.mka_cak != cur_conf.mka_cak
+            || new_conf.mka_ckn != cur_conf.mka_ckn
+    } else {
+        false
+    }
+}
@@ -5,9 +5,12 @@ pub(crate) mod device;
 pub(crate) mod dns;
 mod ieee8021x;
 mod ip;
+mod ip_tunnel;
 mod lldp;
+mod macsec;
 mod mptcp;
 mod ovs;
+mod plan;
 mod profile;
 mod route;
 mod user;
@@ -15,6 +18,7 @@ mod veth;
 mod vlan;
 mod vrf;
 mod vxlan;
+mod wireguard;
 
 pub(crate) use self::apply::nm_apply;
 pub(crate) use self::dns::retrieve_dns_info;
@@ -22,12 +26,16 @@ pub(crate) use self::ieee8021x::nm_802_1x_to_nmstate;
 pub(crate) use self::ip::{
     nm_ip_setting_to_nmstate4, nm_ip_setting_to_nmstate6, query_nmstate_wait_ip,
 };
+pub(crate) use self::ip_tunnel::is_ip_tunnel_changed;
 pub(crate) use self::lldp::{get_lldp, is_lldp_enabled};
+pub(crate) use self::macsec::{is_macsec_changed, nm_macsec_to_nmstate};
 pub(crate) use self::mptcp::{is_mptcp_flags_changed, is_mptcp_supported};
-pub(crate) use self::ovs::delete_orphan_ovs_ports;
+#[cfg(test)]
+pub(crate) use self::plan::gen_nm_apply_plan;
+pub(crate) use self::plan::nm_apply_plan;
 pub(crate) use self::profile::{
     activate_nm_profiles, create_index_for_nm_conns_by_name_type,
-    deactivate_nm_profiles, delete_exist_profiles, save_nm_profiles,
+    deactivate_nm_profiles, save_nm_profiles,
 };
 pub(crate) use self::route::is_route_removed;
 pub(crate) use self::user::get_description;
@@ -35,3 +43,4 @@ pub(crate) use self::veth::is_veth_peer_changed;
 pub(crate) use self::vlan::is_vlan_changed;
 pub(crate) use self::vrf::is_vrf_table_id_changed;
 pub(crate) use self::vxlan::is_vxlan_changed;
+pub(crate) use self::wireguard::nm_wireguard_to_nmstate;
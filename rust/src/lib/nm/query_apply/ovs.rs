@@ -1,21 +1,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::super::nm_dbus::{NmApi, NmConnection};
-use super::super::{
-    query_apply::profile::delete_profiles,
-    settings::{get_exist_profile, NM_SETTING_OVS_PORT_SETTING_NAME},
+use super::super::nm_dbus::NmConnection;
+use super::super::settings::{
+    get_exist_profile, NM_SETTING_OVS_PORT_SETTING_NAME,
 };
 
-use crate::{InterfaceType, MergedInterface, MergedInterfaces, NmstateError};
+use crate::{InterfaceType, MergedInterface, MergedInterfaces};
 
 // When OVS system interface got detached from OVS bridge, we should remove its
 // ovs port also.
-pub(crate) fn delete_orphan_ovs_ports(
-    nm_api: &mut NmApi,
+pub(crate) fn gen_orphan_ovs_port_uuids<'a>(
     merged_ifaces: &MergedInterfaces,
-    exist_nm_conns: &[NmConnection],
+    exist_nm_conns: &'a [NmConnection],
     nm_conns_to_activate: &[NmConnection],
-) -> Result<(), NmstateError> {
+) -> Vec<&'a str> {
     let mut orphans: Vec<&str> = Vec::new();
     for iface in merged_ifaces
         .kernel_ifaces
@@ -73,7 +71,7 @@ pub(crate) fn delete_orphan_ovs_ports(
             }
         }
     }
-    delete_profiles(nm_api, orphans.as_slice())
+    orphans
 }
 
 fn iface_was_ovs_sys_iface(iface: &MergedInterface) -> bool {
@@ -0,0 +1,388 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use super::super::{
+    dns::{cur_dns_ifaces_still_valid_for_dns, store_dns_config_to_iface},
+    error::nm_error_to_nmstate,
+    nm_dbus::{NmActiveConnection, NmApi, NmConnection, NmDevice},
+    profile::{
+        check_nm_nexthop_support, check_nm_route_protocol_support,
+        check_nm_unsupported_ifaces, check_nm_vxlan_fdb_support,
+        perpare_nm_conns, PerparedNmConnections,
+    },
+    query_apply::{
+        apply::{
+            gen_absent_iface_nm_conn_uuids,
+            gen_nm_conn_need_to_deactivate_first, gen_orphan_port_uuids,
+            gen_virtual_ifaces_to_delete,
+        },
+        is_mptcp_supported,
+        ovs::gen_orphan_ovs_port_uuids,
+        profile::{gen_exist_profiles_to_delete, gen_nm_conns_to_activate},
+    },
+    route::store_route_config,
+    route_rule::store_route_rule_config,
+};
+
+use crate::{
+    ApplyPlanAction, ApplyPlanActionKind, MergedNetworkState, NetworkState,
+    NmstateError,
+};
+
+// The changes `nm_apply()` will make in NetworkManager. Generated by
+// `gen_nm_apply_plan()` without changing anything, so `nm_apply()` and
+// `nm_apply_plan()` share the same decisions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NmApplyPlan {
+    pub(crate) memory_only: bool,
+    // Profiles of absent interfaces and their orphan OVS ports
+    pub(crate) absent_uuids: Vec<String>,
+    // Virtual interfaces not removed by profile deletion
+    pub(crate) devs_to_delete: Vec<NmDevice>,
+    pub(crate) hostname: Option<String>,
+    pub(crate) purge_global_dns: bool,
+    // DNS servers and searches to store via NetworkManager global DNS API
+    pub(crate) global_dns: Option<(Vec<String>, Vec<String>)>,
+    pub(crate) to_deactivate_first: Vec<NmConnection>,
+    pub(crate) to_store: Vec<NmConnection>,
+    // Existing profiles replaced by `to_store` and orphan OVS port profiles
+    pub(crate) uuids_to_delete: Vec<String>,
+    pub(crate) to_activate: Vec<NmConnection>,
+    pub(crate) to_deactivate: Vec<NmConnection>,
+    // Used to describe the deleted profiles
+    exist_nm_conns: Vec<NmConnection>,
+    nm_ac_uuids: Vec<String>,
+}
+
+impl NmApplyPlan {
+    // The secrets in keyfile are replaced by
+    // `NetworkState::PASSWORD_HID_BY_NMSTATE` unless `include_secrets` is set.
+    pub(crate) fn to_actions(
+        &self,
+        include_secrets: bool,
+    ) -> Result<Vec<ApplyPlanAction>, NmstateError> {
+        let mut ret = Vec::new();
+        for uuid in self.absent_uuids.iter() {
+            ret.push(self.gen_delete_profile_action(uuid));
+        }
+        for nm_dev in self.devs_to_delete.iter() {
+            let mut action =
+                ApplyPlanAction::new(ApplyPlanActionKind::DeleteInterface);
+            action.name = Some(nm_dev.name.to_string());
+            action.iface_type = Some(nm_dev.iface_type.to_string());
+            ret.push(action);
+        }
+        if let Some(hostname) = self.hostname.as_ref() {
+            let mut action =
+                ApplyPlanAction::new(ApplyPlanActionKind::SetHostname);
+            action.detail = Some(hostname.to_string());
+            ret.push(action);
+        }
+        if self.purge_global_dns {
+            ret.push(ApplyPlanAction::new(ApplyPlanActionKind::PurgeGlobalDns));
+        }
+        if let Some((servers, searches)) = self.global_dns.as_ref() {
+            let mut action =
+                ApplyPlanAction::new(ApplyPlanActionKind::SetGlobalDns);
+            action.detail = Some(format!(
+                "server: {}, search: {}",
+                servers.join(" "),
+                searches.join(" ")
+            ));
+            ret.push(action);
+        }
+        for nm_conn in self.to_deactivate_first.as_slice() {
+            ret.push(gen_action(ApplyPlanActionKind::Deactivate, nm_conn));
+        }
+        for nm_conn in self.to_store.as_slice() {
+            let mut action = gen_action(
+                if nm_conn.obj_path.is_empty() {
+                    ApplyPlanActionKind::AddProfile
+                } else {
+                    ApplyPlanActionKind::ModifyProfile
+                },
+                nm_conn,
+            );
+            action.keyfile = if include_secrets {
+                gen_keyfile(nm_conn)?
+            } else {
+                let mut nm_conn = nm_conn.clone();
+                hide_nm_conn_secrets(&mut nm_conn);
+                gen_keyfile(&nm_conn)?
+            };
+            ret.push(action);
+        }
+        for uuid in self.uuids_to_delete.iter() {
+            ret.push(self.gen_delete_profile_action(uuid));
+        }
+        // Profiles deactivated above will be activated instead of reapplied
+        let nm_ac_uuids: Vec<&str> = self
+            .nm_ac_uuids
+            .iter()
+            .map(|u| u.as_str())
+            .filter(|uuid| {
+                !self
+                    .to_deactivate_first
+                    .iter()
+                    .any(|c| c.uuid() == Some(uuid))
+            })
+            .collect();
+        for (nm_conn, reapply) in gen_nm_conns_to_activate(
+            self.to_activate.as_slice(),
+            nm_ac_uuids.as_slice(),
+        ) {
+            ret.push(gen_action(
+                if reapply {
+                    ApplyPlanActionKind::Reapply
+                } else {
+                    ApplyPlanActionKind::Activate
+                },
+                nm_conn,
+            ));
+        }
+        for nm_conn in self.to_deactivate.as_slice() {
+            ret.push(gen_action(ApplyPlanActionKind::Deactivate, nm_conn));
+        }
+        Ok(ret)
+    }
+
+    fn gen_delete_profile_action(&self, uuid: &str) -> ApplyPlanAction {
+        if let Some(nm_conn) =
+            self.exist_nm_conns.iter().find(|c| c.uuid() == Some(uuid))
+        {
+            gen_action(ApplyPlanActionKind::DeleteProfile, nm_conn)
+        } else {
+            let mut action =
+                ApplyPlanAction::new(ApplyPlanActionKind::DeleteProfile);
+            action.uuid = Some(uuid.to_string());
+            action
+        }
+    }
+}
+
+// Generate the actions `nm_apply()` would take. Nothing is changed in
+// NetworkManager.
+pub(crate) fn nm_apply_plan(
+    merged_state: &MergedNetworkState,
+    include_secrets: bool,
+) -> Result<Vec<ApplyPlanAction>, NmstateError> {
+    let mut nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+    query_nm_apply_plan(&mut nm_api, merged_state)?.to_actions(include_secrets)
+}
+
+// Query NetworkManager for existing profiles and devices, then generate the
+// plan for `nm_apply()`.
+pub(crate) fn query_nm_apply_plan(
+    nm_api: &mut NmApi,
+    merged_state: &MergedNetworkState,
+) -> Result<NmApplyPlan, NmstateError> {
+    let mptcp_supported = is_mptcp_supported(nm_api);
+    let exist_nm_conns =
+        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
+    let nm_acs = nm_api
+        .active_connections_get()
+        .map_err(nm_error_to_nmstate)?;
+    let nm_devs = nm_api.devices_get().map_err(nm_error_to_nmstate)?;
+    let has_global_dns = !nm_api
+        .get_global_dns_configuration()
+        .map_err(nm_error_to_nmstate)?
+        .is_empty();
+
+    gen_nm_apply_plan(
+        merged_state,
+        &exist_nm_conns,
+        &nm_acs,
+        &nm_devs,
+        mptcp_supported,
+        has_global_dns,
+    )
+}
+
+pub(crate) fn gen_nm_apply_plan(
+    merged_state: &MergedNetworkState,
+    exist_nm_conns: &[NmConnection],
+    nm_acs: &[NmActiveConnection],
+    nm_devs: &[NmDevice],
+    mptcp_supported: bool,
+    has_global_dns: bool,
+) -> Result<NmApplyPlan, NmstateError> {
+    check_nm_unsupported_ifaces(merged_state)?;
+    check_nm_nexthop_support(merged_state)?;
+    check_nm_vxlan_fdb_support(merged_state)?;
+    check_nm_route_protocol_support(merged_state)?;
+
+    let mut plan = NmApplyPlan {
+        memory_only: merged_state.memory_only,
+        exist_nm_conns: exist_nm_conns.to_vec(),
+        nm_ac_uuids: nm_acs.iter().map(|nm_ac| nm_ac.uuid.clone()).collect(),
+        ..Default::default()
+    };
+
+    let mut uuids_deleted: HashSet<&str> = HashSet::new();
+    if !merged_state.memory_only {
+        uuids_deleted =
+            gen_absent_iface_nm_conn_uuids(merged_state, exist_nm_conns)?;
+        let mut uuids: Vec<&str> = uuids_deleted.iter().copied().collect();
+        // Sort to make the output consistent
+        uuids.sort_unstable();
+        let orphan_uuids =
+            gen_orphan_port_uuids(exist_nm_conns, &uuids_deleted);
+        uuids.extend(orphan_uuids);
+        uuids_deleted.extend(uuids.iter().copied());
+        plan.absent_uuids = uuids.iter().map(|u| u.to_string()).collect();
+        plan.devs_to_delete =
+            gen_virtual_ifaces_to_delete(merged_state, nm_devs)?
+                .into_iter()
+                .map(|(_, nm_dev)| nm_dev.clone())
+                .collect();
+    }
+    // Profiles of absent interfaces will be deleted before others
+    let exist_nm_conns: Vec<NmConnection> = exist_nm_conns
+        .iter()
+        .filter(|c| c.uuid().map(|u| !uuids_deleted.contains(u)) != Some(false))
+        .cloned()
+        .collect();
+
+    if let Some(hostname) = merged_state
+        .hostname
+        .desired
+        .as_ref()
+        .and_then(|c| c.config.as_ref())
+    {
+        if merged_state.memory_only {
+            log::debug!(
+                "NM: Cannot change configure hostname in memory only mode, \
+                ignoring"
+            );
+        } else {
+            plan.hostname = Some(hostname.to_string());
+        }
+    }
+
+    let mut merged_state = merged_state.clone();
+    store_route_config(&mut merged_state)?;
+    store_route_rule_config(&mut merged_state)?;
+
+    plan.purge_global_dns = has_global_dns
+        && (merged_state.dns.is_changed()
+            || !cur_dns_ifaces_still_valid_for_dns(&merged_state.interfaces));
+
+    if let Err(e) =
+        store_dns_config_to_iface(&mut merged_state, nm_acs, nm_devs)
+    {
+        log::warn!(
+            "Cannot store DNS to NetworkManager interface connection: {e}"
+        );
+        plan.global_dns = Some((
+            merged_state.dns.servers.clone(),
+            merged_state.dns.searches.clone(),
+        ));
+    }
+
+    let PerparedNmConnections {
+        to_store,
+        to_activate,
+        to_deactivate,
+    } = perpare_nm_conns(
+        &merged_state,
+        exist_nm_conns.as_slice(),
+        nm_acs,
+        mptcp_supported,
+        false,
+    )?;
+
+    let activated_nm_conns: Vec<&NmConnection> = exist_nm_conns
+        .iter()
+        .filter(|c| {
+            if let Some(uuid) = c.uuid() {
+                plan.nm_ac_uuids.iter().any(|u| u == uuid)
+            } else {
+                false
+            }
+        })
+        .collect();
+    plan.to_deactivate_first = gen_nm_conn_need_to_deactivate_first(
+        to_activate.as_slice(),
+        activated_nm_conns.as_slice(),
+    );
+
+    if !merged_state.memory_only {
+        for nm_conn in gen_exist_profiles_to_delete(
+            exist_nm_conns.as_slice(),
+            to_store.as_slice(),
+        ) {
+            if let Some(uuid) = nm_conn.uuid() {
+                plan.uuids_to_delete.push(uuid.to_string());
+            }
+        }
+        for uuid in gen_orphan_ovs_port_uuids(
+            &merged_state.interfaces,
+            exist_nm_conns.as_slice(),
+            to_activate.as_slice(),
+        ) {
+            plan.uuids_to_delete.push(uuid.to_string());
+        }
+    }
+    plan.to_store = to_store;
+    plan.to_activate = to_activate;
+    plan.to_deactivate = to_deactivate;
+
+    Ok(plan)
+}
+
+fn gen_action(
+    kind: ApplyPlanActionKind,
+    nm_conn: &NmConnection,
+) -> ApplyPlanAction {
+    let mut action = ApplyPlanAction::new(kind);
+    action.name = nm_conn.iface_name().map(|s| s.to_string());
+    action.iface_type = nm_conn.iface_type().map(|s| s.to_string());
+    action.uuid = nm_conn.uuid().map(|s| s.to_string());
+    action.id = nm_conn.id().map(|s| s.to_string());
+    action
+}
+
+fn hide_nm_conn_secrets(nm_conn: &mut NmConnection) {
+    let hidden = || Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+    if let Some(wg) = nm_conn.wireguard.as_mut() {
+        if wg.private_key.is_some() {
+            wg.private_key = hidden();
+        }
+        for peer in wg.peers.as_deref_mut().unwrap_or_default() {
+            if peer.preshared_key.is_some() {
+                peer.preshared_key = hidden();
+            }
+        }
+    }
+    if let Some(macsec) = nm_conn.macsec.as_mut() {
+        if macsec.mka_cak.is_some() {
+            macsec.mka_cak = hidden();
+        }
+        if macsec.mka_ckn.is_some() {
+            macsec.mka_ckn = hidden();
+        }
+    }
+    if let Some(conf) = nm_conn.ieee8021x.as_mut() {
+        if conf.private_key_password.is_some() {
+            conf.private_key_password = hidden();
+        }
+    }
+}
+
+#[cfg(feature = "gen_conf")]
+fn gen_keyfile(nm_conn: &NmConnection) -> Result<Option<String>, NmstateError> {
+    nm_conn.to_keyfile().map(Some).map_err(|e| {
+        NmstateError::new(
+            crate::ErrorKind::PluginFailure,
+            format!("Bug in NM plugin, failed to generate keyfile: {e}"),
+        )
+    })
+}
+
+#[cfg(not(feature = "gen_conf"))]
+fn gen_keyfile(
+    _nm_conn: &NmConnection,
+) -> Result<Option<String>, NmstateError> {
+    Ok(None)
+}
@@ -28,14 +28,15 @@ pub(crate) const NM_SETTING_CONTROLLERS: [&str; 5] = [
     NM_SETTING_VRF_SETTING_NAME,
 ];
 
-pub(crate) fn delete_exist_profiles(
-    nm_api: &mut NmApi,
-    exist_nm_conns: &[NmConnection],
+// Return the existing profiles holding the same interface name and type of
+// specified `NmConnection` but with different UUID.
+pub(crate) fn gen_exist_profiles_to_delete<'a>(
+    exist_nm_conns: &'a [NmConnection],
     nm_conns: &[NmConnection],
-) -> Result<(), NmstateError> {
+) -> Vec<&'a NmConnection> {
     let mut excluded_uuids: Vec<&str> = Vec::new();
     let mut changed_iface_name_types: Vec<(&str, &str)> = Vec::new();
-    let mut uuids_to_delete = Vec::new();
+    let mut ret = Vec::new();
     for nm_conn in nm_conns {
         if let Some(uuid) = nm_conn.uuid() {
             excluded_uuids.push(uuid);
@@ -73,20 +74,18 @@ pub(crate) fn delete_exist_profiles(
         if !excluded_uuids.contains(&uuid)
             && changed_iface_name_types.contains(&(iface_name, nm_iface_type))
         {
-            if let Some(uuid) = exist_nm_conn.uuid() {
-                uuids_to_delete.push(uuid);
-                log::info!(
-                    "Deleting existing connection \
+            log::info!(
+                "Deleting existing connection \
                 UUID {}, id {:?} type {:?} name {:?}",
-                    uuid,
-                    exist_nm_conn.id(),
-                    exist_nm_conn.iface_type(),
-                    exist_nm_conn.iface_name(),
-                );
-            }
+                uuid,
+                exist_nm_conn.id(),
+                exist_nm_conn.iface_type(),
+                exist_nm_conn.iface_name(),
+            );
+            ret.push(exist_nm_conn);
         }
     }
-    delete_profiles(nm_api, &uuids_to_delete)
+    ret
 }
 
 pub(crate) fn save_nm_profiles(
@@ -169,100 +168,106 @@ fn _activate_nm_profiles(
     nm_conns: &[NmConnection],
     nm_ac_uuids: &[&str],
 ) -> Result<Vec<(NmConnection, NmstateError)>, NmstateError> {
-    let mut new_controllers: Vec<&str> = Vec::new();
     let mut failed_nm_conns: Vec<(NmConnection, NmstateError)> = Vec::new();
-    for nm_conn in nm_conns.iter().filter(|c| {
-        c.iface_type().map(|t| NM_SETTING_CONTROLLERS.contains(&t))
-            == Some(true)
-    }) {
-        if let Some(uuid) = nm_conn.uuid() {
+    for (nm_conn, reapply) in gen_nm_conns_to_activate(nm_conns, nm_ac_uuids) {
+        let uuid = if let Some(u) = nm_conn.uuid() {
+            u
+        } else {
+            continue;
+        };
+        let result = if reapply {
+            log::info!(
+                "Reapplying connection {}: {}/{}",
+                uuid,
+                nm_conn.iface_name().unwrap_or(""),
+                nm_conn.iface_type().unwrap_or("")
+            );
+            reapply_or_activate(nm_api, nm_conn)
+        } else {
             log::info!(
                 "Activating connection {}: {}/{}",
                 uuid,
                 nm_conn.iface_name().unwrap_or(""),
                 nm_conn.iface_type().unwrap_or("")
             );
+            nm_api
+                .connection_activate(uuid)
+                .map_err(nm_error_to_nmstate)
+        };
+        if let Err(e) = result {
+            if e.kind().can_retry() {
+                failed_nm_conns.push((nm_conn.clone(), e));
+            } else {
+                return Err(e);
+            }
+        }
+    }
+    Ok(failed_nm_conns)
+}
+
+// Return the `NmConnection` in activation order with `true` indicating
+// reapply for already activated ones. Controllers are activated first, their
+// ports activated by newly activated controller are skipped.
+pub(crate) fn gen_nm_conns_to_activate<'a>(
+    nm_conns: &'a [NmConnection],
+    nm_ac_uuids: &[&str],
+) -> Vec<(&'a NmConnection, bool)> {
+    let mut new_controllers: Vec<&str> = Vec::new();
+    let mut ret = Vec::new();
+    for nm_conn in nm_conns.iter().filter(|c| is_nm_controller_conn(c)) {
+        if let Some(uuid) = nm_conn.uuid() {
             if nm_ac_uuids.contains(&uuid) {
-                if let Err(e) = reapply_or_activate(nm_api, nm_conn) {
-                    if e.kind().can_retry() {
-                        failed_nm_conns.push((nm_conn.clone(), e));
-                    } else {
-                        return Err(e);
-                    }
-                }
+                ret.push((nm_conn, true));
             } else {
                 new_controllers.push(uuid);
-                if let Err(e) = nm_api
-                    .connection_activate(uuid)
-                    .map_err(nm_error_to_nmstate)
-                {
-                    if e.kind().can_retry() {
-                        failed_nm_conns.push((nm_conn.clone(), e));
-                    } else {
-                        return Err(e);
-                    }
-                }
+                ret.push((nm_conn, false));
             }
         }
     }
-    for nm_conn in nm_conns.iter().filter(|c| {
-        c.iface_type().map(|t| NM_SETTING_CONTROLLERS.contains(&t))
-            != Some(true)
-    }) {
+    for nm_conn in nm_conns.iter().filter(|c| !is_nm_controller_conn(c)) {
         if let Some(uuid) = nm_conn.uuid() {
             if nm_ac_uuids.contains(&uuid) {
+                ret.push((nm_conn, true));
+            } else if is_activated_by_new_controller(
+                nm_conn,
+                new_controllers.as_slice(),
+            ) {
                 log::info!(
-                    "Reapplying connection {}: {}/{}",
+                    "Skip connection activation as its \
+                    controller already activated its ports: \
+                    {}: {}/{}",
                     uuid,
                     nm_conn.iface_name().unwrap_or(""),
                     nm_conn.iface_type().unwrap_or("")
                 );
-                if let Err(e) = reapply_or_activate(nm_api, nm_conn) {
-                    if e.kind().can_retry() {
-                        failed_nm_conns.push((nm_conn.clone(), e));
-                    } else {
-                        return Err(e);
-                    }
-                }
             } else {
-                if let Some(ctrller) = nm_conn.controller() {
-                    if nm_conn.iface_type() != Some("ovs-interface") {
-                        // OVS port does not do auto port activation.
-                        if new_controllers.contains(&ctrller)
-                            && nm_conn.controller_type() != Some("ovs-port")
-                        {
-                            log::info!(
-                                "Skip connection activation as its \
-                                controller already activated its ports: \
-                                {}: {}/{}",
-                                uuid,
-                                nm_conn.iface_name().unwrap_or(""),
-                                nm_conn.iface_type().unwrap_or("")
-                            );
-                            continue;
-                        }
-                    }
-                }
-                log::info!(
-                    "Activating connection {}: {}/{}",
-                    uuid,
-                    nm_conn.iface_name().unwrap_or(""),
-                    nm_conn.iface_type().unwrap_or("")
-                );
-                if let Err(e) = nm_api
-                    .connection_activate(uuid)
-                    .map_err(nm_error_to_nmstate)
-                {
-                    if e.kind().can_retry() {
-                        failed_nm_conns.push((nm_conn.clone(), e));
-                    } else {
-                        return Err(e);
-                    }
-                }
+                ret.push((nm_conn, false));
             }
         }
     }
-    Ok(failed_nm_conns)
+    ret
+}
+
+fn is_nm_controller_conn(nm_conn: &NmConnection) -> bool {
+    nm_conn
+        .iface_type()
+        .map(|t| NM_SETTING_CONTROLLERS.contains(&t))
+        == Some(true)
+}
+
+// Newly activated controller will activate its ports automatically except
+// OVS port and OVS internal interface.
+fn is_activated_by_new_controller(
+    nm_conn: &NmConnection,
+    new_controllers: &[&str],
+) -> bool {
+    if let Some(ctrller) = nm_conn.controller() {
+        nm_conn.iface_type() != Some("ovs-interface")
+            && new_controllers.contains(&ctrller)
+            && nm_conn.controller_type() != Some("ovs-port")
+    } else {
+        false
+    }
 }
 
 pub(crate) fn deactivate_nm_profiles(
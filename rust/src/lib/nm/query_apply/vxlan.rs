@@ -2,6 +2,8 @@
 
 use super::super::nm_dbus::NmConnection;
 
+// NetworkManager cannot reapply the VXLAN properties, the VXLAN device need
+// to be recreated.
 pub(crate) fn is_vxlan_changed(
     new_nm_conn: &NmConnection,
     cur_nm_conn: &NmConnection,
@@ -10,6 +12,16 @@ pub(crate) fn is_vxlan_changed(
         (new_nm_conn.vxlan.as_ref(), cur_nm_conn.vxlan.as_ref())
     {
         new_vxlan_conf.id != cur_vxlan_conf.id
+            || new_vxlan_conf.ttl != cur_vxlan_conf.ttl
+            || new_vxlan_conf.tos != cur_vxlan_conf.tos
+            || new_vxlan_conf.source_port_min != cur_vxlan_conf.source_port_min
+            || new_vxlan_conf.source_port_max != cur_vxlan_conf.source_port_max
+            || new_vxlan_conf.ageing != cur_vxlan_conf.ageing
+            || new_vxlan_conf.limit != cur_vxlan_conf.limit
+            || new_vxlan_conf.proxy != cur_vxlan_conf.proxy
+            || new_vxlan_conf.rsc != cur_vxlan_conf.rsc
+            || new_vxlan_conf.l2_miss != cur_vxlan_conf.l2_miss
+            || new_vxlan_conf.l3_miss != cur_vxlan_conf.l3_miss
     } else {
         false
     }
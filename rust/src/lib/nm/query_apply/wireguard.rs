@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::TryFrom;
+
+use super::super::nm_dbus::NmSettingWireGuard;
+
+use crate::{WireGuardConfig, WireGuardPeerConfig};
+
+pub(crate) fn nm_wireguard_to_nmstate(
+    nm_setting: &NmSettingWireGuard,
+) -> WireGuardConfig {
+    WireGuardConfig {
+        private_key: nm_setting.private_key.clone(),
+        listen_port: nm_setting.listen_port.and_then(|p| u16::try_from(p).ok()),
+        fwmark: nm_setting.fwmark,
+        peers: nm_setting.peers.as_ref().map(|nm_peers| {
+            nm_peers
+                .iter()
+                .filter_map(|nm_peer| {
+                    nm_peer.public_key.as_ref().map(|public_key| {
+                        WireGuardPeerConfig {
+                            public_key: public_key.to_string(),
+                            preshared_key: nm_peer.preshared_key.clone(),
+                            endpoint: nm_peer.endpoint.clone(),
+                            allowed_ips: nm_peer.allowed_ips.clone(),
+                            persistent_keepalive: nm_peer
+                                .persistent_keepalive
+                                .and_then(|k| u16::try_from(k).ok()),
+                        }
+                    })
+                })
+                .collect()
+        }),
+    }
+}
@@ -1,27 +1,33 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::super::nm_dbus::{
-    NmConnection, NmSettingConnection, NmSettingMacVlan, NmSettingVeth,
-    NmSettingVrf, NmSettingVxlan, NmSettingsConnectionFlag,
+    NmConnection, NmSettingConnection, NmSettingIpVlan, NmSettingMacVlan,
+    NmSettingVeth, NmSettingVrf, NmSettingsConnectionFlag,
 };
 use super::{
     bond::gen_nm_bond_setting,
     bridge::{gen_nm_br_port_setting, gen_nm_br_setting},
     ethtool::gen_ethtool_setting,
+    hsr::gen_nm_hsr_setting,
     ieee8021x::gen_nm_802_1x_setting,
     infiniband::gen_nm_ib_setting,
     ip::gen_nm_ip_setting,
+    ip_tunnel::gen_nm_ip_tunnel_setting,
     loopback::gen_nm_loopback_setting,
+    macsec::gen_nm_macsec_setting,
     mptcp::apply_mptcp_conf,
     ovs::{
         create_ovs_port_nm_conn, gen_nm_iface_ovs_db_setting,
         gen_nm_ovs_br_setting, gen_nm_ovs_iface_setting, get_ovs_port_name,
     },
     sriov::gen_nm_sriov_setting,
+    tun::gen_nm_tun_setting,
     user::gen_nm_user_setting,
     veth::create_veth_peer_profile_if_not_found,
     vlan::gen_nm_vlan_setting,
+    vxlan::gen_nm_vxlan_setting,
     wired::gen_nm_wired_setting,
+    wireguard::gen_nm_wireguard_setting,
 };
 
 use crate::{
@@ -43,13 +49,19 @@ pub(crate) const NM_SETTING_VLAN_SETTING_NAME: &str = "vlan";
 pub(crate) const NM_SETTING_VXLAN_SETTING_NAME: &str = "vxlan";
 pub(crate) const NM_SETTING_INFINIBAND_SETTING_NAME: &str = "infiniband";
 pub(crate) const NM_SETTING_LOOPBACK_SETTING_NAME: &str = "loopback";
+pub(crate) const NM_SETTING_WIREGUARD_SETTING_NAME: &str = "wireguard";
+pub(crate) const NM_SETTING_IP_TUNNEL_SETTING_NAME: &str = "ip-tunnel";
+pub(crate) const NM_SETTING_MACSEC_SETTING_NAME: &str = "macsec";
+pub(crate) const NM_SETTING_IPVLAN_SETTING_NAME: &str = "ipvlan";
+pub(crate) const NM_SETTING_TUN_SETTING_NAME: &str = "tun";
+pub(crate) const NM_SETTING_HSR_SETTING_NAME: &str = "hsr";
 
 pub(crate) const NM_SETTING_USER_SPACES: [&str; 2] = [
     NM_SETTING_OVS_BRIDGE_SETTING_NAME,
     NM_SETTING_OVS_PORT_SETTING_NAME,
 ];
 
-pub(crate) const SUPPORTED_NM_KERNEL_IFACE_TYPES: [&str; 12] = [
+pub(crate) const SUPPORTED_NM_KERNEL_IFACE_TYPES: [&str; 18] = [
     NM_SETTING_WIRED_SETTING_NAME,
     NM_SETTING_VETH_SETTING_NAME,
     NM_SETTING_BOND_SETTING_NAME,
@@ -62,6 +74,12 @@ pub(crate) const SUPPORTED_NM_KERNEL_IFACE_TYPES: [&str; 12] = [
     NM_SETTING_MACVLAN_SETTING_NAME,
     NM_SETTING_LOOPBACK_SETTING_NAME,
     NM_SETTING_INFINIBAND_SETTING_NAME,
+    NM_SETTING_WIREGUARD_SETTING_NAME,
+    NM_SETTING_IP_TUNNEL_SETTING_NAME,
+    NM_SETTING_MACSEC_SETTING_NAME,
+    NM_SETTING_IPVLAN_SETTING_NAME,
+    NM_SETTING_TUN_SETTING_NAME,
+    NM_SETTING_HSR_SETTING_NAME,
 ];
 
 pub(crate) fn iface_to_nm_connections(
@@ -177,9 +195,7 @@ pub(crate) fn iface_to_nm_connections(
             gen_nm_vlan_setting(vlan_iface, &mut nm_conn);
         }
         Interface::Vxlan(vxlan_iface) => {
-            if let Some(conf) = vxlan_iface.vxlan.as_ref() {
-                nm_conn.vxlan = Some(NmSettingVxlan::from(conf))
-            }
+            gen_nm_vxlan_setting(vxlan_iface, &mut nm_conn)?;
         }
         Interface::Ethernet(eth_iface) => {
             if let Some(veth_conf) = eth_iface.veth.as_ref() {
@@ -233,6 +249,26 @@ pub(crate) fn iface_to_nm_connections(
         Interface::Loopback(iface) => {
             gen_nm_loopback_setting(iface, &mut nm_conn);
         }
+        Interface::WireGuard(iface) => {
+            gen_nm_wireguard_setting(iface, &mut nm_conn);
+        }
+        Interface::IpTunnel(iface) => {
+            gen_nm_ip_tunnel_setting(iface, &mut nm_conn);
+        }
+        Interface::Macsec(iface) => {
+            gen_nm_macsec_setting(iface, &mut nm_conn);
+        }
+        Interface::IpVlan(iface) => {
+            if let Some(conf) = iface.ipvlan.as_ref() {
+                nm_conn.ip_vlan = Some(NmSettingIpVlan::from(conf));
+            }
+        }
+        Interface::Tun(iface) => {
+            gen_nm_tun_setting(iface, &mut nm_conn)?;
+        }
+        Interface::Hsr(iface) => {
+            gen_nm_hsr_setting(iface, &mut nm_conn)?;
+        }
         _ => (),
     };
 
@@ -340,6 +376,20 @@ pub(crate) fn iface_type_to_nm(
         InterfaceType::Loopback => {
             Ok(NM_SETTING_LOOPBACK_SETTING_NAME.to_string())
         }
+        InterfaceType::WireGuard => {
+            Ok(NM_SETTING_WIREGUARD_SETTING_NAME.to_string())
+        }
+        InterfaceType::Gre
+        | InterfaceType::GreTap
+        | InterfaceType::IpIp
+        | InterfaceType::Sit
+        | InterfaceType::Ip6Tnl => {
+            Ok(NM_SETTING_IP_TUNNEL_SETTING_NAME.to_string())
+        }
+        InterfaceType::Macsec => Ok(NM_SETTING_MACSEC_SETTING_NAME.to_string()),
+        InterfaceType::IpVlan => Ok(NM_SETTING_IPVLAN_SETTING_NAME.to_string()),
+        InterfaceType::Tun => Ok(NM_SETTING_TUN_SETTING_NAME.to_string()),
+        InterfaceType::Hsr => Ok(NM_SETTING_HSR_SETTING_NAME.to_string()),
         InterfaceType::Other(s) => Ok(s.to_string()),
         _ => Err(NmstateError::new(
             ErrorKind::NotImplementedError,
@@ -407,7 +457,11 @@ pub(crate) fn gen_nm_conn_setting(
     let nm_ctrl_type = nm_ctrl_type.as_deref();
     let ctrl_name = iface.base_iface().controller.as_deref();
     if let Some(ctrl_name) = ctrl_name {
-        if ctrl_name.is_empty() {
+        // NetworkManager reference HSR ports by name in HSR setting, the
+        // HSR ports are not NetworkManager ports.
+        if ctrl_name.is_empty()
+            || iface.base_iface().controller_type == Some(InterfaceType::Hsr)
+        {
             nm_conn_set.controller = None;
             nm_conn_set.controller_type = None;
         } else if let Some(nm_ctrl_type) = nm_ctrl_type {
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::NmConnection;
+
+use crate::{ErrorKind, HsrInterface, HsrProtocol, NmstateError};
+
+pub(crate) fn gen_nm_hsr_setting(
+    iface: &HsrInterface,
+    nm_conn: &mut NmConnection,
+) -> Result<(), NmstateError> {
+    let mut nm_setting = nm_conn.hsr.clone().unwrap_or_default();
+    if let Some(conf) = iface.hsr.as_ref() {
+        // NetworkManager always create HSRv0 interface
+        if conf.version.unwrap_or_default() != 0 {
+            let e = NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "NetworkManager does not support HSR version {} of \
+                    interface {}",
+                    conf.version.unwrap_or_default(),
+                    iface.base.name
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        nm_setting.port1 = Some(conf.port1.clone());
+        nm_setting.port2 = Some(conf.port2.clone());
+        if let Some(v) = conf.multicast_spec() {
+            nm_setting.multicast_spec = Some(v.into());
+        }
+        if let Some(protocol) = conf.protocol {
+            nm_setting.prp = Some(protocol == HsrProtocol::Prp);
+        }
+    }
+    nm_conn.hsr = Some(nm_setting);
+    Ok(())
+}
@@ -9,8 +9,8 @@ use super::{
 use crate::nm::nm_dbus::{NmConnection, NmSettingIp, NmSettingIpMethod};
 use crate::{
     BaseInterface, Dhcpv4ClientId, Dhcpv6Duid, ErrorKind, Interface,
-    InterfaceIpv4, InterfaceIpv6, Ipv6AddrGenMode, NmstateError, RouteEntry,
-    WaitIp,
+    InterfaceIpv4, InterfaceIpv6, InterfaceType, Ipv6AddrGenMode, NmstateError,
+    RouteEntry, WaitIp,
 };
 
 const ADDR_GEN_MODE_EUI64: i32 = 0;
@@ -209,6 +209,13 @@ pub(crate) fn gen_nm_ip_setting(
         gen_nm_ipv4_setting(base_iface.ipv4.as_ref(), routes, nm_conn)?;
         gen_nm_ipv6_setting(base_iface.ipv6.as_ref(), routes, nm_conn)?;
         apply_nmstate_wait_ip(base_iface, nm_conn);
+    } else if base_iface.controller_type == Some(InterfaceType::Hsr) {
+        // NetworkManager treat HSR ports as standalone interfaces, hence
+        // disable IP explicitly.
+        let mut nm_setting = NmSettingIp::default();
+        nm_setting.method = Some(NmSettingIpMethod::Disabled);
+        nm_conn.ipv4 = Some(nm_setting.clone());
+        nm_conn.ipv6 = Some(nm_setting);
     } else {
         nm_conn.ipv4 = None;
         nm_conn.ipv6 = None;
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::{
+    NmConnection, NM_IP_TUNNEL_MODE_GRE, NM_IP_TUNNEL_MODE_GRETAP,
+    NM_IP_TUNNEL_MODE_IP6GRE, NM_IP_TUNNEL_MODE_IP6GRETAP,
+    NM_IP_TUNNEL_MODE_IP6IP6, NM_IP_TUNNEL_MODE_IPIP, NM_IP_TUNNEL_MODE_IPIP6,
+    NM_IP_TUNNEL_MODE_SIT,
+};
+
+use crate::{InterfaceType, IpTunnelInterface};
+
+pub(crate) fn gen_nm_ip_tunnel_setting(
+    iface: &IpTunnelInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let mut nm_setting = nm_conn.ip_tunnel.clone().unwrap_or_default();
+    let is_ipv6 = iface.is_ipv6();
+    nm_setting.mode = Some(match iface.base.iface_type {
+        InterfaceType::GreTap if is_ipv6 => NM_IP_TUNNEL_MODE_IP6GRETAP,
+        InterfaceType::GreTap => NM_IP_TUNNEL_MODE_GRETAP,
+        InterfaceType::IpIp => NM_IP_TUNNEL_MODE_IPIP,
+        InterfaceType::Sit => NM_IP_TUNNEL_MODE_SIT,
+        // Preserve the IPv4 over IPv6 mode of existing profile
+        InterfaceType::Ip6Tnl
+            if nm_setting.mode == Some(NM_IP_TUNNEL_MODE_IPIP6) =>
+        {
+            NM_IP_TUNNEL_MODE_IPIP6
+        }
+        InterfaceType::Ip6Tnl => NM_IP_TUNNEL_MODE_IP6IP6,
+        _ if is_ipv6 => NM_IP_TUNNEL_MODE_IP6GRE,
+        _ => NM_IP_TUNNEL_MODE_GRE,
+    });
+    if let Some(conf) = iface.ip_tunnel.as_ref() {
+        if let Some(v) = conf.base_iface.as_ref() {
+            nm_setting.parent = Some(v.to_string());
+        }
+        if let Some(v) = conf.local.as_ref() {
+            nm_setting.local = Some(v.to_string());
+        }
+        if let Some(v) = conf.remote.as_ref() {
+            nm_setting.remote = Some(v.to_string());
+        }
+        if let Some(v) = conf.key {
+            nm_setting.input_key = Some(v.to_string());
+            nm_setting.output_key = Some(v.to_string());
+        }
+        if let Some(v) = conf.ttl {
+            nm_setting.ttl = Some(v.into());
+        }
+        if let Some(v) = conf.tos {
+            nm_setting.tos = Some(v.into());
+        }
+        if let Some(v) = conf.pmtudisc {
+            nm_setting.path_mtu_discovery = Some(v);
+        }
+    }
+    if let Some(mtu) = iface.base.mtu {
+        nm_setting.mtu = Some(mtu as u32);
+    }
+    nm_conn.ip_tunnel = Some(nm_setting);
+}
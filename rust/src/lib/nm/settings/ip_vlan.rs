@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nm::nm_dbus::{
+    NmSettingIpVlan, NM_SETTING_IPVLAN_MODE_L2, NM_SETTING_IPVLAN_MODE_L3,
+    NM_SETTING_IPVLAN_MODE_L3S,
+};
+
+use crate::{IpVlanConfig, IpVlanMode};
+
+impl From<&IpVlanConfig> for NmSettingIpVlan {
+    fn from(config: &IpVlanConfig) -> Self {
+        let mut settings = NmSettingIpVlan::default();
+        settings.parent = Some(config.base_iface.clone());
+        // NetworkManager does not accept unknown mode, hence use the kernel
+        // default mode when not defined.
+        settings.mode = Some(match config.mode.unwrap_or_default() {
+            IpVlanMode::L2 => NM_SETTING_IPVLAN_MODE_L2,
+            IpVlanMode::L3 => NM_SETTING_IPVLAN_MODE_L3,
+            IpVlanMode::L3s => NM_SETTING_IPVLAN_MODE_L3S,
+        });
+        settings.private = config.private;
+        settings.vepa = config.vepa;
+        settings
+    }
+}
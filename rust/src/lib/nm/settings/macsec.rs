@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::{
+    NmConnection, NM_SETTING_MACSEC_MODE_EAP, NM_SETTING_MACSEC_MODE_PSK,
+};
+
+use crate::{MacsecInterface, NetworkState};
+
+pub(crate) fn gen_nm_macsec_setting(
+    iface: &MacsecInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let mut nm_setting = nm_conn.macsec.clone().unwrap_or_default();
+    if let Some(conf) = iface.macsec.as_ref() {
+        nm_setting.parent = Some(conf.base_iface.to_string());
+        if let Some(v) = conf.port {
+            nm_setting.port = Some(v.into());
+        }
+        if let Some(v) = conf.encrypt {
+            nm_setting.encrypt = Some(v);
+        }
+        if let Some(v) = conf.validation {
+            nm_setting.validation = Some(u8::from(v).into());
+        }
+        if let Some(v) = conf.send_sci {
+            nm_setting.send_sci = Some(v);
+        }
+        // Hidden secrets means preserving current value
+        if let Some(cak) = conf.mka_cak.as_ref() {
+            if cak != NetworkState::PASSWORD_HID_BY_NMSTATE {
+                nm_setting.mka_cak = Some(cak.to_string());
+            }
+        }
+        if let Some(ckn) = conf.mka_ckn.as_ref() {
+            if ckn != NetworkState::PASSWORD_HID_BY_NMSTATE {
+                nm_setting.mka_ckn = Some(ckn.to_string());
+            }
+        }
+        // Without MKA pre-shared key, NetworkManager use 802.1X for key
+        // agreement.
+        nm_setting.mode = Some(if nm_setting.mka_ckn.is_some() {
+            NM_SETTING_MACSEC_MODE_PSK
+        } else {
+            NM_SETTING_MACSEC_MODE_EAP
+        });
+    }
+    nm_conn.macsec = Some(nm_setting);
+}
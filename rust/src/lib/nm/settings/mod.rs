@@ -5,23 +5,29 @@ mod bridge;
 mod connection;
 mod dns;
 mod ethtool;
+mod hsr;
 mod ieee8021x;
 mod infiniband;
 mod inter_connections;
 mod ip;
+mod ip_tunnel;
+mod ip_vlan;
 mod loopback;
 mod mac_vlan;
+mod macsec;
 mod mptcp;
 mod ovs;
 mod route;
 mod route_rule;
 mod sriov;
+mod tun;
 mod user;
 mod veth;
 mod vlan;
 mod vrf;
 mod vxlan;
 mod wired;
+mod wireguard;
 
 pub(crate) use self::connection::{
     get_exist_profile, iface_to_nm_connections, SUPPORTED_NM_KERNEL_IFACE_TYPES,
@@ -30,12 +36,15 @@ pub(crate) use self::connection::{
 pub(crate) use self::connection::{
     iface_type_to_nm, NM_SETTING_BOND_SETTING_NAME,
     NM_SETTING_BRIDGE_SETTING_NAME, NM_SETTING_DUMMY_SETTING_NAME,
-    NM_SETTING_INFINIBAND_SETTING_NAME, NM_SETTING_LOOPBACK_SETTING_NAME,
+    NM_SETTING_HSR_SETTING_NAME, NM_SETTING_INFINIBAND_SETTING_NAME,
+    NM_SETTING_IPVLAN_SETTING_NAME, NM_SETTING_IP_TUNNEL_SETTING_NAME,
+    NM_SETTING_LOOPBACK_SETTING_NAME, NM_SETTING_MACSEC_SETTING_NAME,
     NM_SETTING_MACVLAN_SETTING_NAME, NM_SETTING_OVS_BRIDGE_SETTING_NAME,
     NM_SETTING_OVS_IFACE_SETTING_NAME, NM_SETTING_OVS_PORT_SETTING_NAME,
-    NM_SETTING_VETH_SETTING_NAME, NM_SETTING_VLAN_SETTING_NAME,
-    NM_SETTING_VRF_SETTING_NAME, NM_SETTING_VXLAN_SETTING_NAME,
-    NM_SETTING_WIRED_SETTING_NAME,
+    NM_SETTING_TUN_SETTING_NAME, NM_SETTING_VETH_SETTING_NAME,
+    NM_SETTING_VLAN_SETTING_NAME, NM_SETTING_VRF_SETTING_NAME,
+    NM_SETTING_VXLAN_SETTING_NAME, NM_SETTING_WIRED_SETTING_NAME,
+    NM_SETTING_WIREGUARD_SETTING_NAME,
 };
 pub(crate) use self::inter_connections::{
     use_uuid_for_controller_reference, use_uuid_for_parent_reference,
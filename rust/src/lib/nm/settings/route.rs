@@ -37,6 +37,15 @@ pub(crate) fn gen_nm_ip_routes(
                 nm_route.weight = Some(w);
             }
         }
+        nm_route.route_type = route.route_type.map(|t| t.to_string());
+        nm_route.src = route.source.clone();
+        nm_route.mtu = route.mtu;
+        nm_route.advmss = route.advmss;
+        nm_route.initcwnd = route.initcwnd;
+        nm_route.initrwnd = route.initrwnd;
+        nm_route.onlink = route.onlink;
+        nm_route.scope = route.scope.map(u8::from);
+        nm_route.quickack = route.quickack;
         ret.push(nm_route);
     }
     Ok(ret)
@@ -6,7 +6,7 @@ use super::super::nm_dbus::NmIpRouteRule;
 
 use crate::{
     ip::is_ipv6_addr, ip::AddressFamily, ErrorKind, InterfaceIpAddr,
-    NmstateError, RouteRuleEntry,
+    NmstateError, RouteRuleAction, RouteRuleEntry,
 };
 
 const AF_INET6: i32 = 10;
@@ -69,6 +69,51 @@ pub(crate) fn gen_nm_ip_rules(
         if let Some(iif) = rule.iif.as_ref() {
             nm_rule.iifname = Some(iif.to_string());
         }
+        if let Some(oif) = rule.oif.as_ref() {
+            nm_rule.oifname = Some(oif.to_string());
+        }
+        if let Some(uid_range) = rule.uid_range.as_ref() {
+            nm_rule.uid_range_start = Some(uid_range.start);
+            nm_rule.uid_range_end = Some(uid_range.end);
+        }
+        nm_rule.ipproto = rule.ip_proto;
+        if let Some(sport) = rule.sport.as_ref() {
+            nm_rule.sport_start = Some(sport.start);
+            nm_rule.sport_end = Some(sport.end);
+        }
+        if let Some(dport) = rule.dport.as_ref() {
+            nm_rule.dport_start = Some(dport.start);
+            nm_rule.dport_end = Some(dport.end);
+        }
+        nm_rule.tos = rule.tos;
+        if rule.invert == Some(true) {
+            nm_rule.invert = Some(true);
+        }
+        if let Some(v) = rule.suppress_prefix_length {
+            nm_rule.suppress_prefixlength =
+                Some(i32::try_from(v).map_err(|_| {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "The suppress-prefix-length {v} is too big \
+                            for route rule {rule}"
+                        ),
+                    );
+                    log::error!("{}", e);
+                    e
+                })?);
+        }
+        if rule.l3mdev == Some(true) {
+            return Err(nm_rule_not_supported("l3mdev", rule));
+        }
+        if rule.suppress_ifgroup.is_some() {
+            return Err(nm_rule_not_supported("suppress-ifgroup", rule));
+        }
+        if let Some(action @ (RouteRuleAction::Goto | RouteRuleAction::Nop)) =
+            rule.action
+        {
+            return Err(nm_rule_not_supported(&format!("{action}"), rule));
+        }
         if let Some(action) = rule.action.as_ref() {
             nm_rule.action = Some(u8::from(*action).into());
         }
@@ -77,3 +122,15 @@ pub(crate) fn gen_nm_ip_rules(
     }
     Ok(ret)
 }
+
+fn nm_rule_not_supported(prop: &str, rule: &RouteRuleEntry) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::NotSupportedError,
+        format!(
+            "The {prop} route rule is not supported by NetworkManager \
+            backend: {rule}"
+        ),
+    );
+    log::error!("{}", e);
+    e
+}
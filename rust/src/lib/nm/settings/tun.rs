@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::{
+    NmConnection, NM_SETTING_TUN_MODE_TAP, NM_SETTING_TUN_MODE_TUN,
+};
+
+use crate::{ErrorKind, NmstateError, TunInterface, TunMode};
+
+pub(crate) fn gen_nm_tun_setting(
+    iface: &TunInterface,
+    nm_conn: &mut NmConnection,
+) -> Result<(), NmstateError> {
+    let mut nm_setting = nm_conn.tun.clone().unwrap_or_default();
+    if let Some(conf) = iface.tun.as_ref() {
+        // NetworkManager always create persistent TUN/TAP interface
+        if conf.persist == Some(false) {
+            let e = NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "NetworkManager does not support non-persistent \
+                    TUN/TAP interface {}",
+                    iface.base.name
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if let Some(mode) = conf.mode {
+            nm_setting.mode = Some(match mode {
+                TunMode::Tun => NM_SETTING_TUN_MODE_TUN,
+                TunMode::Tap => NM_SETTING_TUN_MODE_TAP,
+            });
+        }
+        if let Some(v) = conf.owner {
+            nm_setting.owner = Some(v.to_string());
+        }
+        if let Some(v) = conf.group {
+            nm_setting.group = Some(v.to_string());
+        }
+        if let Some(v) = conf.multi_queue {
+            nm_setting.multi_queue = Some(v);
+        }
+        if let Some(v) = conf.vnet_hdr {
+            nm_setting.vnet_hdr = Some(v);
+        }
+    }
+    nm_conn.tun = Some(nm_setting);
+    Ok(())
+}
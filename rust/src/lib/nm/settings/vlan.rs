@@ -1,8 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::super::nm_dbus::NmConnection;
+use super::super::nm_dbus::{
+    NmConnection, NM_VLAN_FLAG_GVRP, NM_VLAN_FLAG_LOOSE_BINDING,
+    NM_VLAN_FLAG_MVRP, NM_VLAN_FLAG_REORDER_HEADERS,
+};
 
-use crate::{VlanInterface, VlanProtocol};
+use crate::{VlanInterface, VlanProtocol, VlanQosMapping};
 
 const NM_802_1_AD: &str = "802.1ad";
 const NM_802_1_Q: &str = "802.1Q";
@@ -30,6 +33,36 @@ pub(crate) fn gen_nm_vlan_setting(
                 }
             }
         }
+        let mut flags = nm_vlan.flags.unwrap_or(NM_VLAN_FLAG_REORDER_HEADERS);
+        let mut flags_changed = false;
+        for (flag, value) in [
+            (NM_VLAN_FLAG_REORDER_HEADERS, vlan_conf.reorder_headers),
+            (NM_VLAN_FLAG_GVRP, vlan_conf.gvrp),
+            (NM_VLAN_FLAG_LOOSE_BINDING, vlan_conf.loose_binding),
+            (NM_VLAN_FLAG_MVRP, vlan_conf.mvrp),
+        ] {
+            if let Some(v) = value {
+                flags_changed = true;
+                if v {
+                    flags |= flag;
+                } else {
+                    flags &= !flag;
+                }
+            }
+        }
+        if flags_changed {
+            nm_vlan.flags = Some(flags);
+        }
+        if let Some(qos_map) = vlan_conf.ingress_qos_map.as_deref() {
+            nm_vlan.ingress_priority_map = Some(qos_map_to_nm(qos_map));
+        }
+        if let Some(qos_map) = vlan_conf.egress_qos_map.as_deref() {
+            nm_vlan.egress_priority_map = Some(qos_map_to_nm(qos_map));
+        }
         nm_conn.vlan = Some(nm_vlan);
     }
 }
+
+fn qos_map_to_nm(qos_map: &[VlanQosMapping]) -> Vec<String> {
+    qos_map.iter().map(|m| m.to_string()).collect()
+}
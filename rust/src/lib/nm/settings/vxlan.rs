@@ -1,28 +1,94 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::super::nm_dbus::NmSettingVxlan;
+use super::super::nm_dbus::NmConnection;
 
-use crate::VxlanConfig;
+use crate::{ErrorKind, NmstateError, VxlanConfig, VxlanInterface};
 
-impl From<&VxlanConfig> for NmSettingVxlan {
-    fn from(config: &VxlanConfig) -> Self {
-        let mut setting = NmSettingVxlan::default();
-        setting.id = Some(config.id);
-        if !config.base_iface.is_empty() {
-            setting.parent = Some(config.base_iface.clone());
-        }
-        if let Some(v) = config.learning {
-            setting.learning = Some(v);
-        }
-        if let Some(v) = config.local.as_ref() {
-            setting.local = Some(v.to_string());
-        }
-        if let Some(v) = config.remote.as_ref() {
-            setting.remote = Some(v.to_string());
-        }
-        if let Some(v) = config.dst_port {
-            setting.dst_port = Some(v.into())
+pub(crate) fn gen_nm_vxlan_setting(
+    iface: &VxlanInterface,
+    nm_conn: &mut NmConnection,
+) -> Result<(), NmstateError> {
+    let config = if let Some(c) = iface.vxlan.as_ref() {
+        c
+    } else {
+        return Ok(());
+    };
+    check_nm_unsupported_vxlan_opts(iface.base.name.as_str(), config)?;
+
+    let mut setting = nm_conn.vxlan.clone().unwrap_or_default();
+    setting.id = Some(config.id);
+    if !config.base_iface.is_empty() {
+        setting.parent = Some(config.base_iface.clone());
+    }
+    if let Some(v) = config.learning {
+        setting.learning = Some(v);
+    }
+    if let Some(v) = config.local.as_ref() {
+        setting.local = Some(v.to_string());
+    }
+    if let Some(v) = config.remote.as_ref() {
+        setting.remote = Some(v.to_string());
+    }
+    if let Some(v) = config.dst_port {
+        setting.dst_port = Some(v.into())
+    }
+    if let Some(v) = config.ttl {
+        setting.ttl = Some(v.into())
+    }
+    if let Some(v) = config.tos {
+        setting.tos = Some(v.into())
+    }
+    if let Some(v) = config.source_port_min {
+        setting.source_port_min = Some(v.into())
+    }
+    if let Some(v) = config.source_port_max {
+        setting.source_port_max = Some(v.into())
+    }
+    if let Some(v) = config.ageing {
+        setting.ageing = Some(v)
+    }
+    if let Some(v) = config.limit {
+        setting.limit = Some(v)
+    }
+    if let Some(v) = config.proxy {
+        setting.proxy = Some(v)
+    }
+    if let Some(v) = config.rsc {
+        setting.rsc = Some(v)
+    }
+    if let Some(v) = config.l2_miss {
+        setting.l2_miss = Some(v)
+    }
+    if let Some(v) = config.l3_miss {
+        setting.l3_miss = Some(v)
+    }
+    nm_conn.vxlan = Some(setting);
+    Ok(())
+}
+
+// NetworkManager always create VXLAN interface with these options disabled.
+fn check_nm_unsupported_vxlan_opts(
+    iface_name: &str,
+    config: &VxlanConfig,
+) -> Result<(), NmstateError> {
+    for (opt_name, value) in [
+        ("udp-checksum", config.udp_checksum),
+        ("udp6-zero-checksum-tx", config.udp6_zero_checksum_tx),
+        ("udp6-zero-checksum-rx", config.udp6_zero_checksum_rx),
+        ("gbp", config.gbp),
+        ("external", config.external),
+    ] {
+        if value == Some(true) {
+            let e = NmstateError::new(
+                ErrorKind::NotSupportedError,
+                format!(
+                    "NetworkManager does not support enabling VXLAN option \
+                    {opt_name} of interface {iface_name}"
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
         }
-        setting
     }
+    Ok(())
 }
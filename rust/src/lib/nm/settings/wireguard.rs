@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::super::nm_dbus::{NmConnection, NmSettingWireGuardPeer};
+
+use crate::{NetworkState, WireGuardInterface};
+
+pub(crate) fn gen_nm_wireguard_setting(
+    iface: &WireGuardInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let cur_nm_setting = nm_conn.wireguard.clone();
+    let mut nm_setting = cur_nm_setting.clone().unwrap_or_default();
+    if let Some(conf) = iface.wireguard.as_ref() {
+        // Hidden secrets means preserving current value
+        if let Some(private_key) = conf.private_key.as_ref() {
+            if private_key != NetworkState::PASSWORD_HID_BY_NMSTATE {
+                nm_setting.private_key = Some(private_key.to_string());
+            }
+        }
+        if let Some(v) = conf.listen_port {
+            nm_setting.listen_port = Some(v.into());
+        }
+        if let Some(v) = conf.fwmark {
+            nm_setting.fwmark = Some(v);
+        }
+        if let Some(peers) = conf.peers.as_ref() {
+            let cur_peers = cur_nm_setting
+                .as_ref()
+                .and_then(|s| s.peers.as_deref())
+                .unwrap_or_default();
+            let mut nm_peers = Vec::new();
+            for peer in peers {
+                let mut nm_peer = NmSettingWireGuardPeer::default();
+                nm_peer.public_key = Some(peer.public_key.to_string());
+                nm_peer.preshared_key = match peer.preshared_key.as_deref() {
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE) => cur_peers
+                        .iter()
+                        .find(|p| {
+                            p.public_key.as_deref()
+                                == Some(peer.public_key.as_str())
+                        })
+                        .and_then(|p| p.preshared_key.clone()),
+                    Some(k) => Some(k.to_string()),
+                    None => None,
+                };
+                nm_peer.endpoint = peer.endpoint.clone();
+                nm_peer.allowed_ips = peer.allowed_ips.clone();
+                nm_peer.persistent_keepalive =
+                    peer.persistent_keepalive.map(u32::from);
+                nm_peers.push(nm_peer);
+            }
+            nm_setting.peers = Some(nm_peers);
+        }
+    }
+    nm_conn.wireguard = Some(nm_setting);
+}
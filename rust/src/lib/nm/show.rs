@@ -15,7 +15,8 @@ use super::{
         device::nm_dev_iface_type_to_nmstate, dns::nm_global_dns_to_nmstate,
         get_description, get_lldp, is_lldp_enabled, is_mptcp_supported,
         nm_802_1x_to_nmstate, nm_ip_setting_to_nmstate4,
-        nm_ip_setting_to_nmstate6, query_nmstate_wait_ip, retrieve_dns_info,
+        nm_ip_setting_to_nmstate6, nm_macsec_to_nmstate,
+        nm_wireguard_to_nmstate, query_nmstate_wait_ip, retrieve_dns_info,
     },
     settings::{
         get_bond_balance_slb, NM_SETTING_VETH_SETTING_NAME,
@@ -24,11 +25,12 @@ use super::{
 };
 use crate::{
     BaseInterface, BondConfig, BondInterface, BondOptions, DummyInterface,
-    EthernetInterface, InfiniBandInterface, Interface, InterfaceState,
-    InterfaceType, Interfaces, LinuxBridgeInterface, LoopbackInterface,
-    MacVlanInterface, MacVtapInterface, NetworkState, NmstateError,
-    OvsBridgeInterface, OvsInterface, UnknownInterface, VlanInterface,
-    VrfInterface, VxlanInterface,
+    EthernetInterface, HsrInterface, InfiniBandInterface, Interface,
+    InterfaceState, InterfaceType, Interfaces, IpTunnelInterface,
+    IpVlanInterface, LinuxBridgeInterface, LoopbackInterface, MacVlanInterface,
+    MacVtapInterface, MacsecInterface, NetworkState, NmstateError,
+    OvsBridgeInterface, OvsInterface, TunInterface, UnknownInterface,
+    VlanInterface, VrfInterface, VxlanInterface, WireGuardInterface,
 };
 
 pub(crate) fn nm_retrieve(
@@ -135,9 +137,12 @@ pub(crate) fn nm_retrieve(
                 } else {
                     None
                 };
-                if let Some(mut iface) =
-                    iface_get(nm_dev, nm_conn, nm_saved_conn, lldp_neighbors)
-                {
+                if let Some(mut iface) = iface_get(
+                    nm_dev_iface_type_to_nmstate(nm_dev),
+                    nm_conn,
+                    nm_saved_conn,
+                    lldp_neighbors,
+                ) {
                     // Suppress mptcp only when MPTCP is not supported by
                     // NetworkManager, so user will not get failure when they
                     // apply the returned state.
@@ -162,8 +167,12 @@ pub(crate) fn nm_retrieve(
         .values_mut()
         .chain(net_state.interfaces.user_ifaces.values_mut())
     {
-        // Do not touch interfaces nmstate does not support yet
-        if !InterfaceType::SUPPORTED_LIST.contains(&iface.iface_type()) {
+        // Do not touch interfaces nmstate does not support yet.
+        // NetworkManager has no Geneve support, they can only be managed in
+        // kernel mode.
+        if !InterfaceType::SUPPORTED_LIST.contains(&iface.iface_type())
+            || iface.iface_type() == InterfaceType::Geneve
+        {
             if !iface.base_iface_mut().prop_list.contains(&"state") {
                 iface.base_iface_mut().prop_list.push("state");
             }
@@ -193,8 +202,8 @@ pub(crate) fn nm_retrieve(
     Ok(net_state)
 }
 
-fn nm_conn_to_base_iface(
-    nm_dev: &NmDevice,
+pub(crate) fn nm_conn_to_base_iface(
+    iface_type: InterfaceType,
     nm_conn: &NmConnection,
     nm_saved_conn: Option<&NmConnection>,
     lldp_neighbors: Option<Vec<NmLldpNeighbor>>,
@@ -219,7 +228,7 @@ fn nm_conn_to_base_iface(
             "wait_ip",
         ];
         base_iface.state = InterfaceState::Up;
-        base_iface.iface_type = nm_dev_iface_type_to_nmstate(nm_dev);
+        base_iface.iface_type = iface_type;
         if base_iface.iface_type.is_userspace() {
             // Only override iface type for user space. For other interface,
             // we trust nispor to set the correct interface type.
@@ -245,15 +254,18 @@ fn nm_conn_to_base_iface(
 
 // Applied connection does not hold OVS config, we need the NmConnection
 // used by `NmActiveConnection` also.
-fn iface_get(
-    nm_dev: &NmDevice,
+pub(crate) fn iface_get(
+    iface_type: InterfaceType,
     nm_conn: &NmConnection,
     nm_saved_conn: Option<&NmConnection>,
     lldp_neighbors: Option<Vec<NmLldpNeighbor>>,
 ) -> Option<Interface> {
-    if let Some(base_iface) =
-        nm_conn_to_base_iface(nm_dev, nm_conn, nm_saved_conn, lldp_neighbors)
-    {
+    if let Some(base_iface) = nm_conn_to_base_iface(
+        iface_type,
+        nm_conn,
+        nm_saved_conn,
+        lldp_neighbors,
+    ) {
         let iface = match &base_iface.iface_type {
             InterfaceType::LinuxBridge => Interface::LinuxBridge({
                 let mut iface = LinuxBridgeInterface::new();
@@ -298,6 +310,40 @@ fn iface_get(
                 iface.base = base_iface;
                 iface
             }),
+            InterfaceType::Gre
+            | InterfaceType::GreTap
+            | InterfaceType::IpIp
+            | InterfaceType::Sit
+            | InterfaceType::Ip6Tnl => Interface::IpTunnel({
+                let mut iface = IpTunnelInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::Macsec => Interface::Macsec({
+                let mut iface = MacsecInterface::new();
+                iface.base = base_iface;
+                // The MKA keys are only available in saved connection
+                iface.macsec = nm_saved_conn
+                    .and_then(|c| c.macsec.as_ref())
+                    .or(nm_conn.macsec.as_ref())
+                    .and_then(nm_macsec_to_nmstate);
+                iface
+            }),
+            InterfaceType::Tun => Interface::Tun({
+                let mut iface = TunInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::Hsr => Interface::Hsr({
+                let mut iface = HsrInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::IpVlan => Interface::IpVlan({
+                let mut iface = IpVlanInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
             InterfaceType::MacVlan => Interface::MacVlan({
                 let mut iface = MacVlanInterface::new();
                 iface.base = base_iface;
@@ -323,6 +369,18 @@ fn iface_get(
                 iface.base = base_iface;
                 iface
             }),
+            InterfaceType::WireGuard => Interface::WireGuard({
+                let mut iface = WireGuardInterface::new();
+                iface.base = base_iface;
+                // Kernel does not expose WireGuard keys and peers via
+                // rtnetlink, and the secrets are only available in saved
+                // connection.
+                iface.wireguard = nm_saved_conn
+                    .and_then(|c| c.wireguard.as_ref())
+                    .or(nm_conn.wireguard.as_ref())
+                    .map(nm_wireguard_to_nmstate);
+                iface
+            }),
             _ => {
                 log::debug!("Skip unsupported interface {:?}", base_iface);
                 return None;
@@ -463,6 +521,35 @@ fn nm_dev_to_nm_iface(nm_dev: &NmDevice) -> Option<Interface> {
             iface.base = base_iface;
             iface
         }),
+        InterfaceType::Gre
+        | InterfaceType::GreTap
+        | InterfaceType::IpIp
+        | InterfaceType::Sit
+        | InterfaceType::Ip6Tnl => Interface::IpTunnel({
+            let mut iface = IpTunnelInterface::new();
+            iface.base = base_iface;
+            iface
+        }),
+        InterfaceType::Macsec => Interface::Macsec({
+            let mut iface = MacsecInterface::new();
+            iface.base = base_iface;
+            iface
+        }),
+        InterfaceType::Tun => Interface::Tun({
+            let mut iface = TunInterface::new();
+            iface.base = base_iface;
+            iface
+        }),
+        InterfaceType::Hsr => Interface::Hsr({
+            let mut iface = HsrInterface::new();
+            iface.base = base_iface;
+            iface
+        }),
+        InterfaceType::IpVlan => Interface::IpVlan({
+            let mut iface = IpVlanInterface::new();
+            iface.base = base_iface;
+            iface
+        }),
         InterfaceType::MacVlan => Interface::MacVlan({
             let mut iface = MacVlanInterface::new();
             iface.base = base_iface;
@@ -489,6 +576,11 @@ fn nm_dev_to_nm_iface(nm_dev: &NmDevice) -> Option<Interface> {
                 ..Default::default()
             }
         }),
+        InterfaceType::WireGuard => Interface::WireGuard({
+            let mut iface = WireGuardInterface::new();
+            iface.base = base_iface;
+            iface
+        }),
         iface_type
             if iface_type == &InterfaceType::Other("ovs-port".to_string()) =>
         {
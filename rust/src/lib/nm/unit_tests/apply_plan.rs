@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nm::nm_dbus::{NmActiveConnection, NmConnection};
+use crate::nm::query_apply::gen_nm_apply_plan;
+use crate::{ApplyPlanActionKind, ErrorKind, MergedNetworkState, NetworkState};
+
+const ETH1_UUID: &str = "8aca0200-accc-4d13-a62f-3c89a6da53c5";
+const DUMMY0_UUID: &str = "1c646761-efcc-4d33-a0d9-cb3c1c2d3309";
+
+fn gen_exist_nm_conns() -> Vec<NmConnection> {
+    let mut eth1 = NmConnection::from_keyfile(&format!(
+        r#"
+[connection]
+id=eth1
+uuid={ETH1_UUID}
+type=ethernet
+interface-name=eth1
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+    ))
+    .unwrap();
+    eth1.obj_path = "/org/freedesktop/NetworkManager/Settings/1".to_string();
+    let mut dummy0 = NmConnection::from_keyfile(&format!(
+        r#"
+[connection]
+id=dummy0
+uuid={DUMMY0_UUID}
+type=dummy
+interface-name=dummy0
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+    ))
+    .unwrap();
+    dummy0.obj_path = "/org/freedesktop/NetworkManager/Settings/2".to_string();
+    vec![eth1, dummy0]
+}
+
+fn gen_nm_acs() -> Vec<NmActiveConnection> {
+    vec![NmActiveConnection {
+        uuid: ETH1_UUID.to_string(),
+        iface_type: "802-3-ethernet".to_string(),
+        iface_name: "eth1".to_string(),
+        ..Default::default()
+    }]
+}
+
+#[test]
+fn test_nm_apply_plan() {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+- name: dummy0
+  type: dummy
+  state: up
+"#,
+    )
+    .unwrap();
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+- name: dummy0
+  type: dummy
+  state: absent
+- name: dummy1
+  type: dummy
+  state: up
+"#,
+    )
+    .unwrap();
+    let merged_state =
+        MergedNetworkState::new(desired, current, false, false).unwrap();
+
+    let plan = gen_nm_apply_plan(
+        &merged_state,
+        &gen_exist_nm_conns(),
+        &gen_nm_acs(),
+        &[],
+        true,
+        false,
+    )
+    .unwrap()
+    .to_actions(true)
+    .unwrap();
+
+    let actions: Vec<(ApplyPlanActionKind, Option<&str>)> =
+        plan.iter().map(|a| (a.action, a.name.as_deref())).collect();
+    assert_eq!(
+        actions,
+        vec![
+            (ApplyPlanActionKind::DeleteProfile, Some("dummy0")),
+            (ApplyPlanActionKind::AddProfile, Some("dummy1")),
+            (ApplyPlanActionKind::ModifyProfile, Some("eth1")),
+            (ApplyPlanActionKind::Activate, Some("dummy1")),
+            (ApplyPlanActionKind::Reapply, Some("eth1")),
+        ]
+    );
+    assert_eq!(plan[0].uuid.as_deref(), Some(DUMMY0_UUID));
+    assert_eq!(plan[2].uuid.as_deref(), Some(ETH1_UUID));
+    assert_eq!(plan[4].uuid.as_deref(), Some(ETH1_UUID));
+    #[cfg(feature = "gen_conf")]
+    assert!(plan[2]
+        .keyfile
+        .as_deref()
+        .map(|k| k.contains("mtu=9000"))
+        .unwrap_or_default());
+}
+
+#[test]
+fn test_nm_apply_plan_memory_only() {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: dummy0
+  type: dummy
+  state: up
+"#,
+    )
+    .unwrap();
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: dummy0
+  type: dummy
+  state: absent
+"#,
+    )
+    .unwrap();
+    let merged_state =
+        MergedNetworkState::new(desired, current, false, true).unwrap();
+
+    let plan = gen_nm_apply_plan(
+        &merged_state,
+        &gen_exist_nm_conns(),
+        &gen_nm_acs(),
+        &[],
+        true,
+        false,
+    )
+    .unwrap()
+    .to_actions(true)
+    .unwrap();
+
+    assert!(plan
+        .iter()
+        .all(|a| a.action != ApplyPlanActionKind::DeleteProfile));
+}
+
+fn gen_vxlan_fdb_merged_state(fdb_remotes: &str) -> MergedNetworkState {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: vxlan1
+  type: vxlan
+  state: up
+  vxlan:
+    base-iface: eth1
+    id: 101
+    destination-port: 4789
+    fdb-remotes:
+    - 192.0.2.2
+"#,
+    )
+    .unwrap();
+    let desired: NetworkState = serde_yaml::from_str(&format!(
+        r#"---
+interfaces:
+- name: vxlan1
+  type: vxlan
+  state: up
+  mtu: 1400
+  vxlan:
+    base-iface: eth1
+    id: 101
+    destination-port: 4789
+    fdb-remotes: {fdb_remotes}
+"#
+    ))
+    .unwrap();
+    MergedNetworkState::new(desired, current, false, false).unwrap()
+}
+
+#[test]
+fn test_nm_apply_plan_vxlan_fdb_remotes_changed() {
+    let merged_state = gen_vxlan_fdb_merged_state("[192.0.2.3]");
+
+    let result = gen_nm_apply_plan(&merged_state, &[], &[], &[], true, false);
+
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
+
+#[test]
+fn test_nm_apply_plan_vxlan_fdb_remotes_unchanged() {
+    let merged_state = gen_vxlan_fdb_merged_state("[192.0.2.2]");
+
+    assert!(
+        gen_nm_apply_plan(&merged_state, &[], &[], &[], true, false).is_ok()
+    );
+}
+
+#[test]
+fn test_nm_apply_plan_hide_secrets() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: wg0
+  type: wireguard
+  state: up
+  wireguard:
+    private-key: yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+    peers:
+    - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+      preshared-key: FpCyhws9cxwWoV4xELtfJvjJN+zQVRPISllRWgeopVE=
+- name: macsec0
+  type: macsec
+  state: up
+  macsec:
+    base-iface: eth1
+    encrypt: true
+    port: 1
+    validation: strict
+    send-sci: true
+    mka-cak: 50b71a8ef0bd5751ea76de6d6c98c03a
+    mka-ckn: f2b4297d39da7330910a74abc0449feb45b5c0b9fc23df1430e1898fcf1c4550
+"#,
+    )
+    .unwrap();
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    let merged_state =
+        MergedNetworkState::new(desired, current, false, false).unwrap();
+    let plan =
+        gen_nm_apply_plan(&merged_state, &[], &[], &[], true, false).unwrap();
+
+    let keyfiles: Vec<String> = plan
+        .to_actions(false)
+        .unwrap()
+        .into_iter()
+        .filter_map(|a| a.keyfile)
+        .collect();
+    assert!(keyfiles.iter().any(|k| k.contains("[wireguard]")));
+    assert!(keyfiles.iter().any(|k| k.contains("[macsec]")));
+    for secret in [
+        "yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=",
+        "FpCyhws9cxwWoV4xELtfJvjJN+zQVRPISllRWgeopVE=",
+        "50b71a8ef0bd5751ea76de6d6c98c03a",
+        "f2b4297d39da7330910a74abc0449feb45b5c0b9fc23df1430e1898fcf1c4550",
+    ] {
+        assert!(keyfiles.iter().all(|k| !k.contains(secret)));
+    }
+
+    let keyfiles: Vec<String> = plan
+        .to_actions(true)
+        .unwrap()
+        .into_iter()
+        .filter_map(|a| a.keyfile)
+        .collect();
+    assert!(keyfiles.iter().any(|k| k
+        .contains("private-key=yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=")));
+}
+
+#[test]
+fn test_nm_apply_plan_nexthop_not_supported() {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+nexthops:
+  config:
+  - id: 1
+    gateway: 192.0.2.1
+    device: eth1
+"#,
+    )
+    .unwrap();
+    let merged_state =
+        MergedNetworkState::new(desired, current, false, false).unwrap();
+
+    let result = gen_nm_apply_plan(&merged_state, &[], &[], &[], true, false);
+
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
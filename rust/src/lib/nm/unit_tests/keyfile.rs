@@ -0,0 +1,661 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::nm::nm_dbus::NmConnection;
+use crate::{
+    nm::nm_keyfiles_to_nmstate, BondMode, BridgePortTunkTag,
+    BridgePortVlanMode, HsrProtocol, Interface, InterfaceType, IpVlanMode,
+    MacsecValidation, RouteRulePortRange, RouteRuleUidRange, RouteScope,
+    RouteType, TunMode, VlanQosMapping,
+};
+
+const ETH1_KEYFILE: &str = r#"
+# Comment should be ignored
+[connection]
+id=eth1
+uuid=8aca0200-accc-4d13-a62f-3c89a6da53c5
+type=ethernet
+interface-name=eth1
+
+[ethernet]
+mtu=1400
+
+[ipv4]
+method=manual
+address1=192.0.2.2/24,192.0.2.1
+address2=198.51.100.2/24
+dns=192.0.2.53;
+dns-search=example.com;
+route1=203.0.113.0/24,192.0.2.254,150
+route1_options=table=100
+routing-rule1=priority 1000 from 192.0.2.0/24 table 100
+
+[ipv6]
+method=disabled
+"#;
+
+#[test]
+fn test_nm_keyfile_parse_static_ip() {
+    let nm_conn = NmConnection::from_keyfile(ETH1_KEYFILE).unwrap();
+
+    assert_eq!(nm_conn.iface_name(), Some("eth1"));
+    assert_eq!(nm_conn.iface_type(), Some("802-3-ethernet"));
+    assert_eq!(nm_conn.wired.as_ref().and_then(|s| s.mtu), Some(1400));
+
+    let nm_ip_set = nm_conn.ipv4.as_ref().unwrap();
+    assert_eq!(
+        nm_ip_set.addresses,
+        vec!["192.0.2.2/24".to_string(), "198.51.100.2/24".to_string()]
+    );
+    assert_eq!(nm_ip_set.gateway.as_deref(), Some("192.0.2.1"));
+    assert_eq!(nm_ip_set.dns, Some(vec!["192.0.2.53".to_string()]));
+    assert_eq!(nm_ip_set.routes.len(), 1);
+    assert_eq!(nm_ip_set.routes[0].dest.as_deref(), Some("203.0.113.0"));
+    assert_eq!(nm_ip_set.routes[0].prefix, Some(24));
+    assert_eq!(nm_ip_set.routes[0].metric, Some(150));
+    assert_eq!(nm_ip_set.routes[0].table, Some(100));
+    assert_eq!(nm_ip_set.route_rules.len(), 1);
+    assert_eq!(nm_ip_set.route_rules[0].priority, Some(1000));
+    assert_eq!(nm_ip_set.route_rules[0].from.as_deref(), Some("192.0.2.0"));
+    assert_eq!(nm_ip_set.route_rules[0].from_len, Some(24));
+    assert_eq!(nm_ip_set.route_rules[0].table, Some(100));
+}
+
+#[test]
+fn test_nm_keyfile_parse_invalid_line() {
+    let result = NmConnection::from_keyfile("[connection]\nid\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_nm_keyfile_parse_invalid_route() {
+    let result = NmConnection::from_keyfile(
+        "[connection]\nid=eth1\n[ipv4]\nroute1=192.0.2.0/33\n",
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_nm_keyfile_parse_escaped_value() {
+    let nm_conn = NmConnection::from_keyfile(
+        "[connection]\n\
+        id=  \\sMy\\tconn\\\\1 \n\
+        type=ethernet\n\
+        interface-name=eth1\n\
+        [ipv4]\n\
+        method=auto\n\
+        dns-search=example\\;com;\\sexample.org;\n",
+    )
+    .unwrap();
+
+    assert_eq!(nm_conn.id(), Some(" My\tconn\\1 "));
+    assert_eq!(
+        nm_conn.ipv4.as_ref().unwrap().dns_search,
+        Some(vec!["example;com".to_string(), " example.org".to_string()])
+    );
+}
+
+#[test]
+fn test_nm_keyfile_escape_round_trip() {
+    let nm_conn = NmConnection::from_keyfile(
+        "[connection]\n\
+        id=\\s eth1\\nnew line\\r\\\\\n\
+        type=ethernet\n\
+        interface-name=eth1\n\
+        [ipv4]\n\
+        method=auto\n\
+        dns-search=a\\;b;\\sc\\\\;\n",
+    )
+    .unwrap();
+    assert_eq!(nm_conn.id(), Some("  eth1\nnew line\r\\"));
+
+    let keyfile = nm_conn.to_keyfile().unwrap();
+    assert!(keyfile.contains("id=\\s eth1\\nnew line\\r\\\\\n"));
+    assert!(keyfile.contains("dns-search=a\\;b;\\sc\\\\\n"));
+
+    let new_nm_conn = NmConnection::from_keyfile(&keyfile).unwrap();
+    assert_eq!(new_nm_conn.id(), nm_conn.id());
+    assert_eq!(
+        new_nm_conn.ipv4.as_ref().unwrap().dns_search,
+        Some(vec!["a;b".to_string(), " c\\".to_string()])
+    );
+}
+
+#[test]
+fn test_nm_keyfile_parse_wireguard() {
+    let nm_conn = NmConnection::from_keyfile(
+        r#"
+[connection]
+id=wg0
+uuid=4b6d8b1e-43e1-4a7c-9b1a-3d8b2f43a1c5
+type=wireguard
+interface-name=wg0
+
+[wireguard]
+listen-port=51820
+private-key=yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+
+[wireguard-peer.gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=]
+endpoint=198.51.100.1:51820
+allowed-ips=192.0.2.0/24;2001:db8::/64;
+persistent-keepalive=25
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(nm_conn.iface_type(), Some("wireguard"));
+    let wg_set = nm_conn.wireguard.as_ref().unwrap();
+    assert_eq!(wg_set.listen_port, Some(51820));
+    assert_eq!(
+        wg_set.private_key.as_deref(),
+        Some("yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=")
+    );
+    let peers = wg_set.peers.as_ref().unwrap();
+    assert_eq!(peers.len(), 1);
+    assert_eq!(
+        peers[0].public_key.as_deref(),
+        Some("gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=")
+    );
+    assert_eq!(peers[0].endpoint.as_deref(), Some("198.51.100.1:51820"));
+    assert_eq!(
+        peers[0].allowed_ips,
+        Some(vec![
+            "192.0.2.0/24".to_string(),
+            "2001:db8::/64".to_string()
+        ])
+    );
+    assert_eq!(peers[0].persistent_keepalive, Some(25));
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_ip_tunnel() {
+    let net_state = nm_keyfiles_to_nmstate(&[r#"
+[connection]
+id=gre1
+uuid=0d2ae2e9-8e4e-4d0e-9f4b-7a3e0d0c2c51
+type=ip-tunnel
+interface-name=gre1
+
+[ip-tunnel]
+mode=2
+parent=eth1
+local=192.0.2.1
+remote=198.51.100.1
+input-key=100
+output-key=100
+ttl=64
+mtu=1400
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+    .to_string()])
+    .unwrap();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("gre1").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::Gre);
+    assert_eq!(iface.base_iface().mtu, Some(1400));
+    let conf = if let Interface::IpTunnel(iface) = iface {
+        iface.ip_tunnel.as_ref().unwrap()
+    } else {
+        panic!("Expecting IP tunnel interface, but got {:?}", iface);
+    };
+    assert_eq!(conf.base_iface.as_deref(), Some("eth1"));
+    assert_eq!(conf.local, Some("192.0.2.1".parse().unwrap()));
+    assert_eq!(conf.remote, Some("198.51.100.1".parse().unwrap()));
+    assert_eq!(conf.key, Some(100));
+    assert_eq!(conf.ttl, Some(64));
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_macsec() {
+    let net_state = nm_keyfiles_to_nmstate(&[
+        ETH1_KEYFILE.to_string(),
+        r#"
+[connection]
+id=macsec0
+uuid=4c7d2d0c-3a4e-4f8e-b3a6-1c7c3e9f5b21
+type=macsec
+interface-name=macsec0
+
+[macsec]
+mode=0
+parent=8aca0200-accc-4d13-a62f-3c89a6da53c5
+port=10
+encrypt=false
+validation=1
+send-sci=true
+mka-cak=50b71a8ef0bd5751ea76de6d6c98c03a
+mka-ckn=f2b4297d39da7330910a74abc0449feb
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+        .to_string(),
+    ])
+    .unwrap();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("macsec0").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::Macsec);
+    let conf = if let Interface::Macsec(iface) = iface {
+        iface.macsec.as_ref().unwrap()
+    } else {
+        panic!("Expecting MACsec interface, but got {:?}", iface);
+    };
+    assert_eq!(conf.base_iface, "eth1");
+    assert_eq!(conf.port, Some(10));
+    assert_eq!(conf.encrypt, Some(false));
+    assert_eq!(conf.validation, Some(MacsecValidation::Check));
+    assert_eq!(conf.send_sci, Some(true));
+    assert_eq!(
+        conf.mka_cak.as_deref(),
+        Some("50b71a8ef0bd5751ea76de6d6c98c03a")
+    );
+    assert_eq!(
+        conf.mka_ckn.as_deref(),
+        Some("f2b4297d39da7330910a74abc0449feb")
+    );
+}
+
+#[test]
+fn test_nm_keyfiles_to_nmstate() {
+    let keyfiles = vec![
+        ETH1_KEYFILE.to_string(),
+        r#"
+[connection]
+id=bond0
+uuid=1c646761-efcc-4d33-a0d9-cb3c1c2d3309
+type=bond
+interface-name=bond0
+
+[bond]
+mode=active-backup
+miimon=140
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+        .to_string(),
+        r#"
+[connection]
+id=eth2
+uuid=06935474-b8d3-4e7c-be52-48e2e6e6b3b9
+type=ethernet
+interface-name=eth2
+controller=1c646761-efcc-4d33-a0d9-cb3c1c2d3309
+port-type=bond
+"#
+        .to_string(),
+        r#"
+[connection]
+id=br0
+uuid=3c80d8de-a6d7-47da-b0b3-47d2b1052fe5
+type=bridge
+interface-name=br0
+
+[bridge]
+stp=false
+"#
+        .to_string(),
+        r#"
+[connection]
+id=eth3
+type=ethernet
+interface-name=eth3
+master=br0
+slave-type=bridge
+
+[bridge-port]
+vlans=10 pvid untagged,100,200-299
+"#
+        .to_string(),
+    ];
+
+    let net_state = nm_keyfiles_to_nmstate(&keyfiles).unwrap();
+
+    let eth1 = net_state.interfaces.kernel_ifaces.get("eth1").unwrap();
+    assert_eq!(eth1.iface_type(), InterfaceType::Ethernet);
+    assert_eq!(eth1.base_iface().mtu, Some(1400));
+    let ipv4 = eth1.base_iface().ipv4.as_ref().unwrap();
+    assert_eq!(ipv4.addresses.as_ref().map(|a| a.len()), Some(2));
+
+    let routes = net_state.routes.config.as_ref().unwrap();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].destination.as_deref(), Some("0.0.0.0/0"));
+    assert_eq!(routes[0].next_hop_addr.as_deref(), Some("192.0.2.1"));
+    assert_eq!(routes[1].destination.as_deref(), Some("203.0.113.0/24"));
+    assert_eq!(routes[1].next_hop_iface.as_deref(), Some("eth1"));
+    assert_eq!(routes[1].table_id, Some(100));
+    assert_eq!(net_state.rules.config.as_ref().map(|r| r.len()), Some(1));
+    assert_eq!(
+        net_state
+            .dns
+            .config
+            .as_ref()
+            .and_then(|c| c.server.as_deref()),
+        Some(vec!["192.0.2.53".to_string()].as_slice())
+    );
+
+    if let Some(Interface::Bond(bond0)) =
+        net_state.interfaces.kernel_ifaces.get("bond0")
+    {
+        let bond_conf = bond0.bond.as_ref().unwrap();
+        assert_eq!(bond_conf.mode, Some(BondMode::ActiveBackup));
+        assert_eq!(
+            bond_conf.options.as_ref().and_then(|o| o.miimon),
+            Some(140)
+        );
+        assert_eq!(bond_conf.port, Some(vec!["eth2".to_string()]));
+    } else {
+        panic!("Bond interface bond0 not found");
+    }
+
+    if let Some(Interface::LinuxBridge(br0)) =
+        net_state.interfaces.kernel_ifaces.get("br0")
+    {
+        let ports = br0.bridge.as_ref().unwrap().port.as_ref().unwrap();
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].name, "eth3");
+        let vlan_conf = ports[0].vlan.as_ref().unwrap();
+        assert_eq!(vlan_conf.mode, Some(BridgePortVlanMode::Trunk));
+        assert_eq!(vlan_conf.enable_native, Some(true));
+        assert_eq!(vlan_conf.tag, Some(10));
+        let trunk_tags = vlan_conf.trunk_tags.as_ref().unwrap();
+        assert_eq!(trunk_tags[0], BridgePortTunkTag::Id(100));
+        if let BridgePortTunkTag::IdRange(range) = &trunk_tags[1] {
+            assert_eq!((range.min, range.max), (200, 299));
+        } else {
+            panic!("Expecting VLAN range, got {:?}", trunk_tags[1]);
+        }
+    } else {
+        panic!("Linux bridge interface br0 not found");
+    }
+
+    let eth2 = net_state.interfaces.kernel_ifaces.get("eth2").unwrap();
+    assert_eq!(eth2.base_iface().controller, None);
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_ip_vlan() {
+    let net_state = nm_keyfiles_to_nmstate(&[
+        ETH1_KEYFILE.to_string(),
+        r#"
+[connection]
+id=ipvlan0
+uuid=0f1bb6c6-3f1e-4b0e-9d1c-6a1a5e3e9b7d
+type=ipvlan
+interface-name=ipvlan0
+
+[ipvlan]
+mode=3
+parent=8aca0200-accc-4d13-a62f-3c89a6da53c5
+private=true
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+        .to_string(),
+    ])
+    .unwrap();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("ipvlan0").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::IpVlan);
+    let conf = if let Interface::IpVlan(iface) = iface {
+        iface.ipvlan.as_ref().unwrap()
+    } else {
+        panic!("Expecting IP VLAN interface, but got {:?}", iface);
+    };
+    assert_eq!(conf.base_iface, "eth1");
+    assert_eq!(conf.mode, Some(IpVlanMode::L3s));
+    assert_eq!(conf.private, Some(true));
+    assert_eq!(conf.vepa, None);
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_tun() {
+    let net_state = nm_keyfiles_to_nmstate(&[r#"
+[connection]
+id=tap0
+uuid=5d2f4a1e-8f0b-4a6c-9b1e-2f3c4d5e6f70
+type=tun
+interface-name=tap0
+
+[tun]
+mode=2
+owner=107
+group=108
+multi-queue=true
+vnet-hdr=false
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+    .to_string()])
+    .unwrap();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("tap0").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::Tun);
+    let conf = if let Interface::Tun(iface) = iface {
+        iface.tun.as_ref().unwrap()
+    } else {
+        panic!("Expecting TUN interface, but got {:?}", iface);
+    };
+    assert_eq!(conf.mode, Some(TunMode::Tap));
+    assert_eq!(conf.owner, Some(107));
+    assert_eq!(conf.group, Some(108));
+    assert_eq!(conf.multi_queue, Some(true));
+    assert_eq!(conf.vnet_hdr, Some(false));
+    assert_eq!(conf.persist, Some(true));
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_hsr() {
+    let net_state = nm_keyfiles_to_nmstate(&[r#"
+[connection]
+id=hsr0
+uuid=0c7e5a4b-3f6d-4e2a-8b1c-9d0e1f2a3b4c
+type=hsr
+interface-name=hsr0
+
+[hsr]
+port1=eth1
+port2=eth2
+multicast-spec=42
+prp=false
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+    .to_string()])
+    .unwrap();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("hsr0").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::Hsr);
+    let conf = if let Interface::Hsr(iface) = iface {
+        iface.hsr.as_ref().unwrap()
+    } else {
+        panic!("Expecting HSR interface, but got {:?}", iface);
+    };
+    assert_eq!(conf.port1, "eth1");
+    assert_eq!(conf.port2, "eth2");
+    assert_eq!(
+        conf.supervision_address.as_deref(),
+        Some("01:15:4E:00:01:2A")
+    );
+    assert_eq!(conf.protocol, Some(HsrProtocol::Hsr));
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_vlan_qos_map_and_flags() {
+    let net_state = nm_keyfiles_to_nmstate(&[
+        ETH1_KEYFILE.to_string(),
+        r#"
+[connection]
+id=eth1.101
+uuid=7c3e5f1a-2b4d-4e6f-8a9b-0c1d2e3f4a5b
+type=vlan
+interface-name=eth1.101
+
+[vlan]
+id=101
+parent=eth1
+flags=9
+ingress-priority-map=1:2;3:4;
+egress-priority-map=5:6
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=disabled
+"#
+        .to_string(),
+    ])
+    .unwrap();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("eth1.101").unwrap();
+    let conf = if let Interface::Vlan(iface) = iface {
+        iface.vlan.as_ref().unwrap()
+    } else {
+        panic!("Expecting VLAN interface, but got {:?}", iface);
+    };
+    assert_eq!(conf.id, 101);
+    assert_eq!(conf.reorder_headers, Some(true));
+    assert_eq!(conf.gvrp, Some(false));
+    assert_eq!(conf.mvrp, Some(true));
+    assert_eq!(conf.loose_binding, Some(false));
+    assert_eq!(
+        conf.ingress_qos_map,
+        Some(vec![VlanQosMapping::new(1, 2), VlanQosMapping::new(3, 4)])
+    );
+    assert_eq!(conf.egress_qos_map, Some(vec![VlanQosMapping::new(5, 6)]));
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_route_type() {
+    let net_state = nm_keyfiles_to_nmstate(&[r#"
+[connection]
+id=lo
+uuid=e40b7973-b220-5450-bc07-1d87edc4aff2
+type=loopback
+interface-name=lo
+
+[ipv4]
+method=manual
+address1=127.0.0.1/8
+route1=198.51.100.0/24
+route1_options=table=254,type=blackhole
+
+[ipv6]
+method=disabled
+"#
+    .to_string()])
+    .unwrap();
+
+    let routes = net_state.routes.config.unwrap();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].destination.as_deref(), Some("198.51.100.0/24"));
+    assert_eq!(routes[0].route_type, Some(RouteType::Blackhole));
+    assert_eq!(routes[0].next_hop_iface, None);
+}
+
+#[test]
+fn test_nm_keyfile_to_nmstate_route_extended_attributes() {
+    let net_state = nm_keyfiles_to_nmstate(&[r#"
+[connection]
+id=eth1
+uuid=8aca0200-accc-4d13-a62f-3c89a6da53c5
+type=ethernet
+interface-name=eth1
+
+[ipv4]
+method=manual
+address1=192.0.2.2/24
+route1=198.51.100.0/24,192.0.2.1
+route1_options=src=192.0.2.2,mtu=1400,advmss=1360,initcwnd=20,initrwnd=30,onlink=true,scope=200,quickack=false
+
+[ipv6]
+method=disabled
+"#
+    .to_string()])
+    .unwrap();
+
+    let routes = net_state.routes.config.unwrap();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].source.as_deref(), Some("192.0.2.2"));
+    assert_eq!(routes[0].mtu, Some(1400));
+    assert_eq!(routes[0].advmss, Some(1360));
+    assert_eq!(routes[0].initcwnd, Some(20));
+    assert_eq!(routes[0].initrwnd, Some(30));
+    assert_eq!(routes[0].onlink, Some(true));
+    assert_eq!(routes[0].scope, Some(RouteScope::Site));
+    assert_eq!(routes[0].quickack, Some(false));
+}
+
+#[test]
+fn test_nm_keyfile_route_rule_selectors() {
+    let rule_str = "priority 100 not from 192.0.2.0/24 tos 0x10 ipproto 6 \
+        sport 1024-65000 dport 443 iif eth1 oif eth2 uidrange 1000-1999 \
+        suppress_prefixlength 0 table 100";
+    let keyfile = format!(
+        r#"
+[connection]
+id=eth1
+uuid=8aca0200-accc-4d13-a62f-3c89a6da53c5
+type=ethernet
+interface-name=eth1
+
+[ipv4]
+method=manual
+address1=192.0.2.2/24
+routing-rule1={rule_str}
+
+[ipv6]
+method=disabled
+"#
+    );
+    let nm_conn = NmConnection::from_keyfile(&keyfile).unwrap();
+
+    let nm_rules = &nm_conn.ipv4.as_ref().unwrap().route_rules;
+    assert_eq!(nm_rules.len(), 1);
+    assert_eq!(
+        nm_rules[0].to_keyfile().get(""),
+        Some(&rule_str.to_string())
+    );
+
+    let net_state = nm_keyfiles_to_nmstate(&[keyfile]).unwrap();
+    let rules = net_state.rules.config.unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].invert, Some(true));
+    assert_eq!(rules[0].tos, Some(0x10));
+    assert_eq!(rules[0].ip_proto, Some(6));
+    assert_eq!(rules[0].sport, Some(RouteRulePortRange::new(1024, 65000)));
+    assert_eq!(rules[0].dport, Some(RouteRulePortRange::new(443, 443)));
+    assert_eq!(rules[0].iif.as_deref(), Some("eth1"));
+    assert_eq!(rules[0].oif.as_deref(), Some("eth2"));
+    assert_eq!(rules[0].uid_range, Some(RouteRuleUidRange::new(1000, 1999)));
+    assert_eq!(rules[0].suppress_prefix_length, Some(0));
+}
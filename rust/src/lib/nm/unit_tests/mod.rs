@@ -1,2 +1,6 @@
+#[cfg(all(test, feature = "query_apply"))]
+mod apply_plan;
+#[cfg(all(test, feature = "query_apply"))]
+mod keyfile;
 #[cfg(test)]
 mod profiles;
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::MergedNetworkState;
+
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
 #[non_exhaustive]
 pub struct OvsDbGlobalConfig {
@@ -210,3 +212,36 @@ impl MergedOvsDbGlobalConfig {
         }
     }
 }
+
+impl MergedNetworkState {
+    // Sine desire `ovsdb: {}` means remove all, we cannot
+    // differentiate it with `ovsdb` not defined due to `serde(default)`.
+    // Hence we need to check `MergedNetworkState.prop_list`.
+    pub(crate) fn is_global_ovsdb_changed(&self) -> bool {
+        if self.prop_list.contains(&"ovsdb") {
+            if self.ovsdb.desired.is_none() {
+                true
+            } else {
+                let cur_external_ids = self
+                    .ovsdb
+                    .current
+                    .external_ids
+                    .as_ref()
+                    .cloned()
+                    .unwrap_or_default();
+                let cur_other_config = self
+                    .ovsdb
+                    .current
+                    .other_config
+                    .as_ref()
+                    .cloned()
+                    .unwrap_or_default();
+
+                self.ovsdb.external_ids != cur_external_ids
+                    || self.ovsdb.other_config != cur_other_config
+            }
+        } else {
+            false
+        }
+    }
+}
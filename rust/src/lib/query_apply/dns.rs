@@ -1,14 +1,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{DnsState, ErrorKind, MergedDnsState, NmstateError};
+use crate::{DnsState, MergedDnsState, NmstateError, VerificationMismatch};
 
 impl MergedDnsState {
+    #[cfg(test)]
     pub(crate) fn verify(
         &self,
         current: &DnsState,
     ) -> Result<(), NmstateError> {
+        crate::error::mismatches_to_result(self.collect_mismatches(current)?)
+    }
+
+    pub(crate) fn collect_mismatches(
+        &self,
+        current: &DnsState,
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
+        let mut ret = Vec::new();
         if !self.is_changed() {
-            return Ok(());
+            return Ok(ret);
         }
         let mut current = current.clone();
         current.sanitize().ok();
@@ -29,39 +38,37 @@ impl MergedDnsState {
         let cur_conf = if let Some(c) = current.config.as_ref() {
             c
         } else {
-            return Err(NmstateError::new(
-                ErrorKind::VerificationError,
-                "Current DNS config is empty".to_string(),
+            ret.push(VerificationMismatch::new(
+                "dns-resolver.config".to_string(),
+                serde_json::json!({
+                    "server": self.servers,
+                    "search": self.searches,
+                })
+                .to_string(),
+                "null".to_string(),
             ));
+            return Ok(ret);
         };
 
         if cur_srvs != self.servers
             && !(cur_conf.server.is_none() && self.servers.is_empty())
         {
-            return Err(NmstateError::new(
-                ErrorKind::VerificationError,
-                format!(
-                    "Failed to apply DNS config: desire name servers '{}', \
-                    got '{}'",
-                    self.servers.as_slice().join(" "),
-                    cur_srvs.as_slice().join(" "),
-                ),
+            ret.push(VerificationMismatch::new(
+                "dns-resolver.config.server".to_string(),
+                serde_json::to_string(&self.servers)?,
+                serde_json::to_string(&cur_srvs)?,
             ));
         }
 
         if cur_schs != self.searches
             && !(cur_conf.search.is_none() && self.searches.is_empty())
         {
-            return Err(NmstateError::new(
-                ErrorKind::VerificationError,
-                format!(
-                    "Failed to apply DNS config: desire searches '{}',
-                    got '{}'",
-                    self.searches.as_slice().join(" "),
-                    cur_schs.as_slice().join(" "),
-                ),
+            ret.push(VerificationMismatch::new(
+                "dns-resolver.config.search".to_string(),
+                serde_json::to_string(&self.searches)?,
+                serde_json::to_string(&cur_schs)?,
             ));
         }
-        Ok(())
+        Ok(ret)
     }
 }
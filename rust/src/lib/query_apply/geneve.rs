@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::iface::update_iface_conf;
+use crate::{GeneveConfig, GeneveInterface};
+
+impl GeneveInterface {
+    pub(crate) fn update_geneve(&mut self, other: &GeneveInterface) {
+        update_iface_conf(
+            &mut self.geneve,
+            other.geneve.as_ref(),
+            GeneveConfig::update,
+        );
+    }
+}
+
+impl GeneveConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.id = other.id;
+            self.remote = other.remote;
+            self.ttl = other.ttl;
+            self.tos = other.tos;
+            self.dst_port = other.dst_port;
+            self.external = other.external;
+        }
+    }
+}
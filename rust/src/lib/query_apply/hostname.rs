@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ErrorKind, HostNameState, MergedHostNameState, NmstateError};
+use crate::{
+    ErrorKind, HostNameState, MergedHostNameState, NmstateError,
+    VerificationMismatch,
+};
 
 impl HostNameState {
     pub(crate) fn update(&mut self, other: &Self) {
@@ -14,14 +17,15 @@ impl HostNameState {
 }
 
 impl MergedHostNameState {
-    pub(crate) fn verify(
+    pub(crate) fn collect_mismatches(
         &self,
         current: Option<&HostNameState>,
-    ) -> Result<(), NmstateError> {
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
+        let mut ret = Vec::new();
         let desired = if let Some(d) = &self.desired {
             d
         } else {
-            return Ok(());
+            return Ok(ret);
         };
         let current = if let Some(c) = current {
             c
@@ -36,35 +40,23 @@ impl MergedHostNameState {
 
         if let Some(running) = desired.running.as_ref() {
             if Some(running) != current.running.as_ref() {
-                let e = NmstateError::new(
-                    ErrorKind::VerificationError,
-                    format!(
-                        "Verification fail, desire hostname.running: \
-                        {}, current: {:?}",
-                        running,
-                        current.running.as_ref()
-                    ),
-                );
-                log::error!("{}", e);
-                return Err(e);
+                ret.push(VerificationMismatch::new(
+                    "hostname.running".to_string(),
+                    serde_json::to_string(running)?,
+                    serde_json::to_string(&current.running)?,
+                ));
             }
         }
         if let Some(config) = desired.config.as_ref() {
             if Some(config) != current.config.as_ref() {
-                let e = NmstateError::new(
-                    ErrorKind::VerificationError,
-                    format!(
-                        "Verification fail, desire hostname.config: \
-                        {}, current: {:?}",
-                        config,
-                        current.config.as_ref()
-                    ),
-                );
-                log::error!("{}", e);
-                return Err(e);
+                ret.push(VerificationMismatch::new(
+                    "hostname.config".to_string(),
+                    serde_json::to_string(config)?,
+                    serde_json::to_string(&current.config)?,
+                ));
             }
         }
 
-        Ok(())
+        Ok(ret)
     }
 }
@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::iface::update_iface_conf;
+use crate::{HsrConfig, HsrInterface};
+
+impl HsrInterface {
+    pub(crate) fn update_hsr(&mut self, other: &HsrInterface) {
+        update_iface_conf(&mut self.hsr, other.hsr.as_ref(), HsrConfig::update);
+    }
+}
+
+impl HsrConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.port1 = other.port1.clone();
+            self.port2 = other.port2.clone();
+            if other.supervision_address.is_some() {
+                self.supervision_address = other.supervision_address.clone();
+            }
+            if other.protocol.is_some() {
+                self.protocol = other.protocol;
+            }
+            if other.version.is_some() {
+                self.version = other.version;
+            }
+        }
+    }
+}
@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    state::get_json_value_difference, ErrorKind, Interface, InterfaceType,
-    LinuxBridgeInterface, NmstateError,
+    state::get_json_value_differences, ErrorKind, Interface, InterfaceType,
+    LinuxBridgeInterface, NmstateError, VerificationMismatch,
 };
 
 impl Interface {
@@ -25,14 +25,20 @@ impl Interface {
         }
     }
 
-    pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+    // Return all the properties of desired which are different from
+    // current.
+    pub(crate) fn collect_mismatches(
+        &self,
+        current: &Self,
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
         let mut current = current.clone();
         self.process_allow_extra_address(&mut current);
 
         let self_value = serde_json::to_value(self)?;
         let current_value = serde_json::to_value(&current)?;
 
-        if let Some((reference, desire, current)) = get_json_value_difference(
+        let mut ret = Vec::new();
+        for (reference, desire, current) in get_json_value_differences(
             format!("{}.interface", self.name()),
             &self_value,
             &current_value,
@@ -65,17 +71,13 @@ impl Interface {
                     return Err(e);
                 }
             }
-
-            Err(NmstateError::new(
-                ErrorKind::VerificationError,
-                format!(
-                    "Verification failure: {reference} desire '{desire}', \
-                    current '{current}'"
-                ),
-            ))
-        } else {
-            Ok(())
+            ret.push(VerificationMismatch::new(
+                reference,
+                desire.to_string(),
+                current.to_string(),
+            ));
         }
+        Ok(ret)
     }
 
     pub fn update(&mut self, other: &Interface) {
@@ -206,13 +208,101 @@ impl Interface {
                     );
                 }
             }
+            Self::WireGuard(iface) => {
+                if let Self::WireGuard(other_iface) = other {
+                    iface.update_wireguard(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::IpTunnel(iface) => {
+                if let Self::IpTunnel(other_iface) = other {
+                    iface.update_ip_tunnel(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::Geneve(iface) => {
+                if let Self::Geneve(other_iface) = other {
+                    iface.update_geneve(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::Macsec(iface) => {
+                if let Self::Macsec(other_iface) = other {
+                    iface.update_macsec(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::IpVlan(iface) => {
+                if let Self::IpVlan(other_iface) = other {
+                    iface.update_ip_vlan(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::Tun(iface) => {
+                if let Self::Tun(other_iface) = other {
+                    iface.update_tun(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::Hsr(iface) => {
+                if let Self::Hsr(other_iface) = other {
+                    iface.update_hsr(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
+            Self::Xfrm(iface) => {
+                if let Self::Xfrm(other_iface) = other {
+                    iface.update_xfrm(other_iface);
+                } else {
+                    log::warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface,
+                        other
+                    );
+                }
+            }
             Self::Unknown(_) | Self::Dummy(_) | Self::Loopback(_) => (),
         }
     }
 }
 
 impl InterfaceType {
-    pub(crate) const SUPPORTED_LIST: [InterfaceType; 14] = [
+    pub(crate) const SUPPORTED_LIST: [InterfaceType; 26] = [
         InterfaceType::Bond,
         InterfaceType::LinuxBridge,
         InterfaceType::Dummy,
@@ -227,5 +317,31 @@ impl InterfaceType {
         InterfaceType::InfiniBand,
         InterfaceType::Loopback,
         InterfaceType::Vrf,
+        InterfaceType::WireGuard,
+        InterfaceType::Gre,
+        InterfaceType::GreTap,
+        InterfaceType::IpIp,
+        InterfaceType::Sit,
+        InterfaceType::Ip6Tnl,
+        InterfaceType::Geneve,
+        InterfaceType::Macsec,
+        InterfaceType::IpVlan,
+        InterfaceType::Tun,
+        InterfaceType::Hsr,
+        InterfaceType::Xfrm,
     ];
 }
+
+// Update the interface specific config using the one of other interface, or
+// clone it if not defined yet.
+pub(crate) fn update_iface_conf<T: Clone>(
+    conf: &mut Option<T>,
+    other: Option<&T>,
+    update: fn(&mut T, Option<&T>),
+) {
+    if let Some(conf) = conf.as_mut() {
+        update(conf, other);
+    } else {
+        *conf = other.cloned();
+    }
+}
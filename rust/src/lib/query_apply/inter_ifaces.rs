@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    ErrorKind, Interface, InterfaceType, Interfaces, MergedInterfaces,
-    NmstateError,
+    Interface, InterfaceType, Interfaces, MergedInterface, MergedInterfaces,
+    NmstateError, VerificationMismatch,
 };
 
 impl Interfaces {
@@ -83,31 +83,44 @@ fn find_unknown_type_port<'a>(
 fn verify_desire_absent_but_found_in_current(
     des_iface: &Interface,
     cur_iface: &Interface,
-) -> Result<(), NmstateError> {
+) -> Option<VerificationMismatch> {
     if cur_iface.is_virtual() {
         // Virtual interface should be deleted by absent action
-        let e = NmstateError::new(
-            ErrorKind::VerificationError,
-            format!(
-                "Absent/Down interface {}/{} still found as {:?}",
-                des_iface.name(),
-                des_iface.iface_type(),
-                cur_iface
-            ),
+        log::error!(
+            "Absent/Down interface {}/{} still found as {:?}",
+            des_iface.name(),
+            des_iface.iface_type(),
+            cur_iface
         );
-        log::error!("{}", e);
-        Err(e)
+        Some(VerificationMismatch::new(
+            format!("{}.interface.state", des_iface.name()),
+            serde_json::to_string(&des_iface.base_iface().state)
+                .unwrap_or_default(),
+            serde_json::to_string(&cur_iface.base_iface().state)
+                .unwrap_or_default(),
+        ))
     } else {
         // Hard to predict real hardware state due to backend variety.
-        Ok(())
+        None
     }
 }
 
 impl MergedInterfaces {
+    #[cfg(test)]
     pub(crate) fn verify(
         &self,
         current: &Interfaces,
     ) -> Result<(), NmstateError> {
+        crate::error::mismatches_to_result(self.collect_mismatches(current)?)
+    }
+
+    // The SR-IOV VF check is not treated as mismatch as it is waiting
+    // kernel and udev to create the VF interfaces, error is returned directly.
+    pub(crate) fn collect_mismatches(
+        &self,
+        current: &Interfaces,
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
+        let mut ret = Vec::new();
         let mut current = current.clone();
         current.remove_ignored_ifaces(self.ignored_ifaces.as_slice());
         current.remove_unknown_type_port();
@@ -120,7 +133,11 @@ impl MergedInterfaces {
             iface.sanitize_current_for_verify();
         }
 
-        for des_iface in self.iter().filter(|i| i.is_desired()) {
+        // Sort to make the mismatches in consistent order
+        let mut des_ifaces: Vec<&MergedInterface> =
+            self.iter().filter(|i| i.is_desired()).collect();
+        des_ifaces.sort_by_key(|i| i.merged.name());
+        for des_iface in des_ifaces {
             let mut iface = if let Some(i) = des_iface.for_verify.as_ref() {
                 i.clone()
             } else {
@@ -132,16 +149,16 @@ impl MergedInterfaces {
                 if let Some(cur_iface) =
                     current.get_iface(iface.name(), iface.iface_type())
                 {
-                    verify_desire_absent_but_found_in_current(
+                    ret.extend(verify_desire_absent_but_found_in_current(
                         &iface, cur_iface,
-                    )?;
+                    ));
                 }
             } else if let Some(cur_iface) =
                 current.get_iface(iface.name(), iface.iface_type())
             {
                 // Do not verify physical interface with state:down
                 if iface.is_up() {
-                    iface.verify(cur_iface)?;
+                    ret.extend(iface.collect_mismatches(cur_iface)?);
                     if let Interface::Ethernet(eth_iface) = iface {
                         if eth_iface.sriov_is_enabled() {
                             eth_iface.verify_sriov(&current)?;
@@ -149,16 +166,18 @@ impl MergedInterfaces {
                     }
                 }
             } else if iface.is_up() {
-                return Err(NmstateError::new(
-                    ErrorKind::VerificationError,
-                    format!(
-                        "Failed to find desired interface {} {:?}",
-                        iface.name(),
-                        iface.iface_type()
-                    ),
+                log::error!(
+                    "Failed to find desired interface {} {:?}",
+                    iface.name(),
+                    iface.iface_type()
+                );
+                ret.push(VerificationMismatch::new(
+                    format!("{}.interface.state", iface.name()),
+                    serde_json::to_string(&iface.base_iface().state)?,
+                    "null".to_string(),
                 ));
             }
         }
-        Ok(())
+        Ok(ret)
     }
 }
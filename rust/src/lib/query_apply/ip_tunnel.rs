@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::iface::update_iface_conf;
+use crate::{IpTunnelConfig, IpTunnelInterface};
+
+impl IpTunnelInterface {
+    pub(crate) fn update_ip_tunnel(&mut self, other: &IpTunnelInterface) {
+        update_iface_conf(
+            &mut self.ip_tunnel,
+            other.ip_tunnel.as_ref(),
+            IpTunnelConfig::update,
+        );
+    }
+}
+
+impl IpTunnelConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if other.base_iface.is_some() {
+                self.base_iface = other.base_iface.clone();
+            }
+            if other.local.is_some() {
+                self.local = other.local;
+            }
+            if other.remote.is_some() {
+                self.remote = other.remote;
+            }
+            if other.key.is_some() {
+                self.key = other.key;
+            }
+            if other.ttl.is_some() {
+                self.ttl = other.ttl;
+            }
+            if other.tos.is_some() {
+                self.tos = other.tos;
+            }
+            if other.pmtudisc.is_some() {
+                self.pmtudisc = other.pmtudisc;
+            }
+        }
+    }
+}
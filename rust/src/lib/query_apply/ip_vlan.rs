@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::iface::update_iface_conf;
+use crate::{IpVlanConfig, IpVlanInterface};
+
+impl IpVlanInterface {
+    pub(crate) fn update_ip_vlan(&mut self, other: &IpVlanInterface) {
+        update_iface_conf(
+            &mut self.ipvlan,
+            other.ipvlan.as_ref(),
+            IpVlanConfig::update,
+        );
+    }
+}
+
+impl IpVlanConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.base_iface = other.base_iface.clone();
+            self.mode = other.mode;
+            self.private = other.private;
+            self.vepa = other.vepa;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::iface::update_iface_conf;
+use crate::{MacsecConfig, MacsecInterface};
+
+impl MacsecInterface {
+    pub(crate) fn update_macsec(&mut self, other: &MacsecInterface) {
+        update_iface_conf(
+            &mut self.macsec,
+            other.macsec.as_ref(),
+            MacsecConfig::update,
+        );
+    }
+}
+
+impl MacsecConfig {
+    // The NetworkManager only provides MKA keys which are not available in
+    // kernel, hence only override the defined properties.
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if !other.base_iface.is_empty() {
+                self.base_iface = other.base_iface.clone();
+            }
+            if other.port.is_some() {
+                self.port = other.port;
+            }
+            if other.encrypt.is_some() {
+                self.encrypt = other.encrypt;
+            }
+            if other.validation.is_some() {
+                self.validation = other.validation;
+            }
+            if other.send_sci.is_some() {
+                self.send_sci = other.send_sci;
+            }
+            if other.mka_cak.is_some() {
+                self.mka_cak = other.mka_cak.clone();
+            }
+            if other.mka_ckn.is_some() {
+                self.mka_ckn = other.mka_ckn.clone();
+            }
+        }
+    }
+}
@@ -4,20 +4,29 @@ mod base;
 mod bond;
 mod dns;
 mod ethernet;
+mod geneve;
 mod hostname;
+mod hsr;
 mod iface;
 mod infiniband;
 mod inter_ifaces;
 mod ip;
+mod ip_tunnel;
+mod ip_vlan;
 mod linux_bridge;
 mod mac_vlan;
 mod mac_vtap;
+mod macsec;
 mod mptcp;
 mod net_state;
+mod nexthop;
 mod ovs;
 mod route;
 mod route_rule;
 mod sriov;
+mod tun;
 mod vlan;
 mod vrf;
 mod vxlan;
+mod wireguard;
+mod xfrm;
@@ -1,13 +1,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    nispor::{nispor_apply, nispor_retrieve, set_running_hostname},
+    error::mismatches_to_result,
+    nispor::{
+        is_nispor_checkpoint, nispor_apply, nispor_checkpoint_create,
+        nispor_checkpoint_destroy, nispor_checkpoint_get,
+        nispor_checkpoint_list, nispor_checkpoint_lock, nispor_dns_apply,
+        nispor_dns_backup, nispor_dns_remove_backup, nispor_dns_restore,
+        nispor_dns_retrieve, nispor_retrieve, set_running_hostname,
+        DEFAULT_RESOLV_CONF_PATH,
+    },
     nm::{
-        nm_apply, nm_checkpoint_create, nm_checkpoint_destroy,
-        nm_checkpoint_rollback, nm_checkpoint_timeout_extend, nm_retrieve,
+        nm_apply, nm_apply_plan, nm_checkpoint_create, nm_checkpoint_destroy,
+        nm_checkpoint_rollback, nm_checkpoint_timeout_extend,
+        nm_keyfiles_to_nmstate, nm_retrieve,
     },
     ovsdb::{ovsdb_apply, ovsdb_is_running, ovsdb_retrieve},
-    MergedNetworkState, NetworkState, NmstateError,
+    ApplyPlanAction, ApplyPlanActionKind, ErrorKind, MergedNetworkState,
+    NetworkState, NmstateError,
 };
 
 const DEFAULT_ROLLBACK_TIMEOUT: u32 = 60;
@@ -20,19 +30,96 @@ const RETRY_NM_INTERVAL_MILLISECONDS: u64 = 2000;
 
 const MAX_SUPPORTED_INTERFACES: usize = 1000;
 
+const KERNEL_CHECKPOINT_POLL_INTERVAL_MILLISECONDS: u64 = 1000;
+
 impl NetworkState {
     /// Rollback a checkpoint.
-    /// Not available for `kernel only` mode.
+    /// Empty string means the pending `kernel only` mode checkpoint if any,
+    /// or else the last NetworkManager checkpoint.
     /// Only available for feature `query_apply`.
     pub fn checkpoint_rollback(checkpoint: &str) -> Result<(), NmstateError> {
-        nm_checkpoint_rollback(checkpoint)
+        match get_kernel_checkpoint(checkpoint)? {
+            Some(checkpoint) => {
+                let _lock = nispor_checkpoint_lock()?;
+                kernel_checkpoint_rollback(&checkpoint)
+            }
+            None => nm_checkpoint_rollback(checkpoint),
+        }
     }
 
     /// Commit a checkpoint.
-    /// Not available for `kernel only` mode.
+    /// Empty string means the pending `kernel only` mode checkpoint if any,
+    /// or else the last NetworkManager checkpoint.
+    /// Expired `kernel only` mode checkpoint will be rolled back with error
+    /// returned.
     /// Only available for feature `query_apply`.
     pub fn checkpoint_commit(checkpoint: &str) -> Result<(), NmstateError> {
-        nm_checkpoint_destroy(checkpoint)
+        match get_kernel_checkpoint(checkpoint)? {
+            Some(checkpoint) => {
+                let _lock = nispor_checkpoint_lock()?;
+                kernel_checkpoint_commit(&checkpoint)
+            }
+            None => nm_checkpoint_destroy(checkpoint),
+        }
+    }
+
+    /// Return the pending checkpoint of `kernel only` mode created by
+    /// [NetworkState::apply()] with commit disabled.
+    /// Expired checkpoint is rolled back before returning.
+    /// Only available for feature `query_apply`.
+    pub fn kernel_checkpoint() -> Result<Option<String>, NmstateError> {
+        rollback_expired_kernel_checkpoint()?;
+        Ok(nispor_checkpoint_list()?.pop())
+    }
+
+    /// Block till specified `kernel only` mode checkpoint been committed,
+    /// rolled back or expired. Expired checkpoint will be rolled back.
+    /// Since there is no daemon holding the checkpoint in `kernel only` mode,
+    /// the caller of [NetworkState::apply()] with commit disabled should
+    /// invoke this function (e.g. in a thread or a detached process) to
+    /// rollback on timeout. Expired checkpoint is also rolled back by follow
+    /// up [NetworkState::apply()], [NetworkState::checkpoint_commit()] and
+    /// [NetworkState::kernel_checkpoint()].
+    /// Only available for feature `query_apply`.
+    pub fn kernel_checkpoint_wait(
+        checkpoint: &str,
+    ) -> Result<(), NmstateError> {
+        if !is_nispor_checkpoint(checkpoint) {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Checkpoint {checkpoint} is not a kernel only mode \
+                    checkpoint"
+                ),
+            ));
+        }
+        loop {
+            {
+                let _lock = nispor_checkpoint_lock()?;
+                // Checkpoint removed means committed or rolled back
+                if !nispor_checkpoint_list()?.iter().any(|c| c == checkpoint) {
+                    return Ok(());
+                }
+                if nispor_checkpoint_get(checkpoint)?.is_expired() {
+                    log::info!(
+                        "Rolling back expired checkpoint {}",
+                        checkpoint
+                    );
+                    return kernel_checkpoint_rollback(checkpoint);
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(
+                KERNEL_CHECKPOINT_POLL_INTERVAL_MILLISECONDS,
+            ));
+        }
+    }
+
+    /// Generate `NetworkState` from the content of NetworkManager keyfiles
+    /// (`*.nmconnection`) without contacting NetworkManager daemon.
+    /// Unsupported connections are ignored with warning logged.
+    /// Only available for feature `query_apply`.
+    pub fn from_nm_keyfiles(keyfiles: &[String]) -> Result<Self, NmstateError> {
+        nm_keyfiles_to_nmstate(keyfiles)
     }
 
     /// Retrieve the `NetworkState`.
@@ -45,12 +132,18 @@ impl NetworkState {
         if state.prop_list.contains(&"interfaces") {
             self.interfaces = state.interfaces;
         }
+        if state.prop_list.contains(&"nexthops") {
+            self.nexthops = state.nexthops;
+        }
         if state.prop_list.contains(&"routes") {
             self.routes = state.routes;
         }
         if state.prop_list.contains(&"rules") {
             self.rules = state.rules;
         }
+        if self.kernel_only {
+            self.dns = nispor_dns_retrieve(self.resolv_conf_path());
+        }
         if ovsdb_is_running() {
             match ovsdb_retrieve() {
                 Ok(ovsdb_state) => self.update_state(&ovsdb_state),
@@ -91,11 +184,55 @@ impl NetworkState {
         if !self.kernel_only {
             self.apply_with_nm_backend()
         } else {
-            // TODO: Need checkpoint for kernel only mode
             self.apply_without_nm_backend()
         }
     }
 
+    /// Generate the ordered actions [NetworkState::apply()] would take
+    /// against NetworkManager without changing anything: the profiles to
+    /// add, modify or delete with their keyfile content, and the profiles to
+    /// activate, reapply or deactivate. Activation and deactivation will
+    /// bounce the interface, while reapply will not (unless NetworkManager
+    /// refused the reapply, then activation is used).
+    /// The SR-IOV VF count change which requires applying PF first is not
+    /// included in the plan.
+    /// The secrets in keyfile are hidden unless
+    /// [NetworkState::set_include_secrets()] is set to true.
+    /// Not supported in `kernel only` mode.
+    /// Only available for feature `query_apply`.
+    pub fn apply_plan(&self) -> Result<Vec<ApplyPlanAction>, NmstateError> {
+        if self.kernel_only {
+            return Err(NmstateError::new(
+                ErrorKind::NotSupportedError,
+                "NetworkState::apply_plan() does not support kernel only mode"
+                    .to_string(),
+            ));
+        }
+        let mut cur_net_state = NetworkState::new();
+        cur_net_state.set_include_secrets(true);
+        cur_net_state.retrieve()?;
+
+        let merged_state = MergedNetworkState::new(
+            self.clone(),
+            cur_net_state,
+            false,
+            self.memory_only,
+        )?;
+        let mut ret = nm_apply_plan(&merged_state, self.include_secrets)?;
+        if merged_state.is_global_ovsdb_changed() && ovsdb_is_running() {
+            ret.push(ApplyPlanAction::new(ApplyPlanActionKind::UpdateOvsDb));
+        }
+        if let Some(running_hostname) =
+            self.hostname.as_ref().and_then(|c| c.running.as_ref())
+        {
+            let mut action =
+                ApplyPlanAction::new(ApplyPlanActionKind::SetRunningHostname);
+            action.detail = Some(running_hostname.to_string());
+            ret.push(action);
+        }
+        Ok(ret)
+    }
+
     fn apply_with_nm_backend(&self) -> Result<(), NmstateError> {
         let mut cur_net_state = NetworkState::new();
         cur_net_state.set_kernel_only(self.kernel_only);
@@ -147,7 +284,7 @@ impl NetworkState {
             VERIFY_RETRY_COUNT
         };
 
-        with_nm_checkpoint(&checkpoint, self.no_commit, || {
+        with_checkpoint(&checkpoint, self.no_commit, || {
             if let Some(pf_state) = pf_state {
                 let pf_merged_state = MergedNetworkState::new(
                     pf_state,
@@ -222,37 +359,71 @@ impl NetworkState {
     }
 
     fn apply_without_nm_backend(&self) -> Result<(), NmstateError> {
+        rollback_expired_kernel_checkpoint()?;
+
         let mut cur_net_state = NetworkState::new();
         cur_net_state.set_kernel_only(self.kernel_only);
         cur_net_state.set_include_secrets(true);
         cur_net_state.retrieve()?;
 
-        let merged_state = MergedNetworkState::new(
-            self.clone(),
-            cur_net_state.clone(),
-            false,
-            self.memory_only,
-        )?;
-
-        nispor_apply(&merged_state)?;
-        if let Some(running_hostname) =
-            self.hostname.as_ref().and_then(|c| c.running.as_ref())
-        {
-            set_running_hostname(running_hostname)?;
-        }
-        if !self.no_verify {
-            with_retry(
-                VERIFY_RETRY_INTERVAL_MILLISECONDS,
-                VERIFY_RETRY_COUNT_KERNEL_MODE,
-                || {
-                    let mut new_cur_net_state = cur_net_state.clone();
-                    new_cur_net_state.retrieve()?;
-                    merged_state.verify(&new_cur_net_state)
-                },
-            )
+        let timeout = self.timeout.unwrap_or(DEFAULT_ROLLBACK_TIMEOUT);
+        let resolv_conf = if self.dns.config.is_some() {
+            Some(self.resolv_conf_path())
         } else {
-            Ok(())
+            None
+        };
+        // The checkpoint creation fails if another checkpoint is pending,
+        // hence do the backup afterwards to not override the backup of the
+        // pending one.
+        let checkpoint = nispor_checkpoint_create(
+            self,
+            &cur_net_state,
+            timeout,
+            resolv_conf,
+        )?;
+        log::info!("Created checkpoint {}", &checkpoint);
+        if let Some(resolv_conf) = resolv_conf {
+            if let Err(e) = nispor_dns_backup(resolv_conf) {
+                nispor_checkpoint_destroy(&checkpoint)?;
+                return Err(e);
+            }
         }
+
+        with_checkpoint(&checkpoint, self.no_commit, || {
+            let merged_state = MergedNetworkState::new(
+                self.clone(),
+                cur_net_state.clone(),
+                false,
+                self.memory_only,
+            )?;
+
+            nispor_apply(&merged_state)?;
+            if let Some(running_hostname) =
+                self.hostname.as_ref().and_then(|c| c.running.as_ref())
+            {
+                set_running_hostname(running_hostname)?;
+            }
+            nispor_dns_apply(&merged_state.dns, self.resolv_conf_path())?;
+            if !self.no_verify {
+                with_retry(
+                    VERIFY_RETRY_INTERVAL_MILLISECONDS,
+                    VERIFY_RETRY_COUNT_KERNEL_MODE,
+                    || {
+                        let mut new_cur_net_state = cur_net_state.clone();
+                        new_cur_net_state.retrieve()?;
+                        merged_state.verify(&new_cur_net_state)
+                    },
+                )
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    fn resolv_conf_path(&self) -> &str {
+        self.resolv_conf_path
+            .as_deref()
+            .unwrap_or(DEFAULT_RESOLV_CONF_PATH)
     }
 
     pub(crate) fn update_state(&mut self, other: &Self) {
@@ -277,7 +448,7 @@ impl NetworkState {
     }
 }
 
-fn with_nm_checkpoint<T>(
+fn with_checkpoint<T>(
     checkpoint: &str,
     no_commit: bool,
     func: T,
@@ -288,7 +459,7 @@ where
     match func() {
         Ok(()) => {
             if !no_commit {
-                nm_checkpoint_destroy(checkpoint)?;
+                NetworkState::checkpoint_commit(checkpoint)?;
 
                 log::info!("Destroyed checkpoint {}", checkpoint);
             } else {
@@ -297,8 +468,8 @@ where
             Ok(())
         }
         Err(e) => {
-            if let Err(e) = nm_checkpoint_rollback(checkpoint) {
-                log::warn!("nm_checkpoint_rollback() failed: {}", e);
+            if let Err(e) = NetworkState::checkpoint_rollback(checkpoint) {
+                log::warn!("checkpoint_rollback() failed: {}", e);
             }
             log::info!("Rollbacked to checkpoint {}", checkpoint);
             Err(e)
@@ -306,6 +477,89 @@ where
     }
 }
 
+// Return the kernel only mode checkpoint to work on, or None for
+// NetworkManager checkpoint.
+fn get_kernel_checkpoint(
+    checkpoint: &str,
+) -> Result<Option<String>, NmstateError> {
+    if is_nispor_checkpoint(checkpoint) {
+        Ok(Some(checkpoint.to_string()))
+    } else if checkpoint.is_empty() {
+        NetworkState::kernel_checkpoint()
+    } else {
+        Ok(None)
+    }
+}
+
+fn kernel_checkpoint_commit(checkpoint: &str) -> Result<(), NmstateError> {
+    let cp = nispor_checkpoint_get(checkpoint)?;
+    if cp.is_expired() {
+        kernel_checkpoint_rollback(checkpoint)?;
+        Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Checkpoint {checkpoint} expired and has been rolled back"),
+        ))
+    } else {
+        if let Some(resolv_conf) = cp.resolv_conf.as_deref() {
+            nispor_dns_remove_backup(resolv_conf)?;
+        }
+        nispor_checkpoint_destroy(checkpoint)?;
+        log::info!("Destroyed checkpoint {}", checkpoint);
+        Ok(())
+    }
+}
+
+fn kernel_checkpoint_rollback(checkpoint: &str) -> Result<(), NmstateError> {
+    let cp = nispor_checkpoint_get(checkpoint)?;
+    let mut cur_net_state = NetworkState::new();
+    cur_net_state.set_kernel_only(true);
+    cur_net_state.set_include_secrets(true);
+    cur_net_state.retrieve()?;
+
+    let revert_state = cp.gen_revert_state(&cur_net_state);
+    let result = MergedNetworkState::new(
+        revert_state.clone(),
+        cur_net_state,
+        false,
+        false,
+    )
+    .and_then(|merged_state| nispor_apply(&merged_state))
+    .and_then(|_| {
+        if let Some(running_hostname) = revert_state
+            .hostname
+            .as_ref()
+            .and_then(|c| c.running.as_ref())
+        {
+            set_running_hostname(running_hostname)
+        } else {
+            Ok(())
+        }
+    });
+    // Always restore resolv.conf as it is not related to above failures
+    let result = match cp.resolv_conf.as_deref() {
+        Some(resolv_conf) => result.and(nispor_dns_restore(resolv_conf)),
+        None => result,
+    };
+    // Remove the checkpoint even rollback failed, so follow up apply is not
+    // blocked.
+    nispor_checkpoint_destroy(checkpoint)?;
+    result
+}
+
+// Kernel only mode has no daemon to rollback on timeout, so besides
+// `NetworkState::kernel_checkpoint_wait()`, we also do it before creating new
+// checkpoint or querying pending checkpoint.
+fn rollback_expired_kernel_checkpoint() -> Result<(), NmstateError> {
+    let _lock = nispor_checkpoint_lock()?;
+    for checkpoint in nispor_checkpoint_list()? {
+        if nispor_checkpoint_get(&checkpoint)?.is_expired() {
+            log::info!("Rolling back expired checkpoint {}", checkpoint);
+            kernel_checkpoint_rollback(&checkpoint)?;
+        }
+    }
+    Ok(())
+}
+
 fn with_retry<T>(
     interval_ms: u64,
     count: usize,
@@ -335,9 +589,14 @@ where
 }
 
 impl MergedNetworkState {
+    // Collect all the mismatches instead of stopping at the first one, so
+    // user could know everything failed to apply.
     fn verify(&self, current: &NetworkState) -> Result<(), NmstateError> {
-        self.hostname.verify(current.hostname.as_ref())?;
-        self.interfaces.verify(&current.interfaces)?;
+        let mut mismatches = self
+            .hostname
+            .collect_mismatches(current.hostname.as_ref())?;
+        mismatches
+            .extend(self.interfaces.collect_mismatches(&current.interfaces)?);
         let ignored_kernel_ifaces: Vec<&str> = self
             .interfaces
             .ignored_ifaces
@@ -346,12 +605,20 @@ impl MergedNetworkState {
             .filter(|(_, t)| !t.is_userspace())
             .map(|(n, _)| n.as_str())
             .collect();
-        self.routes
-            .verify(&current.routes, ignored_kernel_ifaces.as_slice())?;
-        self.rules
-            .verify(&current.rules, ignored_kernel_ifaces.as_slice())?;
-        self.dns.verify(&current.dns)?;
-        self.ovsdb.verify(&current.ovsdb)?;
-        Ok(())
+        mismatches.extend(self.nexthops.collect_mismatches(
+            &current.nexthops,
+            ignored_kernel_ifaces.as_slice(),
+        )?);
+        mismatches.extend(self.routes.collect_mismatches(
+            &current.routes,
+            ignored_kernel_ifaces.as_slice(),
+        )?);
+        mismatches.extend(self.rules.collect_mismatches(
+            &current.rules,
+            ignored_kernel_ifaces.as_slice(),
+        )?);
+        mismatches.extend(self.dns.collect_mismatches(&current.dns)?);
+        mismatches.extend(self.ovsdb.collect_mismatches(&current.ovsdb)?);
+        mismatches_to_result(mismatches)
     }
 }
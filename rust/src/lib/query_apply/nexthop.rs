@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    MergedNexthops, NexthopEntry, Nexthops, NmstateError, VerificationMismatch,
+};
+
+impl MergedNexthops {
+    pub(crate) fn collect_mismatches(
+        &self,
+        current: &Nexthops,
+        ignored_ifaces: &[&str],
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
+        let mut ret = Vec::new();
+        let cur_nhs: Vec<&NexthopEntry> = current
+            .config
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|nh| {
+                nh.device
+                    .as_deref()
+                    .map(|d| !ignored_ifaces.contains(&d))
+                    .unwrap_or(true)
+            })
+            .collect();
+        let mut des_nhs = Vec::new();
+        for nh in self.desired.config.as_deref().unwrap_or_default() {
+            let mut nh = nh.clone();
+            nh.sanitize()?;
+            des_nhs.push(nh);
+        }
+
+        for nh in des_nhs.as_slice() {
+            if nh.is_absent() {
+                // Ignore absent nexthop when desired holds the same ID
+                if des_nhs
+                    .iter()
+                    .any(|n| !n.is_absent() && nh.id.is_some() && n.id == nh.id)
+                {
+                    continue;
+                }
+                if let Some(cur_nh) =
+                    cur_nhs.iter().find(|cur_nh| nh.is_match(cur_nh))
+                {
+                    log::debug!(
+                        "Desired absent nexthop {nh} still found after \
+                        apply: {cur_nh}"
+                    );
+                    ret.push(VerificationMismatch::new(
+                        "nexthops.config".to_string(),
+                        serde_json::to_string(nh)?,
+                        serde_json::to_string(cur_nh)?,
+                    ));
+                }
+            } else if !cur_nhs.iter().any(|cur_nh| nh.is_match(cur_nh)) {
+                log::debug!("Desired nexthop {nh} not found after apply");
+                ret.push(VerificationMismatch::new(
+                    "nexthops.config".to_string(),
+                    serde_json::to_string(nh)?,
+                    match cur_nhs.iter().find(|cur_nh| cur_nh.id == nh.id) {
+                        Some(cur_nh) => serde_json::to_string(cur_nh)?,
+                        None => "null".to_string(),
+                    },
+                ));
+            }
+        }
+        Ok(ret)
+    }
+}
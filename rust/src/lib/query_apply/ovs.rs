@@ -3,17 +3,24 @@
 use std::collections::HashMap;
 
 use crate::{
-    state::get_json_value_difference, ErrorKind, MergedNetworkState,
-    MergedOvsDbGlobalConfig, NmstateError, OvsBridgeBondConfig,
-    OvsBridgeConfig, OvsBridgeInterface, OvsDbGlobalConfig, OvsDbIfaceConfig,
-    OvsInterface,
+    state::get_json_value_differences, MergedOvsDbGlobalConfig, NmstateError,
+    OvsBridgeBondConfig, OvsBridgeConfig, OvsBridgeInterface,
+    OvsDbGlobalConfig, OvsDbIfaceConfig, OvsInterface, VerificationMismatch,
 };
 
 impl MergedOvsDbGlobalConfig {
+    #[cfg(test)]
     pub(crate) fn verify(
         &self,
         current: &OvsDbGlobalConfig,
     ) -> Result<(), NmstateError> {
+        crate::error::mismatches_to_result(self.collect_mismatches(current)?)
+    }
+
+    pub(crate) fn collect_mismatches(
+        &self,
+        current: &OvsDbGlobalConfig,
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
         let external_ids: HashMap<String, Option<String>> = self
             .external_ids
             .iter()
@@ -43,21 +50,20 @@ impl MergedOvsDbGlobalConfig {
             serde_json::to_value(current)?
         };
 
-        if let Some((reference, desire, current)) = get_json_value_difference(
-            "ovsdb".to_string(),
+        Ok(get_json_value_differences(
+            "ovs-db".to_string(),
             &desired_value,
             &current_value,
-        ) {
-            Err(NmstateError::new(
-                ErrorKind::VerificationError,
-                format!(
-                    "Verification failure: {reference} desire '{desire}', \
-                    current '{current}'"
-                ),
-            ))
-        } else {
-            Ok(())
-        }
+        )
+        .into_iter()
+        .map(|(reference, desire, current)| {
+            VerificationMismatch::new(
+                reference,
+                desire.to_string(),
+                current.to_string(),
+            )
+        })
+        .collect())
     }
 }
 
@@ -104,39 +110,6 @@ impl OvsInterface {
     }
 }
 
-impl MergedNetworkState {
-    // Sine desire `ovsdb: {}` means remove all, we cannot
-    // differentiate it with `ovsdb` not defined due to `serde(default)`.
-    // Hence we need to check `MergedNetworkState.prop_list`.
-    pub(crate) fn is_global_ovsdb_changed(&self) -> bool {
-        if self.prop_list.contains(&"ovsdb") {
-            if self.ovsdb.desired.is_none() {
-                true
-            } else {
-                let cur_external_ids = self
-                    .ovsdb
-                    .current
-                    .external_ids
-                    .as_ref()
-                    .cloned()
-                    .unwrap_or_default();
-                let cur_other_config = self
-                    .ovsdb
-                    .current
-                    .other_config
-                    .as_ref()
-                    .cloned()
-                    .unwrap_or_default();
-
-                self.ovsdb.external_ids != cur_external_ids
-                    || self.ovsdb.other_config != cur_other_config
-            }
-        } else {
-            false
-        }
-    }
-}
-
 impl OvsDbIfaceConfig {
     pub(crate) fn new_empty() -> Self {
         Self {
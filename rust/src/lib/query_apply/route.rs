@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ErrorKind, MergedRoutes, NmstateError, RouteEntry, Routes};
+use crate::{
+    MergedRoutes, NmstateError, RouteEntry, Routes, VerificationMismatch,
+};
 
 impl MergedRoutes {
     fn routes_for_verify(&self) -> Vec<RouteEntry> {
@@ -35,11 +37,23 @@ impl MergedRoutes {
     // * desired absent route is removed unless another matching route been
     //   added.
     // * desired static route exists.
+    #[cfg(test)]
     pub(crate) fn verify(
         &self,
         current: &Routes,
         ignored_ifaces: &[&str],
     ) -> Result<(), NmstateError> {
+        crate::error::mismatches_to_result(
+            self.collect_mismatches(current, ignored_ifaces)?,
+        )
+    }
+
+    pub(crate) fn collect_mismatches(
+        &self,
+        current: &Routes,
+        ignored_ifaces: &[&str],
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
+        let mut ret = Vec::new();
         let mut cur_routes: Vec<&RouteEntry> = Vec::new();
         if let Some(cur_rts) = current.config.as_ref() {
             for cur_rt in cur_rts {
@@ -70,12 +84,14 @@ impl MergedRoutes {
                     .iter()
                     .find(|cur_rt| rt.is_match(cur_rt))
                 {
-                    return Err(NmstateError::new(
-                        ErrorKind::VerificationError,
-                        format!(
-                            "Desired absent route {rt} still found \
-                            after apply: {cur_rt}",
-                        ),
+                    log::debug!(
+                        "Desired absent route {rt} still found after \
+                        apply: {cur_rt}"
+                    );
+                    ret.push(VerificationMismatch::new(
+                        "routes.config".to_string(),
+                        serde_json::to_string(rt)?,
+                        serde_json::to_string(cur_rt)?,
                     ));
                 }
             } else if !cur_routes
@@ -83,13 +99,15 @@ impl MergedRoutes {
                 .iter()
                 .any(|cur_rt| rt.is_match(cur_rt))
             {
-                return Err(NmstateError::new(
-                    ErrorKind::VerificationError,
-                    format!("Desired route {rt} not found after apply"),
+                log::debug!("Desired route {rt} not found after apply");
+                ret.push(VerificationMismatch::new(
+                    "routes.config".to_string(),
+                    serde_json::to_string(rt)?,
+                    "null".to_string(),
                 ));
             }
         }
 
-        Ok(())
+        Ok(ret)
     }
 }
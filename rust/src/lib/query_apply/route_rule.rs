@@ -1,22 +1,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    ErrorKind, MergedRouteRules, NmstateError, RouteRuleEntry, RouteRules,
+    MergedRouteRules, NmstateError, RouteRuleEntry, RouteRules,
+    VerificationMismatch,
 };
 
 impl MergedRouteRules {
-    pub(crate) fn verify(
+    pub(crate) fn collect_mismatches(
         &self,
         current: &RouteRules,
         ignored_ifaces: &[&str],
-    ) -> Result<(), NmstateError> {
+    ) -> Result<Vec<VerificationMismatch>, NmstateError> {
+        let mut ret = Vec::new();
         let mut cur_rules: Vec<&RouteRuleEntry> = Vec::new();
         if let Some(rules) = current.config.as_ref() {
             for cur_rule in rules {
-                if let Some(iif) = cur_rule.iif.as_ref() {
-                    if ignored_ifaces.contains(&iif.as_str()) {
-                        continue;
-                    }
+                if [cur_rule.iif.as_deref(), cur_rule.oif.as_deref()]
+                    .iter()
+                    .flatten()
+                    .any(|iface| ignored_ifaces.contains(iface))
+                {
+                    continue;
                 }
                 cur_rules.push(cur_rule);
             }
@@ -37,12 +41,14 @@ impl MergedRouteRules {
                     .iter()
                     .find(|cur_r| rule.is_match(cur_r))
                 {
-                    return Err(NmstateError::new(
-                        ErrorKind::VerificationError,
-                        format!(
-                            "Desired absent route rule {rule} still found \
-                            after apply: {cur_rt}"
-                        ),
+                    log::debug!(
+                        "Desired absent route rule {rule} still found \
+                        after apply: {cur_rt}"
+                    );
+                    ret.push(VerificationMismatch::new(
+                        "route-rules.config".to_string(),
+                        serde_json::to_string(rule)?,
+                        serde_json::to_string(cur_rt)?,
                     ));
                 }
             } else if !cur_rules
@@ -50,12 +56,14 @@ impl MergedRouteRules {
                 .iter()
                 .any(|cur_r| rule.is_match(cur_r))
             {
-                return Err(NmstateError::new(
-                    ErrorKind::VerificationError,
-                    format!("Desired route rule {rule} not found after apply"),
+                log::debug!("Desired route rule {rule} not found after apply");
+                ret.push(VerificationMismatch::new(
+                    "route-rules.config".to_string(),
+                    serde_json::to_string(rule)?,
+                    "null".to_string(),
                 ));
             }
         }
-        Ok(())
+        Ok(ret)
     }
 }
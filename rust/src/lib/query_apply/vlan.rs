@@ -19,6 +19,12 @@ impl VlanConfig {
             self.base_iface = other.base_iface.clone();
             self.id = other.id;
             self.protocol = other.protocol;
+            self.reorder_headers = other.reorder_headers;
+            self.gvrp = other.gvrp;
+            self.mvrp = other.mvrp;
+            self.loose_binding = other.loose_binding;
+            self.ingress_qos_map = other.ingress_qos_map.clone();
+            self.egress_qos_map = other.egress_qos_map.clone();
         }
     }
 }
@@ -22,6 +22,22 @@ impl VxlanConfig {
             self.local = other.local;
             self.remote = other.remote;
             self.dst_port = other.dst_port;
+            self.ttl = other.ttl;
+            self.tos = other.tos;
+            self.source_port_min = other.source_port_min;
+            self.source_port_max = other.source_port_max;
+            self.ageing = other.ageing;
+            self.limit = other.limit;
+            self.proxy = other.proxy;
+            self.rsc = other.rsc;
+            self.l2_miss = other.l2_miss;
+            self.l3_miss = other.l3_miss;
+            self.udp_checksum = other.udp_checksum;
+            self.udp6_zero_checksum_tx = other.udp6_zero_checksum_tx;
+            self.udp6_zero_checksum_rx = other.udp6_zero_checksum_rx;
+            self.gbp = other.gbp;
+            self.external = other.external;
+            self.fdb_remotes = other.fdb_remotes.clone();
         }
     }
 }
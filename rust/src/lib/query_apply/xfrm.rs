@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::iface::update_iface_conf;
+use crate::{XfrmConfig, XfrmInterface};
+
+impl XfrmInterface {
+    pub(crate) fn update_xfrm(&mut self, other: &XfrmInterface) {
+        update_iface_conf(
+            &mut self.xfrm,
+            other.xfrm.as_ref(),
+            XfrmConfig::update,
+        );
+    }
+}
+
+impl XfrmConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.base_iface = other.base_iface.clone();
+            self.if_id = other.if_id;
+        }
+    }
+}
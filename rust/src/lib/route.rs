@@ -1,7 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::{hash_map::Entry, HashMap, HashSet};
-use std::net::Ipv4Addr;
+use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr};
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
@@ -64,10 +65,16 @@ impl Routes {
     }
 
     pub fn validate(&self) -> Result<(), NmstateError> {
-        // All desire non-absent route should have next hop interface
+        // All desire non-absent route should have next hop interface except
+        // the route types not bound to any interface and the routes using
+        // nexthop object.
         if let Some(config_routes) = self.config.as_ref() {
             for route in config_routes.iter() {
-                if !route.is_absent() && route.next_hop_iface.is_none() {
+                if !route.is_absent()
+                    && route.next_hop_iface.is_none()
+                    && !route.is_iface_less()
+                    && route.nexthop_id.is_none()
+                {
                     return Err(NmstateError::new(
                         ErrorKind::NotImplementedError,
                         format!(
@@ -99,6 +106,34 @@ impl Default for RouteState {
     }
 }
 
+// Tuple of (no_absent, is_ipv4, table_id, next_hop_iface, destination,
+// next_hop_addr, weight, nexthop_id, route_type)
+type RouteIdentityKey<'a> = (
+    bool,
+    bool,
+    u32,
+    &'a str,
+    &'a str,
+    &'a str,
+    u16,
+    u32,
+    Option<RouteType>,
+);
+
+// Tuple of (source, mtu, advmss, initcwnd, initrwnd, onlink, scope, quickack,
+// protocol)
+type RouteExtendedAttrsKey<'a> = (
+    &'a str,
+    u32,
+    u32,
+    u32,
+    u32,
+    bool,
+    RouteScope,
+    bool,
+    RouteProtocol,
+);
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 #[non_exhaustive]
@@ -154,11 +189,92 @@ pub struct RouteEntry {
         deserialize_with = "crate::deserializer::option_u16_or_string"
     )]
     pub weight: Option<u16>,
+
+    /// Use the nexthop object or nexthop group defined in
+    /// [crate::NetworkState.nexthops] instead of `next-hop-interface`,
+    /// `next-hop-address` and `weight`.
+    /// Only supported in kernel mode, NetworkManager cannot store this route.
+    /// Serialize and deserialize to/from `nexthop-id`.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    pub nexthop_id: Option<u32>,
+
+    /// Route type. `None` means unicast route.
+    /// The `next-hop-interface` is optional for route type
+    /// [RouteType::Blackhole], [RouteType::Unreachable],
+    /// [RouteType::Prohibit] and [RouteType::Throw].
+    /// Serialize and deserialize to/from `route-type`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_type: Option<RouteType>,
+    /// The preferred source address(`src` of iproute) to use when sending to
+    /// the destinations covered by this route.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Route MTU.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    pub mtu: Option<u32>,
+    /// The TCP maximum segment size advertised to the destinations.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    pub advmss: Option<u32>,
+    /// The TCP initial congestion window size.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    pub initcwnd: Option<u32>,
+    /// The TCP initial receive window size advertised to the destinations.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    pub initrwnd: Option<u32>,
+    /// Pretend that the next hop is directly attached to the next hop
+    /// interface, even if it does not match any interface prefix.
+    /// Nmstate cannot retrieve this flag from kernel yet, hence it is not
+    /// included in current state and not verified after applied.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    pub onlink: Option<bool>,
+    /// Route scope. When not defined, [RouteScope::Link] is used for unicast
+    /// route without next hop address, [RouteScope::Host] for local route
+    /// and [RouteScope::Universe] for others.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<RouteScope>,
+    /// Disable TCP delayed acknowledgements for this route.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    pub quickack: Option<bool>,
+    /// Routing protocol installing this route. Only [RouteProtocol::Boot]
+    /// and [RouteProtocol::Static] are supported when applying. When not
+    /// defined, [RouteProtocol::Static] is used. Only shown in current state
+    /// when not [RouteProtocol::Static].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<RouteProtocol>,
 }
 
 impl RouteEntry {
     pub const USE_DEFAULT_METRIC: i64 = -1;
     pub const USE_DEFAULT_ROUTE_TABLE: u32 = 0;
+    pub(crate) const LOOPBACK_IFACE_NAME: &'static str = "lo";
 
     pub fn new() -> Self {
         Self::default()
@@ -195,12 +311,105 @@ impl RouteEntry {
         if self.weight.is_some() && self.weight != other.weight {
             return false;
         }
+        if self.nexthop_id.is_some() && self.nexthop_id != other.nexthop_id {
+            return false;
+        }
+        if self.route_type.is_some() && self.route_type != other.route_type {
+            return false;
+        }
+        if self.source.is_some() && self.source != other.source {
+            return false;
+        }
+        if self.mtu.is_some() && self.mtu != other.mtu {
+            return false;
+        }
+        if self.advmss.is_some() && self.advmss != other.advmss {
+            return false;
+        }
+        if self.initcwnd.is_some() && self.initcwnd != other.initcwnd {
+            return false;
+        }
+        if self.initrwnd.is_some() && self.initrwnd != other.initrwnd {
+            return false;
+        }
+        if self.scope.is_some() && self.scope != Some(other.effective_scope()) {
+            return false;
+        }
+        if self.quickack.is_some()
+            && self.quickack.unwrap_or_default()
+                != other.quickack.unwrap_or_default()
+        {
+            return false;
+        }
+        if self.protocol.is_some()
+            && self.protocol != Some(other.effective_protocol())
+        {
+            return false;
+        }
         true
     }
 
-    // Return tuple of (no_absent, is_ipv4, table_id, next_hop_iface,
-    // destination, next_hop_addr, weight)
-    fn sort_key(&self) -> (bool, bool, u32, &str, &str, &str, u16) {
+    // The protocol used by nmstate when `protocol` is not defined.
+    pub(crate) fn effective_protocol(&self) -> RouteProtocol {
+        self.protocol.unwrap_or(RouteProtocol::Static)
+    }
+
+    // The scope used by kernel when `scope` is not defined.
+    pub(crate) fn effective_scope(&self) -> RouteScope {
+        if let Some(scope) = self.scope {
+            return scope;
+        }
+        match self.route_type {
+            Some(RouteType::Local) => RouteScope::Host,
+            Some(_) => RouteScope::Universe,
+            None if self.nexthop_id.is_some() => RouteScope::Universe,
+            None => {
+                if self
+                    .next_hop_addr
+                    .as_deref()
+                    .and_then(|a| a.parse::<IpAddr>().ok())
+                    .map(|a| !a.is_unspecified())
+                    .unwrap_or_default()
+                {
+                    RouteScope::Universe
+                } else {
+                    RouteScope::Link
+                }
+            }
+        }
+    }
+
+    // Whether any of source, mtu, advmss, initcwnd, initrwnd, onlink, scope,
+    // quickack and protocol is defined.
+    pub(crate) fn has_extended_attrs(&self) -> bool {
+        self.source.is_some()
+            || self.mtu.is_some()
+            || self.advmss.is_some()
+            || self.initcwnd.is_some()
+            || self.initrwnd.is_some()
+            || self.onlink.is_some()
+            || self.scope.is_some()
+            || self.quickack.is_some()
+            || self.protocol.is_some()
+    }
+
+    // Whether this route is not bound to any interface.
+    pub(crate) fn is_iface_less(&self) -> bool {
+        self.route_type.map(|t| t.is_iface_less()) == Some(true)
+    }
+
+    // The interface holding this route. The routes not bound to any
+    // interface are stored to loopback interface.
+    pub(crate) fn iface_for_store(&self) -> Option<&str> {
+        match self.next_hop_iface.as_deref() {
+            Some(i) => Some(i),
+            None if self.is_iface_less() => Some(Self::LOOPBACK_IFACE_NAME),
+            None => None,
+        }
+    }
+
+    // Identify a route regardless of its extended attributes.
+    fn identity_key(&self) -> RouteIdentityKey<'_> {
         (
             !matches!(self.state, Some(RouteState::Absent)),
             !self
@@ -213,10 +422,39 @@ impl RouteEntry {
             self.destination.as_deref().unwrap_or(""),
             self.next_hop_addr.as_deref().unwrap_or(""),
             self.weight.unwrap_or_default(),
+            self.nexthop_id.unwrap_or_default(),
+            self.route_type,
         )
     }
 
+    // Undefined extended attributes are set to the value kernel would use, so
+    // that changing any of them is treated as different route.
+    fn extended_attrs_key(&self) -> RouteExtendedAttrsKey<'_> {
+        (
+            self.source.as_deref().unwrap_or(""),
+            self.mtu.unwrap_or_default(),
+            self.advmss.unwrap_or_default(),
+            self.initcwnd.unwrap_or_default(),
+            self.initrwnd.unwrap_or_default(),
+            self.onlink.unwrap_or_default(),
+            self.effective_scope(),
+            self.quickack.unwrap_or_default(),
+            self.effective_protocol(),
+        )
+    }
+
+    fn sort_key(&self) -> (RouteIdentityKey<'_>, RouteExtendedAttrsKey<'_>) {
+        (self.identity_key(), self.extended_attrs_key())
+    }
+
     pub(crate) fn sanitize(&mut self) -> Result<(), NmstateError> {
+        if let Some(route_type) = self.route_type.filter(|_| !self.is_absent())
+        {
+            self.sanitize_route_type(route_type)?;
+        }
+        if self.nexthop_id.is_some() && !self.is_absent() {
+            self.sanitize_nexthop_id()?;
+        }
         if let Some(dst) = self.destination.as_ref() {
             if dst.is_empty() {
                 self.destination = None;
@@ -243,6 +481,40 @@ impl RouteEntry {
                 self.next_hop_addr = Some(new_via);
             }
         }
+        if let Some(src) = self.source.as_ref() {
+            let new_src = src.parse::<IpAddr>()?;
+            if let Some(dst) = self.destination.as_deref() {
+                if is_ipv6_addr(dst) != new_src.is_ipv6() {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "The source {src} of route {self} is not the \
+                            same IP family as its destination"
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+            let new_src = new_src.to_string();
+            if src != &new_src {
+                log::warn!("Route source {} sanitized to {}", src, new_src);
+                self.source = Some(new_src);
+            }
+        }
+        if let Some(protocol) = self.protocol {
+            if !self.is_absent() && !protocol.is_static() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Route protocol {protocol} is not supported, only \
+                        boot and static are allowed: {self}"
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
         if let Some(weight) = self.weight {
             if !(1..=256).contains(&weight) {
                 return Err(NmstateError::new(
@@ -266,6 +538,64 @@ impl RouteEntry {
         Ok(())
     }
 
+    fn sanitize_route_type(
+        &mut self,
+        route_type: RouteType,
+    ) -> Result<(), NmstateError> {
+        if self.next_hop_addr.is_some() {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Route of type {route_type} cannot have \
+                    next-hop-address: {self}"
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if self.weight.is_some() {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Route of type {route_type} cannot have weight: {self}"
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if route_type.is_iface_less() {
+            if let Some(iface) = self.next_hop_iface.take() {
+                log::warn!(
+                    "Ignoring next-hop-interface {iface} of route {self} as \
+                    route of type {route_type} is not bound to any interface"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // Kernel does not allow route using nexthop object to have its own next
+    // hop.
+    fn sanitize_nexthop_id(&self) -> Result<(), NmstateError> {
+        if self.next_hop_iface.is_some()
+            || self.next_hop_addr.is_some()
+            || self.weight.is_some()
+            || self.route_type.is_some()
+        {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Route using nexthop object cannot have \
+                    next-hop-interface, next-hop-address, weight or \
+                    route-type: {self}"
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
     pub(crate) fn is_ipv6(&self) -> bool {
         self.destination.as_ref().map(|d| is_ipv6_addr(d.as_str()))
             == Some(true)
@@ -320,15 +650,277 @@ impl std::fmt::Display for RouteEntry {
         if let Some(v) = self.weight {
             props.push(format!("weight: {v}"));
         }
+        if let Some(v) = self.nexthop_id {
+            props.push(format!("nexthop-id: {v}"));
+        }
+        if let Some(v) = self.route_type {
+            props.push(format!("route-type: {v}"));
+        }
+        if let Some(v) = self.source.as_ref() {
+            props.push(format!("source: {v}"));
+        }
+        if let Some(v) = self.mtu {
+            props.push(format!("mtu: {v}"));
+        }
+        if let Some(v) = self.advmss {
+            props.push(format!("advmss: {v}"));
+        }
+        if let Some(v) = self.initcwnd {
+            props.push(format!("initcwnd: {v}"));
+        }
+        if let Some(v) = self.initrwnd {
+            props.push(format!("initrwnd: {v}"));
+        }
+        if let Some(v) = self.onlink {
+            props.push(format!("onlink: {v}"));
+        }
+        if let Some(v) = self.scope {
+            props.push(format!("scope: {v}"));
+        }
+        if let Some(v) = self.quickack {
+            props.push(format!("quickack: {v}"));
+        }
+        if let Some(v) = self.protocol {
+            props.push(format!("protocol: {v}"));
+        }
 
         write!(f, "{}", props.join(" "))
     }
 }
 
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum RouteType {
+    /// Silently discard the packets.
+    /// Deserialize and serialize from/to `blackhole`.
+    Blackhole,
+    /// Discard the packets and reply ICMP message host unreachable.
+    /// Deserialize and serialize from/to `unreachable`.
+    Unreachable,
+    /// Discard the packets and reply ICMP message communication
+    /// administratively prohibited.
+    /// Deserialize and serialize from/to `prohibit`.
+    Prohibit,
+    /// Stop the lookup in this route table and continue with next route
+    /// rule, mostly used by policy routing.
+    /// Deserialize and serialize from/to `throw`.
+    Throw,
+    /// The destination is assigned to this host, `next-hop-interface` is
+    /// mandatory.
+    /// Deserialize and serialize from/to `local`.
+    Local,
+}
+
+impl RouteType {
+    pub(crate) fn is_iface_less(&self) -> bool {
+        *self != Self::Local
+    }
+}
+
+impl std::fmt::Display for RouteType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Blackhole => "blackhole",
+                Self::Unreachable => "unreachable",
+                Self::Prohibit => "prohibit",
+                Self::Throw => "throw",
+                Self::Local => "local",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for RouteType {
+    type Err = NmstateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blackhole" => Ok(Self::Blackhole),
+            "unreachable" => Ok(Self::Unreachable),
+            "prohibit" => Ok(Self::Prohibit),
+            "throw" => Ok(Self::Throw),
+            "local" => Ok(Self::Local),
+            _ => Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Unsupported route type {s}"),
+            )),
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum RouteScope {
+    /// Global route.
+    /// Deserialize and serialize from/to `universe`.
+    Universe,
+    /// Interior route in the local autonomous system.
+    /// Deserialize and serialize from/to `site`.
+    Site,
+    /// Route on directly attached link.
+    /// Deserialize and serialize from/to `link`.
+    Link,
+    /// Route on the local host.
+    /// Deserialize and serialize from/to `host`.
+    Host,
+}
+
+impl std::fmt::Display for RouteScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Universe => "universe",
+                Self::Site => "site",
+                Self::Link => "link",
+                Self::Host => "host",
+            }
+        )
+    }
+}
+
+const RT_SCOPE_UNIVERSE: u8 = 0;
+const RT_SCOPE_SITE: u8 = 200;
+const RT_SCOPE_LINK: u8 = 253;
+const RT_SCOPE_HOST: u8 = 254;
+
+impl From<RouteScope> for u8 {
+    fn from(v: RouteScope) -> u8 {
+        match v {
+            RouteScope::Universe => RT_SCOPE_UNIVERSE,
+            RouteScope::Site => RT_SCOPE_SITE,
+            RouteScope::Link => RT_SCOPE_LINK,
+            RouteScope::Host => RT_SCOPE_HOST,
+        }
+    }
+}
+
+impl TryFrom<u8> for RouteScope {
+    type Error = NmstateError;
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            RT_SCOPE_UNIVERSE => Ok(Self::Universe),
+            RT_SCOPE_SITE => Ok(Self::Site),
+            RT_SCOPE_LINK => Ok(Self::Link),
+            RT_SCOPE_HOST => Ok(Self::Host),
+            _ => Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Unsupported route scope {v}"),
+            )),
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum RouteProtocol {
+    /// Route installed during boot, often used by `iproute` command.
+    /// Deserialize and serialize from/to `boot`.
+    Boot,
+    /// Route installed by administrator.
+    /// Deserialize and serialize from/to `static`.
+    Static,
+    /// Route installed by IPv6 router advertisement.
+    /// Deserialize and serialize from/to `ra`.
+    Ra,
+    /// Route installed by DHCP client.
+    /// Deserialize and serialize from/to `dhcp`.
+    Dhcp,
+    /// Route installed by multicast daemon.
+    /// Deserialize and serialize from/to `mrouted`.
+    Mrouted,
+    /// Route installed by keepalived daemon.
+    /// Deserialize and serialize from/to `keepalived`.
+    Keepalived,
+    /// Route installed by babel daemon.
+    /// Deserialize and serialize from/to `babel`.
+    Babel,
+}
+
+impl RouteProtocol {
+    pub(crate) fn is_static(&self) -> bool {
+        matches!(self, Self::Boot | Self::Static)
+    }
+}
+
+impl std::fmt::Display for RouteProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Boot => "boot",
+                Self::Static => "static",
+                Self::Ra => "ra",
+                Self::Dhcp => "dhcp",
+                Self::Mrouted => "mrouted",
+                Self::Keepalived => "keepalived",
+                Self::Babel => "babel",
+            }
+        )
+    }
+}
+
+const RTPROT_BOOT: u8 = 3;
+const RTPROT_STATIC: u8 = 4;
+const RTPROT_RA: u8 = 9;
+const RTPROT_DHCP: u8 = 16;
+const RTPROT_MROUTED: u8 = 17;
+const RTPROT_KEEPALIVED: u8 = 18;
+const RTPROT_BABEL: u8 = 42;
+
+impl From<RouteProtocol> for u8 {
+    fn from(v: RouteProtocol) -> u8 {
+        match v {
+            RouteProtocol::Boot => RTPROT_BOOT,
+            RouteProtocol::Static => RTPROT_STATIC,
+            RouteProtocol::Ra => RTPROT_RA,
+            RouteProtocol::Dhcp => RTPROT_DHCP,
+            RouteProtocol::Mrouted => RTPROT_MROUTED,
+            RouteProtocol::Keepalived => RTPROT_KEEPALIVED,
+            RouteProtocol::Babel => RTPROT_BABEL,
+        }
+    }
+}
+
+impl TryFrom<u8> for RouteProtocol {
+    type Error = NmstateError;
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            RTPROT_BOOT => Ok(Self::Boot),
+            RTPROT_STATIC => Ok(Self::Static),
+            RTPROT_RA => Ok(Self::Ra),
+            RTPROT_DHCP => Ok(Self::Dhcp),
+            RTPROT_MROUTED => Ok(Self::Mrouted),
+            RTPROT_KEEPALIVED => Ok(Self::Keepalived),
+            RTPROT_BABEL => Ok(Self::Babel),
+            _ => Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Unsupported route protocol {v}"),
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub(crate) struct MergedRoutes {
     pub(crate) indexed: HashMap<String, Vec<RouteEntry>>,
     pub(crate) route_changed_ifaces: Vec<String>,
+    // Whether any route using nexthop object is added or removed. These
+    // routes are not bound to any interface.
+    pub(crate) nexthop_route_changed: bool,
     pub(crate) desired: Routes,
     pub(crate) current: Routes,
 }
@@ -350,6 +942,9 @@ impl MergedRoutes {
         }
 
         let mut changed_ifaces: HashSet<&str> = HashSet::new();
+        let mut nexthop_route_changed = desired_routes
+            .iter()
+            .any(|rt| !rt.is_absent() && rt.nexthop_id.is_some());
 
         let ifaces_marked_as_absent: Vec<&str> = merged_ifaces
             .kernel_ifaces
@@ -411,6 +1006,8 @@ impl MergedRoutes {
                     ));
                 }
                 changed_ifaces.insert(via.as_str());
+            } else if let Some(via) = rt.iface_for_store() {
+                changed_ifaces.insert(via);
             }
         }
 
@@ -421,26 +1018,36 @@ impl MergedRoutes {
             if let Some(cur_rts) = current.config.as_ref() {
                 for rt in cur_rts {
                     if absent_rt.is_match(rt) {
-                        if let Some(via) = rt.next_hop_iface.as_ref() {
-                            changed_ifaces.insert(via.as_str());
+                        if let Some(via) = rt.iface_for_store() {
+                            changed_ifaces.insert(via);
+                        } else if rt.nexthop_id.is_some() {
+                            nexthop_route_changed = true;
                         }
                     }
                 }
             }
         }
 
-        let mut flattend_routes: Vec<RouteEntry> = Vec::new();
+        // The desired route takes precedence over the current route with the
+        // same identity but different attributes, hence we place desired
+        // routes first and use stable sort before dedup.
+        let mut flattend_routes: Vec<RouteEntry> = desired_routes
+            .as_slice()
+            .iter()
+            .filter(|rt| !rt.is_absent())
+            .cloned()
+            .collect();
 
         if let Some(cur_rts) = current.config.as_ref() {
             for rt in cur_rts {
-                if let Some(via) = rt.next_hop_iface.as_ref() {
-                    if !ifaces_marked_as_absent.contains(&via.as_str())
-                        && ((rt.is_ipv6()
-                            && !ifaces_with_ipv6_disabled
-                                .contains(&via.as_str()))
-                            || (!rt.is_ipv6()
-                                && !ifaces_with_ipv4_disabled
-                                    .contains(&via.as_str())))
+                if let Some(via) = rt.iface_for_store() {
+                    if rt.is_iface_less()
+                        || !ifaces_marked_as_absent.contains(&via)
+                            && ((rt.is_ipv6()
+                                && !ifaces_with_ipv6_disabled.contains(&via))
+                                || (!rt.is_ipv6()
+                                    && !ifaces_with_ipv4_disabled
+                                        .contains(&via)))
                     {
                         if desired_routes
                             .as_slice()
@@ -457,22 +1064,13 @@ impl MergedRoutes {
             }
         }
 
-        // Append desired routes
-        for rt in desired_routes
-            .as_slice()
-            .iter()
-            .filter(|rt| !rt.is_absent())
-        {
-            flattend_routes.push(rt.clone());
-        }
-
-        flattend_routes.sort_unstable();
-        flattend_routes.dedup();
+        flattend_routes.sort_by(|a, b| a.identity_key().cmp(&b.identity_key()));
+        flattend_routes.dedup_by(|a, b| a.identity_key() == b.identity_key());
 
         let mut indexed: HashMap<String, Vec<RouteEntry>> = HashMap::new();
 
         for rt in flattend_routes {
-            if let Some(via) = rt.next_hop_iface.as_ref() {
+            if let Some(via) = rt.iface_for_store() {
                 let rts: &mut Vec<RouteEntry> =
                     match indexed.entry(via.to_string()) {
                         Entry::Occupied(o) => o.into_mut(),
@@ -490,6 +1088,7 @@ impl MergedRoutes {
             desired,
             current,
             route_changed_ifaces,
+            nexthop_route_changed,
         })
     }
 
@@ -516,7 +1115,37 @@ impl MergedRoutes {
     }
 
     pub(crate) fn is_changed(&self) -> bool {
-        !self.route_changed_ifaces.is_empty()
+        !self.route_changed_ifaces.is_empty() || self.nexthop_route_changed
+    }
+
+    // Return desired routes not exist in current and desired absent routes
+    // matching current.
+    pub(crate) fn gen_diff(&self) -> Result<Routes, NmstateError> {
+        let mut cur_rts = self.current.config.clone().unwrap_or_default();
+        for rt in cur_rts.iter_mut() {
+            rt.sanitize().ok();
+        }
+        let mut changed_rts = Vec::new();
+        for rt in self.desired.config.as_deref().unwrap_or_default() {
+            let mut rt = rt.clone();
+            rt.sanitize()?;
+            let is_changed = if rt.is_absent() {
+                cur_rts.iter().any(|cur_rt| rt.is_match(cur_rt))
+            } else {
+                !cur_rts.iter().any(|cur_rt| {
+                    rt.is_match(cur_rt)
+                        && (rt.metric.is_none() || rt.metric == cur_rt.metric)
+                })
+            };
+            if is_changed {
+                changed_rts.push(rt);
+            }
+        }
+        let mut ret = Routes::new();
+        if !changed_rts.is_empty() {
+            ret.config = Some(changed_rts);
+        }
+        Ok(ret)
     }
 }
 
@@ -120,6 +120,80 @@ pub struct RouteRuleEntry {
     /// Incoming interface.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub iif: Option<String>,
+    /// Outgoing interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oif: Option<String>,
+    /// Range of user ID of the socket owner to match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid_range: Option<RouteRuleUidRange>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        rename = "ipproto",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// IP protocol number to match, for example 6 for TCP and 17 for UDP.
+    /// Serialize and deserialize to/from `ipproto`.
+    pub ip_proto: Option<u8>,
+    /// Range of source port to match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sport: Option<RouteRulePortRange>,
+    /// Range of destination port to match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dport: Option<RouteRulePortRange>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u8_or_string"
+    )]
+    /// Type of service (TOS) byte to match. To match DSCP, please use the
+    /// DSCP value shifted left by 2, for example 0xb8 for DSCP 46(EF).
+    pub tos: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Lookup the route table of the L3 master device(e.g. VRF) of the
+    /// interface the packet is bound to. Cannot be used with `route-table`
+    /// or `action`.
+    /// Not supported by NetworkManager backend.
+    pub l3mdev: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_bool_or_string"
+    )]
+    /// Invert the selector, the action will be taken for packets not
+    /// matching this route rule.
+    pub invert: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Reject the route lookup result if its prefix length is smaller or
+    /// equal to this value. For example, setting 0 with `route-table: 254`
+    /// means using main route table except its default gateway.
+    pub suppress_prefix_length: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Reject the route lookup result if its outgoing interface belongs to
+    /// this interface group.
+    /// Not supported by NetworkManager backend.
+    pub suppress_ifgroup: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "crate::deserializer::option_u32_or_string"
+    )]
+    /// Priority of the route rule to jump to, required when action is
+    /// [RouteRuleAction::Goto]. Should be bigger than the priority of this
+    /// route rule.
+    pub goto_target: Option<u32>,
 }
 
 impl RouteRuleEntry {
@@ -197,6 +271,82 @@ impl RouteRuleEntry {
         Ok(())
     }
 
+    fn validate_selectors(&self) -> Result<(), NmstateError> {
+        if let Some(uid_range) = self.uid_range.as_ref() {
+            if uid_range.start > uid_range.end {
+                return Err(invalid_rule(format!(
+                    "The start of uid-range should not be bigger than its \
+                    end: {self}"
+                )));
+            }
+        }
+        for (name, port_range) in [("sport", self.sport), ("dport", self.dport)]
+        {
+            if let Some(port_range) = port_range {
+                if port_range.start == 0
+                    || port_range.end == u16::MAX
+                    || port_range.start > port_range.end
+                {
+                    return Err(invalid_rule(format!(
+                        "The {name} should be in the range of 1 to 65534 \
+                        and its start should not be bigger than its end: \
+                        {self}"
+                    )));
+                }
+            }
+        }
+        if self.l3mdev == Some(true)
+            && (self.action.is_some()
+                || self
+                    .table_id
+                    .map(|t| t != RouteRuleEntry::USE_DEFAULT_ROUTE_TABLE)
+                    .unwrap_or_default())
+        {
+            return Err(invalid_rule(format!(
+                "The l3mdev route rule cannot have route-table or action \
+                defined: {self}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_action(&self) -> Result<(), NmstateError> {
+        match (self.action, self.goto_target) {
+            (Some(RouteRuleAction::Goto), None) => {
+                return Err(invalid_rule(format!(
+                    "The goto-target is required for goto route rule: {self}"
+                )));
+            }
+            (Some(RouteRuleAction::Goto), Some(target)) => {
+                if let Some(priority) = self.priority.filter(|p| *p >= 0) {
+                    if i64::from(target) <= priority {
+                        return Err(invalid_rule(format!(
+                            "The goto-target should be bigger than the \
+                            priority of route rule: {self}"
+                        )));
+                    }
+                }
+            }
+            (_, Some(_)) => {
+                return Err(invalid_rule(format!(
+                    "The goto-target is only valid for goto route rule: \
+                    {self}"
+                )));
+            }
+            _ => (),
+        }
+        if self.action.is_some()
+            && (self.suppress_prefix_length.is_some()
+                || self.suppress_ifgroup.is_some())
+        {
+            return Err(invalid_rule(format!(
+                "The suppress-prefix-length and suppress-ifgroup are only \
+                valid for route rule looking up route table: {self}"
+            )));
+        }
+        Ok(())
+    }
+
     pub(crate) fn is_absent(&self) -> bool {
         matches!(self.state, Some(RouteRuleState::Absent))
     }
@@ -291,6 +441,52 @@ impl RouteRuleEntry {
         if self.iif.is_some() && self.iif != other.iif {
             return false;
         }
+        if self.oif.is_some() && self.oif != other.oif {
+            return false;
+        }
+        if self.uid_range.is_some() && self.uid_range != other.uid_range {
+            return false;
+        }
+        if self.ip_proto.is_some()
+            && self.ip_proto.unwrap_or(0) != other.ip_proto.unwrap_or(0)
+        {
+            return false;
+        }
+        if self.sport.is_some() && self.sport != other.sport {
+            return false;
+        }
+        if self.dport.is_some() && self.dport != other.dport {
+            return false;
+        }
+        if self.tos.is_some() && self.tos.unwrap_or(0) != other.tos.unwrap_or(0)
+        {
+            return false;
+        }
+        if self.l3mdev.is_some()
+            && self.l3mdev.unwrap_or_default()
+                != other.l3mdev.unwrap_or_default()
+        {
+            return false;
+        }
+        if self.invert.is_some()
+            && self.invert.unwrap_or_default()
+                != other.invert.unwrap_or_default()
+        {
+            return false;
+        }
+        if self.suppress_prefix_length.is_some()
+            && self.suppress_prefix_length != other.suppress_prefix_length
+        {
+            return false;
+        }
+        if self.suppress_ifgroup.is_some()
+            && self.suppress_ifgroup != other.suppress_ifgroup
+        {
+            return false;
+        }
+        if self.goto_target.is_some() && self.goto_target != other.goto_target {
+            return false;
+        }
         if self.action.is_some() && self.action != other.action {
             return false;
         }
@@ -298,8 +494,23 @@ impl RouteRuleEntry {
     }
 
     // Return tuple of (no_absent, is_ipv4, table_id, ip_from,
-    // ip_to, priority, fwmark, fwmask, action)
-    fn sort_key(&self) -> (bool, bool, u32, &str, &str, i64, u32, u32, u8) {
+    // ip_to, priority, fwmark, fwmask, action, selectors, (goto_target,
+    // suppress_prefix_length, suppress_ifgroup))
+    fn sort_key(
+        &self,
+    ) -> (
+        bool,
+        bool,
+        u32,
+        &str,
+        &str,
+        i64,
+        u32,
+        u32,
+        u8,
+        RouteRuleSelectorKey<'_>,
+        RouteRuleActionArgKey,
+    ) {
         (
             !matches!(self.state, Some(RouteRuleState::Absent)),
             {
@@ -326,6 +537,22 @@ impl RouteRuleEntry {
             self.fwmark.unwrap_or(0),
             self.fwmask.unwrap_or(0),
             self.action.map(u8::from).unwrap_or(0),
+            (
+                self.iif.as_deref().unwrap_or(""),
+                self.oif.as_deref().unwrap_or(""),
+                self.uid_range,
+                self.ip_proto.unwrap_or(0),
+                self.sport,
+                self.dport,
+                self.tos.unwrap_or(0),
+                self.l3mdev.unwrap_or_default(),
+                self.invert.unwrap_or_default(),
+            ),
+            (
+                self.goto_target,
+                self.suppress_prefix_length,
+                self.suppress_ifgroup,
+            ),
         )
     }
 
@@ -374,8 +601,13 @@ impl RouteRuleEntry {
         }
         self.validate_ip_from_to()?;
         self.validate_fwmark_and_fwmask()?;
+        self.validate_selectors()?;
+        self.validate_action()?;
 
-        if self.action.is_none() && self.table_id.is_none() {
+        if self.action.is_none()
+            && self.table_id.is_none()
+            && self.l3mdev != Some(true)
+        {
             log::info!(
                 "Route rule {self} has no action or route-table \
                 defined, using default route table 254"
@@ -441,13 +673,141 @@ impl std::fmt::Display for RouteRuleEntry {
         if let Some(v) = self.iif.as_ref() {
             props.push(format!("iif: {v}"));
         }
+        if let Some(v) = self.oif.as_ref() {
+            props.push(format!("oif: {v}"));
+        }
+        if let Some(v) = self.uid_range.as_ref() {
+            props.push(format!("uid-range: {v}"));
+        }
+        if let Some(v) = self.ip_proto.as_ref() {
+            props.push(format!("ipproto: {v}"));
+        }
+        if let Some(v) = self.sport.as_ref() {
+            props.push(format!("sport: {v}"));
+        }
+        if let Some(v) = self.dport.as_ref() {
+            props.push(format!("dport: {v}"));
+        }
+        if let Some(v) = self.tos.as_ref() {
+            props.push(format!("tos: {v:#x}"));
+        }
+        if let Some(v) = self.l3mdev.as_ref() {
+            props.push(format!("l3mdev: {v}"));
+        }
+        if let Some(v) = self.invert.as_ref() {
+            props.push(format!("invert: {v}"));
+        }
+        if let Some(v) = self.suppress_prefix_length.as_ref() {
+            props.push(format!("suppress-prefix-length: {v}"));
+        }
+        if let Some(v) = self.suppress_ifgroup.as_ref() {
+            props.push(format!("suppress-ifgroup: {v}"));
+        }
         if let Some(v) = self.action.as_ref() {
             props.push(format!("action: {v}"));
         }
+        if let Some(v) = self.goto_target.as_ref() {
+            props.push(format!("goto-target: {v}"));
+        }
         write!(f, "{}", props.join(" "))
     }
 }
 
+// Tuple of (goto_target, suppress_prefix_length, suppress_ifgroup)
+type RouteRuleActionArgKey = (Option<u32>, Option<u32>, Option<u32>);
+
+// Tuple of (iif, oif, uid_range, ip_proto, sport, dport, tos, l3mdev, invert)
+type RouteRuleSelectorKey<'a> = (
+    &'a str,
+    &'a str,
+    Option<RouteRuleUidRange>,
+    u8,
+    Option<RouteRulePortRange>,
+    Option<RouteRulePortRange>,
+    u8,
+    bool,
+    bool,
+);
+
+fn invalid_rule(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[non_exhaustive]
+#[serde(deny_unknown_fields)]
+/// Inclusive range of user ID.
+pub struct RouteRuleUidRange {
+    #[serde(deserialize_with = "crate::deserializer::u32_or_string")]
+    pub start: u32,
+    #[serde(deserialize_with = "crate::deserializer::u32_or_string")]
+    pub end: u32,
+}
+
+impl RouteRuleUidRange {
+    pub fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+}
+
+impl std::fmt::Display for RouteRuleUidRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[non_exhaustive]
+#[serde(deny_unknown_fields)]
+/// Inclusive range of TCP/UDP/SCTP port. To match single port, please set
+/// `start` and `end` to the same value.
+pub struct RouteRulePortRange {
+    #[serde(deserialize_with = "crate::deserializer::u16_or_string")]
+    pub start: u16,
+    #[serde(deserialize_with = "crate::deserializer::u16_or_string")]
+    pub end: u16,
+}
+
+impl RouteRulePortRange {
+    pub fn new(start: u16, end: u16) -> Self {
+        Self { start, end }
+    }
+}
+
+impl std::fmt::Display for RouteRulePortRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[non_exhaustive]
@@ -456,6 +816,11 @@ pub enum RouteRuleAction {
     Blackhole,
     Unreachable,
     Prohibit,
+    /// Jump to the route rule with priority defined by
+    /// [RouteRuleEntry.goto_target].
+    Goto,
+    /// Do nothing, used as placeholder or target of goto route rule.
+    Nop,
 }
 
 impl std::fmt::Display for RouteRuleAction {
@@ -467,11 +832,15 @@ impl std::fmt::Display for RouteRuleAction {
                 Self::Blackhole => "blackhole",
                 Self::Unreachable => "unreachable",
                 Self::Prohibit => "prohibit",
+                Self::Goto => "goto",
+                Self::Nop => "nop",
             }
         )
     }
 }
 
+const FR_ACT_GOTO: u8 = 2;
+const FR_ACT_NOP: u8 = 3;
 const FR_ACT_BLACKHOLE: u8 = 6;
 const FR_ACT_UNREACHABLE: u8 = 7;
 const FR_ACT_PROHIBIT: u8 = 8;
@@ -482,6 +851,8 @@ impl From<RouteRuleAction> for u8 {
             RouteRuleAction::Blackhole => FR_ACT_BLACKHOLE,
             RouteRuleAction::Unreachable => FR_ACT_UNREACHABLE,
             RouteRuleAction::Prohibit => FR_ACT_PROHIBIT,
+            RouteRuleAction::Goto => FR_ACT_GOTO,
+            RouteRuleAction::Nop => FR_ACT_NOP,
         }
     }
 }
@@ -563,11 +934,10 @@ impl MergedRouteRules {
             .collect();
 
         self.for_apply.retain(|rule| {
-            if let Some(iif) = rule.iif.as_ref() {
-                !ignored_ifaces.contains(&iif.as_str())
-            } else {
-                true
-            }
+            [rule.iif.as_deref(), rule.oif.as_deref()]
+                .iter()
+                .flatten()
+                .all(|iface| !ignored_ifaces.contains(iface))
         })
     }
 
@@ -576,6 +946,30 @@ impl MergedRouteRules {
             && (self.for_apply
                 != self.current.config.clone().unwrap_or_default())
     }
+
+    // Return desired route rules not exist in current and desired absent
+    // route rules matching current.
+    pub(crate) fn gen_diff(&self) -> Result<RouteRules, NmstateError> {
+        let cur_rules = self.current.config.as_deref().unwrap_or_default();
+        let mut changed_rules = Vec::new();
+        for rule in self.desired.config.as_deref().unwrap_or_default() {
+            let mut rule = rule.clone();
+            rule.sanitize()?;
+            let is_changed = if rule.is_absent() {
+                cur_rules.iter().any(|cur_rule| rule.is_match(cur_rule))
+            } else {
+                !cur_rules.iter().any(|cur_rule| rule.is_match(cur_rule))
+            };
+            if is_changed {
+                changed_rules.push(rule);
+            }
+        }
+        let mut ret = RouteRules::new();
+        if !changed_rules.is_empty() {
+            ret.config = Some(changed_rules);
+        }
+        Ok(ret)
+    }
 }
 
 fn set_auto_priority(
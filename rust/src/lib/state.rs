@@ -3,94 +3,81 @@
 use serde_json::Value;
 
 #[cfg(feature = "query_apply")]
-fn _get_json_value_difference<'a, 'b>(
+fn _get_json_value_differences<'a, 'b>(
     reference: String,
     desire: &'a Value,
     current: &'b Value,
-) -> Option<(String, &'a Value, &'b Value)> {
+    differences: &mut Vec<(String, &'a Value, &'b Value)>,
+) {
     match (desire, current) {
         (Value::Bool(des), Value::Bool(cur)) => {
             if des != cur {
-                Some((reference, desire, current))
-            } else {
-                None
+                differences.push((reference, desire, current));
             }
         }
         (Value::Number(des), Value::Number(cur)) => {
             if des != cur {
-                Some((reference, desire, current))
-            } else {
-                None
+                differences.push((reference, desire, current));
             }
         }
         (Value::String(des), Value::String(cur)) => {
-            if des != cur {
-                if des == crate::NetworkState::PASSWORD_HID_BY_NMSTATE {
-                    None
-                } else {
-                    Some((reference, desire, current))
-                }
-            } else {
-                None
+            if des != cur && des != crate::NetworkState::PASSWORD_HID_BY_NMSTATE
+            {
+                differences.push((reference, desire, current));
             }
         }
         (Value::Array(des), Value::Array(cur)) => {
             if des.len() != cur.len() {
-                Some((reference, desire, current))
+                differences.push((reference, desire, current));
             } else {
                 for (index, des_element) in des.iter().enumerate() {
                     // The [] is safe as we already checked the length
                     let cur_element = &cur[index];
-                    if let Some(difference) = get_json_value_difference(
+                    _get_json_value_differences(
                         format!("{}[{index}]", &reference),
                         des_element,
                         cur_element,
-                    ) {
-                        return Some(difference);
-                    }
+                        differences,
+                    );
                 }
-                None
             }
         }
         (Value::Object(des), Value::Object(cur)) => {
             for (key, des_value) in des.iter() {
                 let reference = format!("{reference}.{key}");
                 if let Some(cur_value) = cur.get(key) {
-                    if let Some(difference) = get_json_value_difference(
-                        reference.clone(),
+                    _get_json_value_differences(
+                        reference,
                         des_value,
                         cur_value,
-                    ) {
-                        return Some(difference);
-                    }
+                        differences,
+                    );
                 } else if des_value != &Value::Null {
-                    return Some((reference, des_value, &Value::Null));
+                    differences.push((reference, des_value, &Value::Null));
                 }
             }
-            None
         }
-        (Value::Null, _) => None,
-        (_, _) => Some((reference, desire, current)),
+        (Value::Null, _) => (),
+        (_, _) => differences.push((reference, desire, current)),
     }
 }
 
+// Return all the differences between desire and current, each entry is
+// the JSON path with desired and current value.
 #[cfg(feature = "query_apply")]
-pub(crate) fn get_json_value_difference<'a, 'b>(
+pub(crate) fn get_json_value_differences<'a, 'b>(
     reference: String,
     desire: &'a Value,
     current: &'b Value,
-) -> Option<(String, &'a Value, &'b Value)> {
-    if let Some((reference, desire, current)) =
-        _get_json_value_difference(reference, desire, current)
-    {
-        if should_ignore(reference.as_str(), desire, current) {
-            None
-        } else {
-            Some((reference, desire, current))
-        }
-    } else {
-        None
-    }
+) -> Vec<(String, &'a Value, &'b Value)> {
+    let mut differences = Vec::new();
+    _get_json_value_differences(reference, desire, current, &mut differences);
+    differences
+        .into_iter()
+        .filter(|(reference, desire, current)| {
+            !should_ignore(reference.as_str(), desire, current)
+        })
+        .collect()
 }
 
 #[cfg(feature = "query_apply")]
@@ -110,6 +97,50 @@ fn should_ignore(reference: &str, desire: &Value, current: &Value) -> bool {
     }
 }
 
+// Return the properties of desired which are different from current.
+// JSON object is compared recursively while other types (including array)
+// are compared and returned as a whole.
+pub(crate) fn gen_diff_json_value(
+    desired: &Value,
+    current: &Value,
+) -> Option<Value> {
+    match (desired, current) {
+        (Value::Object(des), Value::Object(cur)) => {
+            let mut ret = serde_json::Map::new();
+            for (key, des_value) in des.iter() {
+                let diff_value = if let Some(cur_value) = cur.get(key) {
+                    gen_diff_json_value(des_value, cur_value)
+                } else if des_value != &Value::Null {
+                    Some(des_value.clone())
+                } else {
+                    None
+                };
+                if let Some(v) = diff_value {
+                    ret.insert(key.to_string(), v);
+                }
+            }
+            if ret.is_empty() {
+                None
+            } else {
+                Some(Value::Object(ret))
+            }
+        }
+        (Value::Null, _) => None,
+        (Value::String(des), _)
+            if des == crate::NetworkState::PASSWORD_HID_BY_NMSTATE =>
+        {
+            None
+        }
+        (_, _) => {
+            if desired != current {
+                Some(desired.clone())
+            } else {
+                None
+            }
+        }
+    }
+}
+
 // Whatever not defined in desired but defined in current will be copied
 pub(crate) fn merge_json_value(desired: &mut Value, current: &Value) {
     if let (Some(desired), Some(current)) =
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ErrorKind, GeneveInterface, MergedInterfaces, NetworkState};
+
+#[test]
+fn test_geneve_stringlized_attributes() {
+    let iface: GeneveInterface = serde_yaml::from_str(
+        r#"---
+name: geneve1
+type: geneve
+state: up
+geneve:
+  id: "102"
+  remote: 192.0.2.2
+  ttl: "64"
+  tos: "16"
+  destination-port: "6082"
+  external: "false"
+"#,
+    )
+    .unwrap();
+
+    let conf = iface.geneve.unwrap();
+    assert_eq!(conf.id, Some(102));
+    assert_eq!(conf.remote, Some("192.0.2.2".parse().unwrap()));
+    assert_eq!(conf.ttl, Some(64));
+    assert_eq!(conf.tos, Some(16));
+    assert_eq!(conf.dst_port, Some(6082));
+    assert_eq!(conf.external, Some(false));
+}
+
+fn gen_merge_result(desired_yaml: &str) -> Result<(), ErrorKind> {
+    let desired: NetworkState = serde_yaml::from_str(desired_yaml).unwrap();
+    MergedInterfaces::new(
+        desired.interfaces,
+        NetworkState::new().interfaces,
+        false,
+        false,
+    )
+    .map(|_| ())
+    .map_err(|e| e.kind())
+}
+
+#[test]
+fn test_geneve_invalid_id() {
+    assert_eq!(
+        gen_merge_result(
+            r#"---
+interfaces:
+- name: geneve1
+  type: geneve
+  geneve:
+    id: 16777216
+    remote: 192.0.2.2
+"#
+        ),
+        Err(ErrorKind::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_geneve_external_with_remote() {
+    assert_eq!(
+        gen_merge_result(
+            r#"---
+interfaces:
+- name: geneve1
+  type: geneve
+  geneve:
+    remote: 192.0.2.2
+    external: true
+"#
+        ),
+        Err(ErrorKind::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_geneve_external_with_dst_port() {
+    assert_eq!(
+        gen_merge_result(
+            r#"---
+interfaces:
+- name: genev_sys_6081
+  type: geneve
+  geneve:
+    destination-port: 6081
+    external: true
+"#
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_geneve_not_supported_by_nm() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: geneve1
+  type: geneve
+  state: up
+  geneve:
+    id: 102
+    remote: 192.0.2.2
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
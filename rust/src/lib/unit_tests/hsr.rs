@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    unit_tests::testlib::{bond_with_ports, new_eth_iface},
+    ErrorKind, HsrInterface, HsrProtocol, InterfaceType, Interfaces,
+    MergedInterfaces, NetworkState,
+};
+
+#[test]
+fn test_hsr_stringlized_attributes() {
+    let mut iface: HsrInterface = serde_yaml::from_str(
+        r#"---
+name: hsr0
+type: hsr
+state: up
+hsr:
+  port1: eth1
+  port2: eth2
+  supervision-address: 01:15:4e:00:01:2a
+  protocol: hsr
+  version: "1"
+"#,
+    )
+    .unwrap();
+    iface.sanitize(true).unwrap();
+
+    assert_eq!(iface.ports(), Some(vec!["eth1", "eth2"]));
+    let conf = iface.hsr.unwrap();
+    assert_eq!(
+        conf.supervision_address.as_deref(),
+        Some("01:15:4E:00:01:2A")
+    );
+    assert_eq!(conf.multicast_spec(), Some(42));
+    assert_eq!(conf.protocol, Some(HsrProtocol::Hsr));
+    assert_eq!(conf.version, Some(1));
+}
+
+fn expect_invalid_hsr(hsr_conf: &str) {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    cur_ifaces.push(new_eth_iface("eth2"));
+    let ifaces: Interfaces = serde_yaml::from_str(&format!(
+        r#"---
+- name: hsr0
+  type: hsr
+  hsr:
+{hsr_conf}"#
+    ))
+    .unwrap();
+
+    let result = MergedInterfaces::new(ifaces, cur_ifaces, false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_hsr_same_ports() {
+    expect_invalid_hsr("    port1: eth1\n    port2: eth1\n");
+}
+
+#[test]
+fn test_hsr_invalid_supervision_address() {
+    expect_invalid_hsr(
+        "    port1: eth1\n    port2: eth2\n    \
+        supervision-address: 01:15:4E:00:02:00\n",
+    );
+}
+
+#[test]
+fn test_hsr_version_with_prp() {
+    expect_invalid_hsr(
+        "    port1: eth1\n    port2: eth2\n    protocol: prp\n    \
+        version: 0\n",
+    );
+}
+
+#[test]
+fn test_hsr_missing_ports() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: hsr0
+  type: hsr
+"#,
+    )
+    .unwrap();
+
+    let result = MergedInterfaces::new(ifaces, Interfaces::new(), false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_hsr_ports_controller_and_order() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    cur_ifaces.push(new_eth_iface("eth2"));
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: hsr0
+  type: hsr
+  hsr:
+    port1: eth1
+    port2: eth2
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces =
+        MergedInterfaces::new(ifaces, cur_ifaces, false, false).unwrap();
+
+    let hsr_iface = merged_ifaces
+        .get_iface("hsr0", InterfaceType::Hsr)
+        .unwrap()
+        .for_apply
+        .as_ref()
+        .unwrap();
+    for port_name in ["eth1", "eth2"] {
+        let port_iface = merged_ifaces
+            .get_iface(port_name, InterfaceType::Ethernet)
+            .unwrap()
+            .for_apply
+            .as_ref()
+            .unwrap();
+        assert_eq!(port_iface.base_iface().controller.as_deref(), Some("hsr0"));
+        assert_eq!(
+            port_iface.base_iface().controller_type,
+            Some(InterfaceType::Hsr)
+        );
+        assert!(
+            port_iface.base_iface().up_priority
+                > hsr_iface.base_iface().up_priority
+        );
+    }
+}
+
+#[test]
+fn test_hsr_port_overbooked_by_bond() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    cur_ifaces.push(new_eth_iface("eth2"));
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: hsr0
+  type: hsr
+  hsr:
+    port1: eth1
+    port2: eth2
+"#,
+    )
+    .unwrap();
+    ifaces.push(bond_with_ports("bond0", &["eth2"]));
+
+    let result = MergedInterfaces::new(ifaces, cur_ifaces, false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_hsr_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+- name: eth2
+  type: ethernet
+  state: up
+- name: prp0
+  type: hsr
+  state: up
+  hsr:
+    port1: eth1
+    port2: eth2
+    supervision-address: 01:15:4E:00:01:10
+    protocol: prp
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfiles = &confs["NetworkManager"];
+
+    let hsr_keyfile = &keyfiles
+        .iter()
+        .find(|(name, _)| name.starts_with("prp0"))
+        .unwrap()
+        .1;
+    assert!(hsr_keyfile.contains("type=hsr\n"));
+    assert!(hsr_keyfile.contains(
+        "[hsr]\n\
+        multicast-spec=16\n\
+        port1=eth1\n\
+        port2=eth2\n\
+        prp=true\n"
+    ));
+
+    // HSR ports are not NetworkManager ports
+    let port_keyfile = &keyfiles
+        .iter()
+        .find(|(name, _)| name.starts_with("eth1"))
+        .unwrap()
+        .1;
+    assert!(!port_keyfile.contains("master="));
+    assert!(!port_keyfile.contains("slave-type="));
+    assert!(port_keyfile.contains("[ipv4]\nmethod=disabled\n"));
+    assert!(port_keyfile.contains("[ipv6]\nmethod=disabled\n"));
+}
+
+#[test]
+fn test_hsr_version_1_not_supported_by_nm() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+- name: eth2
+  type: ethernet
+  state: up
+- name: hsr0
+  type: hsr
+  state: up
+  hsr:
+    port1: eth1
+    port2: eth2
+    version: 1
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
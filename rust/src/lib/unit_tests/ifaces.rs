@@ -5,7 +5,7 @@ use crate::{
         new_eth_iface, new_ovs_br_iface, new_ovs_iface, new_unknown_iface,
         new_vlan_iface,
     },
-    BondMode, Interface, InterfaceState, InterfaceType, Interfaces,
+    BondMode, ErrorKind, Interface, InterfaceState, InterfaceType, Interfaces,
     MergedInterfaces,
 };
 
@@ -355,3 +355,51 @@ fn test_ifaces_iter_mut() {
     assert_eq!(ifaces_vec[0].base_iface().mtu, Some(1280));
     assert_eq!(ifaces_vec[1].base_iface().mtu, Some(1280));
 }
+
+#[test]
+fn test_verify_collect_all_mismatches() {
+    let current: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+- name: eth2
+  type: ethernet
+  state: up
+  mtu: 1500
+"#,
+    )
+    .unwrap();
+    let desired: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+- name: eth2
+  type: ethernet
+  state: up
+  mtu: 1500
+- name: dummy0
+  type: dummy
+  state: up
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces =
+        MergedInterfaces::new(desired, current.clone(), false, false).unwrap();
+
+    let e = merged_ifaces.verify(&current).unwrap_err();
+
+    assert_eq!(e.kind(), ErrorKind::VerificationError);
+    let mismatches = e.verification_mismatches();
+    assert_eq!(mismatches.len(), 2);
+    assert_eq!(mismatches[0].path, "dummy0.interface.state");
+    assert_eq!(mismatches[0].desired, "\"up\"");
+    assert_eq!(mismatches[0].current, "null");
+    assert_eq!(mismatches[1].path, "eth1.interface.mtu");
+    assert_eq!(mismatches[1].desired, "9000");
+    assert_eq!(mismatches[1].current, "1500");
+}
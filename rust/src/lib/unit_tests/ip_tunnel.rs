@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ErrorKind, Interface, InterfaceType, IpTunnelInterface, MergedInterfaces,
+    NetworkState,
+};
+
+#[test]
+fn test_ip_tunnel_stringlized_attributes() {
+    let iface: IpTunnelInterface = serde_yaml::from_str(
+        r#"---
+name: gre1
+type: gre
+state: up
+ip-tunnel:
+  base-iface: eth1
+  local: 192.0.2.1
+  remote: 198.51.100.1
+  key: "100"
+  ttl: "64"
+  tos: "16"
+  pmtudisc: "true"
+"#,
+    )
+    .unwrap();
+
+    let conf = iface.ip_tunnel.unwrap();
+    assert_eq!(conf.base_iface.as_deref(), Some("eth1"));
+    assert_eq!(conf.key, Some(100));
+    assert_eq!(conf.ttl, Some(64));
+    assert_eq!(conf.tos, Some(16));
+    assert_eq!(conf.pmtudisc, Some(true));
+}
+
+#[test]
+fn test_ip_tunnel_types() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: gre1
+  type: gre
+- name: gretap1
+  type: gretap
+- name: ipip1
+  type: ipip
+- name: sit1
+  type: sit
+- name: ip6tnl1
+  type: ip6tnl
+"#,
+    )
+    .unwrap();
+
+    for (name, iface_type) in [
+        ("gre1", InterfaceType::Gre),
+        ("gretap1", InterfaceType::GreTap),
+        ("ipip1", InterfaceType::IpIp),
+        ("sit1", InterfaceType::Sit),
+        ("ip6tnl1", InterfaceType::Ip6Tnl),
+    ] {
+        let iface = net_state.interfaces.kernel_ifaces.get(name).unwrap();
+        assert!(matches!(iface, Interface::IpTunnel(_)));
+        assert_eq!(iface.iface_type(), iface_type);
+    }
+}
+
+fn gen_merge_error(desired_yaml: &str) -> ErrorKind {
+    let desired: NetworkState = serde_yaml::from_str(desired_yaml).unwrap();
+    let result = MergedInterfaces::new(
+        desired.interfaces,
+        NetworkState::new().interfaces,
+        false,
+        false,
+    );
+    assert!(result.is_err());
+    result.err().unwrap().kind()
+}
+
+#[test]
+fn test_ip_tunnel_mixed_ip_family() {
+    assert_eq!(
+        gen_merge_error(
+            r#"---
+interfaces:
+- name: gre1
+  type: gre
+  ip-tunnel:
+    local: 192.0.2.1
+    remote: 2001:db8::1
+"#
+        ),
+        ErrorKind::InvalidArgument
+    );
+}
+
+#[test]
+fn test_ipip_with_ipv6_address() {
+    assert_eq!(
+        gen_merge_error(
+            r#"---
+interfaces:
+- name: ipip1
+  type: ipip
+  ip-tunnel:
+    remote: 2001:db8::1
+"#
+        ),
+        ErrorKind::InvalidArgument
+    );
+}
+
+#[test]
+fn test_ip_tunnel_key_on_non_gre() {
+    assert_eq!(
+        gen_merge_error(
+            r#"---
+interfaces:
+- name: sit1
+  type: sit
+  ip-tunnel:
+    remote: 198.51.100.1
+    key: 100
+"#
+        ),
+        ErrorKind::InvalidArgument
+    );
+}
+
+#[test]
+fn test_ip_tunnel_pmtudisc_off_with_ttl() {
+    assert_eq!(
+        gen_merge_error(
+            r#"---
+interfaces:
+- name: gre1
+  type: gre
+  ip-tunnel:
+    remote: 198.51.100.1
+    ttl: 64
+    pmtudisc: false
+"#
+        ),
+        ErrorKind::InvalidArgument
+    );
+}
+
+#[test]
+fn test_gre_layer3_ignore_mac() {
+    let mut iface: IpTunnelInterface = serde_yaml::from_str(
+        r#"---
+name: gre1
+type: gre
+mac-address: 00:23:45:67:89:1a
+"#,
+    )
+    .unwrap();
+    iface.sanitize(true).unwrap();
+    assert_eq!(iface.base.mac_address, None);
+}
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    unit_tests::testlib::{bond_with_ports, new_eth_iface},
+    ErrorKind, Interface, InterfaceType, Interfaces, IpVlanInterface,
+    IpVlanMode, MergedInterfaces, NetworkState,
+};
+
+#[test]
+fn test_ip_vlan_stringlized_attributes() {
+    let iface: IpVlanInterface = serde_yaml::from_str(
+        r#"---
+name: ipvlan0
+type: ipvlan
+state: up
+ipvlan:
+  base-iface: "eth1"
+  mode: "l3s"
+  private: "true"
+  vepa: "false"
+"#,
+    )
+    .unwrap();
+
+    let conf = iface.ipvlan.unwrap();
+    assert_eq!(conf.base_iface, "eth1");
+    assert_eq!(conf.mode, Some(IpVlanMode::L3s));
+    assert_eq!(conf.private, Some(true));
+    assert_eq!(conf.vepa, Some(false));
+}
+
+#[test]
+fn test_ip_vlan_private_and_vepa() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: ipvlan0
+  type: ipvlan
+  ipvlan:
+    base-iface: eth1
+    private: true
+    vepa: true
+"#,
+    )
+    .unwrap();
+    let result = MergedInterfaces::new(
+        desired.interfaces,
+        NetworkState::new().interfaces,
+        false,
+        false,
+    );
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ip_vlan_up_after_parent() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: ipvlan0
+  type: ipvlan
+  ipvlan:
+    base-iface: bond0
+"#,
+    )
+    .unwrap();
+    ifaces.push(bond_with_ports("bond0", &["eth1"]));
+
+    let merged_ifaces =
+        MergedInterfaces::new(ifaces, cur_ifaces, false, false).unwrap();
+
+    let bond_iface = merged_ifaces
+        .get_iface("bond0", InterfaceType::Bond)
+        .unwrap()
+        .for_apply
+        .as_ref()
+        .unwrap();
+    let ipvlan_iface = merged_ifaces
+        .get_iface("ipvlan0", InterfaceType::IpVlan)
+        .unwrap()
+        .for_apply
+        .as_ref()
+        .unwrap();
+
+    assert!(
+        ipvlan_iface.base_iface().up_priority
+            > bond_iface.base_iface().up_priority
+    );
+}
+
+#[test]
+fn test_ip_vlan_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: ipvlan0
+  type: ipvlan
+  state: up
+  ipvlan:
+    base-iface: eth1
+    mode: l2
+    vepa: true
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("type=ipvlan\n"));
+    assert!(keyfile.contains(
+        "[ipvlan]\n\
+        mode=1\n\
+        parent=eth1\n\
+        vepa=true\n"
+    ));
+}
+
+#[test]
+fn test_ip_vlan_default_mode_in_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: ipvlan0
+  type: ipvlan
+  state: up
+  ipvlan:
+    base-iface: eth1
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("[ipvlan]\nmode=2\nparent=eth1\n"));
+    assert!(matches!(
+        desired.interfaces.kernel_ifaces.get("ipvlan0"),
+        Some(Interface::IpVlan(_))
+    ));
+}
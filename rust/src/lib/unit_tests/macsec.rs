@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ErrorKind, Interface, InterfaceType, MacsecInterface, MacsecValidation,
+    MergedInterfaces, NetworkState,
+};
+
+const MKA_CAK: &str = "50b71a8ef0bd5751ea76de6d6c98c03a";
+const MKA_CKN: &str =
+    "f2b4297d39da7330910a74abc0449feb45b5c0b9fc23df1430e1898fcf1c4550";
+
+#[test]
+fn test_macsec_stringlized_attributes() {
+    let iface: MacsecInterface = serde_yaml::from_str(
+        r#"---
+name: macsec0
+type: macsec
+state: up
+macsec:
+  base-iface: eth1
+  port: "10"
+  encrypt: "false"
+  validation: check
+  send-sci: "true"
+"#,
+    )
+    .unwrap();
+
+    let conf = iface.macsec.unwrap();
+    assert_eq!(conf.base_iface, "eth1");
+    assert_eq!(conf.port, Some(10));
+    assert_eq!(conf.encrypt, Some(false));
+    assert_eq!(conf.validation, Some(MacsecValidation::Check));
+    assert_eq!(conf.send_sci, Some(true));
+}
+
+#[test]
+fn test_macsec_hide_secrets() {
+    let mut net_state: NetworkState = serde_yaml::from_str(&format!(
+        r#"---
+interfaces:
+- name: macsec0
+  type: macsec
+  state: up
+  macsec:
+    base-iface: eth1
+    mka-cak: {MKA_CAK}
+    mka-ckn: {MKA_CKN}
+"#
+    ))
+    .unwrap();
+    net_state.hide_secrets();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("macsec0").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::Macsec);
+    let conf = if let Interface::Macsec(iface) = iface {
+        iface.macsec.as_ref().unwrap()
+    } else {
+        panic!("Expecting MACsec interface, but got {:?}", iface);
+    };
+    assert_eq!(
+        conf.mka_cak.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+    assert_eq!(
+        conf.mka_ckn.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+}
+
+fn gen_merge_result(desired_yaml: &str) -> Result<(), ErrorKind> {
+    let desired: NetworkState = serde_yaml::from_str(desired_yaml).unwrap();
+    MergedInterfaces::new(
+        desired.interfaces,
+        NetworkState::new().interfaces,
+        false,
+        false,
+    )
+    .map(|_| ())
+    .map_err(|e| e.kind())
+}
+
+#[test]
+fn test_macsec_mka_cak_without_ckn() {
+    assert_eq!(
+        gen_merge_result(&format!(
+            r#"---
+interfaces:
+- name: macsec0
+  type: macsec
+  macsec:
+    base-iface: eth1
+    mka-cak: {MKA_CAK}
+"#
+        )),
+        Err(ErrorKind::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_macsec_invalid_mka_cak() {
+    assert_eq!(
+        gen_merge_result(&format!(
+            r#"---
+interfaces:
+- name: macsec0
+  type: macsec
+  macsec:
+    base-iface: eth1
+    mka-cak: 50b71a8ef0bd5751
+    mka-ckn: {MKA_CKN}
+"#
+        )),
+        Err(ErrorKind::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_macsec_invalid_port() {
+    assert_eq!(
+        gen_merge_result(
+            r#"---
+interfaces:
+- name: macsec0
+  type: macsec
+  macsec:
+    base-iface: eth1
+    port: 0
+"#
+        ),
+        Err(ErrorKind::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_macsec_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(&format!(
+        r#"---
+interfaces:
+- name: macsec0
+  type: macsec
+  state: up
+  macsec:
+    base-iface: eth1
+    port: 10
+    encrypt: true
+    validation: strict
+    send-sci: false
+    mka-cak: {MKA_CAK}
+    mka-ckn: {MKA_CKN}
+"#
+    ))
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("type=macsec\n"));
+    assert!(keyfile.contains(&format!(
+        "[macsec]\n\
+        encrypt=true\n\
+        mka-cak={MKA_CAK}\n\
+        mka-ckn={MKA_CKN}\n\
+        mode=0\n\
+        parent=eth1\n\
+        port=10\n\
+        send-sci=false\n\
+        validation=2\n"
+    )));
+}
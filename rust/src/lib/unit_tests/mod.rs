@@ -11,6 +11,10 @@ mod ethernet;
 #[cfg(test)]
 mod ethtool;
 #[cfg(test)]
+mod geneve;
+#[cfg(test)]
+mod hsr;
+#[cfg(test)]
 mod ifaces;
 #[cfg(test)]
 mod ifaces_ctrller;
@@ -19,16 +23,24 @@ mod infiniband;
 #[cfg(test)]
 mod ip;
 #[cfg(test)]
+mod ip_tunnel;
+#[cfg(test)]
+mod ip_vlan;
+#[cfg(test)]
 mod lldp;
 #[cfg(test)]
 mod mac_vlan;
 #[cfg(test)]
 mod mac_vtap;
 #[cfg(test)]
+mod macsec;
+#[cfg(test)]
 mod mptcp;
 #[cfg(test)]
 mod net_state;
 #[cfg(test)]
+mod nexthop;
+#[cfg(test)]
 mod nm;
 #[cfg(test)]
 mod ovs;
@@ -45,8 +57,14 @@ mod sriov;
 #[cfg(test)]
 mod testlib;
 #[cfg(test)]
+mod tun;
+#[cfg(test)]
 mod vlan;
 #[cfg(test)]
 mod vrf;
 #[cfg(test)]
 mod vxlan;
+#[cfg(test)]
+mod wireguard;
+#[cfg(test)]
+mod xfrm;
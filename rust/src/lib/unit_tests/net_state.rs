@@ -23,3 +23,181 @@ fn test_invalid_top_type() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_gen_diff() {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+dns-resolver:
+  config:
+    server:
+    - 192.0.2.1
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-address: 192.0.2.254
+    next-hop-interface: eth1
+    metric: 100
+    table-id: 254
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+  ipv4:
+    enabled: true
+    dhcp: false
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+- name: eth2
+  type: ethernet
+  state: up
+  mtu: 1500
+- name: dummy0
+  type: dummy
+  state: up
+"#,
+    )
+    .unwrap();
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+dns-resolver:
+  config:
+    server:
+    - 192.0.2.1
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-address: 192.0.2.254
+    next-hop-interface: eth1
+  - destination: 203.0.113.0/24
+    next-hop-address: 192.0.2.254
+    next-hop-interface: eth1
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+  ipv4:
+    enabled: true
+    dhcp: false
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+- name: eth2
+  type: ethernet
+  state: up
+  mtu: 1500
+- name: dummy0
+  type: dummy
+  state: absent
+"#,
+    )
+    .unwrap();
+
+    let diff = desired.gen_diff(&current).unwrap();
+    let expected: NetworkState = serde_yaml::from_str(
+        r#"---
+routes:
+  config:
+  - destination: 203.0.113.0/24
+    next-hop-address: 192.0.2.254
+    next-hop-interface: eth1
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+- name: dummy0
+  type: dummy
+  state: absent
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(diff.dns, expected.dns);
+    assert_eq!(diff.routes, expected.routes);
+    assert_eq!(diff.rules, expected.rules);
+    assert_eq!(diff.interfaces, expected.interfaces);
+}
+
+#[test]
+fn test_gen_diff_no_change() {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+"#,
+    )
+    .unwrap();
+
+    let diff = current.gen_diff(&current).unwrap();
+
+    assert!(diff.interfaces.is_empty());
+    assert!(diff.routes.config.is_none());
+    assert!(diff.dns.config.is_none());
+}
+
+#[test]
+fn test_gen_diff_only_interfaces_serialize() {
+    let current: NetworkState = serde_yaml::from_str(
+        r#"---
+dns-resolver:
+  config:
+    server:
+    - 192.0.2.1
+route-rules:
+  config:
+  - ip-from: 192.0.2.0/24
+    route-table: 100
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-address: 192.0.2.254
+    next-hop-interface: eth1
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+  ipv4:
+    enabled: true
+    dhcp: false
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let mut desired = current.clone();
+    desired.interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+  ipv4:
+    enabled: true
+    dhcp: false
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+
+    let diff = desired.gen_diff(&current).unwrap();
+    let value = serde_yaml::to_value(&diff).unwrap();
+    let keys: Vec<&str> = value
+        .as_mapping()
+        .unwrap()
+        .keys()
+        .filter_map(|k| k.as_str())
+        .collect();
+
+    assert_eq!(keys, vec!["interfaces"]);
+}
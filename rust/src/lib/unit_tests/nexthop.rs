@@ -0,0 +1,288 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    AddressFamily, ErrorKind, MergedNetworkState, MergedNexthops, NetworkState,
+    NexthopEntry, Nexthops,
+};
+
+fn gen_test_nexthops() -> Nexthops {
+    serde_yaml::from_str(
+        r#"
+config:
+- id: 1
+  gateway: 192.0.2.1
+  device: eth1
+- id: 2
+  gateway: 192.0.2.2
+  device: eth2
+- id: 10
+  group:
+  - id: 1
+    weight: 10
+  - id: 2
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_nexthop_deserialize() {
+    let nhs = gen_test_nexthops();
+    let nhs = nhs.config.unwrap();
+
+    assert_eq!(nhs.len(), 3);
+    assert_eq!(nhs[0].id, Some(1));
+    assert_eq!(nhs[0].gateway.as_deref(), Some("192.0.2.1"));
+    assert_eq!(nhs[0].device.as_deref(), Some("eth1"));
+    let group = nhs[2].group.as_ref().unwrap();
+    assert_eq!(group[0].id, 1);
+    assert_eq!(group[0].weight, Some(10));
+    assert_eq!(group[1].weight, None);
+}
+
+#[test]
+fn test_nexthop_sanitize_gateway_family() {
+    let mut nh: NexthopEntry = serde_yaml::from_str(
+        r#"
+id: 1
+gateway: 2001:db8:0::1
+device: eth1
+"#,
+    )
+    .unwrap();
+    nh.sanitize().unwrap();
+
+    assert_eq!(nh.gateway.as_deref(), Some("2001:db8::1"));
+    assert_eq!(nh.family, Some(AddressFamily::IPv6));
+}
+
+fn expect_invalid_nexthop(nh_yaml: &str) {
+    let mut nh: NexthopEntry = serde_yaml::from_str(nh_yaml).unwrap();
+    let result = nh.sanitize();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_nexthop_without_id() {
+    expect_invalid_nexthop("gateway: 192.0.2.1\ndevice: eth1\n");
+}
+
+#[test]
+fn test_nexthop_group_with_device() {
+    expect_invalid_nexthop(
+        r#"
+id: 10
+device: eth1
+group:
+- id: 1
+"#,
+    );
+}
+
+#[test]
+fn test_nexthop_group_invalid_weight() {
+    expect_invalid_nexthop(
+        r#"
+id: 10
+group:
+- id: 1
+  weight: 257
+"#,
+    );
+}
+
+#[test]
+fn test_nexthop_blackhole_with_gateway() {
+    expect_invalid_nexthop(
+        r#"
+id: 1
+blackhole: true
+gateway: 192.0.2.1
+"#,
+    );
+}
+
+#[test]
+fn test_nexthop_group_match_ignore_member_order() {
+    let mut nh1: NexthopEntry = serde_yaml::from_str(
+        r#"
+id: 10
+group:
+- id: 1
+- id: 2
+  weight: 1
+"#,
+    )
+    .unwrap();
+    let mut nh2: NexthopEntry = serde_yaml::from_str(
+        r#"
+id: 10
+group:
+- id: 2
+- id: 1
+"#,
+    )
+    .unwrap();
+    nh1.sanitize().unwrap();
+    nh2.sanitize().unwrap();
+
+    assert!(nh1.is_match(&nh2));
+}
+
+#[test]
+fn test_nexthop_group_member_not_exist() {
+    let desired: Nexthops = serde_yaml::from_str(
+        r#"
+config:
+- id: 10
+  group:
+  - id: 1
+  - id: 3
+"#,
+    )
+    .unwrap();
+    let result = MergedNexthops::new(desired, gen_test_nexthops());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_nexthop_group_member_is_group() {
+    let desired: Nexthops = serde_yaml::from_str(
+        r#"
+config:
+- id: 11
+  group:
+  - id: 10
+"#,
+    )
+    .unwrap();
+    let result = MergedNexthops::new(desired, gen_test_nexthops());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_nexthop_absent_and_gen_diff() {
+    let desired: Nexthops = serde_yaml::from_str(
+        r#"
+config:
+- id: 1
+  gateway: 192.0.2.1
+  device: eth1
+- id: 2
+  gateway: 192.0.2.3
+  device: eth2
+- id: 10
+  state: absent
+"#,
+    )
+    .unwrap();
+    let merged = MergedNexthops::new(desired, gen_test_nexthops()).unwrap();
+
+    assert!(merged.is_changed());
+    assert!(merged.has_nexthop(1));
+    assert!(merged.has_nexthop(2));
+    assert!(!merged.has_nexthop(10));
+
+    let diff = merged.gen_diff().unwrap().config.unwrap();
+    assert_eq!(diff.len(), 2);
+    assert_eq!(diff[0].id, Some(2));
+    assert_eq!(diff[0].gateway.as_deref(), Some("192.0.2.3"));
+    assert_eq!(diff[1].id, Some(10));
+    assert!(diff[1].is_absent());
+}
+
+#[test]
+fn test_route_with_nexthop_id_and_next_hop_iface() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+nexthops:
+  config:
+  - id: 1
+    gateway: 192.0.2.1
+    device: eth1
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    nexthop-id: 1
+    next-hop-interface: eth1
+"#,
+    )
+    .unwrap();
+    let result =
+        MergedNetworkState::new(desired, NetworkState::new(), false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_with_nexthop_id_not_exist() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    nexthop-id: 1
+"#,
+    )
+    .unwrap();
+    let result =
+        MergedNetworkState::new(desired, NetworkState::new(), false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_with_nexthop_id() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+nexthops:
+  config:
+  - id: 1
+    gateway: 192.0.2.1
+    device: eth1
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    nexthop-id: 1
+"#,
+    )
+    .unwrap();
+    let merged =
+        MergedNetworkState::new(desired, NetworkState::new(), false, false)
+            .unwrap();
+
+    assert!(merged.nexthops.is_changed());
+    assert!(merged.routes.nexthop_route_changed);
+}
+
+#[test]
+fn test_nexthop_not_supported_by_nm_gen_conf() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+nexthops:
+  config:
+  - id: 1
+    gateway: 192.0.2.1
+    device: eth1
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
         TEST_RULE_PRIORITY1, TEST_RULE_PRIORITY2, TEST_TABLE_ID1,
         TEST_TABLE_ID2,
     },
-    InterfaceType, Interfaces, MergedNetworkState, NetworkState,
+    ErrorKind, InterfaceType, Interfaces, MergedNetworkState, NetworkState,
     RouteRuleEntry,
 };
 
@@ -376,3 +376,32 @@ interfaces:
         Some(expected_ipv6_rules)
     );
 }
+
+#[test]
+fn test_route_rule_goto_not_supported_by_nm() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+route-rules:
+  config:
+  - family: ipv4
+    priority: 100
+    action: goto
+    goto-target: 200
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
@@ -7,7 +7,8 @@ use crate::{
         TEST_IPV4_NET1, TEST_IPV6_ADDR1, TEST_IPV6_ADDR2, TEST_IPV6_NET1,
         TEST_IPV6_NET2, TEST_NIC, TEST_ROUTE_METRIC,
     },
-    ErrorKind, InterfaceType, MergedRoutes, RouteEntry, RouteState, Routes,
+    ErrorKind, InterfaceType, MergedRoutes, NetworkState, RouteEntry,
+    RouteProtocol, RouteScope, RouteState, RouteType, Routes,
 };
 
 #[test]
@@ -432,3 +433,444 @@ fn test_route_matching_empty_via_with_none() {
     assert!(!absent_route.is_match(&not_match_route));
     assert!(!absent_route.is_match(&match_route));
 }
+
+#[test]
+fn test_route_type_without_next_hop_iface() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  route-type: blackhole
+- destination: 2001:db8:f::/64
+  route-type: unreachable
+  next-hop-interface: eth1
+"#,
+    )
+    .unwrap();
+    let current: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  route-type: blackhole
+  table-id: 254
+- destination: 2001:db8:f::/64
+  route-type: unreachable
+  table-id: 254
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    let merged_routes =
+        MergedRoutes::new(desired, Routes::new(), &merged_ifaces).unwrap();
+
+    assert_eq!(
+        merged_routes.route_changed_ifaces,
+        vec![RouteEntry::LOOPBACK_IFACE_NAME.to_string()]
+    );
+    let lo_routes = merged_routes
+        .indexed
+        .get(RouteEntry::LOOPBACK_IFACE_NAME)
+        .unwrap();
+    assert_eq!(lo_routes.len(), 2);
+    assert_eq!(lo_routes[0].route_type, Some(RouteType::Unreachable));
+    assert_eq!(lo_routes[0].next_hop_iface, None);
+    assert_eq!(lo_routes[1].route_type, Some(RouteType::Blackhole));
+
+    merged_routes.verify(&current, &[]).unwrap();
+}
+
+#[test]
+fn test_route_type_verify_unicast_route_not_match() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  route-type: prohibit
+"#,
+    )
+    .unwrap();
+    let current: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  table-id: 254
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    let merged_routes =
+        MergedRoutes::new(desired, Routes::new(), &merged_ifaces).unwrap();
+
+    let result = merged_routes.verify(&current, &[]);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+    }
+}
+
+#[test]
+fn test_route_type_with_next_hop_addr() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  route-type: throw
+  next-hop-address: 192.0.2.1
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    let result = MergedRoutes::new(desired, Routes::new(), &merged_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_type_local_requires_next_hop_iface() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.1/32
+  route-type: local
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    assert!(MergedRoutes::new(desired, Routes::new(), &merged_ifaces).is_err());
+}
+
+#[test]
+fn test_route_type_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+interfaces:
+- name: lo
+  type: loopback
+  state: up
+  ipv4:
+    enabled: true
+    address:
+    - ip: 127.0.0.1
+      prefix-length: 8
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    route-type: blackhole
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("route0=198.51.100.0/24,,0\n"));
+    assert!(keyfile.contains("route0_options=table=254,type=blackhole\n"));
+}
+
+#[test]
+fn test_route_type_gen_nm_keyfile_without_loopback() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    route-type: blackhole
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let (file_name, keyfile) = &confs["NetworkManager"][0];
+
+    assert_eq!(file_name, "lo.nmconnection");
+    assert!(keyfile.contains("type=loopback\n"));
+    assert!(keyfile.contains("address0=127.0.0.1/8\n"));
+    assert!(keyfile.contains("route0=198.51.100.0/24,,0\n"));
+    assert!(keyfile.contains("route0_options=table=254,type=blackhole\n"));
+}
+
+#[test]
+fn test_route_extended_attributes_stringlized() {
+    let mut route: RouteEntry = serde_yaml::from_str(
+        r#"
+destination: 2001:db8:f::/64
+next-hop-interface: eth1
+source: "2001:db8:1:0::1"
+mtu: "1400"
+advmss: "1360"
+initcwnd: "20"
+initrwnd: "30"
+onlink: "true"
+scope: link
+quickack: "false"
+"#,
+    )
+    .unwrap();
+    route.sanitize().unwrap();
+
+    assert_eq!(route.source.as_deref(), Some("2001:db8:1::1"));
+    assert_eq!(route.mtu, Some(1400));
+    assert_eq!(route.advmss, Some(1360));
+    assert_eq!(route.initcwnd, Some(20));
+    assert_eq!(route.initrwnd, Some(30));
+    assert_eq!(route.onlink, Some(true));
+    assert_eq!(route.scope, Some(RouteScope::Link));
+    assert_eq!(route.quickack, Some(false));
+}
+
+#[test]
+fn test_route_source_ip_family_mismatch() {
+    let mut route: RouteEntry = serde_yaml::from_str(
+        r#"
+destination: 198.51.100.0/24
+next-hop-interface: eth1
+source: 2001:db8:1::1
+"#,
+    )
+    .unwrap();
+    let result = route.sanitize();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_extended_attributes_verify() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  source: 192.0.2.1
+  mtu: 1400
+  scope: link
+  quickack: false
+"#,
+    )
+    .unwrap();
+    let current: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  next-hop-address: 0.0.0.0
+  source: 192.0.2.1
+  mtu: 1400
+  table-id: 254
+"#,
+    )
+    .unwrap();
+    let wrong_mtu: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  source: 192.0.2.1
+  mtu: 1500
+  table-id: 254
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    let merged_routes =
+        MergedRoutes::new(desired, Routes::new(), &merged_ifaces).unwrap();
+
+    merged_routes.verify(&current, &[]).unwrap();
+    let result = merged_routes.verify(&wrong_mtu, &[]);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+    }
+}
+
+#[test]
+fn test_route_desired_attributes_override_current() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  next-hop-address: 192.0.2.1
+  initcwnd: 20
+"#,
+    )
+    .unwrap();
+    let current: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  next-hop-address: 192.0.2.1
+  initcwnd: 10
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    let merged_routes =
+        MergedRoutes::new(desired, current, &merged_ifaces).unwrap();
+
+    let eth1_routes = merged_routes.indexed.get("eth1").unwrap();
+    assert_eq!(eth1_routes.len(), 1);
+    assert_eq!(eth1_routes[0].initcwnd, Some(20));
+}
+
+#[test]
+fn test_route_extended_attributes_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.1
+    source: 192.0.2.2
+    mtu: 1400
+    advmss: 1360
+    initcwnd: 20
+    initrwnd: 30
+    onlink: true
+    scope: universe
+    quickack: true
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains(
+        "route0_options=table=254,src=192.0.2.2,mtu=1400,advmss=1360,\
+        initcwnd=20,initrwnd=30,onlink=true,scope=0,quickack=true\n"
+    ));
+}
+
+#[test]
+fn test_route_protocol_verify() {
+    let desired: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  protocol: boot
+"#,
+    )
+    .unwrap();
+    let current: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  next-hop-address: 0.0.0.0
+  protocol: boot
+  table-id: 254
+"#,
+    )
+    .unwrap();
+    let wrong_protocol: Routes = serde_yaml::from_str(
+        r#"
+config:
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  next-hop-address: 0.0.0.0
+  table-id: 254
+"#,
+    )
+    .unwrap();
+
+    let merged_ifaces = gen_merged_ifaces_for_route_test();
+    let merged_routes =
+        MergedRoutes::new(desired, Routes::new(), &merged_ifaces).unwrap();
+
+    merged_routes.verify(&current, &[]).unwrap();
+    let result = merged_routes.verify(&wrong_protocol, &[]);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+    }
+}
+
+#[test]
+fn test_route_protocol_not_static() {
+    let mut route: RouteEntry = serde_yaml::from_str(
+        r#"
+destination: 198.51.100.0/24
+next-hop-interface: eth1
+protocol: dhcp
+"#,
+    )
+    .unwrap();
+    let result = route.sanitize();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_attributes_changed_not_equal() {
+    let route: RouteEntry = serde_yaml::from_str(
+        r#"
+destination: 198.51.100.0/24
+next-hop-interface: eth1
+next-hop-address: 192.0.2.1
+"#,
+    )
+    .unwrap();
+    let mut route_with_defaults = route.clone();
+    route_with_defaults.quickack = Some(false);
+    route_with_defaults.scope = Some(RouteScope::Universe);
+    route_with_defaults.protocol = Some(RouteProtocol::Static);
+    assert_eq!(route, route_with_defaults);
+
+    let mut changed_route = route.clone();
+    changed_route.advmss = Some(1360);
+    assert_ne!(route, changed_route);
+    changed_route = route.clone();
+    changed_route.protocol = Some(RouteProtocol::Boot);
+    assert_ne!(route, changed_route);
+}
+
+#[test]
+fn test_route_protocol_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.2
+      prefix-length: 24
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.1
+    protocol: boot
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
@@ -1,8 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    unit_tests::testlib::gen_test_rule_entries, MergedRouteRules,
-    RouteRuleEntry, RouteRules,
+    unit_tests::testlib::gen_test_rule_entries, ErrorKind, MergedRouteRules,
+    RouteRuleAction, RouteRuleEntry, RouteRulePortRange, RouteRuleUidRange,
+    RouteRules,
 };
 
 #[test]
@@ -320,3 +321,158 @@ fn test_route_rule_auto_priority_increasing_from_empty() {
     );
     assert_eq!(rules[2].priority, Some(30002));
 }
+
+#[test]
+fn test_route_rule_selectors() {
+    let mut rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"
+family: ipv4
+oif: eth1
+uid-range:
+  start: 1000
+  end: 1999
+ipproto: 6
+sport:
+  start: 1024
+  end: 65000
+dport:
+  start: "443"
+  end: "443"
+tos: 0x10
+invert: true
+route-table: 100
+"#,
+    )
+    .unwrap();
+    rule.sanitize().unwrap();
+
+    assert_eq!(rule.oif.as_deref(), Some("eth1"));
+    assert_eq!(rule.uid_range, Some(RouteRuleUidRange::new(1000, 1999)));
+    assert_eq!(rule.ip_proto, Some(6));
+    assert_eq!(rule.sport, Some(RouteRulePortRange::new(1024, 65000)));
+    assert_eq!(rule.dport, Some(RouteRulePortRange::new(443, 443)));
+    assert_eq!(rule.tos, Some(0x10));
+    assert_eq!(rule.invert, Some(true));
+
+    let absent_rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"
+state: absent
+family: ipv4
+dport:
+  start: 443
+  end: 443
+"#,
+    )
+    .unwrap();
+    let mut other_rule = rule.clone();
+    other_rule.dport = Some(RouteRulePortRange::new(80, 80));
+
+    assert!(absent_rule.is_match(&rule));
+    assert!(!absent_rule.is_match(&other_rule));
+    assert_ne!(rule, other_rule);
+}
+
+#[test]
+fn test_route_rule_invalid_port_range() {
+    let mut rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"
+family: ipv6
+sport:
+  start: 2000
+  end: 1000
+"#,
+    )
+    .unwrap();
+    let result = rule.sanitize();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_rule_l3mdev() {
+    let mut rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"
+family: ipv4
+l3mdev: true
+"#,
+    )
+    .unwrap();
+    rule.sanitize().unwrap();
+    assert_eq!(rule.table_id, None);
+
+    rule.table_id = Some(100);
+    let result = rule.sanitize();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_rule_suppress_prefix_length() {
+    let mut rule: RouteRuleEntry = serde_yaml::from_str(
+        r#"
+family: ipv4
+priority: 100
+route-table: 254
+suppress-prefix-length: 0
+"#,
+    )
+    .unwrap();
+    rule.sanitize().unwrap();
+
+    let mut other_rule = rule.clone();
+    other_rule.suppress_prefix_length = None;
+    assert!(rule.is_match(&rule.clone()));
+    assert!(!rule.is_match(&other_rule));
+
+    rule.table_id = None;
+    rule.action = Some(RouteRuleAction::Blackhole);
+    let result = rule.sanitize();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_route_rule_goto_and_nop() {
+    let rules: RouteRules = serde_yaml::from_str(
+        r#"
+config:
+- family: ipv4
+  priority: 100
+  action: goto
+  goto-target: 200
+- family: ipv4
+  priority: 200
+  action: nop
+"#,
+    )
+    .unwrap();
+    let merged = MergedRouteRules::new(rules, RouteRules::new()).unwrap();
+
+    assert_eq!(merged.for_apply[0].action, Some(RouteRuleAction::Goto));
+    assert_eq!(merged.for_apply[0].goto_target, Some(200));
+    assert_eq!(merged.for_apply[0].table_id, None);
+    assert_eq!(merged.for_apply[1].action, Some(RouteRuleAction::Nop));
+    assert_eq!(merged.for_apply[1].table_id, None);
+}
+
+#[test]
+fn test_route_rule_invalid_goto_target() {
+    for rule_yaml in [
+        "family: ipv4\naction: goto\n",
+        "family: ipv4\npriority: 200\naction: goto\ngoto-target: 100\n",
+        "family: ipv4\nroute-table: 100\ngoto-target: 300\n",
+    ] {
+        let mut rule: RouteRuleEntry = serde_yaml::from_str(rule_yaml).unwrap();
+        let result = rule.sanitize();
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ErrorKind, NetworkState, TunInterface, TunMode};
+
+#[test]
+fn test_tun_stringlized_attributes() {
+    let iface: TunInterface = serde_yaml::from_str(
+        r#"---
+name: tap0
+type: tun
+state: up
+tun:
+  mode: tap
+  owner: "107"
+  group: "108"
+  multi-queue: "true"
+  vnet-hdr: "false"
+  persist: "true"
+"#,
+    )
+    .unwrap();
+
+    let conf = iface.tun.unwrap();
+    assert_eq!(conf.mode, Some(TunMode::Tap));
+    assert_eq!(conf.owner, Some(107));
+    assert_eq!(conf.group, Some(108));
+    assert_eq!(conf.multi_queue, Some(true));
+    assert_eq!(conf.vnet_hdr, Some(false));
+    assert_eq!(conf.persist, Some(true));
+}
+
+#[test]
+fn test_tun_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: tap0
+  type: tun
+  state: up
+  tun:
+    mode: tap
+    owner: 107
+    group: 108
+    multi-queue: true
+    vnet-hdr: true
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("type=tun\n"));
+    assert!(keyfile.contains(
+        "[tun]\n\
+        group=108\n\
+        mode=2\n\
+        multi-queue=true\n\
+        owner=107\n\
+        vnet-hdr=true\n"
+    ));
+}
+
+#[test]
+fn test_tun_tap_as_bridge_port_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: tap0
+  type: tun
+  state: up
+  tun:
+    mode: tap
+- name: br0
+  type: linux-bridge
+  state: up
+  bridge:
+    port:
+    - name: tap0
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let tap_keyfile = confs["NetworkManager"]
+        .iter()
+        .find(|(name, _)| name.starts_with("tap0"))
+        .map(|(_, content)| content)
+        .unwrap();
+
+    assert!(tap_keyfile.contains("type=tun\n"));
+    assert!(tap_keyfile.contains("slave-type=bridge\n"));
+    assert!(tap_keyfile.contains("[bridge-port]\n"));
+}
+
+#[test]
+fn test_tun_non_persistent_gen_nm_keyfile() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: tap0
+  type: tun
+  state: up
+  tun:
+    mode: tap
+    persist: false
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
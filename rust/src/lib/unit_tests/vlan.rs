@@ -1,8 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    ErrorKind, InterfaceType, Interfaces, MergedInterfaces, VlanInterface,
-    VlanProtocol,
+    unit_tests::testlib::new_eth_iface, ErrorKind, InterfaceType, Interfaces,
+    MergedInterfaces, NetworkState, VlanInterface, VlanProtocol,
+    VlanQosMapping,
 };
 
 #[test]
@@ -213,3 +214,120 @@ fn test_vlan_update() {
         Some(VlanProtocol::Ieee8021Q)
     );
 }
+
+#[test]
+fn test_vlan_qos_map_and_flags_stringlized_attributes() {
+    let mut iface: VlanInterface = serde_yaml::from_str(
+        r#"---
+name: eth1.101
+type: vlan
+state: up
+vlan:
+  base-iface: eth1
+  id: 101
+  reorder-headers: "false"
+  gvrp: "true"
+  mvrp: "false"
+  loose-binding: "true"
+  ingress-qos-map:
+  - from: "6"
+    to: "6"
+  - from: "5"
+    to: "4"
+  - from: "1"
+    to: "0"
+  egress-qos-map:
+  - from: "4"
+    to: "5"
+"#,
+    )
+    .unwrap();
+    iface.sanitize(true).unwrap();
+    let vlan_conf = iface.vlan.unwrap();
+
+    assert_eq!(vlan_conf.reorder_headers, Some(false));
+    assert_eq!(vlan_conf.gvrp, Some(true));
+    assert_eq!(vlan_conf.mvrp, Some(false));
+    assert_eq!(vlan_conf.loose_binding, Some(true));
+    // Mapping to 0 is ignored and mappings are sorted
+    assert_eq!(
+        vlan_conf.ingress_qos_map,
+        Some(vec![VlanQosMapping::new(5, 4), VlanQosMapping::new(6, 6)])
+    );
+    assert_eq!(
+        vlan_conf.egress_qos_map,
+        Some(vec![VlanQosMapping::new(4, 5)])
+    );
+}
+
+fn expect_invalid_vlan(vlan_conf: &str) {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    let ifaces: Interfaces = serde_yaml::from_str(&format!(
+        r#"---
+- name: eth1.101
+  type: vlan
+  vlan:
+    base-iface: eth1
+    id: 101
+{vlan_conf}"#
+    ))
+    .unwrap();
+
+    let result = MergedInterfaces::new(ifaces, cur_ifaces, false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_vlan_invalid_ingress_qos_priority() {
+    expect_invalid_vlan("    ingress-qos-map:\n    - from: 8\n      to: 1\n");
+}
+
+#[test]
+fn test_vlan_invalid_egress_qos_priority() {
+    expect_invalid_vlan("    egress-qos-map:\n    - from: 1\n      to: 8\n");
+}
+
+#[test]
+fn test_vlan_duplicate_qos_priority() {
+    expect_invalid_vlan(
+        "    egress-qos-map:\n    - from: 1\n      to: 2\n    \
+        - from: 1\n      to: 3\n",
+    );
+}
+
+#[test]
+fn test_vlan_gen_nm_keyfile_with_qos_map_and_flags() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1.101
+  type: vlan
+  state: up
+  vlan:
+    base-iface: eth1
+    id: 101
+    gvrp: true
+    loose-binding: true
+    ingress-qos-map:
+    - from: 1
+      to: 2
+    - from: 3
+      to: 4
+    egress-qos-map:
+    - from: 5
+      to: 6
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("egress-priority-map=5:6\n"));
+    // Reorder headers is enabled by default
+    assert!(keyfile.contains("flags=7\n"));
+    assert!(keyfile.contains("ingress-priority-map=1:2;3:4\n"));
+}
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::VxlanInterface;
+use crate::{
+    unit_tests::testlib::new_eth_iface, ErrorKind, Interfaces,
+    MergedInterfaces, NetworkState, VxlanInterface,
+};
 
 #[test]
 fn test_vxlan_stringlized_attributes() {
@@ -47,3 +50,166 @@ vxlan:
         Some(std::net::IpAddr::V4("1.2.3.4".parse().unwrap()))
     );
 }
+
+#[test]
+fn test_vxlan_full_options_stringlized_attributes() {
+    let mut iface: VxlanInterface = serde_yaml::from_str(
+        r#"---
+name: vxlan1
+type: vxlan
+state: up
+vxlan:
+  id: 101
+  ttl: "64"
+  tos: "16"
+  source-port-min: "49152"
+  source-port-max: "65535"
+  ageing: "300"
+  limit: "0"
+  proxy: "true"
+  rsc: "false"
+  l2-miss: "true"
+  l3-miss: "false"
+  udp-checksum: "true"
+  udp6-zero-checksum-tx: "false"
+  udp6-zero-checksum-rx: "false"
+  gbp: "true"
+  external: "false"
+  fdb-remotes:
+  - 192.0.2.3
+  - 192.0.2.2
+  - 192.0.2.3
+"#,
+    )
+    .unwrap();
+    iface.sanitize(true).unwrap();
+    let vxlan_conf = iface.vxlan.unwrap();
+
+    assert_eq!(vxlan_conf.ttl, Some(64));
+    assert_eq!(vxlan_conf.tos, Some(16));
+    assert_eq!(vxlan_conf.source_port_min, Some(49152));
+    assert_eq!(vxlan_conf.source_port_max, Some(65535));
+    assert_eq!(vxlan_conf.ageing, Some(300));
+    assert_eq!(vxlan_conf.limit, Some(0));
+    assert_eq!(vxlan_conf.proxy, Some(true));
+    assert_eq!(vxlan_conf.rsc, Some(false));
+    assert_eq!(vxlan_conf.l2_miss, Some(true));
+    assert_eq!(vxlan_conf.l3_miss, Some(false));
+    assert_eq!(vxlan_conf.udp_checksum, Some(true));
+    assert_eq!(vxlan_conf.udp6_zero_checksum_tx, Some(false));
+    assert_eq!(vxlan_conf.udp6_zero_checksum_rx, Some(false));
+    assert_eq!(vxlan_conf.gbp, Some(true));
+    assert_eq!(vxlan_conf.external, Some(false));
+    assert_eq!(
+        vxlan_conf.fdb_remotes,
+        Some(vec![
+            "192.0.2.2".parse().unwrap(),
+            "192.0.2.3".parse().unwrap()
+        ])
+    );
+}
+
+fn expect_invalid_vxlan(vxlan_conf: &str) {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    let ifaces: Interfaces = serde_yaml::from_str(&format!(
+        r#"---
+- name: vxlan1
+  type: vxlan
+  vxlan:
+    base-iface: eth1
+    id: 101
+{vxlan_conf}"#
+    ))
+    .unwrap();
+
+    let result = MergedInterfaces::new(ifaces, cur_ifaces, false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_vxlan_invalid_source_port_range() {
+    expect_invalid_vxlan(
+        "    source-port-min: 2000\n    source-port-max: 1000\n",
+    );
+}
+
+#[test]
+fn test_vxlan_source_port_min_without_max() {
+    expect_invalid_vxlan("    source-port-min: 2000\n");
+}
+
+#[test]
+fn test_vxlan_multicast_fdb_remote() {
+    expect_invalid_vxlan("    fdb-remotes:\n    - 239.1.1.1\n");
+}
+
+#[test]
+fn test_vxlan_fdb_remote_same_as_remote() {
+    expect_invalid_vxlan(
+        "    remote: 192.0.2.2\n    fdb-remotes:\n    - 192.0.2.2\n",
+    );
+}
+
+#[test]
+fn test_vxlan_gen_nm_keyfile_with_full_options() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: vxlan1
+  type: vxlan
+  state: up
+  vxlan:
+    base-iface: eth1
+    id: 101
+    ttl: 64
+    source-port-min: 49152
+    source-port-max: 65535
+    proxy: true
+    l2-miss: true
+    udp-checksum: false
+"#,
+    )
+    .unwrap();
+    let confs = desired.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0].1;
+
+    assert!(keyfile.contains("l2-miss=true\n"));
+    assert!(keyfile.contains("proxy=true\n"));
+    assert!(keyfile.contains("source-port-max=65535\n"));
+    assert!(keyfile.contains("source-port-min=49152\n"));
+    assert!(keyfile.contains("ttl=64\n"));
+}
+
+fn expect_vxlan_not_supported_by_nm(vxlan_conf: &str) {
+    let desired: NetworkState = serde_yaml::from_str(&format!(
+        r#"---
+interfaces:
+- name: vxlan1
+  type: vxlan
+  state: up
+  vxlan:
+    base-iface: eth1
+    id: 101
+{vxlan_conf}"#
+    ))
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}
+
+#[test]
+fn test_vxlan_gbp_not_supported_by_nm() {
+    expect_vxlan_not_supported_by_nm("    gbp: true\n");
+}
+
+#[test]
+fn test_vxlan_fdb_remotes_not_supported_by_nm_gen_conf() {
+    expect_vxlan_not_supported_by_nm("    fdb-remotes:\n    - 192.0.2.2\n");
+}
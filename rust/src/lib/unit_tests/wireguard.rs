@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ErrorKind, Interface, InterfaceType, MergedInterfaces, NetworkState,
+    WireGuardInterface,
+};
+
+#[test]
+fn test_wireguard_stringlized_attributes() {
+    let iface: WireGuardInterface = serde_yaml::from_str(
+        r#"---
+name: wg0
+type: wireguard
+state: up
+wireguard:
+  listen-port: "51820"
+  fwmark: "100"
+  peers:
+  - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+    persistent-keepalive: "25"
+"#,
+    )
+    .unwrap();
+
+    let wg_conf = iface.wireguard.unwrap();
+    assert_eq!(wg_conf.listen_port, Some(51820));
+    assert_eq!(wg_conf.fwmark, Some(100));
+    assert_eq!(
+        wg_conf.peers.as_ref().unwrap()[0].persistent_keepalive,
+        Some(25)
+    );
+}
+
+#[test]
+fn test_wireguard_hide_secrets() {
+    let mut net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: wg0
+  type: wireguard
+  state: up
+  wireguard:
+    private-key: yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+    peers:
+    - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+      preshared-key: FpCyhws9cxwWoV4xELtfJvjJN+zQVRPISllRWgeopVE=
+    - public-key: xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+"#,
+    )
+    .unwrap();
+    net_state.hide_secrets();
+
+    let iface = net_state.interfaces.kernel_ifaces.get("wg0").unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::WireGuard);
+    let wg_conf = if let Interface::WireGuard(iface) = iface {
+        iface.wireguard.as_ref().unwrap()
+    } else {
+        panic!("Expecting WireGuard interface, but got {:?}", iface);
+    };
+    assert_eq!(
+        wg_conf.private_key.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+    let peers = wg_conf.peers.as_ref().unwrap();
+    assert_eq!(
+        peers[0].preshared_key.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+    assert_eq!(peers[1].preshared_key, None);
+}
+
+#[test]
+fn test_wireguard_duplicate_peer() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: wg0
+  type: wireguard
+  state: up
+  wireguard:
+    peers:
+    - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+      endpoint: 198.51.100.1:51820
+    - public-key: gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=
+      endpoint: 198.51.100.2:51820
+"#,
+    )
+    .unwrap();
+
+    let result = MergedInterfaces::new(
+        desired.interfaces,
+        NetworkState::new().interfaces,
+        false,
+        false,
+    );
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
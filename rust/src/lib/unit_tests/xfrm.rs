@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    unit_tests::testlib::new_eth_iface, ErrorKind, Interfaces,
+    MergedInterfaces, MergedNetworkState, NetworkState, XfrmInterface,
+};
+
+#[test]
+fn test_xfrm_stringlized_attributes() {
+    let iface: XfrmInterface = serde_yaml::from_str(
+        r#"---
+name: ipsec1
+type: xfrm
+state: up
+xfrm:
+  base-iface: eth1
+  if-id: "5"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(iface.parent(), Some("eth1"));
+    assert_eq!(iface.xfrm.unwrap().if_id, 5);
+}
+
+#[test]
+fn test_xfrm_without_base_iface() {
+    let iface: XfrmInterface = serde_yaml::from_str(
+        r#"---
+name: ipsec1
+type: xfrm
+state: up
+xfrm:
+  if-id: 5
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(iface.parent(), None);
+    assert!(iface.sanitize(true).is_ok());
+}
+
+#[test]
+fn test_xfrm_invalid_if_id() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: ipsec1
+  type: xfrm
+  xfrm:
+    base-iface: eth1
+    if-id: 0
+"#,
+    )
+    .unwrap();
+
+    let result = MergedInterfaces::new(ifaces, cur_ifaces, false, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_xfrm_route_next_hop() {
+    let mut current = NetworkState::new();
+    current.interfaces.push(new_eth_iface("eth1"));
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: ipsec1
+  type: xfrm
+  state: up
+  xfrm:
+    base-iface: eth1
+    if-id: 5
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: ipsec1
+"#,
+    )
+    .unwrap();
+
+    let merged_state =
+        MergedNetworkState::new(desired, current, false, false).unwrap();
+
+    assert!(merged_state.routes.indexed.contains_key("ipsec1"));
+    assert_eq!(
+        merged_state.routes.route_changed_ifaces,
+        vec!["ipsec1".to_string()]
+    );
+}
+
+#[test]
+fn test_xfrm_not_supported_by_nm() {
+    let desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: ipsec1
+  type: xfrm
+  state: up
+  xfrm:
+    if-id: 5
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotSupportedError);
+    }
+}